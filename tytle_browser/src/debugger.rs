@@ -0,0 +1,187 @@
+use tytle::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::host::BrowserHost;
+
+// a program paused under the debugger.
+// the debugger is suspended between calls, since it can't borrow the session it lives in
+#[wasm_bindgen]
+pub struct DebugSession {
+    env: Environment,
+    cfg: CfgObject,
+    host: BrowserHost,
+    state: Option<DebuggerState>,
+}
+
+#[wasm_bindgen]
+impl DebugSession {
+    #[wasm_bindgen(constructor)]
+    pub fn new(code: &str) -> Result<DebugSession, JsValue> {
+        let mut host = BrowserHost::new();
+
+        let compiled = crate::compile(code, &mut host);
+        if compiled.is_none() {
+            return Err(JsValue::from_str("compilation error"));
+        }
+
+        let (env, cfg) = compiled.unwrap();
+
+        let mut session = Self {
+            env,
+            cfg,
+            host,
+            state: None,
+        };
+
+        let dbg = Debugger::new(&session.cfg, &session.env, &mut session.host);
+        session.state = Some(dbg.suspend());
+
+        Ok(session)
+    }
+
+    pub fn add_line_breakpoint(&mut self, line: usize) -> bool {
+        self.with_debugger(|dbg| dbg.add_line_breakpoint(line))
+    }
+
+    pub fn remove_line_breakpoint(&mut self, line: usize) -> bool {
+        self.with_debugger(|dbg| dbg.remove_line_breakpoint(line))
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.with_debugger(|dbg| dbg.clear_breakpoints())
+    }
+
+    // the following return the reason for pausing:
+    // `STEP`, `BREAKPOINT`, `TRAP`, `FINISHED` or `ERROR: <exception>`
+
+    pub fn run(&mut self) -> String {
+        self.with_debugger(|dbg| debug_result_str(dbg.run()))
+    }
+
+    pub fn step_into(&mut self) -> String {
+        self.with_debugger(|dbg| debug_result_str(dbg.step_into()))
+    }
+
+    pub fn step_over(&mut self) -> String {
+        self.with_debugger(|dbg| debug_result_str(dbg.step_over()))
+    }
+
+    pub fn step_out(&mut self) -> String {
+        self.with_debugger(|dbg| debug_result_str(dbg.step_out()))
+    }
+
+    pub fn is_finished(&mut self) -> bool {
+        self.with_debugger(|dbg| dbg.is_finished())
+    }
+
+    // the source line of the statement about to be executed
+    pub fn current_line(&mut self) -> Option<usize> {
+        self.with_debugger(|dbg| dbg.get_location().map(|loc| loc.line()))
+    }
+
+    // returns the call-stack frames (innermost first) as JSON:
+    // `[{"proc": "F", "line": 3, "variables": [{"name": "X", "value": 10}]}]`
+    pub fn frames(&mut self) -> String {
+        self.with_debugger(|dbg| {
+            let frames: Vec<String> = dbg
+                .get_frames()
+                .iter()
+                .map(|frame| {
+                    let line = match frame.location {
+                        Some(loc) => loc.line().to_string(),
+                        None => "null".to_string(),
+                    };
+
+                    format!(
+                        r#"{{"proc": {}, "line": {}, "variables": {}}}"#,
+                        json_str(&frame.proc_name),
+                        line,
+                        variables_json(dbg, &frame.variables)
+                    )
+                })
+                .collect();
+
+            format!("[{}]", frames.join(", "))
+        })
+    }
+
+    // returns the globals as JSON: `[{"name": "A", "value": 10}]`
+    pub fn globals(&mut self) -> String {
+        self.with_debugger(|dbg| variables_json(dbg, &dbg.get_globals()))
+    }
+}
+
+impl DebugSession {
+    fn with_debugger<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut Debugger) -> T,
+    {
+        let state = self.state.take().unwrap();
+
+        let mut dbg = Debugger::resume(&self.cfg, &self.env, &mut self.host, state);
+        let res = f(&mut dbg);
+
+        self.state = Some(dbg.suspend());
+
+        res
+    }
+}
+
+fn debug_result_str(res: DebugResult) -> String {
+    match res {
+        Ok(DebugEvent::Step) => "STEP".to_string(),
+        Ok(DebugEvent::Breakpoint(..)) => "BREAKPOINT".to_string(),
+        Ok(DebugEvent::Trap(..)) => "TRAP".to_string(),
+        Ok(DebugEvent::Finished) => "FINISHED".to_string(),
+        Err(err) => format!("ERROR: {}", err.to_string()),
+    }
+}
+
+fn variables_json(dbg: &Debugger, vars: &[DebugVariable]) -> String {
+    let vars: Vec<String> = vars
+        .iter()
+        .map(|var| {
+            let value = value_json(dbg, &var.value, &mut Vec::new());
+
+            format!(r#"{{"name": {}, "value": {}}}"#, json_str(&var.name), value)
+        })
+        .collect();
+
+    format!("[{}]", vars.join(", "))
+}
+
+// a record is rendered as a JSON object of its fields.
+// `visiting` holds the records being rendered, so a record referring back to itself ends the recursion
+fn value_json(dbg: &Debugger, value: &MemoryValue, visiting: &mut Vec<usize>) -> String {
+    match *value {
+        MemoryValue::Int(v) => v.to_string(),
+        MemoryValue::Bool(v) => v.to_string(),
+        MemoryValue::Str(ref v) => json_str(v),
+        MemoryValue::Proc(node_id, _) => json_str(dbg.get_proc_name(node_id).unwrap_or("?")),
+        MemoryValue::Struct(struct_ref) => {
+            if visiting.contains(&struct_ref) {
+                return json_str("...");
+            }
+
+            let fields = match dbg.get_struct_fields(struct_ref) {
+                Some(fields) => fields,
+                None => return json_str("?"),
+            };
+
+            visiting.push(struct_ref);
+
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|field| {
+                    let value = value_json(dbg, &field.value, visiting);
+
+                    format!("{}: {}", json_str(&field.name), value)
+                })
+                .collect();
+
+            visiting.pop();
+
+            format!("{{{}}}", fields.join(", "))
+        }
+    }
+}
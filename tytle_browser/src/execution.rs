@@ -0,0 +1,75 @@
+use tytle::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::host::BrowserHost;
+
+// a program executed in slices (see `run_for`), so the page can stay responsive
+// and animate the drawing frame by frame.
+// the interpreter is suspended between slices, since it can't borrow the `Execution` it lives in
+#[wasm_bindgen]
+pub struct Execution {
+    env: Environment,
+    cfg: CfgObject,
+    host: BrowserHost,
+    state: Option<InterpreterState>,
+    done: bool,
+}
+
+#[wasm_bindgen]
+impl Execution {
+    #[wasm_bindgen(constructor)]
+    pub fn new(code: &str) -> Result<Execution, JsValue> {
+        let mut host = BrowserHost::new();
+
+        let compiled = crate::compile(code, &mut host);
+        if compiled.is_none() {
+            return Err(JsValue::from_str("compilation error"));
+        }
+
+        let (env, cfg) = compiled.unwrap();
+
+        let mut execution = Self {
+            env,
+            cfg,
+            host,
+            state: None,
+            done: false,
+        };
+
+        let intr = Interpreter::new(&execution.cfg, &execution.env, &mut execution.host);
+        execution.state = Some(intr.suspend());
+
+        Ok(execution)
+    }
+
+    // executes up to `n_insts` instructions.
+    // returns `true` once the program is done (completed or failed)
+    pub fn run_for(&mut self, n_insts: usize) -> bool {
+        if self.done {
+            return true;
+        }
+
+        let state = self.state.take().unwrap();
+        let mut intr = Interpreter::resume(&self.cfg, &self.env, &mut self.host, state);
+
+        let res = intr.run_for(n_insts);
+        self.state = Some(intr.suspend());
+
+        match res {
+            Ok(RunStatus::Yielded) => false,
+            Ok(RunStatus::Completed) => {
+                self.done = true;
+                true
+            }
+            Err(err) => {
+                self.host.runtime_error(&crate::runtime_error_str(&err));
+                self.done = true;
+                true
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
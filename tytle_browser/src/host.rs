@@ -52,8 +52,23 @@ extern "C" {
     #[wasm_bindgen(method)]
     fn print(this: &TytleHost, msg: &str);
 
+    #[wasm_bindgen(method)]
+    fn label(this: &TytleHost, text: &str);
+
+    #[wasm_bindgen(method)]
+    fn set_font_size(this: &TytleHost, size: i32);
+
+    #[wasm_bindgen(method)]
+    fn trap(this: &TytleHost, node: u32, ip: u32);
+
     #[wasm_bindgen(method)]
     fn compilation_error(this: &TytleHost, error: &str);
+
+    #[wasm_bindgen(method)]
+    fn compilation_warning(this: &TytleHost, warning: &str);
+
+    #[wasm_bindgen(method)]
+    fn runtime_error(this: &TytleHost, error: &str);
 }
 
 pub struct BrowserHost {
@@ -66,6 +81,14 @@ impl BrowserHost {
             browser: TytleHost::new(),
         }
     }
+
+    pub fn compilation_warning(&mut self, warning: &str) {
+        self.browser.compilation_warning(warning);
+    }
+
+    pub fn runtime_error(&mut self, error: &str) {
+        self.browser.runtime_error(error);
+    }
 }
 
 impl Host for BrowserHost {
@@ -101,12 +124,20 @@ impl Host for BrowserHost {
         }
     }
 
-    fn exec_trap(&mut self, _node: usize, _ip: usize) {
-        // TODO
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        self.browser.trap(node as u32, ip as u32);
     }
 
     fn exec_print(&mut self, value: isize) {
         let msg = format!("[PRINT] {}", value);
         self.browser.print(&msg);
     }
+
+    fn exec_label(&mut self, text: &str) {
+        self.browser.label(text);
+    }
+
+    fn exec_set_font_size(&mut self, size: isize) {
+        self.browser.set_font_size(size as i32);
+    }
 }
@@ -3,9 +3,18 @@ extern crate tytle;
 use tytle::prelude::*;
 use wasm_bindgen::prelude::*;
 
+mod debugger;
+mod execution;
 mod host;
 use host::BrowserHost;
 
+pub use debugger::DebugSession;
+pub use execution::Execution;
+
+// the maximum number of instructions a program run by `execute` may execute.
+// (so that a never-ending program doesn't freeze the page)
+const EXECUTE_FUEL: usize = 10_000_000;
+
 #[wasm_bindgen]
 extern "C" {
     fn compilation_error(error: &str);
@@ -14,28 +23,80 @@ extern "C" {
 #[wasm_bindgen]
 pub fn execute(code: &str) {
     let mut host = BrowserHost::new();
-    let parse_res = TytleParser.parse(code);
 
-    if let Err(err) = parse_res {
-        host.compilation_error(&err.to_string());
+    let compiled = compile(code, &mut host);
+    if compiled.is_none() {
         return;
     }
 
-    let mut ast = parse_res.unwrap();
-    let generator = SymbolTableGenerator::new();
-    let mut env = generator.generate(&mut ast).unwrap();
-    let mut type_checker = AstTypeCheck::new(&mut env);
+    let (env, cfg) = compiled.unwrap();
 
-    let type_res = type_checker.check(&mut ast);
-    if let Err(err) = type_res {
-        host.compilation_error(&err.to_string());
-        return;
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    intr.set_fuel(EXECUTE_FUEL);
+
+    let res = intr.exec_code();
+    drop(intr);
+
+    if let Err(err) = res {
+        host.runtime_error(&runtime_error_str(&err));
     }
+}
 
-    let cfg_builder = CfgBuilder::new(&mut env);
-    let cfg = cfg_builder.build(&ast);
+// returns the CFG of `code` as a Graphviz DOT graph (for rendering it on the page).
+// on compilation errors, they're reported and `None` is returned
+#[wasm_bindgen]
+pub fn cfg_dot(code: &str) -> Option<String> {
+    let mut host = BrowserHost::new();
 
-    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    compile(code, &mut host).map(|(env, cfg)| cfg.to_dot(&env))
+}
 
-    let _ = intr.exec_code();
+// returns the syntax-highlighting spans of `code` as JSON (for the editor):
+// `[{"kind": "keyword", "line": 1, "column": 1, "len": 10}]`
+#[wasm_bindgen]
+pub fn highlight_spans(code: &str) -> String {
+    let spans: Vec<String> = highlight(code)
+        .iter()
+        .map(|span| {
+            format!(
+                r#"{{"kind": "{}", "line": {}, "column": {}, "len": {}}}"#,
+                span.kind.name(),
+                span.location.line(),
+                span.location.column(),
+                span.len
+            )
+        })
+        .collect();
+
+    format!("[{}]", spans.join(", "))
+}
+
+fn runtime_error_str(err: &InterpreterException) -> String {
+    match err {
+        InterpreterException::OutOfFuel => {
+            "The program has executed too many instructions (is there a never-ending loop?)"
+                .to_string()
+        }
+        _ => err.to_string(),
+    }
+}
+
+// compiles `code`, each of its compilation errors (and lints warnings) is reported to `host`
+fn compile(code: &str, host: &mut BrowserHost) -> Option<(Environment, CfgObject)> {
+    let (res, warnings) = compile_with_lints(code, &LintConfig::new());
+
+    for warning in warnings.iter() {
+        host.compilation_warning(&warning.to_string());
+    }
+
+    match res {
+        Ok(compiled) => Some(compiled),
+        Err(diagnostics) => {
+            for diag in diagnostics.iter() {
+                host.compilation_error(&diag.to_string());
+            }
+
+            None
+        }
+    }
 }
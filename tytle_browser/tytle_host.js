@@ -1,6 +1,7 @@
 export class TytleHost {
   constructor() {
     this.degree = 0;
+    this.font_size = 14;
     this.setx(400);
     this.sety(800);
     this.pen_down();
@@ -72,10 +73,42 @@ export class TytleHost {
     ctx.clearRect(0, 0, canvas.width, canvas.height);
   }
 
+  label(text) {
+    var canvas = this._getCanvas();
+    var ctx = canvas.getContext("2d");
+    const angle_radian = (this.degree * Math.PI) / 180;
+
+    // the text is drawn along the turtle heading, starting at the turtle position
+    // (`degree = 0` points up, while the canvas text baseline points right)
+    ctx.save();
+    ctx.translate(this.x, this.y);
+    ctx.rotate(-(angle_radian + Math.PI / 2));
+    ctx.font = `${this.font_size}px verdana`;
+    ctx.fillStyle = "#000000";
+    ctx.fillText(text, 0, 0);
+    ctx.restore();
+  }
+
+  set_font_size(size) {
+    this.font_size = size;
+  }
+
+  trap(node, ip) {
+    console.log(`[TRAP] (${node}, ${ip})`);
+  }
+
   compilation_error(msg) {
     console.log("[COMPILE ERROR]", msg);
   }
 
+  compilation_warning(msg) {
+    console.log("[WARNING]", msg);
+  }
+
+  runtime_error(msg) {
+    console.log("[RUNTIME ERROR]", msg);
+  }
+
   _drawLine(x0, y0, x1, y1) {
     var canvas = this._getCanvas();
     var ctx = canvas.getContext("2d");
@@ -0,0 +1,55 @@
+use tytle::ast::statement::{Command, Direction};
+use tytle::vm::{Host, Pen, Turtle};
+
+pub struct ConsoleHost {
+    pen: Pen,
+    turtle: Turtle,
+}
+
+impl ConsoleHost {
+    pub fn new() -> Self {
+        Self {
+            pen: Pen::new(),
+            turtle: Turtle::new(),
+        }
+    }
+}
+
+impl Host for ConsoleHost {
+    fn compilation_error(&mut self, error: &str) {
+        eprintln!("[COMPILE ERROR] {}", error);
+    }
+
+    fn exec_direct(&mut self, direct: &Direction, count: isize) {
+        self.turtle.exec_direct(direct, count);
+    }
+
+    fn exec_cmd(&mut self, cmd: &Command) {
+        match cmd {
+            Command::XCor => println!("XCOR = {}", self.turtle.xcor()),
+            Command::YCor => println!("YCOR = {}", self.turtle.ycor()),
+            Command::ShowTurtle => self.turtle.show(),
+            Command::HideTurtle => self.turtle.hide(),
+            Command::PenUp => self.pen.up(),
+            Command::PenDown => self.pen.down(),
+            Command::PenErase => self.pen.erase(),
+            _ => {
+                // nothing to draw on a console
+            }
+        }
+    }
+
+    fn exec_trap(&mut self, node: usize, ip: usize) {
+        println!("[TRAP] ({}, {})", node, ip);
+    }
+
+    fn exec_print(&mut self, value: isize) {
+        println!("{}", value);
+    }
+
+    fn exec_label(&mut self, text: &str) {
+        println!("[LABEL] {}", text);
+    }
+
+    fn exec_set_font_size(&mut self, _size: isize) {}
+}
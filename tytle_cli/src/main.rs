@@ -0,0 +1,249 @@
+extern crate tytle;
+
+mod host;
+mod repl;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process;
+
+use tytle::prelude::*;
+
+use host::ConsoleHost;
+
+const USAGE: &str = r#"Usage:
+    tytle repl                        starts an interactive session
+    tytle run [-O] <FILE>             compiles and runs a Tytle program, or runs a `.tyb` bytecode file
+                                      (`-O` optimizes the program before running it)
+    tytle build [-O] <FILE>           compiles a Tytle program into a `.tyb` bytecode file
+    tytle ir [-O] <FILE>              compiles a Tytle program and prints its CFG as textual IR
+    tytle dot [-O] <FILE>             compiles a Tytle program and prints its CFG as a Graphviz DOT graph
+    tytle lint [-A <LINT>]... <FILE>  compiles a Tytle program and prints its lints warnings
+                                      (`-A` disables a lint, e.g `-A unused-variable`)
+    tytle symbols <FILE>              compiles a Tytle program and prints its symbols (with their types)
+    tytle profile [--folded] <FILE>   runs a Tytle program and prints its execution profile
+                                      (as JSON, or as folded stacks for flamegraphs)
+    tytle fmt [--check] <FILE>        formats a Tytle program in place
+                                      (`--check` only reports whether it's formatted, failing when it isn't)"#;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(|arg| arg.as_str()) {
+        Some("repl") => repl::run_repl(),
+        Some("run") if args.len() == 2 => run_file(&args[1], false),
+        Some("run") if args.len() == 3 && args[1] == "-O" => run_file(&args[2], true),
+        Some("build") if args.len() == 2 => build_file(&args[1], false),
+        Some("build") if args.len() == 3 && args[1] == "-O" => build_file(&args[2], true),
+        Some("ir") if args.len() == 2 => print_ir(&args[1], false),
+        Some("ir") if args.len() == 3 && args[1] == "-O" => print_ir(&args[2], true),
+        Some("dot") if args.len() == 2 => print_dot(&args[1], false),
+        Some("dot") if args.len() == 3 && args[1] == "-O" => print_dot(&args[2], true),
+        Some("lint") if args.len().is_multiple_of(2) => lint_file(&args[1..]),
+        Some("symbols") if args.len() == 2 => print_symbols(&args[1]),
+        Some("profile") if args.len() == 2 => profile_file(&args[1], false),
+        Some("profile") if args.len() == 3 && args[1] == "--folded" => profile_file(&args[2], true),
+        Some("fmt") if args.len() == 2 => format_file(&args[1], false),
+        Some("fmt") if args.len() == 3 && args[1] == "--check" => format_file(&args[2], true),
+        _ => {
+            eprintln!("{}", USAGE);
+            process::exit(1);
+        }
+    }
+}
+
+fn read_file(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Failed reading `{}`: {}", path, err);
+        process::exit(1);
+    })
+}
+
+fn run_file(path: &str, optimize: bool) {
+    if path.ends_with(".tyb") {
+        return run_bytecode_file(path);
+    }
+
+    let (mut env, mut cfg) = compile_file_with_lints(path, &LintConfig::new());
+
+    if optimize {
+        CfgOptimizer::new().optimize(&mut cfg, &mut env);
+    }
+
+    let mut host = ConsoleHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+
+    if let Err(err) = intr.exec_code() {
+        eprintln!("[RUNTIME ERROR] {}", err.to_string());
+        process::exit(1);
+    }
+}
+
+fn run_bytecode_file(path: &str) {
+    let bytes = fs::read(path).unwrap_or_else(|err| {
+        eprintln!("Failed reading `{}`: {}", path, err);
+        process::exit(1);
+    });
+
+    let program = BytecodeProgram::from_bytes(&bytes).unwrap_or_else(|err| {
+        eprintln!("Failed loading `{}`: {}", path, err.to_string());
+        process::exit(1);
+    });
+
+    let mut host = ConsoleHost::new();
+    let mut vm = BytecodeVm::new(&program, &mut host);
+
+    if let Err(err) = vm.exec_code() {
+        eprintln!("[RUNTIME ERROR] {}", err.to_string());
+        process::exit(1);
+    }
+}
+
+// writes the program bytecode next to the source file (e.g `square.ty` into `square.tyb`)
+fn build_file(path: &str, optimize: bool) {
+    let (mut env, mut cfg) = compile_file(path);
+
+    if optimize {
+        CfgOptimizer::new().optimize(&mut cfg, &mut env);
+    }
+
+    let program = BytecodeBuilder::new(&cfg, &env).build();
+    let out_path = Path::new(path).with_extension("tyb");
+
+    if let Err(err) = fs::write(&out_path, program.to_bytes()) {
+        eprintln!("Failed writing `{}`: {}", out_path.display(), err);
+        process::exit(1);
+    }
+}
+
+fn print_ir(path: &str, optimize: bool) {
+    let (mut env, mut cfg) = compile_file(path);
+
+    if optimize {
+        CfgOptimizer::new().optimize(&mut cfg, &mut env);
+    }
+
+    print!("{}", CfgPrinter::with_env(&env).print_object(&cfg));
+}
+
+fn print_dot(path: &str, optimize: bool) {
+    let (mut env, mut cfg) = compile_file(path);
+
+    if optimize {
+        CfgOptimizer::new().optimize(&mut cfg, &mut env);
+    }
+
+    print!("{}", cfg.to_dot(&env));
+}
+
+// `args` are the `-A <LINT>` pairs followed by the file path
+fn lint_file(args: &[String]) {
+    let (path, allowed) = args.split_last().unwrap();
+    let mut config = LintConfig::new();
+
+    for pair in allowed.chunks(2) {
+        let kind = match (pair[0].as_str(), LintKind::parse(&pair[1])) {
+            ("-A", Some(kind)) => kind,
+            _ => {
+                eprintln!("{}", USAGE);
+                process::exit(1);
+            }
+        };
+
+        config.disable(kind);
+    }
+
+    compile_file_with_lints(path, &config);
+}
+
+fn print_symbols(path: &str) {
+    let (env, _cfg) = compile_file(path);
+
+    print!("{}", env.symbol_table.dump());
+}
+
+fn profile_file(path: &str, folded: bool) {
+    let (env, cfg) = compile_file(path);
+    let mut host = ConsoleHost::new();
+
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    intr.enable_tracing();
+
+    // a program failing at runtime still has a meaningful profile
+    let res = intr.exec_code();
+    let trace = intr.take_trace().unwrap();
+
+    if let Err(err) = res {
+        eprintln!("[RUNTIME ERROR] {}", err.to_string());
+    }
+
+    if trace.is_truncated() {
+        eprintln!(
+            "[WARNING] the profile covers only the first {} instructions ({} more weren't traced)",
+            trace.len(),
+            trace.get_dropped()
+        );
+    }
+
+    let profile = Profile::new(&trace, &cfg, &env);
+
+    if folded {
+        print!("{}", profile.to_folded());
+    } else {
+        println!("{}", profile.to_json());
+    }
+}
+
+fn format_file(path: &str, check: bool) {
+    let code = read_file(path);
+
+    let formatted = format_code(&code).unwrap_or_else(|diagnostics| {
+        let mut host = ConsoleHost::new();
+
+        for diag in diagnostics.iter() {
+            host.compilation_error(&diag.to_string());
+        }
+
+        process::exit(1);
+    });
+
+    if formatted == code {
+        return;
+    }
+
+    if check {
+        eprintln!("`{}` isn't formatted", path);
+        process::exit(1);
+    }
+
+    if let Err(err) = fs::write(path, formatted) {
+        eprintln!("Failed writing `{}`: {}", path, err);
+        process::exit(1);
+    }
+}
+
+// compiles the file at `path`, exits after reporting all of its compilation errors (if any)
+fn compile_file(path: &str) -> (Environment, CfgObject) {
+    compile_file_with_lints(path, &LintConfig::none())
+}
+
+// same as `compile_file`, the warnings of the lints enabled by `config` are reported too
+fn compile_file_with_lints(path: &str, config: &LintConfig) -> (Environment, CfgObject) {
+    let code = read_file(path);
+    let (res, warnings) = compile_with_lints(&code, config);
+
+    for warning in warnings.iter() {
+        eprintln!("[WARNING] {}", warning.to_string());
+    }
+
+    res.unwrap_or_else(|diagnostics| {
+        let mut host = ConsoleHost::new();
+
+        for diag in diagnostics.iter() {
+            host.compilation_error(&diag.to_string());
+        }
+
+        process::exit(1);
+    })
+}
@@ -0,0 +1,52 @@
+use std::io::{self, BufRead, Write};
+
+use tytle::repl::{ReplError, ReplSession};
+use tytle::vm::Host;
+
+use crate::host::ConsoleHost;
+
+const PROMPT: &str = "tytle> ";
+const CONTINUATION_PROMPT: &str = "  ...> ";
+
+pub fn run_repl() {
+    let mut session = ReplSession::new();
+    let mut host = ConsoleHost::new();
+    let mut buffer = String::new();
+
+    let stdin = io::stdin();
+
+    print_prompt(PROMPT);
+
+    for line in stdin.lock().lines() {
+        let line = line.expect("failed reading from stdin");
+
+        buffer.push_str(&line);
+        buffer.push('\n');
+
+        // we keep reading lines until all the `TO ... END` and `[ ... ]` blocks are closed
+        if !ReplSession::is_complete(&buffer) {
+            print_prompt(CONTINUATION_PROMPT);
+            continue;
+        }
+
+        if !buffer.trim().is_empty() {
+            match session.eval(&buffer, &mut host) {
+                Ok(_) => {}
+                Err(ReplError::Interpreter(err)) => {
+                    eprintln!("[RUNTIME ERROR] {}", err.to_string());
+                }
+                Err(err) => host.compilation_error(&err.to_string()),
+            }
+        }
+
+        buffer.clear();
+        print_prompt(PROMPT);
+    }
+
+    println!();
+}
+
+fn print_prompt(prompt: &str) {
+    print!("{}", prompt);
+    io::stdout().flush().unwrap();
+}
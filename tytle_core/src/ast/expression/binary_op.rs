@@ -1,4 +1,6 @@
 use crate::lexer::Token;
+use crate::parser::ParseError;
+use std::convert::TryFrom;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BinaryOp {
@@ -11,32 +13,36 @@ pub enum BinaryOp {
     LessThan,
 }
 
-impl From<&str> for BinaryOp {
-    fn from(tok: &str) -> BinaryOp {
+impl TryFrom<&str> for BinaryOp {
+    type Error = ParseError;
+
+    fn try_from(tok: &str) -> Result<BinaryOp, Self::Error> {
         match tok {
-            "AND" => BinaryOp::And,
-            "OR" => BinaryOp::Or,
-            "+" => BinaryOp::Add,
-            "*" => BinaryOp::Mul,
-            "/" => BinaryOp::Div,
-            ">" => BinaryOp::GreaterThan,
-            "<" => BinaryOp::LessThan,
-            _ => panic!("Invalid binary operator: `{:?}`", tok),
+            "AND" => Ok(BinaryOp::And),
+            "OR" => Ok(BinaryOp::Or),
+            "+" => Ok(BinaryOp::Add),
+            "*" => Ok(BinaryOp::Mul),
+            "/" => Ok(BinaryOp::Div),
+            ">" => Ok(BinaryOp::GreaterThan),
+            "<" => Ok(BinaryOp::LessThan),
+            _ => Err(ParseError::InvalidBinaryOp(tok.to_string())),
         }
     }
 }
 
-impl From<&Token> for BinaryOp {
-    fn from(tok: &Token) -> BinaryOp {
+impl TryFrom<&Token> for BinaryOp {
+    type Error = ParseError;
+
+    fn try_from(tok: &Token) -> Result<BinaryOp, Self::Error> {
         match *tok {
-            Token::AND => BinaryOp::And,
-            Token::OR => BinaryOp::Or,
-            Token::ADD => BinaryOp::Add,
-            Token::MUL => BinaryOp::Mul,
-            Token::DIV => BinaryOp::Div,
-            Token::GT => BinaryOp::GreaterThan,
-            Token::LT => BinaryOp::LessThan,
-            _ => panic!("Invalid binary operator: `{:?}`", tok),
+            Token::AND => Ok(BinaryOp::And),
+            Token::OR => Ok(BinaryOp::Or),
+            Token::ADD => Ok(BinaryOp::Add),
+            Token::MUL => Ok(BinaryOp::Mul),
+            Token::DIV => Ok(BinaryOp::Div),
+            Token::GT => Ok(BinaryOp::GreaterThan),
+            Token::LT => Ok(BinaryOp::LessThan),
+            _ => Err(ParseError::InvalidBinaryOp(tok.to_string())),
         }
     }
 }
@@ -63,50 +69,62 @@ mod tests {
 
     #[test]
     fn binary_op_add() {
-        assert_eq!(BinaryOp::from("+"), BinaryOp::Add);
-        assert_eq!(BinaryOp::from(&Token::ADD), BinaryOp::Add);
+        assert_eq!(Ok(BinaryOp::Add), BinaryOp::try_from("+"));
+        assert_eq!(Ok(BinaryOp::Add), BinaryOp::try_from(&Token::ADD));
         assert_eq!("+", BinaryOp::Add.to_string());
     }
 
     #[test]
     fn binary_op_mul() {
-        assert_eq!(BinaryOp::from("*"), BinaryOp::Mul);
-        assert_eq!(BinaryOp::from(&Token::MUL), BinaryOp::Mul);
+        assert_eq!(Ok(BinaryOp::Mul), BinaryOp::try_from("*"));
+        assert_eq!(Ok(BinaryOp::Mul), BinaryOp::try_from(&Token::MUL));
         assert_eq!("*", BinaryOp::Mul.to_string());
     }
 
     #[test]
     fn binary_op_div() {
-        assert_eq!(BinaryOp::from("/"), BinaryOp::Div);
-        assert_eq!(BinaryOp::from(&Token::DIV), BinaryOp::Div);
+        assert_eq!(Ok(BinaryOp::Div), BinaryOp::try_from("/"));
+        assert_eq!(Ok(BinaryOp::Div), BinaryOp::try_from(&Token::DIV));
         assert_eq!("/", BinaryOp::Div.to_string());
     }
 
     #[test]
     fn binary_op_gt() {
-        assert_eq!(BinaryOp::from(">"), BinaryOp::GreaterThan);
-        assert_eq!(BinaryOp::from(&Token::GT), BinaryOp::GreaterThan);
+        assert_eq!(Ok(BinaryOp::GreaterThan), BinaryOp::try_from(">"));
+        assert_eq!(Ok(BinaryOp::GreaterThan), BinaryOp::try_from(&Token::GT));
         assert_eq!(">", BinaryOp::GreaterThan.to_string());
     }
 
     #[test]
     fn binary_op_lt() {
-        assert_eq!(BinaryOp::from("<"), BinaryOp::LessThan);
-        assert_eq!(BinaryOp::from(&Token::LT), BinaryOp::LessThan);
+        assert_eq!(Ok(BinaryOp::LessThan), BinaryOp::try_from("<"));
+        assert_eq!(Ok(BinaryOp::LessThan), BinaryOp::try_from(&Token::LT));
         assert_eq!("<", BinaryOp::LessThan.to_string());
     }
 
     #[test]
     fn binary_op_and() {
-        assert_eq!(BinaryOp::from("AND"), BinaryOp::And);
-        assert_eq!(BinaryOp::from(&Token::AND), BinaryOp::And);
+        assert_eq!(Ok(BinaryOp::And), BinaryOp::try_from("AND"));
+        assert_eq!(Ok(BinaryOp::And), BinaryOp::try_from(&Token::AND));
         assert_eq!("AND", BinaryOp::And.to_string());
     }
 
+    #[test]
+    fn binary_op_invalid() {
+        assert_eq!(
+            Err(ParseError::InvalidBinaryOp("%".to_string())),
+            BinaryOp::try_from("%")
+        );
+        assert_eq!(
+            Err(ParseError::InvalidBinaryOp(",".to_string())),
+            BinaryOp::try_from(&Token::COMMA)
+        );
+    }
+
     #[test]
     fn binary_op_or() {
-        assert_eq!(BinaryOp::from("OR"), BinaryOp::Or);
-        assert_eq!(BinaryOp::from(&Token::OR), BinaryOp::Or);
+        assert_eq!(Ok(BinaryOp::Or), BinaryOp::try_from("OR"));
+        assert_eq!(Ok(BinaryOp::Or), BinaryOp::try_from(&Token::OR));
         assert_eq!("OR", BinaryOp::Or.to_string());
     }
 }
@@ -80,6 +80,26 @@ impl Expression {
         }
     }
 
+    pub fn as_indirect_call_expr(&self) -> (&Expression, &Vec<Expression>) {
+        match &self.expr_ast {
+            ExpressionAst::IndirectCall(proc_expr, proc_args_exprs) => (proc_expr, proc_args_exprs),
+            _ => panic!(
+                "expected an indirect procedure call expression. got: `{:?}`",
+                self.expr_ast
+            ),
+        }
+    }
+
+    pub fn as_named_arg_expr(&self) -> (&String, &Expression) {
+        match &self.expr_ast {
+            ExpressionAst::NamedArg(param_name, arg_expr) => (param_name, arg_expr),
+            _ => panic!(
+                "expected a named argument expression. got: `{:?}`",
+                self.expr_ast
+            ),
+        }
+    }
+
     pub fn as_proc_call_expr(&self) -> (&String, &Vec<Expression>, Option<&SymbolId>) {
         match &self.expr_ast {
             ExpressionAst::ProcCall(proc_name, proc_args_exprs, proc_id) => {
@@ -91,4 +111,23 @@ impl Expression {
             ),
         }
     }
+
+    pub fn as_new_struct_expr(&self) -> (&String, &Vec<Expression>, Option<&SymbolId>) {
+        match &self.expr_ast {
+            ExpressionAst::NewStruct(struct_name, fields_args, struct_id) => {
+                (struct_name, fields_args, struct_id.as_ref())
+            }
+            _ => panic!(
+                "expected a struct construction expression. got: `{:?}`",
+                self.expr_ast
+            ),
+        }
+    }
+
+    pub fn as_field_expr(&self) -> (&Expression, &String) {
+        match &self.expr_ast {
+            ExpressionAst::Field(record_expr, field_name) => (record_expr, field_name),
+            _ => panic!("expected a field expression. got: `{:?}`", self.expr_ast),
+        }
+    }
 }
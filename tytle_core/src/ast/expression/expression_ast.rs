@@ -5,6 +5,19 @@ use crate::ast::semantic::SymbolId;
 pub enum ExpressionAst {
     Literal(LiteralExpr),
     ProcCall(String, Vec<Expression>, Option<SymbolId>),
+
+    // calls the procedure value of the first expression (e.g `APPLY(F, 10)`)
+    IndirectCall(Box<Expression>, Vec<Expression>),
+
+    // a procedure call argument given by the parameter name (e.g `SQUARE(SIZE: 80)`)
+    NamedArg(String, Box<Expression>),
+
+    // constructs a record of the struct type (e.g `POINT(X: 10, Y: 20)`)
+    NewStruct(String, Vec<Expression>, Option<SymbolId>),
+
+    // reads a record field (e.g `P.X`)
+    Field(Box<Expression>, String),
+
     Binary(BinaryOp, Box<Expression>, Box<Expression>),
     Parentheses(Box<Expression>),
     Not(Box<Expression>),
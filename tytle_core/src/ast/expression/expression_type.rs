@@ -6,6 +6,12 @@ pub enum ExpressionType {
     Str,
     Bool,
     Unit,
+
+    // a procedure reference: the procedure params types and its return type
+    Proc(Vec<ExpressionType>, Box<ExpressionType>),
+
+    // a record of a user-defined `STRUCT` type (e.g `POINT`)
+    Struct(String),
 }
 
 impl From<&str> for ExpressionType {
@@ -15,6 +21,8 @@ impl From<&str> for ExpressionType {
             "STR" => ExpressionType::Str,
             "BOOL" => ExpressionType::Bool,
             "" | "UNIT" => ExpressionType::Unit,
+            _ if type_str.starts_with("PROC(") => proc_type_from(type_str),
+            _ if is_struct_name(type_str) => ExpressionType::Struct(type_str.to_string()),
             _ => panic!(format!(
                 "Can't convert string `{}` to an expression type",
                 type_str
@@ -23,15 +31,83 @@ impl From<&str> for ExpressionType {
     }
 }
 
+// whether the struct exists is checked later, by the `SymbolTableGenerator`
+fn is_struct_name(type_str: &str) -> bool {
+    match type_str.chars().next() {
+        Some(first) if first.is_ascii_uppercase() => type_str
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_'),
+        _ => false,
+    }
+}
+
+// for example: `PROC(INT, PROC(STR)): BOOL` (a procedure returning `UNIT` has no `: UNIT` suffix)
+fn proc_type_from(type_str: &str) -> ExpressionType {
+    let mut depth = 0;
+    let mut params = Vec::new();
+    let mut param_start = "PROC(".len();
+    let mut params_end = None;
+
+    for (i, c) in type_str.char_indices().skip(param_start) {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ')' | ',' if depth == 0 => {
+                let param = type_str[param_start..i].trim();
+
+                if !param.is_empty() {
+                    params.push(ExpressionType::from(param));
+                }
+
+                param_start = i + 1;
+
+                if c == ')' {
+                    params_end = Some(i);
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let params_end = params_end
+        .unwrap_or_else(|| panic!("Can't convert string `{}` to an expression type", type_str));
+
+    let return_type = type_str[params_end + 1..].trim_start_matches(':').trim();
+
+    ExpressionType::Proc(params, Box::new(ExpressionType::from(return_type)))
+}
+
+impl ExpressionType {
+    // the type name as written in code (e.g `INT` or `PROC(INT): BOOL`)
+    pub fn type_name(&self) -> String {
+        match *self {
+            ExpressionType::Int => "INT".to_string(),
+            ExpressionType::Str => "STR".to_string(),
+            ExpressionType::Bool => "BOOL".to_string(),
+            ExpressionType::Unit => "UNIT".to_string(),
+            ExpressionType::Struct(ref name) => name.clone(),
+            ExpressionType::Proc(ref params_types, ref return_type) => {
+                let params: Vec<String> = params_types
+                    .iter()
+                    .map(|param_type| param_type.type_name())
+                    .collect();
+
+                match **return_type {
+                    ExpressionType::Unit => format!("PROC({})", params.join(", ")),
+                    _ => format!("PROC({}): {}", params.join(", "), return_type.type_name()),
+                }
+            }
+        }
+    }
+}
+
 impl From<&BinaryOp> for ExpressionType {
     fn from(bin_op: &BinaryOp) -> ExpressionType {
         match bin_op {
             BinaryOp::Add | BinaryOp::Mul | BinaryOp::Div => ExpressionType::Int,
             BinaryOp::GreaterThan | BinaryOp::LessThan => ExpressionType::Bool,
-            _ => panic!(format!(
-                "Can't convert binary operator `{:?}` to an expression type",
-                bin_op
-            )),
+            BinaryOp::And | BinaryOp::Or => ExpressionType::Bool,
         }
     }
 }
@@ -43,6 +119,7 @@ impl ToString for ExpressionType {
             ExpressionType::Str => "String",
             ExpressionType::Bool => "Boolean",
             ExpressionType::Unit => "()",
+            ExpressionType::Proc(..) | ExpressionType::Struct(_) => return self.type_name(),
         };
 
         s.to_string()
@@ -74,6 +151,25 @@ mod tests {
         assert_eq!(ExpressionType::from("UNIT"), ExpressionType::Unit);
     }
 
+    #[test]
+    fn proc_to_expr_type() {
+        let expected = ExpressionType::Proc(
+            vec![ExpressionType::Int, ExpressionType::Str],
+            Box::new(ExpressionType::Bool),
+        );
+
+        assert_eq!(ExpressionType::from("PROC(INT, STR): BOOL"), expected);
+        assert_eq!("PROC(INT, STR): BOOL", expected.to_string());
+    }
+
+    #[test]
+    fn struct_to_expr_type() {
+        let expected = ExpressionType::Struct("POINT".to_string());
+
+        assert_eq!(ExpressionType::from("POINT"), expected);
+        assert_eq!("POINT", expected.to_string());
+    }
+
     #[test]
     #[should_panic(expected = "Can't convert string `str` to an expression type")]
     fn invalid_str_to_expr_type_should_panic() {
@@ -111,6 +207,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn binary_op_and_or_to_expr_type_bool() {
+        assert_eq!(ExpressionType::from(&BinaryOp::And), ExpressionType::Bool);
+        assert_eq!(ExpressionType::from(&BinaryOp::Or), ExpressionType::Bool);
+    }
+
     #[test]
     fn expr_type_int_to_str() {
         assert_eq!("Integer", ExpressionType::Int.to_string());
@@ -126,6 +228,29 @@ mod tests {
         assert_eq!("Boolean", ExpressionType::Bool.to_string());
     }
 
+    #[test]
+    fn expr_type_name_to_expr_type() {
+        for expr_type in &[
+            ExpressionType::Int,
+            ExpressionType::Str,
+            ExpressionType::Bool,
+            ExpressionType::Unit,
+            ExpressionType::Proc(vec![], Box::new(ExpressionType::Unit)),
+            ExpressionType::Proc(
+                vec![
+                    ExpressionType::Int,
+                    ExpressionType::Proc(vec![ExpressionType::Str], Box::new(ExpressionType::Unit)),
+                ],
+                Box::new(ExpressionType::Bool),
+            ),
+        ] {
+            assert_eq!(
+                *expr_type,
+                ExpressionType::from(expr_type.type_name().as_str())
+            );
+        }
+    }
+
     #[test]
     fn expr_type_unit_to_str() {
         assert_eq!("()", ExpressionType::Unit.to_string());
@@ -6,4 +6,7 @@ pub enum LiteralExpr {
     Int(usize),
     Str(String),
     Var(String, Option<SymbolId>),
+
+    // a procedure reference (a variable name which turns out to be a procedure name)
+    Proc(String, Option<SymbolId>),
 }
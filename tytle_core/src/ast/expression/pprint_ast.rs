@@ -1,26 +1,41 @@
 use crate::ast::expression::*;
 use crate::ast::statement::*;
 use crate::ast::Ast;
+use crate::lexer::{Comment, Location};
 
+// prints an AST as canonical Tytle source code.
+// blocks are indented by 2 spaces, procedures are separated by blank lines,
+// and a single blank line is kept wherever the source has blank lines between statements.
+// the comments (see `pprint_ast_with_comments`) are placed according to their source location
 pub struct PrettyPrintAst;
 
 impl PrettyPrintAst {
     pub fn pprint_ast(ast: &Ast) -> String {
-        let mut buffer = Vec::<String>::new();
+        Self::pprint_ast_with_comments(ast, &[])
+    }
+
+    // `comments` are expected to be ordered by their location (as returned by the lexer)
+    pub fn pprint_ast_with_comments(ast: &Ast, comments: &[Comment]) -> String {
+        let mut printer = StmtPrinter::new(comments);
+
+        printer.pp_stmts(&ast.statements, &ast.locations, None);
+        printer.pp_comments_before(usize::MAX);
+
+        let mut code = printer.lines.join("\n");
 
-        for stmt in &ast.statements {
-            Self::do_pprint_stmt(&mut buffer, stmt);
+        if !code.is_empty() {
+            code.push('\n');
         }
 
-        buffer.join("")
+        code
     }
 
     pub fn pprint_stmt(stmt: &Statement) -> String {
-        let mut buffer = Vec::<String>::new();
+        let mut printer = StmtPrinter::new(&[]);
 
-        Self::do_pprint_stmt(&mut buffer, stmt);
+        printer.pp_stmt(stmt, None);
 
-        buffer.join("")
+        printer.lines.join("\n")
     }
 
     pub fn pprint_expr(expr: &Expression) -> String {
@@ -31,26 +46,15 @@ impl PrettyPrintAst {
         buffer.join("")
     }
 
-    fn do_pprint_stmt(buffer: &mut Vec<String>, stmt: &Statement) {
-        match stmt {
-            Statement::NOP | Statement::EOF => return,
-            Statement::Command(cmd) => Self::pp_command(buffer, cmd),
-            Statement::Direction(direct_stmt) => Self::pp_direction_stmt(buffer, direct_stmt),
-            Statement::Make(make_stmt) => Self::pp_make_stmt(buffer, make_stmt),
-            Statement::If(if_stmt) => Self::pp_if_stmt(buffer, if_stmt),
-            Statement::Repeat(repeat_stmt) => Self::pp_repeat_stmt(buffer, repeat_stmt),
-            Statement::Procedure(proc_stmt) => Self::pp_proc_stmt(buffer, proc_stmt),
-            Statement::Return(ret_stmt) => Self::pp_ret_stmt(buffer, ret_stmt),
-            Statement::Expression(expr) => Self::do_pprint_expr(buffer, expr),
-            _ => unimplemented!(),
-        };
-    }
-
     fn do_pprint_expr(buffer: &mut Vec<String>, expr: &Expression) {
         match expr.expr_ast {
             ExpressionAst::Literal(ref lit_expr) => Self::pp_lit_expr(buffer, &lit_expr),
             ExpressionAst::Binary(_, _, _) => Self::pp_binary_expr(buffer, expr),
             ExpressionAst::ProcCall(_, _, _) => Self::pp_proc_call_expr(buffer, expr),
+            ExpressionAst::IndirectCall(_, _) => Self::pp_indirect_call_expr(buffer, expr),
+            ExpressionAst::NamedArg(_, _) => Self::pp_named_arg_expr(buffer, expr),
+            ExpressionAst::NewStruct(_, _, _) => Self::pp_new_struct_expr(buffer, expr),
+            ExpressionAst::Field(_, _) => Self::pp_field_expr(buffer, expr),
             ExpressionAst::Not(_) => Self::pp_not_expr(buffer, expr),
             ExpressionAst::Parentheses(_) => Self::pp_parentheses_expr(buffer, expr),
         };
@@ -62,7 +66,7 @@ impl PrettyPrintAst {
             LiteralExpr::Bool(false) => buffer.push("FALSE".to_string()),
             LiteralExpr::Int(num) => buffer.push(num.to_string()),
             LiteralExpr::Str(s) => buffer.push(format!("\"{}\"", s)),
-            LiteralExpr::Var(v, _id) => buffer.push(v.clone()),
+            LiteralExpr::Var(v, _id) | LiteralExpr::Proc(v, _id) => buffer.push(v.clone()),
         }
     }
 
@@ -85,17 +89,55 @@ impl PrettyPrintAst {
     fn pp_proc_call_expr(buffer: &mut Vec<String>, proc_call_expr: &Expression) {
         let (proc_name, proc_args, _proc_id) = proc_call_expr.as_proc_call_expr();
 
-        buffer.push(format!("{}(", proc_name));
+        Self::pp_call(buffer, proc_name, proc_args);
+    }
 
-        let mut i = 0;
-        while i < proc_args.len() - 1 {
-            Self::do_pprint_expr(buffer, &proc_args[i]);
-            buffer.push(", ".to_string());
+    // a record construction looks like a procedure call (e.g `POINT(X: 10, Y: 20)`)
+    fn pp_new_struct_expr(buffer: &mut Vec<String>, new_struct_expr: &Expression) {
+        let (struct_name, fields_args, _struct_id) = new_struct_expr.as_new_struct_expr();
 
-            i += 1
+        Self::pp_call(buffer, struct_name, fields_args);
+    }
+
+    fn pp_call(buffer: &mut Vec<String>, callee: &str, args: &[Expression]) {
+        buffer.push(format!("{}(", callee));
+
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                buffer.push(", ".to_string());
+            }
+
+            Self::do_pprint_expr(buffer, arg);
         }
 
-        Self::do_pprint_expr(buffer, &proc_args[i]);
+        buffer.push(")".to_string());
+    }
+
+    fn pp_field_expr(buffer: &mut Vec<String>, field_expr: &Expression) {
+        let (record_expr, field_name) = field_expr.as_field_expr();
+
+        Self::do_pprint_expr(buffer, record_expr);
+        buffer.push(format!(".{}", field_name));
+    }
+
+    fn pp_named_arg_expr(buffer: &mut Vec<String>, named_arg_expr: &Expression) {
+        let (param_name, arg_expr) = named_arg_expr.as_named_arg_expr();
+
+        buffer.push(format!("{}: ", param_name));
+        Self::do_pprint_expr(buffer, arg_expr);
+    }
+
+    // printed as `APPLY(F, ARG1, ARG2)`
+    fn pp_indirect_call_expr(buffer: &mut Vec<String>, indirect_call_expr: &Expression) {
+        let (proc_expr, proc_args) = indirect_call_expr.as_indirect_call_expr();
+
+        buffer.push("APPLY(".to_string());
+        Self::do_pprint_expr(buffer, proc_expr);
+
+        for proc_arg in proc_args {
+            buffer.push(", ".to_string());
+            Self::do_pprint_expr(buffer, proc_arg);
+        }
 
         buffer.push(")".to_string());
     }
@@ -116,63 +158,308 @@ impl PrettyPrintAst {
             BinaryOp::Mul => " * ",
             BinaryOp::GreaterThan => " > ",
             BinaryOp::LessThan => " < ",
-            _ => unimplemented!(),
+            BinaryOp::Div => " / ",
         };
 
         buffer.push(s.to_string());
     }
+}
+
+struct StmtPrinter<'a> {
+    lines: Vec<String>,
+    indent: usize,
+
+    comments: &'a [Comment],
+    next_comment: usize,
+
+    // the source line the last printed line originates from (when known)
+    last_printed_line: Option<usize>,
+
+    // the last source line of the previous statement (or comment) of the current block
+    last_line: Option<usize>,
+    last_was_proc: bool,
+    last_was_comment: bool,
+    block_start: bool,
+}
+
+impl<'a> StmtPrinter<'a> {
+    fn new(comments: &'a [Comment]) -> Self {
+        Self {
+            lines: Vec::new(),
+            indent: 0,
+            comments,
+            next_comment: 0,
+            last_printed_line: None,
+            last_line: None,
+            last_was_proc: false,
+            last_was_comment: false,
+            block_start: true,
+        }
+    }
 
-    fn pp_command(_buffer: &mut Vec<String>, _cmd: &Command) {
-        unimplemented!()
-        // TODO: command into string
+    fn pp_stmts(
+        &mut self,
+        stmts: &[Statement],
+        locations: &[Location],
+        end_location: Option<Location>,
+    ) {
+        for (i, stmt) in stmts.iter().enumerate() {
+            if let Statement::NOP | Statement::EOF = stmt {
+                continue;
+            }
+
+            let location = locations.get(i).cloned();
+            let is_proc = Self::is_proc(stmt);
+
+            if let Some(loc) = location {
+                self.pp_comments_before(loc.line());
+            }
+
+            self.pp_separator(location.map(|loc| loc.line()), is_proc);
+            self.pp_stmt(stmt, location);
+
+            self.last_line = Self::stmt_end_line(stmt, location);
+            self.last_was_proc = is_proc;
+            self.last_was_comment = false;
+        }
+
+        if let Some(loc) = end_location {
+            self.pp_comments_before(loc.line());
+        }
     }
 
-    fn pp_direction_stmt(_buffer: &mut Vec<String>, _direct_stmt: &DirectionStmt) {
-        unimplemented!()
-        // TODO: direction into string
+    fn pp_stmt(&mut self, stmt: &Statement, location: Option<Location>) {
+        let line = location.map(|loc| loc.line());
+
+        match stmt {
+            Statement::NOP | Statement::EOF => (),
+            Statement::If(if_stmt) => {
+                let cond = PrettyPrintAst::pprint_expr(&if_stmt.cond_expr);
+
+                self.push_line(format!("IF {} [", cond), line);
+                let is_empty = self.pp_block(&if_stmt.true_block);
+
+                if let Some(ref false_block) = if_stmt.false_block {
+                    self.pp_block_end("][", &if_stmt.true_block, is_empty);
+
+                    let is_empty = self.pp_block(false_block);
+                    self.pp_block_end("]", false_block, is_empty);
+                } else {
+                    self.pp_block_end("]", &if_stmt.true_block, is_empty);
+                }
+            }
+            Statement::Repeat(repeat_stmt) => {
+                let count = PrettyPrintAst::pprint_expr(&repeat_stmt.count_expr);
+
+                self.push_line(format!("REPEAT {} [", count), line);
+                let is_empty = self.pp_block(&repeat_stmt.block);
+                self.pp_block_end("]", &repeat_stmt.block, is_empty);
+            }
+            Statement::Procedure(proc_stmt) => {
+                self.push_line(Self::pp_proc_signature(proc_stmt), line);
+                self.pp_block(&proc_stmt.block);
+
+                // `END` is always on a line of its own (a procedure signature must end its line)
+                let end_line = proc_stmt.block.end_location.map(|loc| loc.line());
+                self.push_line("END".to_string(), end_line);
+            }
+            _ => self.push_line(Self::pp_simple_stmt(stmt), line),
+        }
     }
 
-    fn pp_make_stmt(buffer: &mut Vec<String>, make_stmt: &MakeStmt) {
-        let kind_str = match make_stmt.kind {
-            MakeStmtKind::Global => "MAKEGLOBAL ",
-            MakeStmtKind::Local => "MAKELOCAL ",
-            MakeStmtKind::Assign => "MAKE ",
-        };
+    // returns whether nothing has been printed for the block (no statements nor comments)
+    fn pp_block(&mut self, block: &BlockStatement) -> bool {
+        let lines_count = self.lines.len();
+        let next_comment = self.next_comment;
+
+        self.indent += 1;
+        self.block_start = true;
+
+        self.pp_stmts(&block.stmts, &block.locations, block.end_location);
+
+        self.indent -= 1;
+        self.block_start = false;
+
+        self.lines.len() == lines_count && self.next_comment == next_comment
+    }
+
+    // an empty block is closed on the line opening it (e.g `REPEAT 4 []`)
+    fn pp_block_end(&mut self, closing: &str, block: &BlockStatement, is_empty: bool) {
+        let end_line = block.end_location.map(|loc| loc.line());
+
+        if is_empty {
+            self.lines.last_mut().unwrap().push_str(closing);
+            self.last_printed_line = end_line;
+        } else {
+            self.push_line(closing.to_string(), end_line);
+        }
+    }
+
+    fn pp_simple_stmt(stmt: &Statement) -> String {
+        let expr_str = PrettyPrintAst::pprint_expr;
 
-        buffer.push(format!("{} {} = ", kind_str, make_stmt.var_name));
-        Self::do_pprint_expr(buffer, &make_stmt.expr)
+        match stmt {
+            Statement::Expression(expr) => expr_str(expr),
+            Statement::Print(expr) => format!("PRINT {}", expr_str(expr)),
+            Statement::Label(expr) => format!("LABEL {}", expr_str(expr)),
+            Statement::SetFontSize(expr) => format!("SETFONTSIZE {}", expr_str(expr)),
+            Statement::Command(cmd) => cmd.as_str().to_string(),
+            Statement::Direction(direct_stmt) => format!(
+                "{} {}",
+                direct_stmt.direction.as_str(),
+                expr_str(&direct_stmt.expr)
+            ),
+            Statement::Make(make_stmt) => {
+                let kind_str = match make_stmt.kind {
+                    MakeStmtKind::Global => "MAKEGLOBAL",
+                    MakeStmtKind::Local => "MAKELOCAL",
+                    MakeStmtKind::Assign => "MAKE",
+                };
+
+                let var_type = match make_stmt.var_type {
+                    Some(ref var_type) => format!(": {}", var_type),
+                    None => "".to_string(),
+                };
+
+                format!(
+                    "{} {}{} = {}",
+                    kind_str,
+                    make_stmt.var_name,
+                    var_type,
+                    expr_str(&make_stmt.expr)
+                )
+            }
+            Statement::SetField(set_field_stmt) => format!(
+                "MAKE {}.{} = {}",
+                expr_str(&set_field_stmt.record_expr),
+                set_field_stmt.field_name,
+                expr_str(&set_field_stmt.expr)
+            ),
+            Statement::Struct(struct_stmt) => {
+                let fields: Vec<String> = struct_stmt
+                    .fields
+                    .iter()
+                    .map(|field| format!("{}: {}", field.field_name, field.field_type))
+                    .collect();
+
+                format!("STRUCT {} [{}]", struct_stmt.name, fields.join(", "))
+            }
+            Statement::Map(map_stmt) => format!(
+                "MAP({}, {})",
+                expr_str(&map_stmt.proc_expr),
+                expr_str(&map_stmt.count_expr)
+            ),
+            // the parser turns `HALT` into a `RETURN` with no expression
+            Statement::Return(ret_stmt) => match ret_stmt.expr {
+                Some(ref expr) => format!("RETURN {}", expr_str(expr)),
+                None => "HALT".to_string(),
+            },
+            _ => unreachable!(),
+        }
     }
 
-    fn pp_if_stmt(buffer: &mut Vec<String>, if_stmt: &IfStmt) {
-        buffer.push("IF ".to_string());
-        Self::do_pprint_expr(buffer, &if_stmt.cond_expr);
+    // `TO NAME(A: INT, B: INT = 10): INT` (a procedure returning nothing has no return type)
+    fn pp_proc_signature(proc_stmt: &ProcedureStmt) -> String {
+        let params: Vec<String> = proc_stmt
+            .params
+            .iter()
+            .map(|param| match param.default_expr {
+                Some(ref expr) => format!(
+                    "{}: {} = {}",
+                    param.param_name,
+                    param.param_type,
+                    PrettyPrintAst::pprint_expr(expr)
+                ),
+                None => format!("{}: {}", param.param_name, param.param_type),
+            })
+            .collect();
+
+        let mut signature = format!("TO {}({})", proc_stmt.name, params.join(", "));
+
+        match proc_stmt.return_type.as_str() {
+            "" | "UNIT" => (),
+            return_type => signature.push_str(&format!(": {}", return_type)),
+        }
 
-        buffer.push("\n".to_string());
-        Self::pp_block_stmt(buffer, &if_stmt.true_block);
+        signature
     }
 
-    fn pp_repeat_stmt(buffer: &mut Vec<String>, repeat_stmt: &RepeatStmt) {
-        buffer.push("REPEAT ".to_string());
-        Self::do_pprint_expr(buffer, &repeat_stmt.count_expr);
+    // prints the comments located before `line`.
+    // a trailing comment stays on the line it follows (when that line has just been printed)
+    fn pp_comments_before(&mut self, line: usize) {
+        while let Some(comment) = self.comments.get(self.next_comment) {
+            let comment_line = comment.location.line();
+
+            if comment_line >= line {
+                break;
+            }
+
+            self.next_comment += 1;
 
-        buffer.push("\n".to_string());
-        Self::pp_block_stmt(buffer, &repeat_stmt.block);
+            let text = format!(";{}", comment.text);
+
+            if comment.trailing && self.last_printed_line == Some(comment_line) {
+                self.lines
+                    .last_mut()
+                    .unwrap()
+                    .push_str(&format!(" {}", text));
+                continue;
+            }
+
+            self.pp_separator(Some(comment_line), false);
+            self.push_line(text, Some(comment_line));
+
+            self.last_line = Some(comment_line);
+            self.last_was_proc = false;
+            self.last_was_comment = true;
+        }
     }
 
-    fn pp_proc_stmt(_buffer: &mut Vec<String>, _proc_stmt: &ProcedureStmt) {
-        unimplemented!()
+    // a blank line separates procedures from their neighbours (a comment right above a procedure goes with it),
+    // and stands for the blank lines found in the source
+    fn pp_separator(&mut self, line: Option<usize>, is_proc: bool) {
+        if self.block_start {
+            self.block_start = false;
+            return;
+        }
+
+        let has_gap = match (self.last_line, line) {
+            (Some(last_line), Some(line)) => line > last_line + 1,
+            _ => false,
+        };
+
+        let around_proc = self.last_was_proc || (is_proc && !self.last_was_comment);
+
+        if has_gap || around_proc {
+            self.lines.push(String::new());
+        }
     }
 
-    fn pp_ret_stmt(_buffer: &mut Vec<String>, _ret_stmt: &ReturnStmt) {
-        unimplemented!()
+    fn push_line(&mut self, code: String, line: Option<usize>) {
+        self.lines
+            .push(format!("{}{}", "  ".repeat(self.indent), code));
+        self.last_printed_line = line;
     }
 
-    fn pp_block_stmt(buffer: &mut Vec<String>, block_stmt: &BlockStatement) {
-        buffer.push("[\n".to_string());
-        for stmt in &block_stmt.stmts {
-            let stmt_str: String = Self::pprint_stmt(stmt);
-            buffer.push(format!("   {}", stmt_str));
+    fn is_proc(stmt: &Statement) -> bool {
+        matches!(stmt, Statement::Procedure(_))
+    }
+
+    // the last source line of a statement (a compound statement ends with its last block)
+    fn stmt_end_line(stmt: &Statement, location: Option<Location>) -> Option<usize> {
+        let last_block = match stmt {
+            Statement::If(if_stmt) => match if_stmt.false_block {
+                Some(ref false_block) => Some(false_block),
+                None => Some(&if_stmt.true_block),
+            },
+            Statement::Repeat(repeat_stmt) => Some(&repeat_stmt.block),
+            Statement::Procedure(proc_stmt) => Some(&proc_stmt.block),
+            _ => None,
+        };
+
+        match last_block.and_then(|block| block.end_location) {
+            Some(end_location) => Some(end_location.line()),
+            None => location.map(|loc| loc.line()),
         }
-        buffer.push("\n]".to_string());
     }
 }
@@ -1,7 +1,9 @@
 #[macro_export]
 macro_rules! direction {
     ($d:ident) => {{
-        $crate::ast::statement::Direction::from(stringify!($d).to_uppercase().as_str())
+        use std::convert::TryFrom;
+
+        $crate::ast::statement::Direction::try_from(stringify!($d).to_uppercase().as_str()).unwrap()
     }};
 }
 
@@ -153,13 +155,14 @@ macro_rules! command_stmt {
 
 #[macro_export]
 macro_rules! __make_stmt {
-    ($kind:expr, $var_name:expr, $expr:expr) => {{
+    ($kind:expr, $var_name:expr, $var_type:expr, $expr:expr) => {{
         use $crate::ast::statement::{MakeStmt, MakeStmtKind, Statement};
 
         Statement::Make(MakeStmt {
             var_id: None,
             kind: $kind,
             var_name: $var_name.to_string(),
+            var_type: $var_type,
             expr: $expr,
         })
     }};
@@ -169,7 +172,16 @@ macro_rules! __make_stmt {
 macro_rules! make_global_stmt {
     ($var_name:expr, $expr:expr) => {{
         use $crate::ast::statement::MakeStmtKind;
-        __make_stmt!(MakeStmtKind::Global, $var_name.to_string(), $expr)
+        __make_stmt!(MakeStmtKind::Global, $var_name.to_string(), None, $expr)
+    }};
+    ($var_name:expr, $var_type:expr, $expr:expr) => {{
+        use $crate::ast::statement::MakeStmtKind;
+        __make_stmt!(
+            MakeStmtKind::Global,
+            $var_name.to_string(),
+            Some($var_type.to_string()),
+            $expr
+        )
     }};
 }
 
@@ -177,7 +189,16 @@ macro_rules! make_global_stmt {
 macro_rules! make_local_stmt {
     ($var_name:expr, $expr:expr) => {{
         use $crate::ast::statement::MakeStmtKind;
-        __make_stmt!(MakeStmtKind::Local, $var_name.to_string(), $expr)
+        __make_stmt!(MakeStmtKind::Local, $var_name.to_string(), None, $expr)
+    }};
+    ($var_name:expr, $var_type:expr, $expr:expr) => {{
+        use $crate::ast::statement::MakeStmtKind;
+        __make_stmt!(
+            MakeStmtKind::Local,
+            $var_name.to_string(),
+            Some($var_type.to_string()),
+            $expr
+        )
     }};
 }
 
@@ -185,7 +206,7 @@ macro_rules! make_local_stmt {
 macro_rules! make_stmt {
     ($var_name:expr, $expr:expr) => {{
         use $crate::ast::statement::MakeStmtKind;
-        __make_stmt!(MakeStmtKind::Assign, $var_name.to_string(), $expr)
+        __make_stmt!(MakeStmtKind::Assign, $var_name.to_string(), None, $expr)
     }};
 }
 
@@ -197,6 +218,22 @@ macro_rules! print_stmt {
     }};
 }
 
+#[macro_export]
+macro_rules! label_stmt {
+    ($expr:expr) => {{
+        use $crate::ast::statement::Statement;
+        Statement::Label($expr)
+    }};
+}
+
+#[macro_export]
+macro_rules! set_font_size_stmt {
+    ($expr:expr) => {{
+        use $crate::ast::statement::Statement;
+        Statement::SetFontSize($expr)
+    }};
+}
+
 #[macro_export]
 macro_rules! with_parentheses {
     ($expr:expr) => {{
@@ -229,8 +266,9 @@ macro_rules! binary_expr {
 
     ($op_str:expr, $lexpr:expr, $rexpr:expr, parens: $parens:expr) => {{
         use $crate::ast::expression::{Expression, ExpressionAst};
+        use std::convert::TryFrom;
 
-        let op = BinaryOp::from($op_str);
+        let op = BinaryOp::try_from($op_str).unwrap();
         let ast = ExpressionAst::Binary(op, $lexpr, $rexpr);
         Expression::adjust_parentheses(ast, $parens)
     }};
@@ -327,6 +365,18 @@ macro_rules! repeat_stmt {
     }};
 }
 
+#[macro_export]
+macro_rules! map_stmt {
+    ($proc:expr, $count:expr) => {{
+        use $crate::ast::statement::{MapStmt, Statement};
+
+        Statement::Map(MapStmt {
+            proc_expr: $proc,
+            count_expr: $count,
+        })
+    }};
+}
+
 #[macro_export]
 macro_rules! ast {
     ($ ($stmt:expr) ,*) => {
@@ -377,6 +427,31 @@ macro_rules! proc_call_expr {
     };
 }
 
+#[macro_export]
+macro_rules! named_arg_expr {
+    ($param_name:expr, $arg:expr) => {{
+        use $crate::ast::expression::{Expression, ExpressionAst};
+
+        let ast = ExpressionAst::NamedArg($param_name.to_string(), Box::new($arg));
+        Expression::new(ast)
+    }};
+}
+
+#[macro_export]
+macro_rules! indirect_call_expr {
+    (proc: $proc_expr:expr, params: [$( $param:expr ),*]) => {
+        {
+            use $crate::ast::expression::{Expression, ExpressionAst};
+
+            let mut params = Vec::<Expression>::new();
+            $( params.push($param); )*
+
+            let ast = ExpressionAst::IndirectCall(Box::new($proc_expr), params);
+            Expression::new(ast)
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! proc_param {
     ($pname:expr, $ptype:expr) => {{
@@ -385,6 +460,60 @@ macro_rules! proc_param {
         ProcParam {
             param_name: $pname.to_string(),
             param_type: $ptype.to_string(),
+            default_expr: None,
         }
     }};
+
+    ($pname:expr, $ptype:expr, default: $default:expr) => {{
+        use $crate::ast::statement::ProcParam;
+
+        ProcParam {
+            param_name: $pname.to_string(),
+            param_type: $ptype.to_string(),
+            default_expr: Some($default),
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! field_expr {
+    ($record:expr, $field_name:expr) => {{
+        use $crate::ast::expression::{Expression, ExpressionAst};
+
+        let ast = ExpressionAst::Field(Box::new($record), $field_name.to_string());
+        Expression::new(ast)
+    }};
+}
+
+#[macro_export]
+macro_rules! set_field_stmt {
+    ($record:expr, $field_name:expr, $expr:expr) => {{
+        use $crate::ast::statement::{SetFieldStmt, Statement};
+
+        Statement::SetField(SetFieldStmt {
+            record_expr: $record,
+            field_name: $field_name.to_string(),
+            expr: $expr,
+        })
+    }};
+}
+
+#[macro_export]
+macro_rules! struct_stmt {
+    (name: $name:expr, fields: [$( ($field_name:expr, $field_type:expr) ),*]) => {
+        {
+            use $crate::ast::statement::{Statement, StructField, StructStmt};
+
+            let mut struct_stmt = StructStmt::new($name.to_string());
+
+            $(
+                struct_stmt.fields.push(StructField {
+                    field_name: $field_name.to_string(),
+                    field_type: $field_type.to_string(),
+                });
+            )*
+
+            Statement::Struct(struct_stmt)
+        }
+    };
 }
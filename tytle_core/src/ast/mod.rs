@@ -4,16 +4,39 @@ pub mod semantic;
 pub mod statement;
 
 use crate::ast::statement::*;
+use crate::lexer::Location;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Ast {
     pub statements: Vec<Statement>,
+
+    // the source location of each statement (filled by the parser).
+    // locations aren't part of the AST equality
+    pub locations: Vec<Location>,
 }
 
 impl Default for Ast {
     fn default() -> Self {
         Self {
             statements: Default::default(),
+            locations: Default::default(),
         }
     }
 }
+
+impl PartialEq for Ast {
+    fn eq(&self, other: &Self) -> bool {
+        self.statements == other.statements
+    }
+}
+
+impl Ast {
+    pub fn add_located_statement(&mut self, stmt: Statement, location: Location) {
+        self.statements.push(stmt);
+        self.locations.push(location);
+    }
+
+    pub fn get_stmt_location(&self, index: usize) -> Option<Location> {
+        self.locations.get(index).cloned()
+    }
+}
@@ -0,0 +1,398 @@
+use crate::ast::semantic::*;
+use crate::ast::Ast;
+use crate::ast::{expression::*, statement::*};
+use crate::compiler::Warning;
+use crate::lexer::Location;
+use std::collections::HashSet;
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum LintKind {
+    UnusedVariable,
+    UnusedParam,
+    UnusedProcedure,
+    ShadowedGlobal,
+    RepeatZero,
+    ConstantCondition,
+}
+
+impl LintKind {
+    pub fn all() -> Vec<LintKind> {
+        vec![
+            LintKind::UnusedVariable,
+            LintKind::UnusedParam,
+            LintKind::UnusedProcedure,
+            LintKind::ShadowedGlobal,
+            LintKind::RepeatZero,
+            LintKind::ConstantCondition,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::UnusedVariable => "unused-variable",
+            LintKind::UnusedParam => "unused-param",
+            LintKind::UnusedProcedure => "unused-procedure",
+            LintKind::ShadowedGlobal => "shadowed-global",
+            LintKind::RepeatZero => "repeat-zero",
+            LintKind::ConstantCondition => "constant-condition",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<LintKind> {
+        LintKind::all().into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintWarning {
+    UnusedVariable(String),
+    UnusedParam(String, String),
+    UnusedProcedure(String),
+    ShadowedGlobal(String),
+    RepeatZero,
+    ConstantCondition(bool),
+}
+
+impl LintWarning {
+    pub fn kind(&self) -> LintKind {
+        match self {
+            LintWarning::UnusedVariable(..) => LintKind::UnusedVariable,
+            LintWarning::UnusedParam(..) => LintKind::UnusedParam,
+            LintWarning::UnusedProcedure(..) => LintKind::UnusedProcedure,
+            LintWarning::ShadowedGlobal(..) => LintKind::ShadowedGlobal,
+            LintWarning::RepeatZero => LintKind::RepeatZero,
+            LintWarning::ConstantCondition(..) => LintKind::ConstantCondition,
+        }
+    }
+}
+
+impl ToString for LintWarning {
+    fn to_string(&self) -> String {
+        match self {
+            LintWarning::UnusedVariable(var) => format!("Variable `{}` is never read", var),
+            LintWarning::UnusedParam(proc, param) => {
+                format!("Param `{}` of procedure `{}` is never read", param, proc)
+            }
+            LintWarning::UnusedProcedure(proc) => format!("Procedure `{}` is never called", proc),
+            LintWarning::ShadowedGlobal(var) => {
+                format!("Local variable `{}` shadows a global variable", var)
+            }
+            LintWarning::RepeatZero => "`REPEAT 0` never runs its block".to_string(),
+            LintWarning::ConstantCondition(cond) => format!(
+                "`IF` condition is always `{}`",
+                if *cond { "TRUE" } else { "FALSE" }
+            ),
+        }
+    }
+}
+
+// the lints to run (all of them by default)
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintConfig {
+    disabled: HashSet<LintKind>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self {
+            disabled: HashSet::new(),
+        }
+    }
+
+    // a config with all the lints disabled
+    pub fn none() -> Self {
+        Self {
+            disabled: LintKind::all().into_iter().collect(),
+        }
+    }
+
+    pub fn enable(&mut self, kind: LintKind) {
+        self.disabled.remove(&kind);
+    }
+
+    pub fn disable(&mut self, kind: LintKind) {
+        self.disabled.insert(kind);
+    }
+
+    pub fn is_enabled(&self, kind: LintKind) -> bool {
+        !self.disabled.contains(&kind)
+    }
+
+    pub fn is_none(&self) -> bool {
+        LintKind::all().iter().all(|kind| !self.is_enabled(*kind))
+    }
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum ConstValue {
+    Bool(bool),
+    Int(usize),
+}
+
+// reports hints about code which is valid, but probably not what was meant.
+// the lints run over a type-checked AST (so that its symbols are resolved),
+// and their warnings never fail the compilation
+pub struct AstLint<'a> {
+    env: &'a Environment,
+    config: &'a LintConfig,
+    warnings: Vec<Warning>,
+
+    // the location of the statement being walked
+    location: Option<Location>,
+
+    // the procedures being walked (innermost last), starting with `__main__`
+    procs: Vec<Option<SymbolId>>,
+
+    // the `MAKELOCAL` declarations, along with their locations
+    locals: Vec<(SymbolId, Option<Location>)>,
+
+    // the procedures declarations, along with their locations
+    procs_decls: Vec<(SymbolId, Option<Location>)>,
+
+    read_vars: HashSet<SymbolId>,
+
+    // the procedures called (or referenced as a value) by another procedure
+    used_procs: HashSet<SymbolId>,
+}
+
+impl<'a> AstLint<'a> {
+    pub fn new(env: &'a Environment, config: &'a LintConfig) -> Self {
+        Self {
+            env,
+            config,
+            warnings: Vec::new(),
+            location: None,
+            procs: vec![env.main_proc_id],
+            locals: Vec::new(),
+            procs_decls: Vec::new(),
+            read_vars: HashSet::new(),
+            used_procs: HashSet::new(),
+        }
+    }
+
+    // the warnings are ordered by their location
+    pub fn lint(mut self, ast: &mut Ast) -> Vec<Warning> {
+        if self.config.is_none() {
+            return Vec::new();
+        }
+
+        let _ = self.walk_ast(ast);
+
+        self.report_unused_locals();
+        self.report_unused_procs();
+
+        self.warnings
+            .sort_by_key(|warning| warning.location.map(|loc| (loc.line(), loc.column())));
+
+        self.warnings
+    }
+
+    fn warn(&mut self, warning: LintWarning, location: Option<Location>) {
+        if self.config.is_enabled(warning.kind()) {
+            self.warnings.push(Warning::new(warning, location));
+        }
+    }
+
+    fn report_unused_locals(&mut self) {
+        for (var_id, location) in self.locals.clone() {
+            if !self.read_vars.contains(&var_id) {
+                let var = self.env.symbol_table.get_var_by_id(var_id);
+
+                self.warn(LintWarning::UnusedVariable(var.name.clone()), location);
+            }
+        }
+    }
+
+    fn report_unused_procs(&mut self) {
+        for (proc_id, location) in self.procs_decls.clone() {
+            let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+            if !self.used_procs.contains(&proc_id) {
+                self.warn(LintWarning::UnusedProcedure(proc.name.clone()), location);
+            }
+
+            let params_ids: Vec<SymbolId> = self
+                .env
+                .locals_symbols
+                .get(&proc_id)
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|var_id| self.env.symbol_table.get_var_by_id(*var_id).is_param())
+                .collect();
+
+            for param_id in params_ids {
+                if !self.read_vars.contains(&param_id) {
+                    let param = self.env.symbol_table.get_var_by_id(param_id);
+                    let warning = LintWarning::UnusedParam(proc.name.clone(), param.name.clone());
+
+                    self.warn(warning, location);
+                }
+            }
+        }
+    }
+
+    fn use_proc(&mut self, proc_id: Option<SymbolId>) {
+        if let Some(proc_id) = proc_id {
+            // a recursive call doesn't count as a use
+            if self.procs.last() != Some(&Some(proc_id)) {
+                self.used_procs.insert(proc_id);
+            }
+        }
+    }
+
+    fn is_global_name(&self, var_name: &str) -> bool {
+        self.env.globals_symbols.values().any(|var_id| {
+            let var = self.env.symbol_table.get_var_by_id(*var_id);
+
+            var.name == var_name
+        })
+    }
+
+    // evaluates an expression made of literals only (`None` otherwise)
+    fn const_eval(expr: &Expression) -> Option<ConstValue> {
+        match expr.expr_ast {
+            ExpressionAst::Literal(LiteralExpr::Bool(v)) => Some(ConstValue::Bool(v)),
+            ExpressionAst::Literal(LiteralExpr::Int(v)) => Some(ConstValue::Int(v)),
+            ExpressionAst::Parentheses(ref inner_expr) => Self::const_eval(inner_expr),
+            ExpressionAst::Not(ref inner_expr) => match Self::const_eval(inner_expr)? {
+                ConstValue::Bool(v) => Some(ConstValue::Bool(!v)),
+                _ => None,
+            },
+            ExpressionAst::Binary(ref op, ref lexpr, ref rexpr) => {
+                let lvalue = Self::const_eval(lexpr)?;
+                let rvalue = Self::const_eval(rexpr)?;
+
+                match (op, lvalue, rvalue) {
+                    (BinaryOp::And, ConstValue::Bool(l), ConstValue::Bool(r)) => {
+                        Some(ConstValue::Bool(l && r))
+                    }
+                    (BinaryOp::Or, ConstValue::Bool(l), ConstValue::Bool(r)) => {
+                        Some(ConstValue::Bool(l || r))
+                    }
+                    (BinaryOp::Add, ConstValue::Int(l), ConstValue::Int(r)) => {
+                        l.checked_add(r).map(ConstValue::Int)
+                    }
+                    (BinaryOp::Mul, ConstValue::Int(l), ConstValue::Int(r)) => {
+                        l.checked_mul(r).map(ConstValue::Int)
+                    }
+                    (BinaryOp::Div, ConstValue::Int(l), ConstValue::Int(r)) => {
+                        l.checked_div(r).map(ConstValue::Int)
+                    }
+                    (BinaryOp::GreaterThan, ConstValue::Int(l), ConstValue::Int(r)) => {
+                        Some(ConstValue::Bool(l > r))
+                    }
+                    (BinaryOp::LessThan, ConstValue::Int(l), ConstValue::Int(r)) => {
+                        Some(ConstValue::Bool(l < r))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> AstWalker for AstLint<'a> {
+    // the location is restored after walking a statement,
+    // so that the hooks called after walking a block get the location of the enclosing statement
+    fn walk_located_stmt(
+        &mut self,
+        ctx_proc: &str,
+        stmt: &mut Statement,
+        location: Option<Location>,
+    ) -> AstWalkResult {
+        let outer_location = self.location;
+        self.location = location;
+
+        let res = self.walk_stmt(ctx_proc, stmt);
+
+        self.location = outer_location;
+
+        res
+    }
+
+    fn on_proc_start(&mut self, _ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        if let Some(proc_id) = proc_stmt.id {
+            self.procs_decls.push((proc_id, self.location));
+        }
+
+        self.procs.push(proc_stmt.id);
+
+        for param in proc_stmt.params.iter() {
+            if self.is_global_name(&param.param_name) {
+                let warning = LintWarning::ShadowedGlobal(param.param_name.clone());
+
+                self.warn(warning, self.location);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_proc_end(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.procs.pop();
+
+        Ok(())
+    }
+
+    fn on_literal_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        match expr.as_lit_expr() {
+            LiteralExpr::Var(_, Some(var_id)) => {
+                self.read_vars.insert(*var_id);
+            }
+            LiteralExpr::Proc(_, proc_id) => {
+                let proc_id = *proc_id;
+
+                self.use_proc(proc_id);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn on_proc_call_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (_, _, proc_id) = expr.as_proc_call_expr();
+        let proc_id = proc_id.cloned();
+
+        self.use_proc(proc_id);
+
+        Ok(())
+    }
+
+    fn on_make_local_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        if let Some(var_id) = make_stmt.var_id {
+            self.locals.push((var_id, self.location));
+        }
+
+        if self.is_global_name(&make_stmt.var_name) {
+            let warning = LintWarning::ShadowedGlobal(make_stmt.var_name.clone());
+
+            self.warn(warning, self.location);
+        }
+
+        Ok(())
+    }
+
+    fn on_if_stmt(&mut self, _ctx_proc: &str, if_stmt: &mut IfStmt) -> AstWalkResult {
+        if let Some(ConstValue::Bool(cond)) = Self::const_eval(&if_stmt.cond_expr) {
+            self.warn(LintWarning::ConstantCondition(cond), self.location);
+        }
+
+        Ok(())
+    }
+
+    fn on_repeat_stmt(&mut self, _ctx_proc: &str, repeat_stmt: &mut RepeatStmt) -> AstWalkResult {
+        if let Some(ConstValue::Int(0)) = Self::const_eval(&repeat_stmt.count_expr) {
+            self.warn(LintWarning::RepeatZero, self.location);
+        }
+
+        Ok(())
+    }
+}
@@ -1,22 +1,77 @@
 use crate::ast::semantic::*;
 use crate::ast::Ast;
 use crate::ast::{expression::*, statement::*};
+use crate::compiler::Diagnostic;
+use crate::lexer::Location;
+use std::collections::{HashMap, HashSet};
 
+// an expression having no type (`expr_type = None`) after being walked has an unknown type.
+// it's the outcome of an error reported earlier (e.g an undeclared variable)
+// so checks involving unknown types are skipped, instead of reporting more errors
 pub struct AstTypeCheck<'env> {
     env: &'env mut Environment,
+
+    // `Some` when recovering from errors (see `check_recover`)
+    diagnostics: Option<Vec<Diagnostic>>,
+
+    // variables whose declaration expression has an unknown type
+    unknown_vars: HashSet<SymbolId>,
+
+    // the location of each variable declaration (see `TypeInference`)
+    declarations: HashMap<SymbolId, Location>,
+
+    // the procedures being walked (innermost last), `None` for a failed declaration
+    procs: Vec<Option<SymbolId>>,
 }
 
 impl<'env> AstTypeCheck<'env> {
     pub fn new(env: &'env mut Environment) -> Self {
-        Self { env }
+        Self {
+            env,
+            diagnostics: None,
+            unknown_vars: HashSet::new(),
+            declarations: HashMap::new(),
+            procs: Vec::new(),
+        }
     }
 
     pub fn check(&mut self, ast: &mut Ast) -> AstWalkResult {
-        self.walk_ast(ast)
+        self.infer_types(ast);
+
+        self.walk_ast(ast)?;
+        self.check_main_return_paths(ast)
+    }
+
+    // type-checks without stopping on the first error
+    pub fn check_recover(&mut self, ast: &mut Ast) -> Vec<Diagnostic> {
+        self.diagnostics = Some(Vec::new());
+        self.infer_types(ast);
+
+        // errors are collected by `on_stmt_error`, so the walk always completes
+        let _ = self.walk_ast(ast);
+        let _ = self.check_main_return_paths(ast);
+
+        self.diagnostics.take().unwrap()
+    }
+
+    fn infer_types(&mut self, ast: &mut Ast) {
+        let inference = TypeInference::new(self.env);
+
+        self.declarations = inference.infer(ast);
     }
 }
 
 impl<'env> AstWalker for AstTypeCheck<'env> {
+    fn on_stmt_error(&mut self, err: AstWalkError, location: Option<Location>) -> AstWalkResult {
+        match self.diagnostics {
+            Some(ref mut diagnostics) => {
+                diagnostics.push(Diagnostic::ast_walk(err, location));
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
     fn on_literal_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
         let lit_expr: &LiteralExpr = expr.as_lit_expr();
 
@@ -25,17 +80,29 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
             LiteralExpr::Int(_) => ExpressionType::Int,
             LiteralExpr::Str(_) => ExpressionType::Str,
             LiteralExpr::Var(var_name, var_id) => {
+                if var_id.is_none() || self.unknown_vars.contains(&var_id.unwrap()) {
+                    return Ok(());
+                }
+
                 let var = self.env.symbol_table.get_var_by_id(var_id.unwrap());
 
                 if let Some(ref var_type) = var.var_type {
                     var_type.to_owned()
                 } else {
-                    panic!(format!(
-                        "Variable `{}`, type couldn't be inferred",
-                        var_name
-                    ))
+                    // the variable is used before its declaration assigned it a type
+                    let err = AstWalkError::VariableTypeMissing(var_name.to_string());
+                    return Err(err);
                 }
             }
+            LiteralExpr::Proc(_, proc_id) => {
+                if proc_id.is_none() {
+                    return Ok(());
+                }
+
+                let proc = self.env.symbol_table.get_proc_by_id(proc_id.unwrap());
+
+                proc.proc_type()
+            }
         };
 
         expr.expr_type = Some(expr_type);
@@ -52,10 +119,18 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         Ok(())
     }
 
+    fn on_named_arg_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (_param_name, arg_expr) = expr.as_named_arg_expr();
+
+        expr.expr_type = arg_expr.expr_type.clone();
+
+        Ok(())
+    }
+
     fn on_not_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
         let inner_expr = expr.as_not_expr();
 
-        if inner_expr.expr_type != Some(ExpressionType::Bool) {
+        if is_type_mismatch(inner_expr, ExpressionType::Bool) {
             let expr_str = PrettyPrintAst::pprint_expr(inner_expr);
             let err = AstWalkError::NotBooleanExpr(expr_str);
             return Err(err);
@@ -67,46 +142,78 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
     }
 
     fn on_proc_call_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
-        let (proc_name, proc_args_exprs, _proc_id) = expr.as_proc_call_expr();
+        let (proc_name, proc_args_exprs, proc_id) = expr.as_proc_call_expr();
+
+        if proc_id.is_none() {
+            // an undeclared procedure
+            return Ok(());
+        }
 
-        let proc = self.env.symbol_table.get_proc_by_name(proc_name);
+        let proc = self.env.symbol_table.get_proc_by_id(*proc_id.unwrap());
+        let args_exprs = proc.bind_call_args(proc_args_exprs)?;
 
-        let expected_params_types = proc.params_types.clone();
-        let expected_args_count = expected_params_types.len();
-        let actual_args_count = proc_args_exprs.len();
+        typecheck_call_args(proc_name, &proc.params_types, &args_exprs)?;
 
-        if expected_args_count != actual_args_count {
-            let err = AstWalkError::InvalidProcCallArgsCount(
-                proc_name.clone(),
-                expected_args_count,
-                actual_args_count,
-            );
-            return Err(err);
-        }
+        expr.expr_type = Some(proc.return_type.clone());
 
-        let mut arg_pos = 1;
-        let mut actual_iter = proc_args_exprs.iter();
-        let mut expected_iter = expected_params_types.iter();
+        Ok(())
+    }
 
-        while arg_pos <= expected_args_count {
-            let arg_expr: &Expression = actual_iter.next().unwrap();
+    fn on_indirect_call_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (proc_expr, proc_args_exprs) = expr.as_indirect_call_expr();
 
-            let actual_type: ExpressionType = arg_expr.expr_type.clone().unwrap();
-            let expected_type: &ExpressionType = expected_iter.next().unwrap();
+        let return_type = match proc_expr.expr_type {
+            None => return Ok(()),
+            Some(ExpressionType::Proc(ref params_types, ref return_type)) => {
+                let proc_name = PrettyPrintAst::pprint_expr(proc_expr);
 
-            if *expected_type != actual_type {
-                let err = AstWalkError::InvalidProcCallArgType(
-                    arg_pos,
-                    expected_type.clone(),
-                    actual_type.clone(),
-                );
+                // a procedure value has no params names
+                let mut args_exprs = Vec::new();
+
+                for arg_expr in proc_args_exprs {
+                    if let ExpressionAst::NamedArg(ref param_name, _) = arg_expr.expr_ast {
+                        let err = AstWalkError::UnknownProcParam(proc_name, param_name.clone());
+                        return Err(err);
+                    }
+
+                    args_exprs.push(arg_expr);
+                }
+
+                typecheck_call_args(&proc_name, params_types, &args_exprs)?;
+
+                (**return_type).clone()
+            }
+            Some(_) => {
+                let expr_str = PrettyPrintAst::pprint_expr(proc_expr);
+                let err = AstWalkError::NotProcExpr(expr_str);
                 return Err(err);
             }
+        };
 
-            arg_pos += 1;
-        }
+        expr.expr_type = Some(return_type);
 
-        expr.expr_type = Some(proc.return_type.clone());
+        Ok(())
+    }
+
+    fn on_new_struct_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (struct_name, fields_args, struct_id) = expr.as_new_struct_expr();
+
+        let struct_type = self.env.symbol_table.get_struct_by_id(*struct_id.unwrap());
+        let args_exprs = struct_type.bind_fields_args(fields_args)?;
+
+        typecheck_call_args(struct_name, &struct_type.fields_types, &args_exprs)?;
+
+        expr.expr_type = Some(ExpressionType::Struct(struct_name.clone()));
+
+        Ok(())
+    }
+
+    fn on_field_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        let (record_expr, field_name) = expr.as_field_expr();
+
+        let field_type = self.typecheck_field(record_expr, field_name)?;
+
+        expr.expr_type = field_type;
 
         Ok(())
     }
@@ -131,32 +238,63 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
     }
 
     fn on_make_assign_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        if make_stmt.var_id.is_none() || make_stmt.expr.expr_type.is_none() {
+            return Ok(());
+        }
+
         let var_id = make_stmt.var_id.unwrap();
-        let var: &mut Variable = self.env.symbol_table.get_var_by_id_mut(var_id);
+
+        if self.unknown_vars.contains(&var_id) {
+            return Ok(());
+        }
+
+        let var: &Variable = self.env.symbol_table.get_var_by_id(var_id);
 
         if var.var_type.is_none() {
-            panic!()
+            let err = AstWalkError::VariableTypeMissing(var.name.to_string());
+            return Err(err);
         }
 
         let expr_type = make_stmt.expr.expr_type.as_ref().unwrap().to_owned();
         let var_type = var.var_type.clone().unwrap();
 
         if expr_type != var_type {
-            let err = AstWalkError::TypeMismatch(var_type, expr_type);
+            let err = AstWalkError::ConflictingAssignment {
+                var_name: var.name.to_string(),
+                var_type,
+                declared_at: self.declarations.get(&var_id).cloned(),
+                assigned_type: expr_type,
+            };
             return Err(err);
         }
 
         Ok(())
     }
 
+    fn on_set_field_stmt(
+        &mut self,
+        _ctx_proc: &str,
+        set_field_stmt: &mut SetFieldStmt,
+    ) -> AstWalkResult {
+        let record_expr = &set_field_stmt.record_expr;
+        let field_type = self.typecheck_field(record_expr, &set_field_stmt.field_name)?;
+
+        if let (Some(field_type), Some(expr_type)) = (field_type, &set_field_stmt.expr.expr_type) {
+            if field_type != *expr_type {
+                let err = AstWalkError::TypeMismatch(field_type, expr_type.clone());
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
     fn on_direct_stmt(
         &mut self,
         _ctx_proc: &str,
         direct_stmt: &mut DirectionStmt,
     ) -> AstWalkResult {
-        let expr_type = &direct_stmt.expr.expr_type;
-
-        if *expr_type != Some(ExpressionType::Int) {
+        if is_type_mismatch(&direct_stmt.expr, ExpressionType::Int) {
             let expr_str = PrettyPrintAst::pprint_expr(&direct_stmt.expr);
             let err = AstWalkError::NotIntExpr(expr_str);
             return Err(err);
@@ -165,10 +303,40 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         Ok(())
     }
 
+    fn on_label(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        if is_type_mismatch(expr, ExpressionType::Str) {
+            let expr_str = PrettyPrintAst::pprint_expr(expr);
+            let err = AstWalkError::NotStrExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_print(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        if is_type_mismatch(expr, ExpressionType::Int) {
+            let expr_str = PrettyPrintAst::pprint_expr(expr);
+            let err = AstWalkError::NotIntExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_set_font_size(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
+        if is_type_mismatch(expr, ExpressionType::Int) {
+            let expr_str = PrettyPrintAst::pprint_expr(expr);
+            let err = AstWalkError::NotIntExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
     fn on_if_stmt(&mut self, _ctx_proc: &str, if_stmt: &mut IfStmt) -> AstWalkResult {
         let cond_expr = &if_stmt.cond_expr;
 
-        if cond_expr.expr_type != Some(ExpressionType::Bool) {
+        if is_type_mismatch(cond_expr, ExpressionType::Bool) {
             let expr_str = PrettyPrintAst::pprint_expr(cond_expr);
             let err = AstWalkError::NotBooleanExpr(expr_str);
             return Err(err);
@@ -180,7 +348,7 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
     fn on_repeat_stmt(&mut self, _ctx_proc: &str, repeat_stmt: &mut RepeatStmt) -> AstWalkResult {
         let count_expr = &repeat_stmt.count_expr;
 
-        if count_expr.expr_type != Some(ExpressionType::Int) {
+        if is_type_mismatch(count_expr, ExpressionType::Int) {
             let expr_str = PrettyPrintAst::pprint_expr(count_expr);
             let err = AstWalkError::NotIntExpr(expr_str);
             return Err(err);
@@ -189,14 +357,123 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
         Ok(())
     }
 
-    fn on_ret_stmt(&mut self, ctx_proc: &str, ret_stmt: &mut ReturnStmt) -> AstWalkResult {
-        let proc = self.env.symbol_table.get_proc_by_name(ctx_proc);
+    fn on_map_stmt(&mut self, _ctx_proc: &str, map_stmt: &mut MapStmt) -> AstWalkResult {
+        // `MAP` calls the procedure with the iteration index
+        let expected_type =
+            ExpressionType::Proc(vec![ExpressionType::Int], Box::new(ExpressionType::Unit));
+
+        if let Some(ref proc_type) = map_stmt.proc_expr.expr_type {
+            if *proc_type != expected_type {
+                let err = AstWalkError::TypeMismatch(expected_type, proc_type.clone());
+                return Err(err);
+            }
+        }
+
+        let count_expr = &map_stmt.count_expr;
+
+        if is_type_mismatch(count_expr, ExpressionType::Int) {
+            let expr_str = PrettyPrintAst::pprint_expr(count_expr);
+            let err = AstWalkError::NotIntExpr(expr_str);
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_proc_start(&mut self, _ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.procs.push(proc_stmt.id);
+
+        Ok(())
+    }
+
+    fn on_proc_param(&mut self, _ctx_proc: &str, proc_param: &mut ProcParam) -> AstWalkResult {
+        let default_expr = match proc_param.default_expr {
+            Some(ref default_expr) => default_expr,
+            None => return Ok(()),
+        };
+
+        let param_type = ExpressionType::from(proc_param.param_type.as_str());
+
+        if is_type_mismatch(default_expr, param_type.clone()) {
+            let actual_type = default_expr.expr_type.clone().unwrap();
+            let err = AstWalkError::InvalidProcParamDefaultType(
+                proc_param.param_name.clone(),
+                param_type,
+                actual_type,
+            );
+            return Err(err);
+        }
+
+        // the procedure symbol gets the checked default value, for the calls to evaluate it
+        if let Some(Some(proc_id)) = self.procs.last() {
+            let mut visited = HashSet::new();
+
+            if self.expr_calls_proc(default_expr, *proc_id, &mut visited) {
+                let proc = self.env.symbol_table.get_proc_by_id(*proc_id);
+                let err = AstWalkError::RecursiveProcParamDefault(
+                    proc.name.clone(),
+                    proc_param.param_name.clone(),
+                );
+                return Err(err);
+            }
+
+            let proc = self.env.symbol_table.get_proc_by_id_mut(*proc_id);
+
+            let param_index = proc
+                .params_names
+                .iter()
+                .position(|name| *name == proc_param.param_name)
+                .unwrap();
+
+            proc.params_defaults[param_index] = Some(default_expr.clone());
+        }
+
+        Ok(())
+    }
+
+    fn on_proc_end(&mut self, _ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.procs.pop();
+
+        if proc_stmt.id.is_none() {
+            // the procedure declaration has failed (e.g a duplicate procedure)
+            return Ok(());
+        }
+
+        let mut unreachable = Vec::new();
+        let returns = block_returns(&proc_stmt.block, &mut unreachable);
+
+        self.report_unreachable(&proc_stmt.name, unreachable)?;
+
+        let proc = self.env.symbol_table.get_proc_by_id(proc_stmt.id.unwrap());
+
+        if !returns && proc.return_type != ExpressionType::Unit {
+            let err = AstWalkError::MissingReturn(proc_stmt.name.to_string());
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    fn on_ret_stmt(&mut self, _ctx_proc: &str, ret_stmt: &mut ReturnStmt) -> AstWalkResult {
+        let proc_id = match self.procs.last() {
+            Some(proc_id) => *proc_id,
+            None => self.env.main_proc_id,
+        };
+
+        if proc_id.is_none() {
+            // an undeclared procedure (e.g a duplicate procedure)
+            return Ok(());
+        }
+
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id.unwrap());
 
         let actual_ret_type = if ret_stmt.expr.is_some() {
             let ret_expr = ret_stmt.expr.as_ref().unwrap();
-            let ret_expr_type = ret_expr.expr_type.as_ref().unwrap();
 
-            ret_expr_type.clone()
+            match ret_expr.expr_type {
+                Some(ref ret_expr_type) => ret_expr_type.clone(),
+                None => return Ok(()),
+            }
         } else {
             ExpressionType::Unit
         };
@@ -211,8 +488,154 @@ impl<'env> AstWalker for AstTypeCheck<'env> {
 }
 
 impl<'env> AstTypeCheck<'env> {
+    // the main procedure has no return value, so we only look for unreachable statements
+    fn check_main_return_paths(&mut self, ast: &Ast) -> AstWalkResult {
+        let mut unreachable = Vec::new();
+
+        stmts_return(&ast.statements, &ast.locations, &mut unreachable);
+
+        self.report_unreachable("__main__", unreachable)
+    }
+
+    fn report_unreachable(
+        &mut self,
+        proc_name: &str,
+        unreachable: Vec<Option<Location>>,
+    ) -> AstWalkResult {
+        for location in unreachable {
+            let err = AstWalkError::UnreachableStmt(proc_name.to_string());
+
+            self.on_stmt_error(err, location)?;
+        }
+
+        Ok(())
+    }
+
+    // returns the type of field `field_name` of the record `record_expr`
+    // (`None` when the record has an unknown type)
+    fn typecheck_field(
+        &self,
+        record_expr: &Expression,
+        field_name: &str,
+    ) -> Result<Option<ExpressionType>, AstWalkError> {
+        let struct_name = match record_expr.expr_type {
+            None => return Ok(None),
+            Some(ExpressionType::Struct(ref struct_name)) => struct_name,
+            Some(_) => {
+                let expr_str = PrettyPrintAst::pprint_expr(record_expr);
+                let err = AstWalkError::NotStructExpr(expr_str);
+                return Err(err);
+            }
+        };
+
+        let struct_type = self
+            .env
+            .symbol_table
+            .get_struct_by_name(struct_name)
+            .unwrap();
+
+        match struct_type.field_type(field_name) {
+            Some(field_type) => Ok(Some(field_type.clone())),
+            None => {
+                let err =
+                    AstWalkError::UnknownStructField(struct_name.clone(), field_name.to_string());
+                Err(err)
+            }
+        }
+    }
+
+    // returns whether evaluating `expr` calls procedure `proc_id`, either directly
+    // or through the params default values of the procedures it calls
+    fn expr_calls_proc(
+        &self,
+        expr: &Expression,
+        proc_id: SymbolId,
+        visited: &mut HashSet<SymbolId>,
+    ) -> bool {
+        match expr.expr_ast {
+            ExpressionAst::Literal(_) => false,
+            ExpressionAst::ProcCall(_, ref args, callee_id) => {
+                if let Some(callee_id) = callee_id {
+                    if callee_id == proc_id {
+                        return true;
+                    }
+
+                    if visited.insert(callee_id) {
+                        let callee = self.env.symbol_table.get_proc_by_id(callee_id);
+
+                        let defaults_call =
+                            callee.params_defaults.iter().any(|default| match default {
+                                Some(default_expr) => {
+                                    self.expr_calls_proc(default_expr, proc_id, visited)
+                                }
+                                None => false,
+                            });
+
+                        if defaults_call {
+                            return true;
+                        }
+                    }
+                }
+
+                self.exprs_call_proc(args, proc_id, visited)
+            }
+            ExpressionAst::IndirectCall(ref proc_expr, ref args) => {
+                self.expr_calls_proc(proc_expr, proc_id, visited)
+                    || self.exprs_call_proc(args, proc_id, visited)
+            }
+            ExpressionAst::NewStruct(_, ref args, _) => {
+                self.exprs_call_proc(args, proc_id, visited)
+            }
+            ExpressionAst::Binary(_, ref lexpr, ref rexpr) => {
+                self.expr_calls_proc(lexpr, proc_id, visited)
+                    || self.expr_calls_proc(rexpr, proc_id, visited)
+            }
+            ExpressionAst::NamedArg(_, ref expr)
+            | ExpressionAst::Field(ref expr, _)
+            | ExpressionAst::Parentheses(ref expr)
+            | ExpressionAst::Not(ref expr) => self.expr_calls_proc(expr, proc_id, visited),
+        }
+    }
+
+    fn exprs_call_proc(
+        &self,
+        exprs: &[Expression],
+        proc_id: SymbolId,
+        visited: &mut HashSet<SymbolId>,
+    ) -> bool {
+        exprs
+            .iter()
+            .any(|expr| self.expr_calls_proc(expr, proc_id, visited))
+    }
+
     fn typecheck_var_declare(&mut self, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        if make_stmt.var_id.is_none() {
+            // the declaration itself has failed (e.g a duplicate variable)
+            return Ok(());
+        }
+
         let var_id = make_stmt.var_id.unwrap();
+
+        if make_stmt.expr.expr_type.is_none() {
+            self.unknown_vars.insert(var_id);
+            return Ok(());
+        }
+
+        let var_type = self
+            .env
+            .symbol_table
+            .get_var_by_id(var_id)
+            .var_type
+            .as_ref();
+
+        if let Some(var_type) = var_type {
+            if !is_type_declared(&self.env.symbol_table, var_type) {
+                // the annotation is an undeclared type (reported by `SymbolTableGenerator`)
+                self.unknown_vars.insert(var_id);
+                return Ok(());
+            }
+        }
+
         let var: &mut Variable = self.env.symbol_table.get_var_by_id_mut(var_id);
 
         let expr_type: &ExpressionType = make_stmt.expr.expr_type.as_ref().unwrap();
@@ -222,9 +645,18 @@ impl<'env> AstTypeCheck<'env> {
             return Err(err);
         }
 
-        var.var_type = Some(expr_type.to_owned());
-
-        Ok(())
+        // the variable type is annotated or inferred (see `TypeInference`)
+        match var.var_type {
+            Some(ref var_type) if var_type != expr_type => {
+                let err = AstWalkError::TypeMismatch(var_type.clone(), expr_type.clone());
+                Err(err)
+            }
+            Some(_) => Ok(()),
+            None => {
+                var.var_type = Some(expr_type.to_owned());
+                Ok(())
+            }
+        }
     }
 
     fn do_binary_expr_typecheck(
@@ -233,6 +665,10 @@ impl<'env> AstTypeCheck<'env> {
         lexpr: &Expression,
         rexpr: &Expression,
     ) -> AstWalkResult {
+        if lexpr.expr_type.is_none() || rexpr.expr_type.is_none() {
+            return Ok(());
+        }
+
         let ltype = lexpr.expr_type.clone().unwrap();
         let rtype = rexpr.expr_type.clone().unwrap();
 
@@ -276,3 +712,104 @@ impl<'env> AstTypeCheck<'env> {
         }
     }
 }
+
+// checks the arguments of a call to procedure `proc_name` against the procedure params types
+fn typecheck_call_args(
+    proc_name: &str,
+    params_types: &[ExpressionType],
+    args_exprs: &[&Expression],
+) -> AstWalkResult {
+    if params_types.len() != args_exprs.len() {
+        let err = AstWalkError::InvalidProcCallArgsCount(
+            proc_name.to_string(),
+            params_types.len(),
+            args_exprs.len(),
+        );
+        return Err(err);
+    }
+
+    for (i, (arg_expr, expected_type)) in args_exprs.iter().zip(params_types).enumerate() {
+        if is_type_mismatch(arg_expr, expected_type.clone()) {
+            let actual_type = arg_expr.expr_type.clone().unwrap();
+            let err =
+                AstWalkError::InvalidProcCallArgType(i + 1, expected_type.clone(), actual_type);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+// returns whether each struct type within `expr_type` has a `STRUCT` declaration
+fn is_type_declared(symbol_table: &SymbolTable, expr_type: &ExpressionType) -> bool {
+    match expr_type {
+        ExpressionType::Struct(name) => symbol_table.get_struct_by_name(name).is_some(),
+        ExpressionType::Proc(params_types, return_type) => {
+            params_types
+                .iter()
+                .all(|param_type| is_type_declared(symbol_table, param_type))
+                && is_type_declared(symbol_table, return_type)
+        }
+        _ => true,
+    }
+}
+
+// returns whether `expr` has a known type which isn't `expected`
+fn is_type_mismatch(expr: &Expression, expected: ExpressionType) -> bool {
+    match expr.expr_type {
+        Some(ref expr_type) => *expr_type != expected,
+        None => false,
+    }
+}
+
+// returns whether `block` returns on every path.
+// the location of the first unreachable statement of each (reachable) block is appended to `unreachable`
+fn block_returns(block: &BlockStatement, unreachable: &mut Vec<Option<Location>>) -> bool {
+    stmts_return(&block.stmts, &block.locations, unreachable)
+}
+
+fn stmts_return(
+    stmts: &[Statement],
+    locations: &[Location],
+    unreachable: &mut Vec<Option<Location>>,
+) -> bool {
+    for (i, stmt) in stmts.iter().enumerate() {
+        let returns = match stmt {
+            Statement::Return(_) => true,
+            Statement::If(if_stmt) => {
+                let true_returns = block_returns(&if_stmt.true_block, unreachable);
+
+                match if_stmt.false_block {
+                    Some(ref false_block) => {
+                        let false_returns = block_returns(false_block, unreachable);
+
+                        true_returns && false_returns
+                    }
+                    None => false,
+                }
+            }
+            Statement::Repeat(repeat_stmt) => {
+                // the loop body might not run at all, so it never returns on every path
+                block_returns(&repeat_stmt.block, unreachable);
+                false
+            }
+            _ => false,
+        };
+
+        if returns {
+            // nested procedures aren't executed in place, so they're never unreachable
+            for (j, next_stmt) in stmts.iter().enumerate().skip(i + 1) {
+                if let Statement::Procedure(_) = next_stmt {
+                    continue;
+                }
+
+                unreachable.push(locations.get(j).cloned());
+                break;
+            }
+
+            return true;
+        }
+    }
+
+    false
+}
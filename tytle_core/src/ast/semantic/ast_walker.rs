@@ -2,18 +2,34 @@ use crate::ast::expression::*;
 use crate::ast::semantic::AstWalkError;
 use crate::ast::statement::*;
 use crate::ast::Ast;
+use crate::lexer::Location;
 
 pub type AstWalkResult = Result<(), AstWalkError>;
 
 pub trait AstWalker {
     fn walk_ast(&mut self, ast: &mut Ast) -> AstWalkResult {
-        for stmt in &mut ast.statements {
-            self.walk_stmt("__main__", stmt)?;
+        for (i, stmt) in ast.statements.iter_mut().enumerate() {
+            let location = ast.locations.get(i).cloned();
+
+            if let Err(err) = self.walk_located_stmt("__main__", stmt, location) {
+                self.on_stmt_error(err, location)?;
+            }
         }
 
         Ok(())
     }
 
+    fn walk_located_stmt(
+        &mut self,
+        ctx_proc: &str,
+        stmt: &mut Statement,
+        location: Option<Location>,
+    ) -> AstWalkResult {
+        self.on_stmt_start(ctx_proc, location)?;
+
+        self.walk_stmt(ctx_proc, stmt)
+    }
+
     fn walk_stmt(&mut self, ctx_proc: &str, stmt: &mut Statement) -> AstWalkResult {
         match stmt {
             Statement::NOP | Statement::EOF => {}
@@ -21,16 +37,29 @@ pub trait AstWalker {
                 self.walk_expr(ctx_proc, expr)?;
                 self.on_print(ctx_proc, expr)?
             }
+            Statement::Label(ref mut expr) => {
+                self.walk_expr(ctx_proc, expr)?;
+                self.on_label(ctx_proc, expr)?
+            }
+            Statement::SetFontSize(ref mut expr) => {
+                self.walk_expr(ctx_proc, expr)?;
+                self.on_set_font_size(ctx_proc, expr)?
+            }
             Statement::Command(ref mut cmd_stmt) => self.on_command(ctx_proc, cmd_stmt)?,
             Statement::Direction(ref mut direct_stmt) => {
                 self.walk_direct_stmt(ctx_proc, direct_stmt)?
             }
             Statement::If(ref mut if_stmt) => self.walk_if_stmt(ctx_proc, if_stmt)?,
             Statement::Make(ref mut make_stmt) => self.walk_make_stmt(ctx_proc, make_stmt)?,
+            Statement::SetField(ref mut set_field_stmt) => {
+                self.walk_set_field_stmt(ctx_proc, set_field_stmt)?
+            }
             Statement::Repeat(ref mut repeat_stmt) => {
                 self.walk_repeat_stmt(ctx_proc, repeat_stmt)?
             }
+            Statement::Map(ref mut map_stmt) => self.walk_map_stmt(ctx_proc, map_stmt)?,
             Statement::Procedure(ref mut proc_stmt) => self.walk_proc_stmt(ctx_proc, proc_stmt)?,
+            Statement::Struct(ref mut struct_stmt) => self.on_struct_stmt(ctx_proc, struct_stmt)?,
             Statement::Return(ref mut return_stmt) => self.walk_ret_stmt(ctx_proc, return_stmt)?,
             Statement::Expression(ref mut expr) => self.walk_expr_stmt(ctx_proc, expr)?,
         }
@@ -39,14 +68,32 @@ pub trait AstWalker {
     }
 
     fn walk_proc_stmt(&mut self, ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        // the params default values are evaluated by the callers,
+        // so they're walked within the enclosing procedure (before the procedure scope starts)
+        for param in &mut proc_stmt.params {
+            if let Some(ref mut default_expr) = param.default_expr {
+                self.walk_expr(ctx_proc, default_expr)?;
+            }
+        }
+
         self.on_proc_start(ctx_proc, proc_stmt)?;
 
-        self.walk_proc_params(ctx_proc, proc_stmt)?;
+        if let Err(err) = self.walk_proc_params(ctx_proc, proc_stmt) {
+            // we still end the procedure, so that walkers recovering from errors stay balanced
+            self.on_proc_end(ctx_proc, proc_stmt)?;
+            return Err(err);
+        }
 
         // we don't call `walk_proc_stmt` in order to avoid starting a new scope.
         // we want the procedure params and the procedure root-block to share the same scope
-        for stmt in &mut proc_stmt.block.stmts {
-            self.walk_stmt(proc_stmt.name.as_str(), stmt)?;
+        let block = &mut proc_stmt.block;
+
+        for (i, stmt) in block.stmts.iter_mut().enumerate() {
+            let location = block.locations.get(i).cloned();
+
+            if let Err(err) = self.walk_located_stmt(proc_stmt.name.as_str(), stmt, location) {
+                self.on_stmt_error(err, location)?;
+            }
         }
 
         self.on_proc_end(ctx_proc, proc_stmt)?;
@@ -104,8 +151,12 @@ pub trait AstWalker {
     ) -> AstWalkResult {
         self.on_block_stmt_start(ctx_proc, block_stmt)?;
 
-        for stmt in &mut block_stmt.stmts {
-            self.walk_stmt(ctx_proc, stmt)?;
+        for (i, stmt) in block_stmt.stmts.iter_mut().enumerate() {
+            let location = block_stmt.locations.get(i).cloned();
+
+            if let Err(err) = self.walk_located_stmt(ctx_proc, stmt, location) {
+                self.on_stmt_error(err, location)?;
+            }
         }
 
         self.on_block_stmt_end(ctx_proc, block_stmt)
@@ -119,6 +170,27 @@ pub trait AstWalker {
 
                 self.on_proc_call_expr(ctx_proc, expr)
             }
+            ExpressionAst::IndirectCall(ref mut proc_expr, ref mut proc_args) => {
+                self.walk_expr(ctx_proc, proc_expr)?;
+
+                for proc_arg in proc_args {
+                    self.walk_expr(ctx_proc, proc_arg)?;
+                }
+
+                self.on_indirect_call_expr(ctx_proc, expr)
+            }
+            ExpressionAst::NewStruct(_, ref mut fields_args, _) => {
+                for field_arg in fields_args {
+                    self.walk_expr(ctx_proc, field_arg)?;
+                }
+
+                self.on_new_struct_expr(ctx_proc, expr)
+            }
+            ExpressionAst::Field(ref mut record_expr, _) => {
+                self.walk_expr(ctx_proc, record_expr)?;
+
+                self.on_field_expr(ctx_proc, expr)
+            }
             ExpressionAst::Binary(_, ref mut lexpr, ref mut rexpr) => {
                 self.walk_expr(ctx_proc, lexpr)?;
                 self.walk_expr(ctx_proc, rexpr)?;
@@ -130,6 +202,11 @@ pub trait AstWalker {
 
                 self.on_parentheses_expr(ctx_proc, expr)
             }
+            ExpressionAst::NamedArg(_, ref mut arg_expr) => {
+                self.walk_expr(ctx_proc, arg_expr)?;
+
+                self.on_named_arg_expr(ctx_proc, expr)
+            }
             ExpressionAst::Not(ref mut inner_expr) => {
                 self.walk_expr(ctx_proc, inner_expr)?;
 
@@ -178,6 +255,17 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn walk_set_field_stmt(
+        &mut self,
+        ctx_proc: &str,
+        set_field_stmt: &mut SetFieldStmt,
+    ) -> AstWalkResult {
+        self.walk_expr(ctx_proc, &mut set_field_stmt.record_expr)?;
+        self.walk_expr(ctx_proc, &mut set_field_stmt.expr)?;
+
+        self.on_set_field_stmt(ctx_proc, set_field_stmt)
+    }
+
     fn walk_repeat_stmt(&mut self, ctx_proc: &str, repeat_stmt: &mut RepeatStmt) -> AstWalkResult {
         self.walk_expr(ctx_proc, &mut repeat_stmt.count_expr)?;
 
@@ -186,7 +274,26 @@ pub trait AstWalker {
         self.on_repeat_stmt(ctx_proc, repeat_stmt)
     }
 
+    fn walk_map_stmt(&mut self, ctx_proc: &str, map_stmt: &mut MapStmt) -> AstWalkResult {
+        self.walk_expr(ctx_proc, &mut map_stmt.proc_expr)?;
+        self.walk_expr(ctx_proc, &mut map_stmt.count_expr)?;
+
+        self.on_map_stmt(ctx_proc, map_stmt)
+    }
+
     // hooks
+
+    // called when walking a statement fails (`location` is the statement's location).
+    // returning `Ok` carries on walking from the next statement
+    fn on_stmt_error(&mut self, err: AstWalkError, _location: Option<Location>) -> AstWalkResult {
+        Err(err)
+    }
+
+    // called before walking each statement of a block (`location` is the statement's location)
+    fn on_stmt_start(&mut self, _ctx_proc: &str, _location: Option<Location>) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_proc_start(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
         Ok(())
     }
@@ -225,6 +332,10 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_indirect_call_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_binary_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
         Ok(())
     }
@@ -237,6 +348,18 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_named_arg_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_new_struct_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_field_expr(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_expr_stmt(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
         Ok(())
     }
@@ -279,6 +402,19 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_set_field_stmt(
+        &mut self,
+        _ctx_proc: &str,
+        _set_field_stmt: &mut SetFieldStmt,
+    ) -> AstWalkResult {
+        Ok(())
+    }
+
+    // `STRUCT` declarations
+    fn on_struct_stmt(&mut self, _ctx_proc: &str, _struct_stmt: &mut StructStmt) -> AstWalkResult {
+        Ok(())
+    }
+
     // misc
     fn on_if_stmt(&mut self, _ctx_proc: &str, _if_stmt: &mut IfStmt) -> AstWalkResult {
         Ok(())
@@ -288,6 +424,10 @@ pub trait AstWalker {
         Ok(())
     }
 
+    fn on_map_stmt(&mut self, _ctx_proc: &str, _map_stmt: &mut MapStmt) -> AstWalkResult {
+        Ok(())
+    }
+
     fn on_ret_stmt(&mut self, _ctx_proc: &str, _return_stmt: &mut ReturnStmt) -> AstWalkResult {
         Ok(())
     }
@@ -307,4 +447,12 @@ pub trait AstWalker {
     fn on_print(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
         Ok(())
     }
+
+    fn on_label(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
+
+    fn on_set_font_size(&mut self, _ctx_proc: &str, _expr: &mut Expression) -> AstWalkResult {
+        Ok(())
+    }
 }
@@ -1,22 +1,57 @@
 use crate::ast::expression::{BinaryOp, ExpressionType};
+use crate::lexer::Location;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AstWalkError {
     DuplicateGlobalVar(String),
     DuplicateProc(String),
+    DuplicateStruct(String),
+    DuplicateStructField(String, String),
+    StructNamedAsBuiltinType(String),
+    RecursiveStruct(String),
+    ProcNamedAsStruct(String),
+    GlobalVarNamedAsStruct(String),
     DuplicateProcLocalVar(String),
     DuplicateProcParam(String, String),
     MissingVarDeclaration(String),
+    MissingProcDeclaration(String),
+    MissingTypeDeclaration(String),
     ProcNotAllowedToDeclareGlobals(String),
+    NestedProcDeclaration(String),
+    NestedStructDeclaration(String),
     InvalidReturnType(ExpressionType, ExpressionType),
     LocalsNotAllowedUnderRootScope(String),
     TypeMismatch(ExpressionType, ExpressionType),
+
+    // assigning a variable a value whose type differs from the variable (declared or inferred) type.
+    // `declared_at` is the location of the variable declaration
+    ConflictingAssignment {
+        var_name: String,
+        var_type: ExpressionType,
+        declared_at: Option<Location>,
+        assigned_type: ExpressionType,
+    },
+
     InvalidBinaryOp(BinaryOp, ExpressionType, ExpressionType),
     InvalidProcCallArgsCount(String, usize, usize),
     InvalidProcCallArgType(usize, ExpressionType, ExpressionType),
+    UnknownProcParam(String, String),
+    DuplicateProcCallArg(String, String),
+    MissingProcCallArg(String, String),
+    InvalidProcParamDefaultType(String, ExpressionType, ExpressionType),
+
+    // a param default value calling its own procedure (possibly through the defaults of other procedures).
+    // the defaults are evaluated by the callers, so the calls would expand endlessly
+    RecursiveProcParamDefault(String, String),
     VariableTypeMissing(String),
+    MissingReturn(String),
+    UnreachableStmt(String),
     NotBooleanExpr(String),
     NotIntExpr(String),
+    NotStrExpr(String),
+    NotProcExpr(String),
+    NotStructExpr(String),
+    UnknownStructField(String, String),
 }
 
 impl ToString for AstWalkError {
@@ -24,6 +59,23 @@ impl ToString for AstWalkError {
         match self {
             AstWalkError::DuplicateGlobalVar(var) => format!("Duplicate global var: `{}`", var),
             AstWalkError::DuplicateProc(proc) => format!("Duplicate procedure: `{}`", proc),
+            AstWalkError::DuplicateStruct(name) => format!("Duplicate struct: `{}`", name),
+            AstWalkError::DuplicateStructField(name, field) => format!(
+                "Duplicate struct field: `{}` (struct: `{}`)",
+                field, name
+            ),
+            AstWalkError::StructNamedAsBuiltinType(name) => {
+                format!("Struct `{}` has the name of a built-in type", name)
+            }
+            AstWalkError::RecursiveStruct(name) => {
+                format!("Struct `{}` contains itself (through its fields types)", name)
+            }
+            AstWalkError::ProcNamedAsStruct(proc) => {
+                format!("Procedure `{}` has the name of a struct", proc)
+            }
+            AstWalkError::GlobalVarNamedAsStruct(var) => {
+                format!("Global var `{}` has the name of a struct", var)
+            }
             AstWalkError::DuplicateProcLocalVar(local) => {
                 format!("Duplicate procedure local: `{}`", local)
             }
@@ -34,10 +86,24 @@ impl ToString for AstWalkError {
             AstWalkError::MissingVarDeclaration(var) => {
                 format!("Missing variable declaration for `{}`", var)
             }
+            AstWalkError::MissingProcDeclaration(proc) => {
+                format!("Missing procedure declaration for `{}`", proc)
+            }
+            AstWalkError::MissingTypeDeclaration(name) => {
+                format!("Missing type declaration for `{}`", name)
+            }
             AstWalkError::ProcNotAllowedToDeclareGlobals(proc) => format!(
                 "Procedure not allowed to declare globals (procedure `{}`)",
                 proc
             ),
+            AstWalkError::NestedProcDeclaration(proc) => format!(
+                "Procedures can only be declared at the top-level or within a procedure body (procedure: `{}`)",
+                proc
+            ),
+            AstWalkError::NestedStructDeclaration(name) => format!(
+                "Structs can only be declared at the top-level (struct: `{}`)",
+                name
+            ),
             AstWalkError::InvalidReturnType(expected, actual) => format!(
                 "Invalid return type. expected: `{}`, actual: `{}`",
                 expected.to_string(),
@@ -49,16 +115,37 @@ impl ToString for AstWalkError {
             ),
             AstWalkError::TypeMismatch(expected, actual) =>
                 format!("Type mismatch. expected: `{}`, actual: `{}`", expected.to_string(), actual.to_string()),
+            AstWalkError::ConflictingAssignment { var_name, var_type, declared_at, assigned_type } => {
+                let declared = match declared_at {
+                    Some(loc) => format!("`{}` (line: {}, column: {})", var_type.to_string(), loc.line(), loc.column()),
+                    None => format!("`{}`", var_type.to_string()),
+                };
+
+                format!("Conflicting assignment to `{}`. declared: {}, assigned: `{}`", var_name, declared, assigned_type.to_string())
+            },
             AstWalkError::InvalidBinaryOp(bin_op, ltype, rtype) =>
                 format!("Invalid binary operator `{}`(left expression-type: `{}`, right expression-type: `{}`", bin_op.to_string(), ltype.to_string(), rtype.to_string()),
             AstWalkError::InvalidProcCallArgsCount(proc, expected, actual) => {
                 format!("Prcedure call wrong number of arguments for `{}` (expected: {}, actual: {})", proc, expected, actual)
             },
             AstWalkError::VariableTypeMissing(var) => format!("Missing type for variable: `{}`", var),
+            AstWalkError::MissingReturn(proc) => format!("Not all paths return a value (procedure: `{}`)", proc),
+            AstWalkError::UnreachableStmt(proc) => format!("Unreachable statement (procedure: `{}`)", proc),
             AstWalkError::NotBooleanExpr(expr) => format!("Expression `{}` isn't a Boolean expression", expr),
             AstWalkError::NotIntExpr(expr) => format!("Expression `{}` isn't an Integer expression", expr),
+            AstWalkError::NotStrExpr(expr) => format!("Expression `{}` isn't a String expression", expr),
+            AstWalkError::NotProcExpr(expr) => format!("Expression `{}` isn't a procedure", expr),
+            AstWalkError::NotStructExpr(expr) => format!("Expression `{}` isn't a struct", expr),
+            AstWalkError::UnknownStructField(name, field) => format!("Struct `{}` has no field named `{}`", name, field),
             AstWalkError::InvalidProcCallArgType(arg_index, expected, actual) =>
-                format!("expected the {} argument to be `{}` (actual: `{}`)", self.indexify_arg(*arg_index), expected.to_string(), actual.to_string())
+                format!("expected the {} argument to be `{}` (actual: `{}`)", self.indexify_arg(*arg_index), expected.to_string(), actual.to_string()),
+            AstWalkError::UnknownProcParam(proc, param) => format!("Procedure `{}` has no param named `{}`", proc, param),
+            AstWalkError::DuplicateProcCallArg(proc, param) => format!("Param `{}` is passed more than once (procedure: `{}`)", param, proc),
+            AstWalkError::MissingProcCallArg(proc, param) => format!("Missing argument for param `{}` (procedure: `{}`)", param, proc),
+            AstWalkError::InvalidProcParamDefaultType(param, expected, actual) =>
+                format!("Invalid default value for param `{}`. expected: `{}`, actual: `{}`", param, expected.to_string(), actual.to_string()),
+            AstWalkError::RecursiveProcParamDefault(proc, param) =>
+                format!("The default value of param `{}` calls procedure `{}` recursively", param, proc),
         }
     }
 }
@@ -1,7 +1,8 @@
 use crate::ast::expression::ExpressionType;
-use crate::ast::semantic::{IdGenerator, Procedure, SymbolId, SymbolTable, Variable};
+use crate::ast::semantic::{IdGenerator, Procedure, StructType, SymbolId, SymbolTable, Variable};
 use std::collections::HashMap;
 
+#[derive(Debug, Clone)]
 pub struct Environment {
     pub symbol_table: SymbolTable,
     pub id_generator: IdGenerator,
@@ -33,14 +34,18 @@ impl Environment {
         name: &str,
         params_types: Vec<ExpressionType>,
         return_type: ExpressionType,
+        parent_id: Option<SymbolId>,
     ) -> SymbolId {
         let id = self.id_generator.get_next_id();
 
         let proc = Procedure {
             id,
             name: name.to_string(),
+            params_names: vec![String::new(); params_types.len()],
+            params_defaults: vec![None; params_types.len()],
             params_types,
             return_type,
+            parent_id,
         };
 
         self.symbol_table.create_proc_symbol(proc);
@@ -48,6 +53,26 @@ impl Environment {
         id
     }
 
+    pub fn create_struct(
+        &mut self,
+        name: &str,
+        fields_names: Vec<String>,
+        fields_types: Vec<ExpressionType>,
+    ) -> SymbolId {
+        let id = self.id_generator.get_next_id();
+
+        let struct_type = StructType {
+            id,
+            name: name.to_string(),
+            fields_names,
+            fields_types,
+        };
+
+        self.symbol_table.create_struct_symbol(struct_type);
+
+        id
+    }
+
     pub fn create_tmp_var(
         &mut self,
         proc_id: SymbolId,
@@ -76,6 +101,7 @@ impl Environment {
             name: var_name.to_string(),
             var_type,
             index: Some(global_id),
+            proc_id: None,
         };
 
         self.symbol_table.create_var_symbol(var);
@@ -101,6 +127,7 @@ impl Environment {
             global: false,
             param: is_param,
             index: Some(var_index),
+            proc_id: Some(proc_id),
         };
 
         self.symbol_table.create_var_symbol(var);
@@ -1,5 +1,6 @@
 use crate::ast::semantic::SymbolId;
 
+#[derive(Debug, Clone)]
 pub struct IdGenerator {
     pub next_id: usize,
 }
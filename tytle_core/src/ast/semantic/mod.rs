@@ -1,3 +1,4 @@
+mod ast_lint;
 mod ast_typecheck;
 mod ast_walker;
 mod ast_walker_error;
@@ -5,11 +6,15 @@ mod environment;
 mod id_generator;
 mod procedure;
 mod scope;
+mod struct_type;
 mod symbol;
+mod symbol_index;
 mod symbol_table;
 mod symbol_table_generator;
+mod type_inference;
 mod variable;
 
+pub use ast_lint::*;
 pub use ast_typecheck::*;
 pub use ast_walker::*;
 pub use ast_walker_error::*;
@@ -17,7 +22,10 @@ pub use environment::Environment;
 pub use id_generator::IdGenerator;
 pub use procedure::*;
 pub use scope::*;
+pub use struct_type::*;
 pub use symbol::*;
+pub use symbol_index::*;
 pub use symbol_table::*;
 pub use symbol_table_generator::*;
+pub use type_inference::*;
 pub use variable::*;
@@ -1,12 +1,19 @@
-use crate::ast::expression::ExpressionType;
-use crate::ast::semantic::SymbolId;
+use crate::ast::expression::{Expression, ExpressionAst, ExpressionType};
+use crate::ast::semantic::{AstWalkError, SymbolId};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Procedure {
     pub id: SymbolId,
     pub name: String,
     pub params_types: Vec<ExpressionType>,
+    pub params_names: Vec<String>,
+
+    // the default value of each optional param (evaluated by the callers)
+    pub params_defaults: Vec<Option<Expression>>,
     pub return_type: ExpressionType,
+
+    // the lexically enclosing procedure (`None` for a top-level procedure)
+    pub parent_id: Option<SymbolId>,
 }
 
 impl Procedure {
@@ -15,7 +22,115 @@ impl Procedure {
             id,
             name: name.to_owned(),
             params_types: Vec::new(),
+            params_names: Vec::new(),
+            params_defaults: Vec::new(),
             return_type: ExpressionType::Unit,
+            parent_id: None,
+        }
+    }
+
+    // the type of a reference to the procedure (e.g `PROC(INT, STR): BOOL`)
+    pub fn proc_type(&self) -> ExpressionType {
+        let return_type = Box::new(self.return_type.clone());
+
+        ExpressionType::Proc(self.params_types.clone(), return_type)
+    }
+
+    // matches the arguments of a call to the procedure params.
+    // returns for each param its argument, or its default value when the call doesn't pass it
+    pub fn bind_call_args<'a>(
+        &'a self,
+        args_exprs: &'a [Expression],
+    ) -> Result<Vec<&'a Expression>, AstWalkError> {
+        let default_of = |i: usize| self.params_defaults[i].as_ref();
+
+        bind_args(&self.name, &self.params_names, default_of, args_exprs)
+    }
+
+    // for example: `MYPROC(INT, STR): BOOL` (a procedure returning `UNIT` has no `: UNIT` suffix)
+    pub fn signature(&self) -> String {
+        let params: Vec<String> = self
+            .params_types
+            .iter()
+            .map(|param_type| param_type.type_name())
+            .collect();
+
+        match self.return_type {
+            ExpressionType::Unit => format!("{}({})", self.name, params.join(", ")),
+            ref return_type => format!(
+                "{}({}): {}",
+                self.name,
+                params.join(", "),
+                return_type.type_name()
+            ),
         }
     }
 }
+
+// matches call arguments (positional first, then named) to the `params_names` of `callee`.
+// a param having no argument gets its `default_of` value
+pub fn bind_args<'a, F>(
+    callee: &str,
+    params_names: &[String],
+    default_of: F,
+    args_exprs: &'a [Expression],
+) -> Result<Vec<&'a Expression>, AstWalkError>
+where
+    F: Fn(usize) -> Option<&'a Expression>,
+{
+    let params_count = params_names.len();
+    let positional_count = args_exprs
+        .iter()
+        .filter(|arg_expr| !matches!(arg_expr.expr_ast, ExpressionAst::NamedArg(..)))
+        .count();
+
+    // too many named arguments are reported by name (e.g a param passed twice)
+    if positional_count > params_count {
+        let err = AstWalkError::InvalidProcCallArgsCount(
+            callee.to_string(),
+            params_count,
+            args_exprs.len(),
+        );
+        return Err(err);
+    }
+
+    let mut bound_args: Vec<Option<&Expression>> = vec![None; params_count];
+
+    // the positional arguments come first (the parser rejects a positional after a named one)
+    for (i, arg_expr) in args_exprs.iter().enumerate() {
+        let (param_index, arg_expr) = match arg_expr.expr_ast {
+            ExpressionAst::NamedArg(ref param_name, ref arg_expr) => {
+                match params_names.iter().position(|name| name == param_name) {
+                    Some(param_index) => (param_index, &**arg_expr),
+                    None => {
+                        let err =
+                            AstWalkError::UnknownProcParam(callee.to_string(), param_name.clone());
+                        return Err(err);
+                    }
+                }
+            }
+            _ => (i, arg_expr),
+        };
+
+        if bound_args[param_index].is_some() {
+            let param_name = params_names[param_index].clone();
+            let err = AstWalkError::DuplicateProcCallArg(callee.to_string(), param_name);
+            return Err(err);
+        }
+
+        bound_args[param_index] = Some(arg_expr);
+    }
+
+    bound_args
+        .into_iter()
+        .enumerate()
+        .map(|(i, arg_expr)| match arg_expr.or_else(|| default_of(i)) {
+            Some(arg_expr) => Ok(arg_expr),
+            None => {
+                let param_name = params_names[i].clone();
+                let err = AstWalkError::MissingProcCallArg(callee.to_string(), param_name);
+                Err(err)
+            }
+        })
+        .collect()
+}
@@ -25,6 +25,7 @@ impl Scope {
 
         symbols.insert(SymbolKind::Var, HashMap::new());
         symbols.insert(SymbolKind::Proc, HashMap::new());
+        symbols.insert(SymbolKind::Type, HashMap::new());
 
         Self {
             id,
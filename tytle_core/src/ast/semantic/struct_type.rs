@@ -0,0 +1,53 @@
+use crate::ast::expression::{Expression, ExpressionType};
+use crate::ast::semantic::{bind_args, AstWalkError, SymbolId};
+
+// a user-defined record type (e.g `STRUCT POINT [X: INT, Y: INT]`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructType {
+    pub id: SymbolId,
+    pub name: String,
+    pub fields_names: Vec<String>,
+    pub fields_types: Vec<ExpressionType>,
+}
+
+impl StructType {
+    pub fn new(name: &str, id: SymbolId) -> Self {
+        Self {
+            id,
+            name: name.to_owned(),
+            fields_names: Vec::new(),
+            fields_types: Vec::new(),
+        }
+    }
+
+    pub fn field_index(&self, field_name: &str) -> Option<usize> {
+        self.fields_names.iter().position(|name| name == field_name)
+    }
+
+    pub fn field_type(&self, field_name: &str) -> Option<&ExpressionType> {
+        let index = self.field_index(field_name)?;
+
+        self.fields_types.get(index)
+    }
+
+    // a record is constructed like calling a procedure whose params are the record fields
+    // (e.g `POINT(10, Y: 20)`). returns the argument of each field
+    pub fn bind_fields_args<'a>(
+        &'a self,
+        args_exprs: &'a [Expression],
+    ) -> Result<Vec<&'a Expression>, AstWalkError> {
+        bind_args(&self.name, &self.fields_names, |_| None, args_exprs)
+    }
+
+    // for example: `POINT [X: INT, Y: INT]`
+    pub fn signature(&self) -> String {
+        let fields: Vec<String> = self
+            .fields_names
+            .iter()
+            .zip(self.fields_types.iter())
+            .map(|(name, field_type)| format!("{}: {}", name, field_type.type_name()))
+            .collect();
+
+        format!("{} [{}]", self.name, fields.join(", "))
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ast::semantic::{Procedure, Variable};
+use crate::ast::semantic::{Procedure, StructType, Variable};
 use std::fmt;
 
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq)]
@@ -14,6 +14,7 @@ impl fmt::Display for SymbolId {
 pub enum Symbol {
     Var(Variable),
     Proc(Procedure),
+    Struct(StructType),
 }
 
 impl Symbol {
@@ -21,6 +22,7 @@ impl Symbol {
         match *self {
             Symbol::Var(_) => &SymbolKind::Var,
             Symbol::Proc(_) => &SymbolKind::Proc,
+            Symbol::Struct(_) => &SymbolKind::Type,
         }
     }
 
@@ -55,12 +57,21 @@ impl Symbol {
             panic!("expected symbol `{}` to be a Procedure", self.name());
         }
     }
+
+    pub fn as_struct(&self) -> &StructType {
+        if let Symbol::Struct(struct_type) = self {
+            struct_type
+        } else {
+            panic!("expected symbol `{}` to be a Struct", self.name());
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum SymbolKind {
     Var,
     Proc,
+    Type,
 }
 
 impl Symbol {
@@ -68,6 +79,35 @@ impl Symbol {
         match self {
             Symbol::Var(ref var) => var.name.to_owned(),
             Symbol::Proc(ref proc) => proc.name.to_owned(),
+            Symbol::Struct(ref struct_type) => struct_type.name.to_owned(),
+        }
+    }
+
+    pub fn id(&self) -> SymbolId {
+        match self {
+            Symbol::Var(ref var) => var.id,
+            Symbol::Proc(ref proc) => proc.id,
+            Symbol::Struct(ref struct_type) => struct_type.id,
+        }
+    }
+
+    // the symbol kind followed by its signature. for example:
+    // `proc MYPROC(INT): BOOL`, `param A: INT` or `struct POINT [X: INT, Y: INT]`
+    pub fn describe(&self) -> String {
+        match self {
+            Symbol::Proc(proc) => format!("proc {}", proc.signature()),
+            Symbol::Struct(struct_type) => format!("struct {}", struct_type.signature()),
+            Symbol::Var(var) => {
+                let kind = if var.global {
+                    "global"
+                } else if var.param {
+                    "param"
+                } else {
+                    "local"
+                };
+
+                format!("{} {}", kind, var.signature())
+            }
         }
     }
 }
@@ -0,0 +1,263 @@
+use crate::ast::expression::{Expression, ExpressionAst, LiteralExpr};
+use crate::ast::semantic::{Environment, Symbol, SymbolId};
+use crate::ast::statement::*;
+use crate::ast::Ast;
+use crate::lexer::{Lexer, Location, Token, TytleLexer};
+use std::collections::HashMap;
+
+// an occurrence of a symbol name within the source code
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolRef {
+    pub symbol_id: SymbolId,
+    pub name: String,
+
+    // the location of the name first character
+    pub location: Location,
+
+    // whether the occurrence declares the symbol (e.g `TO SQUARE(...)` or `MAKELOCAL A = 1`)
+    pub is_decl: bool,
+}
+
+// a name referenced by a statement, `None` stands for a name which isn't a symbol (e.g a record field)
+type StmtName = (String, Option<(SymbolId, bool)>);
+
+// locates the occurrences of the procedures, variables and structs within the source code.
+// the AST has only the statements locations, so the names referenced by each statement
+// are matched (in their source order) against the name tokens following the statement location.
+// it expects an AST whose symbols are resolved (see `SymbolTableGenerator`), a name left unresolved isn't indexed
+pub struct SymbolIndex {
+    refs: Vec<SymbolRef>,
+}
+
+impl SymbolIndex {
+    pub fn build(code: &str, ast: &Ast, env: &Environment) -> Self {
+        let mut index = Self { refs: Vec::new() };
+
+        let tokens = Self::lex_names(code);
+
+        // the params aren't resolved within the AST, so they're looked up by their procedure
+        let params_ids: HashMap<(SymbolId, String), SymbolId> = env
+            .symbol_table
+            .get_symbols()
+            .into_iter()
+            .filter_map(|symbol| match symbol {
+                Symbol::Var(var) if var.param => var
+                    .proc_id
+                    .map(|proc_id| ((proc_id, var.name.clone()), var.id)),
+                _ => None,
+            })
+            .collect();
+
+        index.index_stmts(&tokens, &params_ids, &ast.statements, &ast.locations);
+
+        index
+            .refs
+            .sort_by_key(|sym_ref| (sym_ref.location.line(), sym_ref.location.column()));
+
+        index
+    }
+
+    pub fn get_refs(&self) -> &[SymbolRef] {
+        &self.refs
+    }
+
+    // the occurrence whose name is at `location` (a location right after the name counts too)
+    pub fn ref_at(&self, location: Location) -> Option<&SymbolRef> {
+        self.refs.iter().find(|sym_ref| {
+            let start = sym_ref.location.column();
+            let end = start + sym_ref.name.chars().count();
+
+            sym_ref.location.line() == location.line()
+                && start <= location.column()
+                && location.column() <= end
+        })
+    }
+
+    pub fn get_decl(&self, symbol_id: SymbolId) -> Option<&SymbolRef> {
+        self.refs
+            .iter()
+            .find(|sym_ref| sym_ref.symbol_id == symbol_id && sym_ref.is_decl)
+    }
+
+    fn lex_names(code: &str) -> Vec<(String, Location)> {
+        let mut lexer = TytleLexer::new(code);
+        let mut names = Vec::new();
+
+        while let Some((token, location)) = lexer.pop_current_token() {
+            if let Token::VALUE(name) = token {
+                names.push((name, location));
+            }
+        }
+
+        names
+    }
+
+    fn index_stmts(
+        &mut self,
+        tokens: &[(String, Location)],
+        params_ids: &HashMap<(SymbolId, String), SymbolId>,
+        stmts: &[Statement],
+        locations: &[Location],
+    ) {
+        for (stmt, location) in stmts.iter().zip(locations.iter()) {
+            let mut names = Vec::new();
+            Self::stmt_names(params_ids, stmt, &mut names);
+
+            self.match_names(tokens, *location, names);
+
+            match stmt {
+                Statement::If(if_stmt) => {
+                    let block = &if_stmt.true_block;
+                    self.index_stmts(tokens, params_ids, &block.stmts, &block.locations);
+
+                    if let Some(ref block) = if_stmt.false_block {
+                        self.index_stmts(tokens, params_ids, &block.stmts, &block.locations);
+                    }
+                }
+                Statement::Repeat(RepeatStmt { block, .. })
+                | Statement::Procedure(ProcedureStmt { block, .. }) => {
+                    self.index_stmts(tokens, params_ids, &block.stmts, &block.locations);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // each name is looked for starting right after the previous one (a name which isn't found is skipped)
+    fn match_names(
+        &mut self,
+        tokens: &[(String, Location)],
+        stmt_location: Location,
+        names: Vec<StmtName>,
+    ) {
+        let start_key = (stmt_location.line(), stmt_location.column());
+        let mut pos = tokens
+            .iter()
+            .position(|(_, loc)| (loc.line(), loc.column()) >= start_key)
+            .unwrap_or(tokens.len());
+
+        for (name, symbol) in names {
+            let found = tokens[pos..]
+                .iter()
+                .position(|(token_name, _)| *token_name == name);
+
+            if let Some(offset) = found {
+                pos += offset;
+
+                if let Some((symbol_id, is_decl)) = symbol {
+                    self.refs.push(SymbolRef {
+                        symbol_id,
+                        name,
+                        location: tokens[pos].1,
+                        is_decl,
+                    });
+                }
+
+                pos += 1;
+            }
+        }
+    }
+
+    // the names of a statement, not including the statements of its blocks
+    fn stmt_names(
+        params_ids: &HashMap<(SymbolId, String), SymbolId>,
+        stmt: &Statement,
+        names: &mut Vec<StmtName>,
+    ) {
+        match stmt {
+            Statement::Procedure(proc_stmt) => {
+                names.push(Self::name(&proc_stmt.name, proc_stmt.id, true));
+
+                for param in proc_stmt.params.iter() {
+                    let param_id = proc_stmt.id.and_then(|proc_id| {
+                        params_ids
+                            .get(&(proc_id, param.param_name.clone()))
+                            .cloned()
+                    });
+
+                    names.push(Self::name(&param.param_name, param_id, true));
+
+                    if let Some(ref default_expr) = param.default_expr {
+                        Self::expr_names(default_expr, names);
+                    }
+                }
+            }
+            Statement::Struct(struct_stmt) => {
+                names.push(Self::name(&struct_stmt.name, struct_stmt.id, true));
+            }
+            Statement::Make(make_stmt) => {
+                let is_decl = make_stmt.kind != MakeStmtKind::Assign;
+
+                names.push(Self::name(&make_stmt.var_name, make_stmt.var_id, is_decl));
+                Self::expr_names(&make_stmt.expr, names);
+            }
+            Statement::SetField(set_field_stmt) => {
+                Self::expr_names(&set_field_stmt.record_expr, names);
+                names.push(Self::name(&set_field_stmt.field_name, None, false));
+                Self::expr_names(&set_field_stmt.expr, names);
+            }
+            Statement::If(if_stmt) => Self::expr_names(&if_stmt.cond_expr, names),
+            Statement::Repeat(repeat_stmt) => Self::expr_names(&repeat_stmt.count_expr, names),
+            Statement::Map(map_stmt) => {
+                Self::expr_names(&map_stmt.proc_expr, names);
+                Self::expr_names(&map_stmt.count_expr, names);
+            }
+            Statement::Direction(direct_stmt) => Self::expr_names(&direct_stmt.expr, names),
+            Statement::Return(ReturnStmt { expr: Some(expr) })
+            | Statement::Expression(expr)
+            | Statement::Print(expr)
+            | Statement::Label(expr)
+            | Statement::SetFontSize(expr) => Self::expr_names(expr, names),
+            _ => (),
+        }
+    }
+
+    fn expr_names(expr: &Expression, names: &mut Vec<StmtName>) {
+        match expr.expr_ast {
+            ExpressionAst::Literal(LiteralExpr::Var(ref name, var_id)) => {
+                names.push(Self::name(name, var_id, false));
+            }
+            ExpressionAst::Literal(LiteralExpr::Proc(ref name, proc_id)) => {
+                names.push(Self::name(name, proc_id, false));
+            }
+            ExpressionAst::Literal(_) => (),
+            ExpressionAst::ProcCall(ref name, ref args, proc_id) => {
+                names.push(Self::name(name, proc_id, false));
+                Self::exprs_names(args, names);
+            }
+            ExpressionAst::NewStruct(ref name, ref args, struct_id) => {
+                names.push(Self::name(name, struct_id, false));
+                Self::exprs_names(args, names);
+            }
+            ExpressionAst::IndirectCall(ref proc_expr, ref args) => {
+                Self::expr_names(proc_expr, names);
+                Self::exprs_names(args, names);
+            }
+            ExpressionAst::NamedArg(ref param_name, ref arg_expr) => {
+                names.push(Self::name(param_name, None, false));
+                Self::expr_names(arg_expr, names);
+            }
+            ExpressionAst::Field(ref record_expr, ref field_name) => {
+                Self::expr_names(record_expr, names);
+                names.push(Self::name(field_name, None, false));
+            }
+            ExpressionAst::Binary(_, ref lexpr, ref rexpr) => {
+                Self::expr_names(lexpr, names);
+                Self::expr_names(rexpr, names);
+            }
+            ExpressionAst::Not(ref expr) | ExpressionAst::Parentheses(ref expr) => {
+                Self::expr_names(expr, names);
+            }
+        }
+    }
+
+    fn exprs_names(exprs: &[Expression], names: &mut Vec<StmtName>) {
+        for expr in exprs.iter() {
+            Self::expr_names(expr, names);
+        }
+    }
+
+    fn name(name: &str, symbol_id: Option<SymbolId>, is_decl: bool) -> StmtName {
+        (name.to_string(), symbol_id.map(|id| (id, is_decl)))
+    }
+}
@@ -105,6 +105,17 @@ impl SymbolTable {
         symbol.unwrap().as_proc_mut()
     }
 
+    pub fn get_struct_by_id(&self, struct_id: SymbolId) -> &StructType {
+        let symbol = self.lookup_by_symbol_id(struct_id);
+        symbol.unwrap().as_struct()
+    }
+
+    // structs are declared at the top-level only
+    pub fn get_struct_by_name(&self, struct_name: &str) -> Option<&StructType> {
+        let symbol = self.lookup(0, struct_name, &SymbolKind::Type);
+        symbol.map(|symbol| symbol.as_struct())
+    }
+
     pub fn lookup(
         &self,
         scope_id: ScopeId,
@@ -162,7 +173,7 @@ impl SymbolTable {
     }
 
     pub fn create_proc_symbol(&mut self, proc: Procedure) {
-        let proc_sym = self.lookup(self.next_scope_depth, &proc.name, &SymbolKind::Proc);
+        let proc_sym = self.lookup(self.get_current_scope_id(), &proc.name, &SymbolKind::Proc);
 
         if proc_sym.is_some() {
             panic!("Procedure `{}` already exists under the scope", proc.name);
@@ -171,13 +182,52 @@ impl SymbolTable {
         self.store_proc(proc);
     }
 
+    pub fn create_struct_symbol(&mut self, struct_type: StructType) {
+        let struct_sym = self.lookup(
+            self.get_current_scope_id(),
+            &struct_type.name,
+            &SymbolKind::Type,
+        );
+
+        if struct_sym.is_some() {
+            panic!(
+                "Struct `{}` already exists under the scope",
+                struct_type.name
+            );
+        }
+
+        let struct_id = struct_type.id;
+        let struct_name = struct_type.name.to_string();
+
+        self.store_symbol(struct_name, struct_id, Symbol::Struct(struct_type));
+    }
+
+    // all the symbols, ordered by id
+    pub fn get_symbols(&self) -> Vec<&Symbol> {
+        let mut symbols: Vec<&Symbol> = self.symbols.values().collect();
+
+        symbols.sort_by_key(|symbol| symbol.id().0);
+
+        symbols
+    }
+
+    // a listing of all the symbols (ordered by id), one symbol per line. for example:
+    // `#1 proc MYPROC(INT): BOOL`
+    // `#2 param A: INT`
+    pub fn dump(&self) -> String {
+        self.get_symbols()
+            .iter()
+            .map(|symbol| format!("{} {}\n", symbol.id(), symbol.describe()))
+            .collect()
+    }
+
     pub fn get_proc_by_name(&self, proc_name: &str) -> &Procedure {
         let symbol = self.lookup(0, proc_name, &SymbolKind::Proc);
         symbol.unwrap().as_proc()
     }
 
     pub fn get_current_scope_id(&self) -> usize {
-        self.next_scope_id - 1
+        self.get_current_scope().id
     }
 
     pub fn get_next_scope_parent_id(&self) -> Option<usize> {
@@ -229,7 +279,7 @@ impl SymbolTable {
         self.symbols.insert(symbol_id, symbol);
     }
 
-    fn lookup_by_symbol_id(&self, symbol_id: SymbolId) -> Option<&Symbol> {
+    pub fn lookup_by_symbol_id(&self, symbol_id: SymbolId) -> Option<&Symbol> {
         self.symbols.get(&symbol_id)
     }
 
@@ -1,27 +1,73 @@
 use crate::ast::semantic::*;
 use crate::ast::Ast;
 use crate::ast::{expression::*, statement::*};
+use crate::compiler::Diagnostic;
+use crate::lexer::Location;
+use std::collections::HashSet;
 
 pub struct SymbolTableGenerator {
     env: Environment,
+
+    // `Some` when recovering from errors (see `generate_recover`)
+    diagnostics: Option<Vec<Diagnostic>>,
+
+    // the scopes of the procedures being walked (innermost last)
+    procs_scopes: Vec<ScopeId>,
+
+    // the procedures whose declaration has failed (e.g an undeclared param type).
+    // the error is reported once, so their calls don't report them as missing
+    failed_procs: HashSet<String>,
 }
 
 type EnvironmentResult = Result<Environment, AstWalkError>;
 
 impl AstWalker for SymbolTableGenerator {
+    fn on_stmt_error(&mut self, err: AstWalkError, location: Option<Location>) -> AstWalkResult {
+        if let AstWalkError::MissingProcDeclaration(ref proc_name) = err {
+            if self.failed_procs.contains(proc_name) {
+                return Ok(());
+            }
+        }
+
+        match self.diagnostics {
+            Some(ref mut diagnostics) => {
+                diagnostics.push(Diagnostic::ast_walk(err, location));
+                Ok(())
+            }
+            None => Err(err),
+        }
+    }
+
     fn on_make_global_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
         if self.env.symbol_table.is_inner_scope() {
             let err = AstWalkError::ProcNotAllowedToDeclareGlobals(make_stmt.var_name.to_string());
             Err(err)
         } else {
-            Ok(())
+            self.check_var_type_declared(make_stmt)
         }
     }
 
     fn on_make_local_stmt(&mut self, ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        if let Err(err) = self.check_var_type_declared(make_stmt) {
+            // the variable is still declared (its type is unknown to `AstTypeCheck`),
+            // so its uses don't report it as missing
+            let _ = self.create_local_var_symbol(ctx_proc, make_stmt);
+            return Err(err);
+        }
+
         self.create_local_var_symbol(ctx_proc, make_stmt)
     }
 
+    fn on_struct_stmt(&mut self, _ctx_proc: &str, struct_stmt: &mut StructStmt) -> AstWalkResult {
+        // the top-level structs are already declared by `declare_ast`
+        if self.env.symbol_table.is_inner_scope() {
+            let err = AstWalkError::NestedStructDeclaration(struct_stmt.name.to_string());
+            Err(err)
+        } else {
+            Ok(())
+        }
+    }
+
     fn on_make_assign_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
         let var = self.get_var_symbol(&make_stmt.var_name)?;
 
@@ -33,9 +79,43 @@ impl AstWalker for SymbolTableGenerator {
     fn on_proc_call_expr(&mut self, _ctx_proc: &str, expr: &mut Expression) -> AstWalkResult {
         let (proc_name, _proc_args, proc_id) = expr.as_proc_call_expr_mut();
 
-        let proc = self.env.symbol_table.get_proc_by_name(&proc_name);
+        let proc_err = match self.get_proc_symbol(proc_name) {
+            Ok(proc) => {
+                proc_id.replace(proc.id);
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+
+        // calling a struct name constructs a record (e.g `POINT(X: 10, Y: 20)`)
+        if let Some(struct_type) = self.env.symbol_table.get_struct_by_name(proc_name) {
+            let struct_id = struct_type.id;
+            let (struct_name, fields_args, _) = expr.as_proc_call_expr_mut();
+
+            let struct_name = struct_name.clone();
+            let fields_args = std::mem::take(fields_args);
+
+            expr.expr_ast = ExpressionAst::NewStruct(struct_name, fields_args, Some(struct_id));
+
+            return Ok(());
+        }
+
+        // calling a variable holding a procedure (e.g `F(10)`) is an indirect call
+        let var_id = match self.try_get_symbol_recur(proc_name, SymbolKind::Var) {
+            Some(Symbol::Var(var)) => var.id,
+            _ => return Err(proc_err),
+        };
+
+        let (proc_name, proc_args, _) = expr.as_proc_call_expr_mut();
 
-        proc_id.replace(proc.id);
+        let proc_lit = LiteralExpr::Var(proc_name.clone(), Some(var_id));
+        let proc_expr = Expression {
+            expr_ast: ExpressionAst::Literal(proc_lit),
+            expr_type: None,
+        };
+        let proc_args = std::mem::take(proc_args);
+
+        expr.expr_ast = ExpressionAst::IndirectCall(Box::new(proc_expr), proc_args);
 
         Ok(())
     }
@@ -64,12 +144,37 @@ impl AstWalker for SymbolTableGenerator {
         }
     }
 
-    fn on_proc_start(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+    fn on_proc_start(&mut self, _ctx_proc: &str, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        let current_scope_id = self.env.symbol_table.get_current_scope_id();
+
+        if self.env.symbol_table.is_inner_scope()
+            && self.procs_scopes.last() != Some(&current_scope_id)
+        {
+            // a procedure declared within an `IF` / `REPEAT` block
+            let err = AstWalkError::NestedProcDeclaration(proc_stmt.name.to_string());
+            return Err(err);
+        }
+
+        // the params default values are walked before the procedure starts, so they're resolved by now.
+        // (`AstTypeCheck` follows the calls within the defaults of any procedure)
+        if let Some(proc_id) = proc_stmt.id {
+            let proc = self.env.symbol_table.get_proc_by_id_mut(proc_id);
+
+            for (i, param) in proc_stmt.params.iter().enumerate() {
+                proc.params_defaults[i] = param.default_expr.clone();
+            }
+        }
+
         self.start_scope();
-        Ok(())
+
+        let scope_id = self.env.symbol_table.get_current_scope_id();
+        self.procs_scopes.push(scope_id);
+
+        self.declare_proc_block(&proc_stmt.name, &mut proc_stmt.block)
     }
 
     fn on_proc_end(&mut self, _ctx_proc: &str, _proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+        self.procs_scopes.pop();
         self.end_scope();
         Ok(())
     }
@@ -97,16 +202,21 @@ impl AstWalker for SymbolTableGenerator {
 
         match lit_expr {
             LiteralExpr::Var(var_name, var_id) => {
-                let var = self.get_var_symbol(var_name);
+                let var_err = match self.get_var_symbol(var_name) {
+                    Ok(var) => {
+                        var_id.replace(var.id);
+                        return Ok(());
+                    }
+                    Err(err) => err,
+                };
 
-                if var.is_ok() {
-                    let var = var.unwrap();
+                // a procedure name used as a value (e.g `DRAW(SQUARE, 3)`)
+                let proc_id = match self.try_get_symbol_recur(var_name, SymbolKind::Proc) {
+                    Some(Symbol::Proc(proc)) => proc.id,
+                    _ => return Err(var_err),
+                };
 
-                    var_id.replace(var.id);
-                } else {
-                    // TODO: return error if variable isn't found
-                    unimplemented!()
-                }
+                *lit_expr = LiteralExpr::Proc(var_name.clone(), Some(proc_id));
             }
             _ => {}
         };
@@ -117,39 +227,105 @@ impl AstWalker for SymbolTableGenerator {
 
 impl SymbolTableGenerator {
     pub fn new() -> Self {
+        Self::with_env(Environment::new())
+    }
+
+    // extends an `Environment` returned by a previous `generate`,
+    // so that code compiled later can use its globals and procedures
+    pub fn with_env(env: Environment) -> Self {
         Self {
-            env: Environment::new(),
+            env,
+            diagnostics: None,
+            procs_scopes: Vec::new(),
+            failed_procs: HashSet::new(),
         }
     }
 
+    // the symbols are generated in two phases:
+    // 1. `declare_ast` declares the structs, the procedures and the globals
+    // 2. walking the AST resolves the procedure calls and the variables (and declares the locals).
+    //    the procedures nested within a procedure are declared when starting to walk it
+    //
+    // so a procedure can be called before its `TO` definition (e.g from `__main__` code at the top),
+    // and procedures can be mutually recursive
     pub fn generate(mut self, ast: &mut Ast) -> EnvironmentResult {
         self.generate_main_symbol();
-        self.prewalk_ast(ast)?;
+        self.declare_ast(ast)?;
         self.walk_ast(ast)?;
 
         Ok(self.env)
     }
 
-    pub fn prewalk_ast(&mut self, ast: &mut Ast) -> AstWalkResult {
-        for stmt in &mut ast.statements {
-            match stmt {
+    // generates the symbols without stopping on the first error.
+    // a statement having an error is skipped and the returned `Environment` is a best-effort one
+    pub fn generate_recover(mut self, ast: &mut Ast) -> (Environment, Vec<Diagnostic>) {
+        self.diagnostics = Some(Vec::new());
+
+        // errors are collected by `on_stmt_error`, so the walk always completes
+        self.generate_main_symbol();
+        let _ = self.declare_ast(ast);
+        let _ = self.walk_ast(ast);
+
+        let diagnostics = self.diagnostics.take().unwrap();
+
+        (self.env, diagnostics)
+    }
+
+    // the declaration phase, only the top-level statements may declare structs, procedures and globals.
+    // the structs are declared first, so that any procedure or struct may use them as types
+    pub fn declare_ast(&mut self, ast: &mut Ast) -> AstWalkResult {
+        for (i, stmt) in ast.statements.iter_mut().enumerate() {
+            if let Statement::Struct(struct_stmt) = stmt {
+                if let Err(err) = self.create_struct_symbol(struct_stmt) {
+                    self.on_stmt_error(err, ast.locations.get(i).cloned())?;
+                }
+            }
+        }
+
+        for (i, stmt) in ast.statements.iter_mut().enumerate() {
+            if let Statement::Struct(struct_stmt) = stmt {
+                if let Err(err) = self.check_struct_fields_types(struct_stmt) {
+                    self.on_stmt_error(err, ast.locations.get(i).cloned())?;
+                }
+            }
+        }
+
+        for (i, stmt) in ast.statements.iter_mut().enumerate() {
+            let res = match stmt {
                 Statement::Make(make_stmt) => match make_stmt.kind {
                     MakeStmtKind::Global => {
                         // only `__main__` can declare globals
-                        self.create_global_var_symbol("__main__", make_stmt)?;
+                        self.create_global_var_symbol("__main__", make_stmt)
                     }
                     MakeStmtKind::Local => {
                         let err = AstWalkError::LocalsNotAllowedUnderRootScope(
                             make_stmt.var_name.clone(),
                         );
-                        return Err(err);
+                        Err(err)
                     }
                     _ => continue,
                 },
-                Statement::Procedure(proc_stmt) => {
-                    self.create_proc_symbol(proc_stmt)?;
-                }
+                Statement::Procedure(proc_stmt) => self.create_proc_symbol(proc_stmt, None),
                 _ => continue,
+            };
+
+            if let Err(err) = res {
+                self.on_stmt_error(err, ast.locations.get(i).cloned())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // declares the procedures nested directly within procedure `proc_name` body
+    fn declare_proc_block(&mut self, proc_name: &str, block: &mut BlockStatement) -> AstWalkResult {
+        let parent_id = self.get_proc_symbol(proc_name)?.id;
+
+        for (i, stmt) in block.stmts.iter_mut().enumerate() {
+            if let Statement::Procedure(proc_stmt) = stmt {
+                if let Err(err) = self.create_proc_symbol(proc_stmt, Some(parent_id)) {
+                    self.on_stmt_error(err, block.locations.get(i).cloned())?;
+                }
             }
         }
 
@@ -171,7 +347,34 @@ impl SymbolTableGenerator {
         }
     }
 
-    fn create_proc_symbol(&mut self, proc_stmt: &mut ProcedureStmt) -> AstWalkResult {
+    fn get_proc_symbol(&self, proc_name: &str) -> Result<&Procedure, AstWalkError> {
+        let symbol = self.try_get_symbol_recur(proc_name, SymbolKind::Proc);
+
+        match symbol {
+            Some(Symbol::Proc(proc)) => Ok(proc),
+            _ => {
+                let err = AstWalkError::MissingProcDeclaration(proc_name.to_owned());
+                Err(err)
+            }
+        }
+    }
+
+    fn create_proc_symbol(
+        &mut self,
+        proc_stmt: &mut ProcedureStmt,
+        parent_id: Option<SymbolId>,
+    ) -> AstWalkResult {
+        if self
+            .env
+            .symbol_table
+            .get_struct_by_name(&proc_stmt.name)
+            .is_some()
+        {
+            // a call to the procedure would construct a record instead
+            let err = AstWalkError::ProcNamedAsStruct(proc_stmt.name.to_owned());
+            return Err(err);
+        }
+
         let symbol = self.try_get_symbol_recur(&proc_stmt.name, SymbolKind::Proc);
 
         if symbol.is_none() {
@@ -184,9 +387,27 @@ impl SymbolTableGenerator {
                 .map(|param| ExpressionType::from(param.param_type.as_str()))
                 .collect::<Vec<ExpressionType>>();
 
-            let proc_id = self
-                .env
-                .create_proc(&proc_stmt.name, params_types, return_type);
+            let types_declared = std::iter::once(&return_type)
+                .chain(params_types.iter())
+                .try_for_each(|expr_type| self.check_type_declared(expr_type));
+
+            if let Err(err) = types_declared {
+                self.failed_procs.insert(proc_stmt.name.clone());
+                return Err(err);
+            }
+
+            let proc_id =
+                self.env
+                    .create_proc(&proc_stmt.name, params_types, return_type, parent_id);
+
+            // the default values are unresolved yet (`AstTypeCheck` replaces them once checked),
+            // but they already mark the optional params for the calls
+            let proc = self.env.symbol_table.get_proc_by_id_mut(proc_id);
+
+            for (i, param) in proc_stmt.params.iter().enumerate() {
+                proc.params_names[i] = param.param_name.clone();
+                proc.params_defaults[i] = param.default_expr.clone();
+            }
 
             proc_stmt.id = Some(proc_id);
 
@@ -197,6 +418,148 @@ impl SymbolTableGenerator {
         }
     }
 
+    fn create_struct_symbol(&mut self, struct_stmt: &mut StructStmt) -> AstWalkResult {
+        // a struct named `INT` would be shadowed by the built-in type
+        if !is_struct_name_available(&struct_stmt.name) {
+            let err = AstWalkError::StructNamedAsBuiltinType(struct_stmt.name.to_owned());
+            return Err(err);
+        }
+
+        if self
+            .env
+            .symbol_table
+            .get_struct_by_name(&struct_stmt.name)
+            .is_some()
+        {
+            let err = AstWalkError::DuplicateStruct(struct_stmt.name.to_owned());
+            return Err(err);
+        }
+
+        // a procedure or a global declared by a previous `generate` (see `with_env`)
+        if self
+            .try_get_symbol(&struct_stmt.name, SymbolKind::Proc)
+            .is_some()
+        {
+            let err = AstWalkError::ProcNamedAsStruct(struct_stmt.name.to_owned());
+            return Err(err);
+        }
+
+        if self
+            .try_get_symbol(&struct_stmt.name, SymbolKind::Var)
+            .is_some()
+        {
+            let err = AstWalkError::GlobalVarNamedAsStruct(struct_stmt.name.to_owned());
+            return Err(err);
+        }
+
+        let mut fields_names: Vec<String> = Vec::new();
+
+        for field in &struct_stmt.fields {
+            if fields_names.contains(&field.field_name) {
+                let err = AstWalkError::DuplicateStructField(
+                    struct_stmt.name.to_owned(),
+                    field.field_name.to_owned(),
+                );
+                return Err(err);
+            }
+
+            fields_names.push(field.field_name.clone());
+        }
+
+        let fields_types = struct_stmt
+            .fields
+            .iter()
+            .map(|field| ExpressionType::from(field.field_type.as_str()))
+            .collect();
+
+        let struct_id = self
+            .env
+            .create_struct(&struct_stmt.name, fields_names, fields_types);
+
+        struct_stmt.id = Some(struct_id);
+
+        Ok(())
+    }
+
+    fn check_struct_fields_types(&self, struct_stmt: &StructStmt) -> AstWalkResult {
+        if let Some(struct_id) = struct_stmt.id {
+            let struct_type = self.env.symbol_table.get_struct_by_id(struct_id);
+
+            for field_type in &struct_type.fields_types {
+                self.check_type_declared(field_type)?;
+            }
+
+            // a record of a recursive struct could never be constructed
+            let mut visited = HashSet::new();
+
+            if self.struct_contains(struct_id, &struct_type.name, &mut visited) {
+                let err = AstWalkError::RecursiveStruct(struct_type.name.to_owned());
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    // returns whether struct `struct_id` has a field of struct `name`, directly or within its fields structs
+    fn struct_contains(
+        &self,
+        struct_id: SymbolId,
+        name: &str,
+        visited: &mut HashSet<SymbolId>,
+    ) -> bool {
+        if !visited.insert(struct_id) {
+            return false;
+        }
+
+        let struct_type = self.env.symbol_table.get_struct_by_id(struct_id);
+
+        struct_type
+            .fields_types
+            .iter()
+            .any(|field_type| match field_type {
+                ExpressionType::Struct(field_struct) if field_struct == name => true,
+                ExpressionType::Struct(field_struct) => {
+                    match self.env.symbol_table.get_struct_by_name(field_struct) {
+                        Some(field_struct) => self.struct_contains(field_struct.id, name, visited),
+                        None => false,
+                    }
+                }
+                _ => false,
+            })
+    }
+
+    fn check_var_type_declared(&self, make_stmt: &MakeStmt) -> AstWalkResult {
+        match make_stmt.var_type {
+            Some(ref var_type) => {
+                self.check_type_declared(&ExpressionType::from(var_type.as_str()))
+            }
+            None => Ok(()),
+        }
+    }
+
+    // a struct type (possibly nested within a procedure type) must have a `STRUCT` declaration
+    fn check_type_declared(&self, expr_type: &ExpressionType) -> AstWalkResult {
+        match expr_type {
+            ExpressionType::Struct(name)
+                if self.env.symbol_table.get_struct_by_name(name).is_none() =>
+            {
+                let err = AstWalkError::MissingTypeDeclaration(name.to_owned());
+                return Err(err);
+            }
+            ExpressionType::Proc(params_types, return_type) => {
+                for param_type in params_types {
+                    self.check_type_declared(param_type)?;
+                }
+
+                self.check_type_declared(return_type)?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     fn create_global_var_symbol(
         &mut self,
         ctx_proc: &str,
@@ -204,6 +567,11 @@ impl SymbolTableGenerator {
     ) -> AstWalkResult {
         let var_name = &make_stmt.var_name;
 
+        if self.env.symbol_table.get_struct_by_name(var_name).is_some() {
+            let err = AstWalkError::GlobalVarNamedAsStruct(var_name.to_owned());
+            return Err(err);
+        }
+
         let symbol = self.try_get_symbol_recur(var_name, SymbolKind::Var);
 
         if symbol.is_none() {
@@ -251,10 +619,11 @@ impl SymbolTableGenerator {
         if is_global {
             self.env.create_global_var(var_id, var_name, var_type);
         } else {
-            let proc = self.env.symbol_table.get_proc_by_name(ctx_proc);
+            // `ctx_proc` may be a nested procedure, so it's looked-up from the current scope
+            let proc_id = self.get_proc_symbol(ctx_proc)?.id;
 
             self.env
-                .create_local_var(proc.id, var_id, var_name, var_type, is_param);
+                .create_local_var(proc_id, var_id, var_name, var_type, is_param);
         }
 
         Ok(var_id)
@@ -283,7 +652,20 @@ impl SymbolTableGenerator {
     }
 
     fn generate_main_symbol(&mut self) {
-        self.env
-            .create_proc("__main__", vec![], ExpressionType::Unit);
+        if self.env.main_proc_id.is_some() {
+            // we're extending an environment which already has a `__main__`
+            return;
+        }
+
+        let main_proc_id = self
+            .env
+            .create_proc("__main__", vec![], ExpressionType::Unit, None);
+
+        self.env.main_proc_id = Some(main_proc_id);
     }
 }
+
+// the built-in types names can't be used as struct names
+fn is_struct_name_available(name: &str) -> bool {
+    !matches!(name, "INT" | "STR" | "BOOL" | "UNIT")
+}
@@ -0,0 +1,148 @@
+use crate::ast::semantic::*;
+use crate::ast::Ast;
+use crate::ast::{expression::*, statement::*};
+use crate::lexer::Location;
+use std::collections::HashMap;
+
+// assigns each declared variable a type, out of its annotation (`MAKELOCAL A: INT = 1`)
+// or else out of its declaration expression.
+//
+// the AST is walked until no more variables get typed, so a variable may be used
+// before its declaration is walked (e.g a procedure using a global declared after it).
+// type errors aren't reported here, that's the job of `AstTypeCheck`
+pub struct TypeInference<'env> {
+    env: &'env mut Environment,
+
+    // the location of the statement currently walked
+    location: Option<Location>,
+
+    // the location of each variable declaration
+    declarations: HashMap<SymbolId, Location>,
+
+    // whether the current walk has typed a variable
+    progress: bool,
+}
+
+impl<'env> TypeInference<'env> {
+    pub fn new(env: &'env mut Environment) -> Self {
+        Self {
+            env,
+            location: None,
+            declarations: HashMap::new(),
+            progress: false,
+        }
+    }
+
+    // returns the location of each variable declaration
+    pub fn infer(mut self, ast: &mut Ast) -> HashMap<SymbolId, Location> {
+        loop {
+            self.progress = false;
+
+            // the walk never fails, all the hooks used here return `Ok`
+            let _ = self.walk_ast(ast);
+
+            if !self.progress {
+                return self.declarations;
+            }
+        }
+    }
+
+    fn infer_var_declare(&mut self, make_stmt: &MakeStmt) {
+        if make_stmt.var_id.is_none() {
+            // the declaration itself has failed (e.g a duplicate variable)
+            return;
+        }
+
+        let var_id = make_stmt.var_id.unwrap();
+
+        if let Some(location) = self.location {
+            self.declarations.entry(var_id).or_insert(location);
+        }
+
+        let var = self.env.symbol_table.get_var_by_id(var_id);
+
+        if var.var_type.is_some() {
+            return;
+        }
+
+        let var_type = match make_stmt.var_type {
+            Some(ref var_type) => Some(ExpressionType::from(var_type.as_str())),
+            None => self.infer_expr_type(&make_stmt.expr),
+        };
+
+        // a `Unit` expression can't be assigned to a variable (reported by `AstTypeCheck`)
+        if var_type.is_some() && var_type != Some(ExpressionType::Unit) {
+            let var = self.env.symbol_table.get_var_by_id_mut(var_id);
+            var.var_type = var_type;
+
+            self.progress = true;
+        }
+    }
+
+    // returns `None` when the expression type depends on a variable having no type (yet)
+    fn infer_expr_type(&self, expr: &Expression) -> Option<ExpressionType> {
+        match expr.expr_ast {
+            ExpressionAst::Literal(LiteralExpr::Bool(_)) => Some(ExpressionType::Bool),
+            ExpressionAst::Literal(LiteralExpr::Int(_)) => Some(ExpressionType::Int),
+            ExpressionAst::Literal(LiteralExpr::Str(_)) => Some(ExpressionType::Str),
+            ExpressionAst::Literal(LiteralExpr::Var(_, var_id)) => {
+                let var = self.env.symbol_table.get_var_by_id(var_id?);
+
+                var.var_type.clone()
+            }
+            ExpressionAst::Literal(LiteralExpr::Proc(_, proc_id)) => {
+                let proc = self.env.symbol_table.get_proc_by_id(proc_id?);
+
+                Some(proc.proc_type())
+            }
+            ExpressionAst::ProcCall(_, _, proc_id) => {
+                let proc = self.env.symbol_table.get_proc_by_id(proc_id?);
+
+                Some(proc.return_type.clone())
+            }
+            ExpressionAst::IndirectCall(ref proc_expr, _) => {
+                match self.infer_expr_type(proc_expr)? {
+                    ExpressionType::Proc(_, return_type) => Some(*return_type),
+                    _ => None,
+                }
+            }
+            ExpressionAst::NewStruct(ref struct_name, _, _) => {
+                Some(ExpressionType::Struct(struct_name.clone()))
+            }
+            ExpressionAst::Field(ref record_expr, ref field_name) => {
+                match self.infer_expr_type(record_expr)? {
+                    ExpressionType::Struct(struct_name) => {
+                        let struct_type = self.env.symbol_table.get_struct_by_name(&struct_name)?;
+
+                        struct_type.field_type(field_name).cloned()
+                    }
+                    _ => None,
+                }
+            }
+            ExpressionAst::Binary(ref bin_op, _, _) => Some(ExpressionType::from(bin_op)),
+            ExpressionAst::Not(_) => Some(ExpressionType::Bool),
+            ExpressionAst::Parentheses(ref inner_expr) => self.infer_expr_type(inner_expr),
+            ExpressionAst::NamedArg(_, ref arg_expr) => self.infer_expr_type(arg_expr),
+        }
+    }
+}
+
+impl<'env> AstWalker for TypeInference<'env> {
+    fn on_stmt_start(&mut self, _ctx_proc: &str, location: Option<Location>) -> AstWalkResult {
+        self.location = location;
+
+        Ok(())
+    }
+
+    fn on_make_global_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        self.infer_var_declare(make_stmt);
+
+        Ok(())
+    }
+
+    fn on_make_local_stmt(&mut self, _ctx_proc: &str, make_stmt: &mut MakeStmt) -> AstWalkResult {
+        self.infer_var_declare(make_stmt);
+
+        Ok(())
+    }
+}
@@ -12,6 +12,9 @@ pub struct Variable {
     // for global variables - `index` will be the index within the global variables
     // for local  variables - `index` will be the local index within the enclosing procedure
     pub index: Option<usize>,
+
+    // the procedure owning a local variable (`None` for a global variable)
+    pub proc_id: Option<SymbolId>,
 }
 
 impl Variable {
@@ -35,10 +38,21 @@ impl Variable {
             name: name.to_string(),
             var_type: None,
             index: None,
+            proc_id: None,
         }
     }
 
     pub fn is_param(&self) -> bool {
         self.param == true
     }
+
+    // for example: `A: INT` (`A: ?` when the variable has no type)
+    pub fn signature(&self) -> String {
+        let var_type = match self.var_type {
+            Some(ref var_type) => var_type.type_name(),
+            None => "?".to_string(),
+        };
+
+        format!("{}: {}", self.name, var_type)
+    }
 }
@@ -1,14 +1,30 @@
 use crate::ast::statement::Statement;
+use crate::lexer::Location;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct BlockStatement {
     pub stmts: Vec<Statement>,
+
+    // the source location of each statement (filled by the parser).
+    // locations aren't part of the AST equality
+    pub locations: Vec<Location>,
+
+    // the location of the block closing token (`]` or `END`), it isn't part of the AST equality either
+    pub end_location: Option<Location>,
+}
+
+impl PartialEq for BlockStatement {
+    fn eq(&self, other: &Self) -> bool {
+        self.stmts == other.stmts
+    }
 }
 
 impl BlockStatement {
     pub fn new() -> Self {
         Self {
             stmts: Default::default(),
+            locations: Default::default(),
+            end_location: None,
         }
     }
 
@@ -17,4 +33,15 @@ impl BlockStatement {
             self.stmts.push(stmt);
         }
     }
+
+    pub fn add_located_statement(&mut self, stmt: Statement, location: Location) {
+        if stmt != Statement::NOP {
+            self.stmts.push(stmt);
+            self.locations.push(location);
+        }
+    }
+
+    pub fn get_stmt_location(&self, index: usize) -> Option<Location> {
+        self.locations.get(index).cloned()
+    }
 }
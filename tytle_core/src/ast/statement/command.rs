@@ -35,4 +35,24 @@ impl Command {
             _ => None,
         }
     }
+
+    // the command keyword (the inverse of `parse`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Command::XCor => "XCOR",
+            Command::YCor => "YCOR",
+            Command::PenUp => "PENUP",
+            Command::PenDown => "PENDOWN",
+            Command::ShowTurtle => "SHOWTURTLE",
+            Command::HideTurtle => "HIDETURTLE",
+            Command::PenErase => "PENERASE",
+            Command::Clean => "CLEAN",
+            Command::ClearScreen => "CLEARSCREEN",
+            Command::SetPenColor => "SETPENCOLOR",
+            Command::SetBackgroundColor => "SETBACKGROUND",
+            Command::Wait => "WAIT",
+            Command::Stop => "STOP",
+            Command::Trap => "TRAP",
+        }
+    }
 }
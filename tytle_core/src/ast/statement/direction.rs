@@ -1,3 +1,6 @@
+use crate::parser::ParseError;
+use std::convert::TryFrom;
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Direction {
     Left,
@@ -8,16 +11,32 @@ pub enum Direction {
     SetY,
 }
 
-impl From<&str> for Direction {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Direction {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "FORWARD" => Direction::Forward,
-            "BACKWARD" => Direction::Backward,
-            "LEFT" => Direction::Left,
-            "RIGHT" => Direction::Right,
-            "SETX" => Direction::SetX,
-            "SETY" => Direction::SetY,
-            _ => panic!("Undefined direction: {}", s),
+            "FORWARD" => Ok(Direction::Forward),
+            "BACKWARD" => Ok(Direction::Backward),
+            "LEFT" => Ok(Direction::Left),
+            "RIGHT" => Ok(Direction::Right),
+            "SETX" => Ok(Direction::SetX),
+            "SETY" => Ok(Direction::SetY),
+            _ => Err(ParseError::InvalidDirection(s.to_string())),
+        }
+    }
+}
+
+impl Direction {
+    // the direction keyword (the inverse of `try_from`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Direction::Forward => "FORWARD",
+            Direction::Backward => "BACKWARD",
+            Direction::Left => "LEFT",
+            Direction::Right => "RIGHT",
+            Direction::SetX => "SETX",
+            Direction::SetY => "SETY",
         }
     }
 }
@@ -28,37 +47,39 @@ mod tests {
 
     #[test]
     fn forward() {
-        assert_eq!(Direction::from("FORWARD"), Direction::Forward);
+        assert_eq!(Ok(Direction::Forward), Direction::try_from("FORWARD"));
     }
 
     #[test]
     fn backward() {
-        assert_eq!(Direction::from("BACKWARD"), Direction::Backward);
+        assert_eq!(Ok(Direction::Backward), Direction::try_from("BACKWARD"));
     }
 
     #[test]
     fn left() {
-        assert_eq!(Direction::from("LEFT"), Direction::Left);
+        assert_eq!(Ok(Direction::Left), Direction::try_from("LEFT"));
     }
 
     #[test]
     fn right() {
-        assert_eq!(Direction::from("RIGHT"), Direction::Right);
+        assert_eq!(Ok(Direction::Right), Direction::try_from("RIGHT"));
     }
 
     #[test]
     fn set_x() {
-        assert_eq!(Direction::from("SETX"), Direction::SetX);
+        assert_eq!(Ok(Direction::SetX), Direction::try_from("SETX"));
     }
 
     #[test]
     fn set_y() {
-        assert_eq!(Direction::from("SETY"), Direction::SetY);
+        assert_eq!(Ok(Direction::SetY), Direction::try_from("SETY"));
     }
 
     #[test]
-    #[should_panic]
     fn invalid() {
-        Direction::from("INVALID");
+        assert_eq!(
+            Err(ParseError::InvalidDirection("INVALID".to_string())),
+            Direction::try_from("INVALID")
+        );
     }
 }
@@ -13,5 +13,10 @@ pub struct MakeStmt {
     pub kind: MakeStmtKind,
     pub var_name: String,
     pub var_id: Option<SymbolId>,
+
+    // the type annotation of a declaration (e.g `INT` for `MAKELOCAL A: INT = 1`).
+    // when `None` the variable type is inferred out of its expression
+    pub var_type: Option<String>,
+
     pub expr: Expression,
 }
@@ -0,0 +1,8 @@
+use crate::ast::expression::Expression;
+
+// `MAP(PROC_EXPR, COUNT_EXPR)` calls the procedure with `0, 1, ..., COUNT - 1`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapStmt {
+    pub proc_expr: Expression,
+    pub count_expr: Expression,
+}
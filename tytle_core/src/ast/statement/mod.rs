@@ -4,10 +4,13 @@ mod direction;
 mod direction_stmt;
 mod if_stmt;
 mod make_stmt;
+mod map_stmt;
 mod procedure_stmt;
 mod repeat_stmt;
 mod return_stmt;
+mod set_field_stmt;
 mod stmt;
+mod struct_stmt;
 
 pub use block_stmt::BlockStatement;
 pub use command::Command;
@@ -15,7 +18,10 @@ pub use direction::Direction;
 pub use direction_stmt::DirectionStmt;
 pub use if_stmt::IfStmt;
 pub use make_stmt::*;
+pub use map_stmt::MapStmt;
 pub use procedure_stmt::{ProcParam, ProcedureStmt};
 pub use repeat_stmt::RepeatStmt;
 pub use return_stmt::ReturnStmt;
+pub use set_field_stmt::SetFieldStmt;
 pub use stmt::Statement;
+pub use struct_stmt::{StructField, StructStmt};
@@ -1,3 +1,4 @@
+use crate::ast::expression::Expression;
 use crate::ast::semantic::SymbolId;
 use crate::ast::statement::BlockStatement;
 use std::default::Default;
@@ -6,6 +7,9 @@ use std::default::Default;
 pub struct ProcParam {
     pub param_name: String,
     pub param_type: String,
+
+    // the value used when a call doesn't pass the param (e.g `TO SQUARE(SIZE: INT = 50)`)
+    pub default_expr: Option<Expression>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -0,0 +1,10 @@
+use crate::ast::expression::Expression;
+
+// `MAKE P.X = 10` assigns `10` to the field `X` of the record `P`.
+// `record_expr` may itself be a field access (e.g `P.A` for `MAKE P.A.X = 10`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetFieldStmt {
+    pub record_expr: Expression,
+    pub field_name: String,
+    pub expr: Expression,
+}
@@ -1,7 +1,8 @@
 use crate::ast::expression::Expression;
 
 use crate::ast::statement::{
-    Command, DirectionStmt, IfStmt, MakeStmt, ProcedureStmt, RepeatStmt, ReturnStmt,
+    Command, DirectionStmt, IfStmt, MakeStmt, MapStmt, ProcedureStmt, RepeatStmt, ReturnStmt,
+    SetFieldStmt, StructStmt,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -10,12 +11,17 @@ pub enum Statement {
     EOF,
     Expression(Expression),
     Print(Expression),
+    Label(Expression),
+    SetFontSize(Expression),
     Command(Command),
     Direction(DirectionStmt),
     Make(MakeStmt),
+    SetField(SetFieldStmt),
     If(IfStmt),
     Repeat(RepeatStmt),
+    Map(MapStmt),
     Procedure(ProcedureStmt),
+    Struct(StructStmt),
     Return(ReturnStmt),
 }
 
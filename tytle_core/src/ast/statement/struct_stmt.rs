@@ -0,0 +1,25 @@
+use crate::ast::semantic::SymbolId;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructField {
+    pub field_name: String,
+    pub field_type: String,
+}
+
+// `STRUCT POINT [X: INT, Y: INT]` declares a record type
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructStmt {
+    pub id: Option<SymbolId>,
+    pub name: String,
+    pub fields: Vec<StructField>,
+}
+
+impl StructStmt {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            id: None,
+            fields: Vec::new(),
+        }
+    }
+}
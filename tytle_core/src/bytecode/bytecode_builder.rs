@@ -0,0 +1,330 @@
+use crate::ast::expression::ExpressionType;
+use crate::ast::semantic::{Environment, SymbolId, Variable};
+use crate::bytecode::{BytecodeConst, BytecodeOp, BytecodeProc, BytecodeProgram, BytecodeStruct};
+use crate::ir::{CfgInstruction, CfgJumpType, CfgNodeId, CfgObject};
+use std::collections::HashMap;
+
+// lowers a `CfgObject` into a `BytecodeProgram`:
+// * the CFG nodes are laid out one after the other (ordered by their id),
+//   and their edges are turned into jumps to the `pc` of the destination node
+// * the variables are resolved into a global index, or a local index along with
+//   the number of static links to follow for reaching the frame owning it
+pub struct BytecodeBuilder<'cfg, 'env> {
+    cfg: &'cfg CfgObject,
+    env: &'env Environment,
+    code: Vec<BytecodeOp>,
+    nodes_procs: HashMap<CfgNodeId, SymbolId>,
+    nodes_pcs: HashMap<CfgNodeId, usize>,
+
+    // the jumps ops `pc`s along with the node they jump to (patched once all nodes are laid out)
+    jumps: Vec<(usize, CfgNodeId)>,
+    procs_indexes: HashMap<SymbolId, usize>,
+    structs: Vec<BytecodeStruct>,
+    structs_indexes: HashMap<SymbolId, usize>,
+}
+
+impl<'cfg, 'env> BytecodeBuilder<'cfg, 'env> {
+    pub fn new(cfg: &'cfg CfgObject, env: &'env Environment) -> Self {
+        Self {
+            cfg,
+            env,
+            code: Vec::new(),
+            nodes_procs: cfg.get_nodes_procs(),
+            nodes_pcs: HashMap::new(),
+            jumps: Vec::new(),
+            procs_indexes: HashMap::new(),
+            structs: Vec::new(),
+            structs_indexes: HashMap::new(),
+        }
+    }
+
+    pub fn build(mut self) -> BytecodeProgram {
+        let mut procs_nodes: Vec<(CfgNodeId, SymbolId)> = self
+            .cfg
+            .jmp_table
+            .iter()
+            .map(|(node_id, proc_id)| (*node_id, *proc_id))
+            .collect();
+        procs_nodes.sort_by_key(|(node_id, _)| *node_id);
+
+        for (i, (_, proc_id)) in procs_nodes.iter().enumerate() {
+            self.procs_indexes.insert(*proc_id, i);
+        }
+
+        let mut locations = Vec::new();
+        let nodes_ids = self.cfg.graph.get_nodes_ids();
+
+        for (i, node_id) in nodes_ids.iter().enumerate() {
+            let node_pc = self.code.len();
+            self.nodes_pcs.insert(*node_id, node_pc);
+
+            // each CFG instruction is lowered into a single op
+            for (ip, loc) in self.cfg.source_map.get_node_stmts(*node_id) {
+                locations.push((node_pc + ip, *loc));
+            }
+
+            let next_node_id = nodes_ids.get(i + 1).cloned();
+            self.build_node(*node_id, next_node_id);
+        }
+
+        for (pc, node_id) in self.jumps.iter() {
+            let target_pc = self.nodes_pcs[node_id];
+
+            match self.code[*pc] {
+                BytecodeOp::Jump(ref mut target)
+                | BytecodeOp::JumpIfTrue(ref mut target)
+                | BytecodeOp::JumpIfFalse(ref mut target) => *target = target_pc,
+                _ => unreachable!(),
+            }
+        }
+
+        let procs = procs_nodes
+            .iter()
+            .map(|(node_id, proc_id)| self.build_proc(self.nodes_pcs[node_id], *proc_id))
+            .collect();
+
+        let main_proc = self.env.symbol_table.get_proc_by_name("__main__");
+        let globals = self.build_globals();
+
+        BytecodeProgram {
+            main_proc: self.procs_indexes[&main_proc.id],
+            code: self.code,
+            procs,
+            structs: self.structs,
+            globals,
+            locations,
+        }
+    }
+
+    fn build_node(&mut self, node_id: CfgNodeId, next_node_id: Option<CfgNodeId>) {
+        let node = self.cfg.graph.get_node(node_id);
+        let proc_id = self.nodes_procs.get(&node_id).cloned();
+
+        for (ip, inst) in node.insts.iter().enumerate() {
+            let op = match proc_id {
+                Some(proc_id) => self.build_inst(proc_id, node_id, ip, inst),
+                None => BytecodeOp::End, // an unreachable node
+            };
+
+            self.code.push(op);
+        }
+
+        let mut when_true = None;
+        let mut fallback = None;
+        let mut always = None;
+
+        for edge in node.outgoing.iter() {
+            match edge.jmp_type {
+                CfgJumpType::WhenTrue => when_true = Some(edge.node_id),
+                CfgJumpType::Fallback => fallback = Some(edge.node_id),
+                CfgJumpType::Always => always = Some(edge.node_id),
+            }
+        }
+
+        match (when_true, fallback, always) {
+            (Some(true_id), Some(false_id), _) if Some(false_id) == next_node_id => {
+                self.push_jump(BytecodeOp::JumpIfTrue(0), true_id)
+            }
+            (Some(true_id), Some(false_id), _) if Some(true_id) == next_node_id => {
+                self.push_jump(BytecodeOp::JumpIfFalse(0), false_id)
+            }
+            (Some(true_id), Some(false_id), _) => {
+                self.push_jump(BytecodeOp::JumpIfTrue(0), true_id);
+                self.push_jump(BytecodeOp::Jump(0), false_id);
+            }
+            (Some(true_id), None, _) => {
+                self.push_jump(BytecodeOp::JumpIfTrue(0), true_id);
+                self.code.push(BytecodeOp::End);
+            }
+            (None, Some(false_id), _) => {
+                self.push_jump(BytecodeOp::JumpIfFalse(0), false_id);
+                self.code.push(BytecodeOp::End);
+            }
+            (None, None, Some(dst_id)) if Some(dst_id) == next_node_id => {}
+            (None, None, Some(dst_id)) => self.push_jump(BytecodeOp::Jump(0), dst_id),
+            (None, None, None) => match node.insts.last() {
+                Some(CfgInstruction::Return) | Some(CfgInstruction::EOC) => {}
+                // a node without edges completes the program
+                _ => self.code.push(BytecodeOp::End),
+            },
+        }
+    }
+
+    fn push_jump(&mut self, op: BytecodeOp, node_id: CfgNodeId) {
+        self.jumps.push((self.code.len(), node_id));
+        self.code.push(op);
+    }
+
+    fn build_inst(
+        &mut self,
+        proc_id: SymbolId,
+        node_id: CfgNodeId,
+        ip: usize,
+        inst: &CfgInstruction,
+    ) -> BytecodeOp {
+        match inst {
+            CfgInstruction::Command(cmd) => BytecodeOp::Command(cmd.clone()),
+            CfgInstruction::Direction(direct) => BytecodeOp::Direction(*direct),
+            CfgInstruction::Load(var_id) => {
+                let var = self.env.symbol_table.get_var_by_id(*var_id);
+
+                match self.local_hops(proc_id, var) {
+                    Some(hops) => BytecodeOp::LoadLocal(hops, var.index.unwrap()),
+                    None => BytecodeOp::LoadGlobal(var.index.unwrap()),
+                }
+            }
+            CfgInstruction::Store(var_id) => {
+                let var = self.env.symbol_table.get_var_by_id(*var_id);
+
+                match self.local_hops(proc_id, var) {
+                    Some(hops) => BytecodeOp::StoreLocal(hops, var.index.unwrap()),
+                    None => BytecodeOp::StoreGlobal(var.index.unwrap()),
+                }
+            }
+            CfgInstruction::Call(callee_node_id) => {
+                let (callee_index, hops) = self.resolve_proc(proc_id, *callee_node_id);
+
+                BytecodeOp::Call(callee_index, hops)
+            }
+            CfgInstruction::CallIndirect => BytecodeOp::CallIndirect,
+            CfgInstruction::ProcRef(proc_node_id) => {
+                let (proc_index, hops) = self.resolve_proc(proc_id, *proc_node_id);
+
+                BytecodeOp::ProcRef(proc_index, hops)
+            }
+            CfgInstruction::NewStruct(struct_id) => {
+                BytecodeOp::NewStruct(self.struct_index(*struct_id))
+            }
+            CfgInstruction::LoadField(index) => BytecodeOp::LoadField(*index),
+            CfgInstruction::StoreField(index) => BytecodeOp::StoreField(*index),
+            CfgInstruction::Bool(v) => BytecodeOp::Bool(*v),
+            CfgInstruction::Int(v) => BytecodeOp::Int(*v),
+            CfgInstruction::Str(v) => BytecodeOp::Str(v.clone()),
+            CfgInstruction::Return => BytecodeOp::Return,
+            CfgInstruction::Trap => BytecodeOp::Trap(node_id, ip),
+            CfgInstruction::Print => BytecodeOp::Print,
+            CfgInstruction::Label => BytecodeOp::Label,
+            CfgInstruction::SetFontSize => BytecodeOp::SetFontSize,
+            CfgInstruction::EOC => BytecodeOp::End,
+            CfgInstruction::Add => BytecodeOp::Add,
+            CfgInstruction::Mul => BytecodeOp::Mul,
+            CfgInstruction::Div => BytecodeOp::Div,
+            CfgInstruction::Not => BytecodeOp::Not,
+            CfgInstruction::And => BytecodeOp::And,
+            CfgInstruction::Or => BytecodeOp::Or,
+            CfgInstruction::GreaterThan => BytecodeOp::GreaterThan,
+            CfgInstruction::LessThan => BytecodeOp::LessThan,
+        }
+    }
+
+    // the number of static links between the frame of `proc_id` and the frame owning `var`
+    // (`None` for a global variable)
+    fn local_hops(&self, proc_id: SymbolId, var: &Variable) -> Option<usize> {
+        if var.global {
+            return None;
+        }
+
+        Some(self.proc_hops(proc_id, var.proc_id.unwrap()))
+    }
+
+    // the number of lexical nesting levels between procedure `proc_id` and its enclosing procedure `ancestor_id`
+    fn proc_hops(&self, proc_id: SymbolId, ancestor_id: SymbolId) -> usize {
+        let mut hops = 0;
+        let mut current_id = proc_id;
+
+        while current_id != ancestor_id {
+            let proc = self.env.symbol_table.get_proc_by_id(current_id);
+
+            current_id = proc.parent_id.unwrap();
+            hops += 1;
+        }
+
+        hops
+    }
+
+    fn resolve_proc(&self, proc_id: SymbolId, callee_node_id: CfgNodeId) -> (usize, Option<usize>) {
+        let callee_id = self.cfg.jmp_table[&callee_node_id];
+        let callee = self.env.symbol_table.get_proc_by_id(callee_id);
+
+        // a nested procedure is linked to the frame of its enclosing procedure
+        let hops = callee
+            .parent_id
+            .map(|parent_id| self.proc_hops(proc_id, parent_id));
+
+        (self.procs_indexes[&callee_id], hops)
+    }
+
+    fn struct_index(&mut self, struct_id: SymbolId) -> usize {
+        if let Some(index) = self.structs_indexes.get(&struct_id) {
+            return *index;
+        }
+
+        let struct_type = self.env.symbol_table.get_struct_by_id(struct_id);
+        let index = self.structs.len();
+
+        self.structs.push(BytecodeStruct {
+            name: struct_type.name.clone(),
+            fields_names: struct_type.fields_names.clone(),
+        });
+        self.structs_indexes.insert(struct_id, index);
+
+        index
+    }
+
+    fn build_proc(&self, entry_pc: usize, proc_id: SymbolId) -> BytecodeProc {
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        let mut params_count = 0;
+        let mut locals = Vec::new();
+
+        let locals_ids = self.env.locals_symbols.get(&proc_id).cloned();
+
+        for var_id in locals_ids.unwrap_or_default() {
+            let var = self.env.symbol_table.get_var_by_id(var_id);
+
+            if var.is_param() {
+                params_count += 1;
+                continue;
+            }
+
+            // the initial value of a local integer is `-1` (see `Interpreter::init_proc_locals`)
+            let value = match var.var_type.as_ref().unwrap() {
+                ExpressionType::Int => BytecodeConst::Int(-1),
+                _ => Self::default_value(var),
+            };
+
+            locals.push(value);
+        }
+
+        BytecodeProc {
+            name: proc.name.clone(),
+            entry_pc,
+            params_count,
+            locals,
+            returns_value: proc.return_type != ExpressionType::Unit,
+        }
+    }
+
+    fn build_globals(&self) -> Vec<BytecodeConst> {
+        (0..self.env.globals_index)
+            .map(|i| {
+                let var_id = self.env.globals_symbols[&i];
+                let var = self.env.symbol_table.get_var_by_id(var_id);
+
+                Self::default_value(var)
+            })
+            .collect()
+    }
+
+    fn default_value(var: &Variable) -> BytecodeConst {
+        match var.var_type.as_ref().unwrap() {
+            ExpressionType::Int => BytecodeConst::Int(0),
+            ExpressionType::Str => BytecodeConst::Str("".to_string()),
+            // there is no default procedure or record, so using an unassigned one is an error
+            ExpressionType::Bool | ExpressionType::Proc(..) | ExpressionType::Struct(_) => {
+                BytecodeConst::Bool(false)
+            }
+            ExpressionType::Unit => panic!("variable can't be of type `Unit`"),
+        }
+    }
+}
@@ -0,0 +1,560 @@
+use crate::ast::statement::{Command, Direction};
+use crate::bytecode::{BytecodeConst, BytecodeOp, BytecodeProc, BytecodeProgram, BytecodeStruct};
+use crate::lexer::Location;
+
+// the `.tyb` file format:
+// * the magic `TYB\0` followed by the format version (a `u16`)
+// * the program tables: procedures, records types, globals, code and locations
+//
+// all integers are little-endian. counts, lengths and indexes are `u32`, integer constants are `i64`
+// and strings are UTF-8 prefixed by their length
+pub const BYTECODE_MAGIC: &[u8; 4] = b"TYB\0";
+pub const BYTECODE_VERSION: u16 = 1;
+
+#[derive(Debug, PartialEq)]
+pub enum BytecodeFormatError {
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    UnexpectedEof,
+    InvalidOpcode(u8),
+    InvalidConst(u8),
+    InvalidCommand(u8),
+    InvalidDirection(u8),
+    InvalidUtf8,
+
+    // the program refers to a missing procedure, record type or `pc`
+    InvalidProgram(String),
+    TrailingBytes,
+}
+
+impl ToString for BytecodeFormatError {
+    fn to_string(&self) -> String {
+        match self {
+            BytecodeFormatError::InvalidMagic => "Not a Tytle bytecode file".to_string(),
+            BytecodeFormatError::UnsupportedVersion(version) => format!(
+                "Unsupported bytecode version: {} (expected: {})",
+                version, BYTECODE_VERSION
+            ),
+            BytecodeFormatError::UnexpectedEof => "Unexpected end of bytecode".to_string(),
+            BytecodeFormatError::InvalidOpcode(opcode) => format!("Invalid opcode: {}", opcode),
+            BytecodeFormatError::InvalidConst(tag) => format!("Invalid constant tag: {}", tag),
+            BytecodeFormatError::InvalidCommand(cmd) => format!("Invalid command: {}", cmd),
+            BytecodeFormatError::InvalidDirection(direct) => {
+                format!("Invalid direction: {}", direct)
+            }
+            BytecodeFormatError::InvalidUtf8 => "Invalid UTF-8 string".to_string(),
+            BytecodeFormatError::InvalidProgram(reason) => {
+                format!("Invalid bytecode program: {}", reason)
+            }
+            BytecodeFormatError::TrailingBytes => "Trailing bytes after bytecode".to_string(),
+        }
+    }
+}
+
+const COMMANDS: [Command; 14] = [
+    Command::XCor,
+    Command::YCor,
+    Command::PenUp,
+    Command::PenDown,
+    Command::PenErase,
+    Command::ShowTurtle,
+    Command::HideTurtle,
+    Command::Clean,
+    Command::ClearScreen,
+    Command::SetPenColor,
+    Command::SetBackgroundColor,
+    Command::Wait,
+    Command::Stop,
+    Command::Trap,
+];
+
+const DIRECTIONS: [Direction; 6] = [
+    Direction::Left,
+    Direction::Right,
+    Direction::Forward,
+    Direction::Backward,
+    Direction::SetX,
+    Direction::SetY,
+];
+
+impl BytecodeProgram {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = BytecodeWriter { bytes: Vec::new() };
+
+        writer.bytes.extend_from_slice(BYTECODE_MAGIC);
+        writer
+            .bytes
+            .extend_from_slice(&BYTECODE_VERSION.to_le_bytes());
+
+        writer.write_program(self);
+
+        writer.bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<BytecodeProgram, BytecodeFormatError> {
+        let mut reader = BytecodeReader { bytes, pos: 0 };
+
+        if reader.read_bytes(4).ok() != Some(&BYTECODE_MAGIC[..]) {
+            return Err(BytecodeFormatError::InvalidMagic);
+        }
+
+        let version = reader.read_u16()?;
+
+        if version != BYTECODE_VERSION {
+            return Err(BytecodeFormatError::UnsupportedVersion(version));
+        }
+
+        let program = reader.read_program()?;
+
+        if reader.pos != bytes.len() {
+            return Err(BytecodeFormatError::TrailingBytes);
+        }
+
+        program.validate()?;
+
+        Ok(program)
+    }
+
+    // checks that the program tables entries referred by the code exist.
+    // (the locals slots depend on the procedure executing the op, they're checked by the `BytecodeVm`)
+    fn validate(&self) -> Result<(), BytecodeFormatError> {
+        let invalid = |reason: &str| Err(BytecodeFormatError::InvalidProgram(reason.to_string()));
+
+        if self.main_proc >= self.procs.len() {
+            return invalid("missing main procedure");
+        }
+
+        if self.procs[self.main_proc].params_count > 0 {
+            return invalid("main procedure having params");
+        }
+
+        for proc in self.procs.iter() {
+            if proc.entry_pc >= self.code.len() {
+                return invalid("procedure entry out of the code");
+            }
+
+            // each argument is pushed by (at least) one op of the caller
+            if proc.params_count > self.code.len() {
+                return invalid("procedure having more params than the code ops");
+            }
+        }
+
+        for op in self.code.iter() {
+            let is_valid = match op {
+                BytecodeOp::LoadGlobal(index) | BytecodeOp::StoreGlobal(index) => {
+                    *index < self.globals.len()
+                }
+                BytecodeOp::Call(proc, _) | BytecodeOp::ProcRef(proc, _) => {
+                    *proc < self.procs.len()
+                }
+                BytecodeOp::NewStruct(index) => *index < self.structs.len(),
+                BytecodeOp::Jump(pc) | BytecodeOp::JumpIfTrue(pc) | BytecodeOp::JumpIfFalse(pc) => {
+                    *pc < self.code.len()
+                }
+                _ => true,
+            };
+
+            if !is_valid {
+                return invalid("op refers to a missing entry");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+struct BytecodeWriter {
+    bytes: Vec<u8>,
+}
+
+impl BytecodeWriter {
+    fn write_program(&mut self, program: &BytecodeProgram) {
+        self.write_usize(program.main_proc);
+
+        self.write_usize(program.procs.len());
+
+        for proc in program.procs.iter() {
+            self.write_str(&proc.name);
+            self.write_usize(proc.entry_pc);
+            self.write_usize(proc.params_count);
+            self.write_consts(&proc.locals);
+            self.write_bool(proc.returns_value);
+        }
+
+        self.write_usize(program.structs.len());
+
+        for struct_type in program.structs.iter() {
+            self.write_str(&struct_type.name);
+            self.write_usize(struct_type.fields_names.len());
+
+            for field_name in struct_type.fields_names.iter() {
+                self.write_str(field_name);
+            }
+        }
+
+        self.write_consts(&program.globals);
+
+        self.write_usize(program.code.len());
+
+        for op in program.code.iter() {
+            self.write_op(op);
+        }
+
+        self.write_usize(program.locations.len());
+
+        for (pc, loc) in program.locations.iter() {
+            self.write_usize(*pc);
+            self.write_usize(loc.line());
+            self.write_usize(loc.column());
+        }
+    }
+
+    fn write_op(&mut self, op: &BytecodeOp) {
+        match op {
+            BytecodeOp::Command(cmd) => {
+                self.write_u8(0);
+                let index = COMMANDS.iter().position(|c| c == cmd).unwrap();
+                self.write_u8(index as u8);
+            }
+            BytecodeOp::Direction(direct) => {
+                self.write_u8(1);
+                let index = DIRECTIONS.iter().position(|d| d == direct).unwrap();
+                self.write_u8(index as u8);
+            }
+            BytecodeOp::LoadGlobal(index) => {
+                self.write_u8(2);
+                self.write_usize(*index);
+            }
+            BytecodeOp::StoreGlobal(index) => {
+                self.write_u8(3);
+                self.write_usize(*index);
+            }
+            BytecodeOp::LoadLocal(hops, index) => {
+                self.write_u8(4);
+                self.write_usize(*hops);
+                self.write_usize(*index);
+            }
+            BytecodeOp::StoreLocal(hops, index) => {
+                self.write_u8(5);
+                self.write_usize(*hops);
+                self.write_usize(*index);
+            }
+            BytecodeOp::Call(proc, hops) => {
+                self.write_u8(6);
+                self.write_usize(*proc);
+                self.write_hops(*hops);
+            }
+            BytecodeOp::CallIndirect => self.write_u8(7),
+            BytecodeOp::ProcRef(proc, hops) => {
+                self.write_u8(8);
+                self.write_usize(*proc);
+                self.write_hops(*hops);
+            }
+            BytecodeOp::NewStruct(index) => {
+                self.write_u8(9);
+                self.write_usize(*index);
+            }
+            BytecodeOp::LoadField(index) => {
+                self.write_u8(10);
+                self.write_usize(*index);
+            }
+            BytecodeOp::StoreField(index) => {
+                self.write_u8(11);
+                self.write_usize(*index);
+            }
+            BytecodeOp::Bool(v) => {
+                self.write_u8(12);
+                self.write_bool(*v);
+            }
+            BytecodeOp::Int(v) => {
+                self.write_u8(13);
+                self.write_i64(*v as i64);
+            }
+            BytecodeOp::Str(v) => {
+                self.write_u8(14);
+                self.write_str(v);
+            }
+            BytecodeOp::Return => self.write_u8(15),
+            BytecodeOp::Trap(node, ip) => {
+                self.write_u8(16);
+                self.write_usize(*node);
+                self.write_usize(*ip);
+            }
+            BytecodeOp::Print => self.write_u8(17),
+            BytecodeOp::Label => self.write_u8(18),
+            BytecodeOp::SetFontSize => self.write_u8(19),
+            BytecodeOp::End => self.write_u8(20),
+            BytecodeOp::Add => self.write_u8(21),
+            BytecodeOp::Mul => self.write_u8(22),
+            BytecodeOp::Div => self.write_u8(23),
+            BytecodeOp::Not => self.write_u8(24),
+            BytecodeOp::And => self.write_u8(25),
+            BytecodeOp::Or => self.write_u8(26),
+            BytecodeOp::GreaterThan => self.write_u8(27),
+            BytecodeOp::LessThan => self.write_u8(28),
+            BytecodeOp::Jump(pc) => {
+                self.write_u8(29);
+                self.write_usize(*pc);
+            }
+            BytecodeOp::JumpIfTrue(pc) => {
+                self.write_u8(30);
+                self.write_usize(*pc);
+            }
+            BytecodeOp::JumpIfFalse(pc) => {
+                self.write_u8(31);
+                self.write_usize(*pc);
+            }
+        }
+    }
+
+    fn write_consts(&mut self, consts: &[BytecodeConst]) {
+        self.write_usize(consts.len());
+
+        for value in consts.iter() {
+            match value {
+                BytecodeConst::Int(v) => {
+                    self.write_u8(0);
+                    self.write_i64(*v as i64);
+                }
+                BytecodeConst::Bool(v) => {
+                    self.write_u8(1);
+                    self.write_bool(*v);
+                }
+                BytecodeConst::Str(v) => {
+                    self.write_u8(2);
+                    self.write_str(v);
+                }
+            }
+        }
+    }
+
+    // `0` stands for `None`, otherwise it's `hops + 1`
+    fn write_hops(&mut self, hops: Option<usize>) {
+        match hops {
+            Some(hops) => self.write_usize(hops + 1),
+            None => self.write_usize(0),
+        }
+    }
+
+    fn write_u8(&mut self, v: u8) {
+        self.bytes.push(v);
+    }
+
+    fn write_bool(&mut self, v: bool) {
+        self.write_u8(v as u8);
+    }
+
+    fn write_usize(&mut self, v: usize) {
+        self.bytes.extend_from_slice(&(v as u32).to_le_bytes());
+    }
+
+    fn write_i64(&mut self, v: i64) {
+        self.bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_str(&mut self, s: &str) {
+        self.write_usize(s.len());
+        self.bytes.extend_from_slice(s.as_bytes());
+    }
+}
+
+struct BytecodeReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> BytecodeReader<'a> {
+    fn read_program(&mut self) -> Result<BytecodeProgram, BytecodeFormatError> {
+        let main_proc = self.read_usize()?;
+
+        let procs_count = self.read_usize()?;
+        let mut procs = Vec::new();
+
+        for _ in 0..procs_count {
+            procs.push(BytecodeProc {
+                name: self.read_str()?,
+                entry_pc: self.read_usize()?,
+                params_count: self.read_usize()?,
+                locals: self.read_consts()?,
+                returns_value: self.read_bool()?,
+            });
+        }
+
+        let structs_count = self.read_usize()?;
+        let mut structs = Vec::new();
+
+        for _ in 0..structs_count {
+            let name = self.read_str()?;
+            let fields_count = self.read_usize()?;
+            let mut fields_names = Vec::new();
+
+            for _ in 0..fields_count {
+                fields_names.push(self.read_str()?);
+            }
+
+            structs.push(BytecodeStruct { name, fields_names });
+        }
+
+        let globals = self.read_consts()?;
+
+        let ops_count = self.read_usize()?;
+        let mut code = Vec::new();
+
+        for _ in 0..ops_count {
+            code.push(self.read_op()?);
+        }
+
+        let locations_count = self.read_usize()?;
+        let mut locations = Vec::new();
+
+        for _ in 0..locations_count {
+            let pc = self.read_usize()?;
+            let line = self.read_usize()?;
+            let column = self.read_usize()?;
+
+            locations.push((pc, Location(line, column)));
+        }
+
+        Ok(BytecodeProgram {
+            main_proc,
+            code,
+            procs,
+            structs,
+            globals,
+            locations,
+        })
+    }
+
+    fn read_op(&mut self) -> Result<BytecodeOp, BytecodeFormatError> {
+        let opcode = self.read_u8()?;
+
+        let op = match opcode {
+            0 => {
+                let index = self.read_u8()?;
+
+                match COMMANDS.get(index as usize) {
+                    Some(cmd) => BytecodeOp::Command(cmd.clone()),
+                    None => return Err(BytecodeFormatError::InvalidCommand(index)),
+                }
+            }
+            1 => {
+                let index = self.read_u8()?;
+
+                match DIRECTIONS.get(index as usize) {
+                    Some(direct) => BytecodeOp::Direction(*direct),
+                    None => return Err(BytecodeFormatError::InvalidDirection(index)),
+                }
+            }
+            2 => BytecodeOp::LoadGlobal(self.read_usize()?),
+            3 => BytecodeOp::StoreGlobal(self.read_usize()?),
+            4 => BytecodeOp::LoadLocal(self.read_usize()?, self.read_usize()?),
+            5 => BytecodeOp::StoreLocal(self.read_usize()?, self.read_usize()?),
+            6 => BytecodeOp::Call(self.read_usize()?, self.read_hops()?),
+            7 => BytecodeOp::CallIndirect,
+            8 => BytecodeOp::ProcRef(self.read_usize()?, self.read_hops()?),
+            9 => BytecodeOp::NewStruct(self.read_usize()?),
+            10 => BytecodeOp::LoadField(self.read_usize()?),
+            11 => BytecodeOp::StoreField(self.read_usize()?),
+            12 => BytecodeOp::Bool(self.read_bool()?),
+            13 => BytecodeOp::Int(self.read_i64()? as isize),
+            14 => BytecodeOp::Str(self.read_str()?),
+            15 => BytecodeOp::Return,
+            16 => BytecodeOp::Trap(self.read_usize()?, self.read_usize()?),
+            17 => BytecodeOp::Print,
+            18 => BytecodeOp::Label,
+            19 => BytecodeOp::SetFontSize,
+            20 => BytecodeOp::End,
+            21 => BytecodeOp::Add,
+            22 => BytecodeOp::Mul,
+            23 => BytecodeOp::Div,
+            24 => BytecodeOp::Not,
+            25 => BytecodeOp::And,
+            26 => BytecodeOp::Or,
+            27 => BytecodeOp::GreaterThan,
+            28 => BytecodeOp::LessThan,
+            29 => BytecodeOp::Jump(self.read_usize()?),
+            30 => BytecodeOp::JumpIfTrue(self.read_usize()?),
+            31 => BytecodeOp::JumpIfFalse(self.read_usize()?),
+            _ => return Err(BytecodeFormatError::InvalidOpcode(opcode)),
+        };
+
+        Ok(op)
+    }
+
+    fn read_consts(&mut self) -> Result<Vec<BytecodeConst>, BytecodeFormatError> {
+        let count = self.read_usize()?;
+        let mut consts = Vec::new();
+
+        for _ in 0..count {
+            let tag = self.read_u8()?;
+
+            let value = match tag {
+                0 => BytecodeConst::Int(self.read_i64()? as isize),
+                1 => BytecodeConst::Bool(self.read_bool()?),
+                2 => BytecodeConst::Str(self.read_str()?),
+                _ => return Err(BytecodeFormatError::InvalidConst(tag)),
+            };
+
+            consts.push(value);
+        }
+
+        Ok(consts)
+    }
+
+    fn read_hops(&mut self) -> Result<Option<usize>, BytecodeFormatError> {
+        match self.read_usize()? {
+            0 => Ok(None),
+            hops => Ok(Some(hops - 1)),
+        }
+    }
+
+    fn read_bytes(&mut self, n: usize) -> Result<&'a [u8], BytecodeFormatError> {
+        if self.bytes.len() - self.pos < n {
+            return Err(BytecodeFormatError::UnexpectedEof);
+        }
+
+        let bytes = &self.bytes[self.pos..self.pos + n];
+        self.pos += n;
+
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, BytecodeFormatError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, BytecodeFormatError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, BytecodeFormatError> {
+        let mut buf = [0; 2];
+        buf.copy_from_slice(self.read_bytes(2)?);
+
+        Ok(u16::from_le_bytes(buf))
+    }
+
+    fn read_usize(&mut self) -> Result<usize, BytecodeFormatError> {
+        let mut buf = [0; 4];
+        buf.copy_from_slice(self.read_bytes(4)?);
+
+        Ok(u32::from_le_bytes(buf) as usize)
+    }
+
+    fn read_i64(&mut self) -> Result<i64, BytecodeFormatError> {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(self.read_bytes(8)?);
+
+        Ok(i64::from_le_bytes(buf))
+    }
+
+    fn read_str(&mut self) -> Result<String, BytecodeFormatError> {
+        let len = self.read_usize()?;
+        let bytes = self.read_bytes(len)?;
+
+        match std::str::from_utf8(bytes) {
+            Ok(s) => Ok(s.to_string()),
+            Err(_) => Err(BytecodeFormatError::InvalidUtf8),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::ast::statement::{Command, Direction};
+
+// a constant value (used for the initial values of the variables)
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytecodeConst {
+    Int(isize),
+    Bool(bool),
+    Str(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytecodeOp {
+    Command(Command),
+    Direction(Direction),
+    LoadGlobal(usize),
+    StoreGlobal(usize),
+
+    // `(hops, index)`: the local `index` of the frame `hops` static links away from the current frame
+    LoadLocal(usize, usize),
+    StoreLocal(usize, usize),
+
+    // `(proc, hops)`: calls procedure `proc`. `hops` locates the frame of its enclosing procedure
+    // (`None` for a top-level procedure)
+    Call(usize, Option<usize>),
+
+    // pops a procedure value and calls it
+    CallIndirect,
+
+    // `(proc, hops)`: pushes a procedure value
+    ProcRef(usize, Option<usize>),
+
+    // pops the struct fields values (the last field first) and pushes a new record
+    NewStruct(usize),
+    LoadField(usize),
+    StoreField(usize),
+    Bool(bool),
+    Int(isize),
+    Str(String),
+    Return,
+
+    // `(node, ip)`: the CFG position of the `TRAP` (reported to the host)
+    Trap(usize, usize),
+    Print,
+    Label,
+    SetFontSize,
+
+    // ends the program
+    End,
+    Add,
+    Mul,
+    Div,
+    Not,
+    And,
+    Or,
+    GreaterThan,
+    LessThan,
+    Jump(usize),
+
+    // pops a boolean, and jumps when it's `true` (`JumpIfFalse` jumps when it's `false`)
+    JumpIfTrue(usize),
+    JumpIfFalse(usize),
+}
@@ -0,0 +1,46 @@
+use crate::bytecode::{BytecodeConst, BytecodeOp};
+use crate::lexer::Location;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BytecodeProc {
+    pub name: String,
+    pub entry_pc: usize,
+    pub params_count: usize,
+
+    // the initial values of the procedure locals (following the params)
+    pub locals: Vec<BytecodeConst>,
+    pub returns_value: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BytecodeStruct {
+    pub name: String,
+    pub fields_names: Vec<String>,
+}
+
+// a program lowered into a linear code, with its jumps and variables resolved.
+// it doesn't depend on the `Environment` it was compiled with, so it can be saved (see `to_bytes`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BytecodeProgram {
+    pub main_proc: usize,
+    pub code: Vec<BytecodeOp>,
+    pub procs: Vec<BytecodeProc>,
+    pub structs: Vec<BytecodeStruct>,
+
+    // the initial values of the globals
+    pub globals: Vec<BytecodeConst>,
+
+    // the location of each statement first op, ordered by `pc`
+    pub locations: Vec<(usize, Location)>,
+}
+
+impl BytecodeProgram {
+    // returns the location of the statement `pc` belongs to
+    pub fn get_location(&self, pc: usize) -> Option<Location> {
+        self.locations
+            .iter()
+            .rev()
+            .find(|(stmt_pc, _)| *stmt_pc <= pc)
+            .map(|(_, loc)| *loc)
+    }
+}
@@ -0,0 +1,514 @@
+use crate::ast::statement::{Command, Direction};
+use crate::bytecode::{BytecodeConst, BytecodeOp, BytecodeProgram};
+use crate::vm::{ErrorSite, Host, InterpreterException, InterpreterResult, MAX_STACK_DEPTH};
+
+#[derive(Debug, Clone, PartialEq)]
+enum BytecodeValue {
+    Int(isize),
+    Bool(bool),
+    Str(String),
+
+    // a procedure value: the procedure index and (for a nested procedure)
+    // the `(index, id)` of the frame of its enclosing procedure activation
+    Proc(usize, Option<(usize, usize)>),
+
+    // a record reference (an index into the records heap)
+    Struct(usize),
+}
+
+impl BytecodeValue {
+    fn from_const(value: &BytecodeConst) -> Self {
+        match value {
+            BytecodeConst::Int(v) => BytecodeValue::Int(*v),
+            BytecodeConst::Bool(v) => BytecodeValue::Bool(*v),
+            BytecodeConst::Str(v) => BytecodeValue::Str(v.clone()),
+        }
+    }
+}
+
+struct BytecodeFrame {
+    proc: usize,
+
+    // unique per opened frame
+    id: usize,
+    static_link: Option<usize>,
+
+    // the `pc` to continue from once the procedure returns
+    ret_pc: usize,
+
+    // the procedure params and locals followed by the procedure operands
+    values: Vec<BytecodeValue>,
+}
+
+// executes a `BytecodeProgram`.
+// it has the same semantics (and runtime errors) as the `Interpreter` executing the CFG
+// the program has been lowered from
+pub struct BytecodeVm<'program, 'host> {
+    pc: usize,
+    program: &'program BytecodeProgram,
+    host: &'host mut dyn Host,
+    frames: Vec<BytecodeFrame>,
+    globals: Vec<BytecodeValue>,
+    structs: Vec<Vec<BytecodeValue>>,
+    next_frame_id: usize,
+    fuel: Option<usize>,
+}
+
+impl<'program, 'host> BytecodeVm<'program, 'host> {
+    pub fn new(program: &'program BytecodeProgram, host: &'host mut dyn Host) -> Self {
+        let globals = program
+            .globals
+            .iter()
+            .map(BytecodeValue::from_const)
+            .collect();
+
+        let mut vm = Self {
+            pc: program.procs[program.main_proc].entry_pc,
+            program,
+            host,
+            frames: Vec::new(),
+            globals,
+            structs: Vec::new(),
+            next_frame_id: 0,
+            fuel: None,
+        };
+
+        vm.open_frame(program.main_proc, None, Vec::new(), 0);
+
+        vm
+    }
+
+    // limits the number of ops left to execute.
+    // once the fuel is used up, execution fails with `InterpreterException::OutOfFuel`
+    pub fn set_fuel(&mut self, fuel: usize) {
+        self.fuel = Some(fuel);
+    }
+
+    pub fn exec_code(&mut self) -> InterpreterResult {
+        loop {
+            let completed = self.exec_next()?;
+
+            if completed {
+                return Ok(());
+            }
+        }
+    }
+
+    pub fn exec_next(&mut self) -> Result<bool, InterpreterException> {
+        let program = self.program;
+
+        let op = match program.code.get(self.pc) {
+            Some(op) => op,
+            None => return Ok(true),
+        };
+
+        if let Some(fuel) = self.fuel {
+            if fuel == 0 {
+                return Err(InterpreterException::OutOfFuel);
+            }
+
+            self.fuel = Some(fuel - 1);
+        }
+
+        let mut next_pc = self.pc + 1;
+
+        match op {
+            BytecodeOp::Command(cmd) => self.exec_cmd(cmd),
+            BytecodeOp::Direction(direct) => self.exec_direct(direct)?,
+            BytecodeOp::LoadGlobal(index) => {
+                let value = self.globals[*index].clone();
+                self.push(value);
+            }
+            BytecodeOp::StoreGlobal(index) => {
+                let value = self.pop("a value")?;
+                self.globals[*index] = value;
+            }
+            BytecodeOp::LoadLocal(hops, index) => {
+                let frame_index = self.lookup_local(*hops, *index)?;
+                let value = self.frames[frame_index].values[*index].clone();
+
+                self.push(value);
+            }
+            BytecodeOp::StoreLocal(hops, index) => {
+                let value = self.pop("a value")?;
+                let frame_index = self.lookup_local(*hops, *index)?;
+
+                self.frames[frame_index].values[*index] = value;
+            }
+            BytecodeOp::Call(proc, hops) => {
+                let static_link = match hops {
+                    Some(hops) => Some(self.lookup_frame(*hops)?),
+                    None => None,
+                };
+
+                next_pc = self.enter_proc(*proc, static_link)?;
+            }
+            BytecodeOp::CallIndirect => next_pc = self.exec_call_indirect()?,
+            BytecodeOp::ProcRef(proc, hops) => {
+                let frame_ref = match hops {
+                    Some(hops) => {
+                        let frame_index = self.lookup_frame(*hops)?;
+
+                        Some((frame_index, self.frames[frame_index].id))
+                    }
+                    None => None,
+                };
+
+                self.push(BytecodeValue::Proc(*proc, frame_ref));
+            }
+            BytecodeOp::NewStruct(struct_index) => self.exec_new_struct(*struct_index)?,
+            // the field index isn't checked by `from_bytes` (the record type is known only when executing)
+            BytecodeOp::LoadField(index) => {
+                let struct_ref = self.pop_struct()?;
+
+                let value = match self.structs[struct_ref].get(*index) {
+                    Some(value) => value.clone(),
+                    None => return Err(self.invalid_stack_item("a record having the field")),
+                };
+
+                self.push(value);
+            }
+            BytecodeOp::StoreField(index) => {
+                let value = self.pop("a value")?;
+                let struct_ref = self.pop_struct()?;
+
+                if *index >= self.structs[struct_ref].len() {
+                    return Err(self.invalid_stack_item("a record having the field"));
+                }
+
+                self.structs[struct_ref][*index] = value;
+            }
+            BytecodeOp::Bool(v) => self.push(BytecodeValue::Bool(*v)),
+            BytecodeOp::Int(v) => self.push(BytecodeValue::Int(*v)),
+            BytecodeOp::Str(v) => self.push(BytecodeValue::Str(v.clone())),
+            BytecodeOp::Return => next_pc = self.exec_ret()?,
+            BytecodeOp::Trap(node, ip) => self.host.exec_trap(*node, *ip),
+            BytecodeOp::Print => {
+                let value = self.pop_int()?;
+                self.host.exec_print(value);
+            }
+            BytecodeOp::Label => {
+                let text = self.pop_str()?;
+                self.host.exec_label(&text);
+            }
+            BytecodeOp::SetFontSize => {
+                let size = self.pop_int()?;
+                self.host.exec_set_font_size(size);
+            }
+            BytecodeOp::End => {
+                self.frames.clear();
+                return Ok(true);
+            }
+            BytecodeOp::Add | BytecodeOp::Mul | BytecodeOp::Div => self.exec_int_binary(op)?,
+            BytecodeOp::Not => {
+                let a = self.pop_bool()?;
+                self.push(BytecodeValue::Bool(!a));
+            }
+            BytecodeOp::And | BytecodeOp::Or | BytecodeOp::GreaterThan | BytecodeOp::LessThan => {
+                self.exec_bool_binary(op)?
+            }
+            BytecodeOp::Jump(pc) => next_pc = *pc,
+            BytecodeOp::JumpIfTrue(pc) => {
+                if self.pop_bool()? {
+                    next_pc = *pc;
+                }
+            }
+            BytecodeOp::JumpIfFalse(pc) => {
+                if !self.pop_bool()? {
+                    next_pc = *pc;
+                }
+            }
+        }
+
+        self.pc = next_pc;
+
+        Ok(false)
+    }
+
+    fn exec_cmd(&mut self, cmd: &Command) {
+        self.host.exec_cmd(cmd);
+    }
+
+    fn exec_direct(&mut self, direct: &Direction) -> InterpreterResult {
+        let count = self.pop_int()?;
+        self.host.exec_direct(direct, count);
+
+        Ok(())
+    }
+
+    fn exec_call_indirect(&mut self) -> Result<usize, InterpreterException> {
+        let (proc, frame_ref) = match self.pop("a procedure")? {
+            BytecodeValue::Proc(proc, frame_ref) => (proc, frame_ref),
+            _ => return Err(self.invalid_stack_item("a procedure")),
+        };
+
+        // the static link of a nested procedure value is the frame captured by `ProcRef`
+        let static_link = match frame_ref {
+            Some((index, id)) => {
+                let is_open = match self.frames.get(index) {
+                    Some(frame) => frame.id == id,
+                    None => false,
+                };
+
+                if !is_open {
+                    return Err(InterpreterException::ProcOutOfScope(self.error_site()));
+                }
+
+                Some(index)
+            }
+            None => None,
+        };
+
+        self.enter_proc(proc, static_link)
+    }
+
+    // returns the `pc` of the callee first op
+    fn enter_proc(
+        &mut self,
+        proc: usize,
+        static_link: Option<usize>,
+    ) -> Result<usize, InterpreterException> {
+        let program = self.program;
+        let callee = &program.procs[proc];
+
+        let mut params = Vec::new();
+
+        for _ in 0..callee.params_count {
+            params.push(self.pop("a value")?);
+        }
+
+        params.reverse();
+
+        if self.frames.len() >= MAX_STACK_DEPTH {
+            return Err(InterpreterException::StackOverflow);
+        }
+
+        let ret_pc = self.pc + 1;
+        self.open_frame(proc, static_link, params, ret_pc);
+
+        Ok(callee.entry_pc)
+    }
+
+    fn open_frame(
+        &mut self,
+        proc: usize,
+        static_link: Option<usize>,
+        mut values: Vec<BytecodeValue>,
+        ret_pc: usize,
+    ) {
+        let program = self.program;
+
+        for local in program.procs[proc].locals.iter() {
+            values.push(BytecodeValue::from_const(local));
+        }
+
+        self.frames.push(BytecodeFrame {
+            proc,
+            id: self.next_frame_id,
+            static_link,
+            ret_pc,
+            values,
+        });
+
+        self.next_frame_id += 1;
+    }
+
+    // returns the `pc` to continue from
+    fn exec_ret(&mut self) -> Result<usize, InterpreterException> {
+        let program = self.program;
+        let proc = self.current_frame().proc;
+
+        let ret_value = if program.procs[proc].returns_value {
+            Some(self.pop("a return value")?)
+        } else {
+            None
+        };
+
+        // `__main__` has no frame to return into
+        if self.frames.len() == 1 {
+            return Err(self.invalid_stack_item("a return address"));
+        }
+
+        let frame = self.frames.pop().unwrap();
+
+        if let Some(value) = ret_value {
+            self.push(value);
+        }
+
+        Ok(frame.ret_pc)
+    }
+
+    fn exec_new_struct(&mut self, struct_index: usize) -> InterpreterResult {
+        let program = self.program;
+        let fields_count = program.structs[struct_index].fields_names.len();
+
+        let mut fields = Vec::with_capacity(fields_count);
+
+        // the last field is on top of the stack
+        for _ in 0..fields_count {
+            fields.push(self.pop("a field value")?);
+        }
+
+        fields.reverse();
+
+        self.structs.push(fields);
+
+        let struct_ref = self.structs.len() - 1;
+        self.push(BytecodeValue::Struct(struct_ref));
+
+        Ok(())
+    }
+
+    fn exec_int_binary(&mut self, op: &BytecodeOp) -> InterpreterResult {
+        let a = self.pop_int()?;
+        let b = self.pop_int()?;
+
+        let res = match op {
+            BytecodeOp::Add => b.checked_add(a),
+            BytecodeOp::Mul => b.checked_mul(a),
+            BytecodeOp::Div => {
+                if a == 0 {
+                    return Err(InterpreterException::DivisionByZero(self.error_site()));
+                }
+
+                // `isize::MIN / -1` overflows
+                b.checked_div(a)
+            }
+            _ => panic!("invalid binary-op: `{:?}`", op),
+        };
+
+        match res {
+            Some(v) => {
+                self.push(BytecodeValue::Int(v));
+                Ok(())
+            }
+            None => Err(InterpreterException::IntegerOverflow(self.error_site())),
+        }
+    }
+
+    fn exec_bool_binary(&mut self, op: &BytecodeOp) -> InterpreterResult {
+        let v = match op {
+            BytecodeOp::And | BytecodeOp::Or => {
+                let a = self.pop_bool()?;
+                let b = self.pop_bool()?;
+
+                if *op == BytecodeOp::And {
+                    a && b
+                } else {
+                    a || b
+                }
+            }
+            BytecodeOp::GreaterThan | BytecodeOp::LessThan => {
+                let a = self.pop_int()?;
+                let b = self.pop_int()?;
+
+                if *op == BytecodeOp::GreaterThan {
+                    b > a
+                } else {
+                    b < a
+                }
+            }
+            _ => panic!("invalid binary-op: `{:?}`", op),
+        };
+
+        self.push(BytecodeValue::Bool(v));
+
+        Ok(())
+    }
+
+    // the index of the frame `hops` static links away from the current frame
+    fn lookup_frame(&self, hops: usize) -> Result<usize, InterpreterException> {
+        let mut frame_index = self.frames.len() - 1;
+
+        for _ in 0..hops {
+            frame_index = match self.frames[frame_index].static_link {
+                Some(frame_index) => frame_index,
+                None => return Err(self.invalid_stack_item("an enclosing procedure frame")),
+            };
+        }
+
+        Ok(frame_index)
+    }
+
+    // the frame holding local `index` (`from_bytes` can't tell the procedure each op belongs to,
+    // so a corrupted program may refer to a slot the procedure doesn't have)
+    fn lookup_local(&self, hops: usize, index: usize) -> Result<usize, InterpreterException> {
+        let frame_index = self.lookup_frame(hops)?;
+        let proc = &self.program.procs[self.frames[frame_index].proc];
+
+        if index < proc.params_count + proc.locals.len() {
+            Ok(frame_index)
+        } else {
+            Err(self.invalid_stack_item("a local variable"))
+        }
+    }
+
+    fn current_frame(&self) -> &BytecodeFrame {
+        self.frames.last().unwrap()
+    }
+
+    fn push(&mut self, value: BytecodeValue) {
+        self.frames.last_mut().unwrap().values.push(value);
+    }
+
+    // the procedure operands are popped, but never its params and locals
+    fn pop(&mut self, expected: &str) -> Result<BytecodeValue, InterpreterException> {
+        let program = self.program;
+        let frame = self.frames.last_mut().unwrap();
+        let proc = &program.procs[frame.proc];
+
+        if frame.values.len() > proc.params_count + proc.locals.len() {
+            Ok(frame.values.pop().unwrap())
+        } else {
+            Err(self.invalid_stack_item(expected))
+        }
+    }
+
+    fn pop_int(&mut self) -> Result<isize, InterpreterException> {
+        match self.pop("an integer")? {
+            BytecodeValue::Int(v) => Ok(v),
+            _ => Err(self.invalid_stack_item("an integer")),
+        }
+    }
+
+    fn pop_bool(&mut self) -> Result<bool, InterpreterException> {
+        match self.pop("a boolean")? {
+            BytecodeValue::Bool(v) => Ok(v),
+            _ => Err(self.invalid_stack_item("a boolean")),
+        }
+    }
+
+    fn pop_str(&mut self) -> Result<String, InterpreterException> {
+        match self.pop("a string")? {
+            BytecodeValue::Str(v) => Ok(v),
+            _ => Err(self.invalid_stack_item("a string")),
+        }
+    }
+
+    fn pop_struct(&mut self) -> Result<usize, InterpreterException> {
+        match self.pop("a record")? {
+            BytecodeValue::Struct(struct_ref) => Ok(struct_ref),
+            _ => Err(self.invalid_stack_item("a record")),
+        }
+    }
+
+    fn invalid_stack_item(&self, expected: &str) -> InterpreterException {
+        InterpreterException::InvalidStackItem {
+            expected: expected.to_string(),
+            site: self.error_site(),
+        }
+    }
+
+    // the procedure and source location of the op being executed
+    fn error_site(&self) -> ErrorSite {
+        let proc_name = match self.frames.last() {
+            Some(frame) => self.program.procs[frame.proc].name.clone(),
+            None => "__main__".to_string(),
+        };
+
+        ErrorSite {
+            proc_name,
+            location: self.program.get_location(self.pc),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+mod bytecode_builder;
+mod bytecode_format;
+mod bytecode_op;
+mod bytecode_program;
+mod bytecode_vm;
+
+pub use bytecode_builder::BytecodeBuilder;
+pub use bytecode_format::*;
+pub use bytecode_op::{BytecodeConst, BytecodeOp};
+pub use bytecode_program::{BytecodeProc, BytecodeProgram, BytecodeStruct};
+pub use bytecode_vm::BytecodeVm;
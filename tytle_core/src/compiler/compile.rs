@@ -0,0 +1,50 @@
+use crate::ast::semantic::{AstLint, AstTypeCheck, Environment, LintConfig, SymbolTableGenerator};
+use crate::ast::Ast;
+use crate::compiler::{Diagnostic, Warning};
+use crate::ir::{CfgBuilder, CfgObject};
+use crate::parser::TytleParser;
+
+pub type CompileResult = Result<(Environment, CfgObject), Vec<Diagnostic>>;
+
+// compiles `code` and reports all of its errors (rather than only the first one).
+// the semantic passes run over the best-effort AST returned by the parser,
+// and the diagnostics are ordered by their location
+pub fn compile(code: &str) -> CompileResult {
+    let (res, _warnings) = compile_with_lints(code, &LintConfig::none());
+
+    res
+}
+
+// compiles `code` (see `compile`), and runs the lints enabled by `config` once it compiles with no errors
+pub fn compile_with_lints(code: &str, config: &LintConfig) -> (CompileResult, Vec<Warning>) {
+    let (mut ast, mut env, diagnostics) = analyze(code);
+
+    if !diagnostics.is_empty() {
+        return (Err(diagnostics), Vec::new());
+    }
+
+    let warnings = AstLint::new(&env, config).lint(&mut ast);
+
+    let builder = CfgBuilder::new(&mut env);
+    let cfg = builder.build(&ast);
+
+    (Ok((env, cfg)), warnings)
+}
+
+// runs the front-end passes over `code` (parsing, symbols generation and type checking).
+// unlike `compile`, the best-effort AST and environment are returned along with the diagnostics
+// (which are ordered by their location), so tools (e.g the language server) can use them even when there are errors
+pub fn analyze(code: &str) -> (Ast, Environment, Vec<Diagnostic>) {
+    let (mut ast, mut diagnostics) = TytleParser.parse_recover(code);
+
+    let generator = SymbolTableGenerator::new();
+    let (mut env, symbols_diagnostics) = generator.generate_recover(&mut ast);
+    diagnostics.extend(symbols_diagnostics);
+
+    let mut checker = AstTypeCheck::new(&mut env);
+    diagnostics.extend(checker.check_recover(&mut ast));
+
+    diagnostics.sort_by_key(|diag| diag.location.map(|loc| (loc.line(), loc.column())));
+
+    (ast, env, diagnostics)
+}
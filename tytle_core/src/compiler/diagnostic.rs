@@ -0,0 +1,126 @@
+use crate::ast::semantic::{AstWalkError, LintWarning};
+use crate::lexer::Location;
+use crate::parser::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticError {
+    Parse(ParseError),
+    AstWalk(AstWalkError),
+}
+
+// a compilation error, along with the location of the statement it was found at.
+// `location` is `None` for an AST which has no locations (e.g an AST built by the `ast!` macros)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub error: DiagnosticError,
+    pub location: Option<Location>,
+}
+
+impl Diagnostic {
+    pub fn parse(err: ParseError, location: Location) -> Self {
+        Self {
+            error: DiagnosticError::Parse(err),
+            location: Some(location),
+        }
+    }
+
+    pub fn ast_walk(err: AstWalkError, location: Option<Location>) -> Self {
+        Self {
+            error: DiagnosticError::AstWalk(err),
+            location,
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|loc| loc.line())
+    }
+}
+
+impl ToString for DiagnosticError {
+    fn to_string(&self) -> String {
+        match self {
+            DiagnosticError::Parse(err) => err.to_string(),
+            DiagnosticError::AstWalk(err) => err.to_string(),
+        }
+    }
+}
+
+impl ToString for Diagnostic {
+    fn to_string(&self) -> String {
+        match self.location {
+            Some(loc) => format!(
+                "[{}:{}] {}",
+                loc.line(),
+                loc.column(),
+                self.error.to_string()
+            ),
+            None => self.error.to_string(),
+        }
+    }
+}
+
+// a lint finding (see `AstLint`), it doesn't fail the compilation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub warning: LintWarning,
+    pub location: Option<Location>,
+}
+
+impl Warning {
+    pub fn new(warning: LintWarning, location: Option<Location>) -> Self {
+        Self { warning, location }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|loc| loc.line())
+    }
+}
+
+// the lint name is appended, so it's known which lint to disable
+impl ToString for Warning {
+    fn to_string(&self) -> String {
+        let msg = format!(
+            "{} ({})",
+            self.warning.to_string(),
+            self.warning.kind().name()
+        );
+
+        match self.location {
+            Some(loc) => format!("[{}:{}] {}", loc.line(), loc.column(), msg),
+            None => msg,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostic_to_string() {
+        let diag = Diagnostic::parse(ParseError::MissingColon, Location(3, 5));
+
+        assert_eq!("[3:5] Missing colon", diag.to_string());
+    }
+
+    #[test]
+    fn diagnostic_without_location_to_string() {
+        let err = AstWalkError::MissingVarDeclaration("A".to_string());
+        let diag = Diagnostic::ast_walk(err, None);
+
+        assert_eq!("Missing variable declaration for `A`", diag.to_string());
+    }
+
+    #[test]
+    fn warning_to_string() {
+        let warning = Warning::new(
+            LintWarning::UnusedVariable("A".to_string()),
+            Some(Location(2, 9)),
+        );
+
+        assert_eq!(
+            "[2:9] Variable `A` is never read (unused-variable)",
+            warning.to_string()
+        );
+    }
+}
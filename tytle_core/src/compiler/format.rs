@@ -0,0 +1,28 @@
+use crate::ast::expression::PrettyPrintAst;
+use crate::compiler::Diagnostic;
+use crate::lexer::{Lexer, TytleLexer};
+use crate::parser::TytleParser;
+
+// formats `code` in the canonical style (see `PrettyPrintAst`), keeping its comments.
+// code having parse errors isn't formatted (the statements failing to parse would get lost)
+pub fn format_code(code: &str) -> Result<String, Vec<Diagnostic>> {
+    let (ast, diagnostics) = TytleParser.parse_recover(code);
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    // the parser doesn't expose its lexer, so the comments are collected by lexing `code` once more
+    let mut lexer = TytleLexer::new(code);
+    while lexer.pop_current_token().is_some() {}
+
+    Ok(PrettyPrintAst::pprint_ast_with_comments(
+        &ast,
+        lexer.get_comments(),
+    ))
+}
+
+// whether `code` is already formatted (i.e formatting it leaves it as is)
+pub fn is_formatted(code: &str) -> Result<bool, Vec<Diagnostic>> {
+    format_code(code).map(|formatted| formatted == code)
+}
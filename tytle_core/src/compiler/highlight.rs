@@ -0,0 +1,182 @@
+use crate::ast::semantic::{Environment, Symbol, SymbolId, SymbolIndex};
+use crate::ast::statement::{Command, Direction};
+use crate::compiler::analyze;
+use crate::lexer::{Lexer, Location, Token, TytleLexer};
+use crate::parser::KEYWORDS;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum HighlightKind {
+    Keyword,
+    Command,
+    Direction,
+    Procedure,
+    GlobalVar,
+    LocalVar,
+    Param,
+    Type,
+    Field,
+    Literal,
+    Operator,
+    Comment,
+}
+
+impl HighlightKind {
+    pub fn all() -> Vec<HighlightKind> {
+        vec![
+            HighlightKind::Keyword,
+            HighlightKind::Command,
+            HighlightKind::Direction,
+            HighlightKind::Procedure,
+            HighlightKind::GlobalVar,
+            HighlightKind::LocalVar,
+            HighlightKind::Param,
+            HighlightKind::Type,
+            HighlightKind::Field,
+            HighlightKind::Literal,
+            HighlightKind::Operator,
+            HighlightKind::Comment,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HighlightKind::Keyword => "keyword",
+            HighlightKind::Command => "command",
+            HighlightKind::Direction => "direction",
+            HighlightKind::Procedure => "procedure",
+            HighlightKind::GlobalVar => "global",
+            HighlightKind::LocalVar => "local",
+            HighlightKind::Param => "param",
+            HighlightKind::Type => "type",
+            HighlightKind::Field => "field",
+            HighlightKind::Literal => "literal",
+            HighlightKind::Operator => "operator",
+            HighlightKind::Comment => "comment",
+        }
+    }
+}
+
+// a classified span of the source code (never spanning more than one line)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighlightSpan {
+    pub kind: HighlightKind,
+    pub location: Location,
+
+    // the span length (in characters)
+    pub len: usize,
+}
+
+// classifies the tokens and comments of `code` for syntax highlighting, ordered by their location.
+// the names are resolved against the symbol table (see `SymbolIndex`), so a variable is told apart
+// from a procedure or a command. the analysis is a best-effort one: code having errors is highlighted too,
+// but a name left unresolved isn't classified (i.e there's no span for it)
+pub fn highlight(code: &str) -> Vec<HighlightSpan> {
+    let (ast, env, _diagnostics) = analyze(code);
+    let index = SymbolIndex::build(code, &ast, &env);
+
+    highlight_with_index(code, &env, &index)
+}
+
+// same as `highlight`, for code already analyzed (`index` is the symbol index of `code`)
+pub fn highlight_with_index(
+    code: &str,
+    env: &Environment,
+    index: &SymbolIndex,
+) -> Vec<HighlightSpan> {
+    let symbols: HashMap<(usize, usize), SymbolId> = index
+        .get_refs()
+        .iter()
+        .map(|sym_ref| {
+            let key = (sym_ref.location.line(), sym_ref.location.column());
+            (key, sym_ref.symbol_id)
+        })
+        .collect();
+
+    let mut lexer = TytleLexer::new(code);
+    let mut spans = Vec::new();
+    let mut prev_token = Token::EOF;
+
+    while let Some((token, location)) = lexer.pop_current_token() {
+        let kind = match token {
+            Token::VALUE(ref name) => {
+                let symbol_id = symbols.get(&(location.line(), location.column()));
+
+                match symbol_id {
+                    Some(symbol_id) => env
+                        .symbol_table
+                        .lookup_by_symbol_id(*symbol_id)
+                        .map(symbol_kind),
+                    None => name_kind(name, &prev_token),
+                }
+            }
+            Token::UNTERMINATED(_) => Some(HighlightKind::Literal),
+            Token::ADD
+            | Token::MUL
+            | Token::DIV
+            | Token::LT
+            | Token::GT
+            | Token::ASSIGN
+            | Token::AND
+            | Token::OR
+            | Token::NOT => Some(HighlightKind::Operator),
+            _ => None,
+        };
+
+        if let Some(kind) = kind {
+            spans.push(HighlightSpan {
+                kind,
+                location,
+                len: token.to_string().chars().count(),
+            });
+        }
+
+        prev_token = token;
+    }
+
+    for comment in lexer.get_comments().iter() {
+        spans.push(HighlightSpan {
+            kind: HighlightKind::Comment,
+            location: comment.location,
+            len: 1 + comment.text.chars().count(), // the `;` and its text
+        });
+    }
+
+    spans.sort_by_key(|span| (span.location.line(), span.location.column()));
+
+    spans
+}
+
+fn symbol_kind(symbol: &Symbol) -> HighlightKind {
+    match symbol {
+        Symbol::Proc(_) => HighlightKind::Procedure,
+        Symbol::Struct(_) => HighlightKind::Type,
+        Symbol::Var(var) if var.global => HighlightKind::GlobalVar,
+        Symbol::Var(var) if var.param => HighlightKind::Param,
+        Symbol::Var(_) => HighlightKind::LocalVar,
+    }
+}
+
+// classifies a name which isn't a resolved symbol
+fn name_kind(name: &str, prev_token: &Token) -> Option<HighlightKind> {
+    if Direction::try_from(name).is_ok() {
+        return Some(HighlightKind::Direction);
+    }
+
+    if Command::parse(name).is_some() {
+        return Some(HighlightKind::Command);
+    }
+
+    let is_number = name.chars().all(|ch| ch.is_ascii_digit());
+
+    match name {
+        "TRAP" | "PRINT" | "LABEL" | "SETFONTSIZE" => Some(HighlightKind::Command),
+        "TRUE" | "FALSE" => Some(HighlightKind::Literal),
+        "INT" | "STR" | "BOOL" => Some(HighlightKind::Type),
+        _ if is_number || name.starts_with('"') => Some(HighlightKind::Literal),
+        _ if KEYWORDS.contains(name) => Some(HighlightKind::Keyword),
+        _ if *prev_token == Token::DOT => Some(HighlightKind::Field),
+        _ => None,
+    }
+}
@@ -0,0 +1,9 @@
+mod compile;
+mod diagnostic;
+mod format;
+mod highlight;
+
+pub use compile::*;
+pub use diagnostic::*;
+pub use format::*;
+pub use highlight::*;
@@ -0,0 +1,192 @@
+use crate::ir::analysis::Lattice;
+use crate::ir::{CfgGraph, CfgNode, CfgNodeId};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DataflowDirection {
+    Forward,
+    Backward,
+}
+
+pub trait DataflowAnalysis {
+    type Fact: Lattice;
+
+    fn direction(&self) -> DataflowDirection;
+
+    // the fact flowing into the analyzed nodes.
+    // on entry of the entry nodes when forward, and on exit of the exit nodes when backward
+    fn boundary(&self) -> Self::Fact;
+
+    // computes the fact on exit of `node` out of its entry fact when forward (and vice versa when backward)
+    fn transfer(&self, node: &CfgNode, input: &Self::Fact) -> Self::Fact;
+}
+
+#[derive(Debug)]
+pub struct DataflowResult<F> {
+    pub entry: HashMap<CfgNodeId, F>,
+    pub exit: HashMap<CfgNodeId, F>,
+}
+
+impl<F> DataflowResult<F> {
+    pub fn get_entry(&self, node_id: CfgNodeId) -> &F {
+        self.entry.get(&node_id).unwrap()
+    }
+
+    pub fn get_exit(&self, node_id: CfgNodeId) -> &F {
+        self.exit.get(&node_id).unwrap()
+    }
+}
+
+// solves a dataflow analysis over the nodes reachable from the entry nodes
+// (usually the start node of a procedure, the edges never cross procedures).
+// the nodes are visited using a worklist until their facts don't change anymore
+pub struct DataflowSolver<'g> {
+    graph: &'g CfgGraph,
+}
+
+impl<'g> DataflowSolver<'g> {
+    pub fn new(graph: &'g CfgGraph) -> Self {
+        Self { graph }
+    }
+
+    pub fn solve<A: DataflowAnalysis>(
+        &self,
+        analysis: &A,
+        entries: &[CfgNodeId],
+    ) -> DataflowResult<A::Fact> {
+        let forward = analysis.direction() == DataflowDirection::Forward;
+
+        let mut order = self.reverse_postorder(entries);
+        if !forward {
+            order.reverse();
+        }
+
+        let region: HashSet<CfgNodeId> = order.iter().cloned().collect();
+
+        // `inputs` / `outputs` are the entry / exit facts when forward (and vice versa when backward)
+        let mut inputs: HashMap<CfgNodeId, A::Fact> = HashMap::new();
+        let mut outputs: HashMap<CfgNodeId, A::Fact> = HashMap::new();
+
+        for node_id in order.iter() {
+            outputs.insert(*node_id, A::Fact::bottom());
+        }
+
+        let mut worklist: VecDeque<CfgNodeId> = order.iter().cloned().collect();
+        let mut pending: HashSet<CfgNodeId> = region.clone();
+
+        while let Some(node_id) = worklist.pop_front() {
+            pending.remove(&node_id);
+
+            let node = self.graph.get_node(node_id);
+
+            let (sources, dependents) = if forward {
+                (self.preds(node, &region), self.succs(node, &region))
+            } else {
+                (self.succs(node, &region), self.preds(node, &region))
+            };
+
+            let mut input = A::Fact::bottom();
+
+            let is_boundary = if forward {
+                entries.contains(&node_id)
+            } else {
+                sources.is_empty()
+            };
+
+            if is_boundary {
+                input.join(&analysis.boundary());
+            }
+
+            for src_id in sources {
+                input.join(&outputs[&src_id]);
+            }
+
+            let output = analysis.transfer(node, &input);
+            inputs.insert(node_id, input);
+
+            if output != outputs[&node_id] {
+                outputs.insert(node_id, output);
+
+                for dep_id in dependents {
+                    if pending.insert(dep_id) {
+                        worklist.push_back(dep_id);
+                    }
+                }
+            }
+        }
+
+        if forward {
+            DataflowResult {
+                entry: inputs,
+                exit: outputs,
+            }
+        } else {
+            DataflowResult {
+                entry: outputs,
+                exit: inputs,
+            }
+        }
+    }
+
+    // the nodes reachable from `entries`, each node is ordered before its successors
+    // (except for the loops back-edges)
+    pub fn reverse_postorder(&self, entries: &[CfgNodeId]) -> Vec<CfgNodeId> {
+        let mut postorder = Vec::new();
+        let mut visited = HashSet::new();
+
+        for entry_id in entries {
+            if !visited.insert(*entry_id) {
+                continue;
+            }
+
+            // each stack item holds a node and its successors left to visit
+            let mut stack = vec![(*entry_id, self.sorted_succs(*entry_id))];
+
+            while let Some((node_id, succs)) = stack.last_mut() {
+                match succs.pop() {
+                    Some(succ_id) => {
+                        if visited.insert(succ_id) {
+                            let succ_succs = self.sorted_succs(succ_id);
+                            stack.push((succ_id, succ_succs));
+                        }
+                    }
+                    None => {
+                        postorder.push(*node_id);
+                        stack.pop();
+                    }
+                }
+            }
+        }
+
+        postorder.reverse();
+        postorder
+    }
+
+    // the successors are popped, so they're sorted in descending order
+    fn sorted_succs(&self, node_id: CfgNodeId) -> Vec<CfgNodeId> {
+        let node = self.graph.get_node(node_id);
+
+        let mut succs: Vec<CfgNodeId> = node.outgoing.iter().map(|edge| edge.node_id).collect();
+        succs.sort();
+        succs.dedup();
+        succs.reverse();
+
+        succs
+    }
+
+    fn preds(&self, node: &CfgNode, region: &HashSet<CfgNodeId>) -> Vec<CfgNodeId> {
+        node.incoming
+            .iter()
+            .map(|edge| edge.node_id)
+            .filter(|node_id| region.contains(node_id))
+            .collect()
+    }
+
+    fn succs(&self, node: &CfgNode, region: &HashSet<CfgNodeId>) -> Vec<CfgNodeId> {
+        node.outgoing
+            .iter()
+            .map(|edge| edge.node_id)
+            .filter(|node_id| region.contains(node_id))
+            .collect()
+    }
+}
@@ -0,0 +1,143 @@
+use crate::ir::analysis::{DataflowAnalysis, DataflowDirection, DataflowSolver, MustSet};
+use crate::ir::{CfgGraph, CfgNode, CfgNodeId};
+use std::collections::{HashMap, HashSet};
+
+// node `A` dominates node `B` when every path from the entry node to `B` goes through `A`.
+// the dominators are computed as a forward "must" analysis over the nodes reachable from the entry node
+#[derive(Debug)]
+pub struct Dominators {
+    entry_id: CfgNodeId,
+    doms: HashMap<CfgNodeId, HashSet<CfgNodeId>>,
+    idoms: HashMap<CfgNodeId, CfgNodeId>,
+}
+
+struct DominatorsAnalysis;
+
+impl DataflowAnalysis for DominatorsAnalysis {
+    type Fact = MustSet<CfgNodeId>;
+
+    fn direction(&self) -> DataflowDirection {
+        DataflowDirection::Forward
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        MustSet::empty()
+    }
+
+    fn transfer(&self, node: &CfgNode, input: &Self::Fact) -> Self::Fact {
+        let mut output = input.clone();
+        output.insert(node.id);
+
+        output
+    }
+}
+
+impl Dominators {
+    pub fn compute(graph: &CfgGraph, entry_id: CfgNodeId) -> Self {
+        let result = DataflowSolver::new(graph).solve(&DominatorsAnalysis, &[entry_id]);
+
+        let doms: HashMap<CfgNodeId, HashSet<CfgNodeId>> = result
+            .exit
+            .into_iter()
+            .map(|(node_id, fact)| (node_id, fact.0.unwrap()))
+            .collect();
+
+        // the immediate dominator of a node is its closest strict dominator,
+        // which is the one having the most dominators itself
+        let mut idoms = HashMap::new();
+
+        for (node_id, node_doms) in doms.iter() {
+            let idom = node_doms
+                .iter()
+                .filter(|dom_id| *dom_id != node_id)
+                .max_by_key(|dom_id| doms[dom_id].len());
+
+            if let Some(idom_id) = idom {
+                idoms.insert(*node_id, *idom_id);
+            }
+        }
+
+        Self {
+            entry_id,
+            doms,
+            idoms,
+        }
+    }
+
+    pub fn get_entry_id(&self) -> CfgNodeId {
+        self.entry_id
+    }
+
+    // the nodes reachable from the entry node, in ascending order
+    pub fn get_nodes_ids(&self) -> Vec<CfgNodeId> {
+        let mut nodes_ids: Vec<CfgNodeId> = self.doms.keys().cloned().collect();
+        nodes_ids.sort();
+
+        nodes_ids
+    }
+
+    pub fn is_reachable(&self, node_id: CfgNodeId) -> bool {
+        self.doms.contains_key(&node_id)
+    }
+
+    // each node dominates itself
+    pub fn dominates(&self, dom_id: CfgNodeId, node_id: CfgNodeId) -> bool {
+        match self.doms.get(&node_id) {
+            Some(node_doms) => node_doms.contains(&dom_id),
+            None => false,
+        }
+    }
+
+    // `None` for the entry node (and for the unreachable nodes)
+    pub fn immediate_dominator(&self, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        self.idoms.get(&node_id).cloned()
+    }
+
+    // the nodes immediately dominated by `node_id` (its children within the dominator tree), in ascending order
+    pub fn dominated_children(&self, node_id: CfgNodeId) -> Vec<CfgNodeId> {
+        let mut children: Vec<CfgNodeId> = self
+            .idoms
+            .iter()
+            .filter(|(_, idom_id)| **idom_id == node_id)
+            .map(|(child_id, _)| *child_id)
+            .collect();
+        children.sort();
+
+        children
+    }
+
+    // the dominance frontier of node `A` is the set of nodes `B` such that `A` dominates
+    // a predecessor of `B`, but doesn't strictly dominate `B` (that's where the `A` definitions meet other definitions)
+    pub fn frontiers(&self, graph: &CfgGraph) -> HashMap<CfgNodeId, HashSet<CfgNodeId>> {
+        let mut frontiers: HashMap<CfgNodeId, HashSet<CfgNodeId>> = HashMap::new();
+
+        for node_id in self.get_nodes_ids() {
+            frontiers.entry(node_id).or_default();
+
+            let preds: Vec<CfgNodeId> = graph
+                .get_node(node_id)
+                .incoming
+                .iter()
+                .map(|edge| edge.node_id)
+                .filter(|pred_id| self.is_reachable(*pred_id))
+                .collect();
+
+            // walking up the dominator tree from each predecessor, until reaching the node immediate dominator
+            let idom = self.immediate_dominator(node_id);
+
+            for pred_id in preds {
+                let mut runner = Some(pred_id);
+
+                while runner.is_some() && runner != idom {
+                    let runner_id = runner.unwrap();
+
+                    frontiers.entry(runner_id).or_default().insert(node_id);
+
+                    runner = self.immediate_dominator(runner_id);
+                }
+            }
+        }
+
+        frontiers
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+// the facts computed by a dataflow analysis form a (join semi-)lattice.
+// the facts of a node only go up the lattice while solving, so the solver always terminates
+pub trait Lattice: Clone + PartialEq {
+    // the least element (the fact of a node which hasn't been visited yet)
+    fn bottom() -> Self;
+
+    // merges `other` into `self`, returns whether `self` has changed
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+// a "may" set (e.g the live variables), joined by union
+impl<T: Clone + Eq + Hash> Lattice for HashSet<T> {
+    fn bottom() -> Self {
+        HashSet::new()
+    }
+
+    fn join(&mut self, other: &Self) -> bool {
+        let len = self.len();
+
+        self.extend(other.iter().cloned());
+
+        self.len() != len
+    }
+}
+
+// a "must" set (e.g the dominators), joined by intersection.
+// its least element is the set of all the elements, represented by `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MustSet<T: Eq + Hash>(pub Option<HashSet<T>>);
+
+impl<T: Clone + Eq + Hash> MustSet<T> {
+    pub fn empty() -> Self {
+        MustSet(Some(HashSet::new()))
+    }
+
+    pub fn contains(&self, elem: &T) -> bool {
+        match self.0 {
+            Some(ref set) => set.contains(elem),
+            None => true,
+        }
+    }
+
+    pub fn insert(&mut self, elem: T) {
+        if let Some(ref mut set) = self.0 {
+            set.insert(elem);
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Lattice for MustSet<T> {
+    fn bottom() -> Self {
+        MustSet(None)
+    }
+
+    fn join(&mut self, other: &Self) -> bool {
+        match (&mut self.0, &other.0) {
+            (_, None) => false,
+            (None, Some(other_set)) => {
+                self.0 = Some(other_set.clone());
+                true
+            }
+            (Some(set), Some(other_set)) => {
+                let len = set.len();
+
+                set.retain(|elem| other_set.contains(elem));
+
+                set.len() != len
+            }
+        }
+    }
+}
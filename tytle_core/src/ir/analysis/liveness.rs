@@ -0,0 +1,39 @@
+use crate::ast::semantic::SymbolId;
+use crate::ir::analysis::{DataflowAnalysis, DataflowDirection};
+use crate::ir::{CfgInstruction, CfgNode};
+use std::collections::HashSet;
+
+// a variable is live at a point when its current value may be loaded later on.
+// only the `LOAD` / `STORE` instructions of the analyzed nodes are taken into account,
+// so the variables accessed by the called procedures (the globals and the enclosing procedures locals) are ignored
+pub struct Liveness;
+
+impl DataflowAnalysis for Liveness {
+    type Fact = HashSet<SymbolId>;
+
+    fn direction(&self) -> DataflowDirection {
+        DataflowDirection::Backward
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn transfer(&self, node: &CfgNode, input: &Self::Fact) -> Self::Fact {
+        let mut live = input.clone();
+
+        for inst in node.insts.iter().rev() {
+            match inst {
+                CfgInstruction::Store(var_id) => {
+                    live.remove(var_id);
+                }
+                CfgInstruction::Load(var_id) => {
+                    live.insert(*var_id);
+                }
+                _ => {}
+            }
+        }
+
+        live
+    }
+}
@@ -0,0 +1,11 @@
+mod dataflow;
+mod dominators;
+mod lattice;
+mod liveness;
+mod reaching_defs;
+
+pub use dataflow::{DataflowAnalysis, DataflowDirection, DataflowResult, DataflowSolver};
+pub use dominators::Dominators;
+pub use lattice::{Lattice, MustSet};
+pub use liveness::Liveness;
+pub use reaching_defs::{Definition, ReachingDefs};
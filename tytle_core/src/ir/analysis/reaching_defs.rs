@@ -0,0 +1,46 @@
+use crate::ast::semantic::SymbolId;
+use crate::ir::analysis::{DataflowAnalysis, DataflowDirection};
+use crate::ir::{CfgInstruction, CfgNode, CfgNodeId};
+use std::collections::HashSet;
+
+// a definition is a `STORE` instruction (at `ip` within node `node_id`) of variable `var_id`
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Definition {
+    pub node_id: CfgNodeId,
+    pub ip: usize,
+    pub var_id: SymbolId,
+}
+
+// a definition reaches a point when there's a path from it to the point without another definition of its variable.
+// the values a procedure starts with (e.g its params) have no definition
+pub struct ReachingDefs;
+
+impl DataflowAnalysis for ReachingDefs {
+    type Fact = HashSet<Definition>;
+
+    fn direction(&self) -> DataflowDirection {
+        DataflowDirection::Forward
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        HashSet::new()
+    }
+
+    fn transfer(&self, node: &CfgNode, input: &Self::Fact) -> Self::Fact {
+        let mut defs = input.clone();
+
+        for (ip, inst) in node.insts.iter().enumerate() {
+            if let CfgInstruction::Store(var_id) = inst {
+                defs.retain(|def| def.var_id != *var_id);
+
+                defs.insert(Definition {
+                    node_id: node.id,
+                    ip,
+                    var_id: *var_id,
+                });
+            }
+        }
+
+        defs
+    }
+}
@@ -1,17 +1,22 @@
 pub use crate::ast::{expression::*, semantic::*, statement::*, Ast};
 pub use crate::ir::*;
+pub use crate::lexer::Location;
 pub use std::collections::HashMap;
+use std::convert::TryFrom;
 
 pub struct CfgBuilder<'env> {
     cfg_graph: CfgGraph,
     env: &'env mut Environment,
+    entry_node_id: CfgNodeId,
     current_proc_id: SymbolId,
     proc_jmp_table: HashMap<SymbolId, CfgProc>,
+    source_map: CfgSourceMap,
 }
 
 impl<'env> CfgBuilder<'env> {
     pub fn new(env: &'env mut Environment) -> Self {
-        let mut cfg_graph = CfgGraph::new();
+        let cfg_graph = CfgGraph::new();
+        let entry_node_id = cfg_graph.get_entry_node_id();
 
         let main_proc = env.symbol_table.get_proc_by_name("__main__");
 
@@ -19,16 +24,59 @@ impl<'env> CfgBuilder<'env> {
             current_proc_id: main_proc.id,
             cfg_graph,
             env,
+            entry_node_id,
             proc_jmp_table: HashMap::new(),
+            source_map: CfgSourceMap::new(),
         }
     }
 
+    // continues building on top of a previously built `CfgObject`.
+    // the new `main` code is emitted into a fresh entry node,
+    // while the procedures built earlier remain callable
+    pub fn extend(env: &'env mut Environment, cfg_obj: CfgObject) -> Self {
+        let main_proc = env.symbol_table.get_proc_by_name("__main__");
+        let main_proc_id = main_proc.id;
+
+        let proc_jmp_table = cfg_obj
+            .jmp_table
+            .iter()
+            .filter(|(_, proc_id)| **proc_id != main_proc_id)
+            .map(|(node_id, proc_id)| {
+                let cfg_proc = CfgProc {
+                    node_id: *node_id,
+                    proc_id: *proc_id,
+                    built: true,
+                };
+
+                (*proc_id, cfg_proc)
+            })
+            .collect();
+
+        let mut cfg_graph = cfg_obj.graph;
+        let entry_node_id = cfg_graph.new_node();
+
+        Self {
+            current_proc_id: main_proc_id,
+            cfg_graph,
+            env,
+            entry_node_id,
+            proc_jmp_table,
+            source_map: cfg_obj.source_map,
+        }
+    }
+
+    pub fn get_entry_node_id(&self) -> CfgNodeId {
+        self.entry_node_id
+    }
+
     pub fn build(mut self, ast: &Ast) -> CfgObject {
-        let entry_id = self.cfg_graph.get_entry_node_id();
+        let entry_id = self.entry_node_id;
 
         let mut node_id = entry_id;
 
-        for stmt in &ast.statements {
+        for (i, stmt) in ast.statements.iter().enumerate() {
+            self.add_stmt_location(node_id, stmt, ast.get_stmt_location(i));
+
             node_id = self.build_stmt(node_id, stmt);
         }
 
@@ -48,6 +96,7 @@ impl<'env> CfgBuilder<'env> {
         CfgObject {
             graph: self.cfg_graph,
             jmp_table,
+            source_map: self.source_map,
         }
     }
 
@@ -58,11 +107,16 @@ impl<'env> CfgBuilder<'env> {
             Statement::Direction(direct_stmt) => self.build_direct(node_id, direct_stmt),
             Statement::Expression(expr) => self.build_expr(node_id, expr),
             Statement::Make(make_stmt) => self.build_make(node_id, make_stmt),
+            Statement::SetField(set_field_stmt) => self.build_set_field(node_id, set_field_stmt),
+            Statement::Struct(_) => node_id,
             Statement::If(if_stmt) => self.build_if(node_id, if_stmt),
             Statement::Repeat(repeat_stmt) => self.build_repeat(node_id, repeat_stmt),
+            Statement::Map(map_stmt) => self.build_map(node_id, map_stmt),
             Statement::Procedure(proc_stmt) => self.build_proc(node_id, proc_stmt),
             Statement::Return(return_stmt) => self.build_return(node_id, return_stmt),
             Statement::Print(expr) => self.build_print(node_id, expr),
+            Statement::Label(expr) => self.build_label(node_id, expr),
+            Statement::SetFontSize(expr) => self.build_set_font_size(node_id, expr),
         }
     }
 
@@ -75,6 +129,22 @@ impl<'env> CfgBuilder<'env> {
         node_id
     }
 
+    fn build_label(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        self.build_expr(node_id, expr);
+
+        self.append_inst(node_id, CfgInstruction::Label);
+
+        node_id
+    }
+
+    fn build_set_font_size(&mut self, node_id: CfgNodeId, expr: &Expression) -> CfgNodeId {
+        self.build_expr(node_id, expr);
+
+        self.append_inst(node_id, CfgInstruction::SetFontSize);
+
+        node_id
+    }
+
     fn build_return(&mut self, node_id: CfgNodeId, return_stmt: &ReturnStmt) -> CfgNodeId {
         if return_stmt.expr.is_some() {
             let expr: &Expression = return_stmt.expr.as_ref().unwrap();
@@ -174,6 +244,18 @@ impl<'env> CfgBuilder<'env> {
         self.build_assign(node_id, var_id, expr)
     }
 
+    fn build_set_field(&mut self, node_id: CfgNodeId, set_field_stmt: &SetFieldStmt) -> CfgNodeId {
+        let record_expr = &set_field_stmt.record_expr;
+        let field_index = self.field_index(record_expr, &set_field_stmt.field_name);
+
+        self.build_expr(node_id, record_expr);
+        self.build_expr(node_id, &set_field_stmt.expr);
+
+        self.append_inst(node_id, CfgInstruction::StoreField(field_index));
+
+        node_id
+    }
+
     fn build_assign(
         &mut self,
         node_id: CfgNodeId,
@@ -195,7 +277,13 @@ impl<'env> CfgBuilder<'env> {
             ExpressionAst::Not(_) => self.build_not_expr(node_id, expr),
             ExpressionAst::Binary(..) => self.build_bin_expr(node_id, expr),
             ExpressionAst::Parentheses(_) => self.build_parentheses_expr(node_id, expr),
+            ExpressionAst::NamedArg(_, ref arg_expr) => {
+                self.build_expr(node_id, arg_expr);
+            }
             ExpressionAst::ProcCall(..) => self.build_proc_call_expr(node_id, expr),
+            ExpressionAst::IndirectCall(..) => self.build_indirect_call_expr(node_id, expr),
+            ExpressionAst::NewStruct(..) => self.build_new_struct_expr(node_id, expr),
+            ExpressionAst::Field(..) => self.build_field_expr(node_id, expr),
         }
 
         node_id
@@ -203,30 +291,101 @@ impl<'env> CfgBuilder<'env> {
 
     fn build_proc_call_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
         let (_proc_name, proc_args_exprs, proc_id) = expr.as_proc_call_expr();
+        let proc_id = *proc_id.unwrap();
+
+        // the arguments are pushed in the params order,
+        // filling in the default value of each param the call doesn't pass
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+        let args_exprs: Vec<Expression> = proc
+            .bind_call_args(proc_args_exprs)
+            .unwrap()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        for arg_expr in args_exprs.iter() {
+            self.build_expr(node_id, arg_expr);
+        }
+
+        let jmp_node_id = self.proc_node_id(proc_id);
+
+        self.append_inst(node_id, CfgInstruction::Call(jmp_node_id));
+    }
+
+    fn build_indirect_call_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
+        let (proc_expr, proc_args_exprs) = expr.as_indirect_call_expr();
 
         for proc_arg_expr in proc_args_exprs {
             self.build_expr(node_id, proc_arg_expr);
         }
 
-        let proc_id = *proc_id.unwrap();
-        let cfg_proc = self.proc_jmp_table.get(&proc_id);
+        // the procedure value is evaluated last, so it's popped first by `CallIndirect`
+        self.build_expr(node_id, proc_expr);
 
-        let jmp_node_id = if cfg_proc.is_none() {
-            let proc_node_id = self.cfg_graph.new_node();
+        self.append_inst(node_id, CfgInstruction::CallIndirect);
+    }
 
-            let cfg_proc = CfgProc {
-                node_id: proc_node_id,
-                proc_id,
-                built: false,
-            };
-            self.proc_jmp_table.insert(proc_id, cfg_proc);
+    fn build_new_struct_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
+        let (_struct_name, fields_args, struct_id) = expr.as_new_struct_expr();
+        let struct_id = *struct_id.unwrap();
+
+        // the fields values are pushed in the fields order
+        let struct_type = self.env.symbol_table.get_struct_by_id(struct_id);
+        let args_exprs: Vec<Expression> = struct_type
+            .bind_fields_args(fields_args)
+            .unwrap()
+            .into_iter()
+            .cloned()
+            .collect();
 
-            proc_node_id
-        } else {
-            cfg_proc.unwrap().node_id
+        for arg_expr in args_exprs.iter() {
+            self.build_expr(node_id, arg_expr);
+        }
+
+        self.append_inst(node_id, CfgInstruction::NewStruct(struct_id));
+    }
+
+    fn build_field_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
+        let (record_expr, field_name) = expr.as_field_expr();
+        let field_index = self.field_index(record_expr, field_name);
+
+        self.build_expr(node_id, record_expr);
+
+        self.append_inst(node_id, CfgInstruction::LoadField(field_index));
+    }
+
+    // the index of field `field_name` within the struct of the type-checked `record_expr`
+    fn field_index(&self, record_expr: &Expression, field_name: &str) -> usize {
+        let struct_name = match record_expr.expr_type {
+            Some(ExpressionType::Struct(ref struct_name)) => struct_name,
+            _ => panic!("expected a record expression. got: `{:?}`", record_expr),
         };
 
-        self.append_inst(node_id, CfgInstruction::Call(jmp_node_id));
+        let struct_type = self
+            .env
+            .symbol_table
+            .get_struct_by_name(struct_name)
+            .unwrap();
+
+        struct_type.field_index(field_name).unwrap()
+    }
+
+    // returns the CFG node of the procedure, allocating it when the procedure isn't built yet
+    fn proc_node_id(&mut self, proc_id: SymbolId) -> CfgNodeId {
+        if let Some(cfg_proc) = self.proc_jmp_table.get(&proc_id) {
+            return cfg_proc.node_id;
+        }
+
+        let proc_node_id = self.cfg_graph.new_node();
+
+        let cfg_proc = CfgProc {
+            node_id: proc_node_id,
+            proc_id,
+            built: false,
+        };
+        self.proc_jmp_table.insert(proc_id, cfg_proc);
+
+        proc_node_id
     }
 
     fn build_parentheses_expr(&mut self, node_id: CfgNodeId, expr: &Expression) {
@@ -270,15 +429,25 @@ impl<'env> CfgBuilder<'env> {
             LiteralExpr::Var(_, ref var_id) => {
                 self.append_var_lit(node_id, var_id.as_ref().unwrap())
             }
+            LiteralExpr::Proc(_, ref proc_id) => self.append_proc_lit(node_id, proc_id.unwrap()),
         }
     }
 
+    fn append_proc_lit(&mut self, node_id: CfgNodeId, proc_id: SymbolId) {
+        let proc_node_id = self.proc_node_id(proc_id);
+
+        self.append_inst(node_id, CfgInstruction::ProcRef(proc_node_id));
+    }
+
     fn append_bool_lit(&mut self, node_id: CfgNodeId, lit: bool) {
         self.append_inst(node_id, CfgInstruction::Bool(lit));
     }
 
     fn append_int_lit(&mut self, node_id: CfgNodeId, lit: usize) {
-        self.append_inst(node_id, CfgInstruction::Int(lit as isize));
+        // the parser rejects the literals out of the `isize` range (so the value never wraps)
+        let lit = isize::try_from(lit).expect("integer literal out of range");
+
+        self.append_inst(node_id, CfgInstruction::Int(lit));
     }
 
     fn append_str_lit(&mut self, node_id: CfgNodeId, lit: &str) {
@@ -396,6 +565,101 @@ impl<'env> CfgBuilder<'env> {
         after_node_id
     }
 
+    fn build_map(&mut self, node_id: CfgNodeId, map_stmt: &MapStmt) -> CfgNodeId {
+        // `MAP(F, N)` is built like `REPEAT N [APPLY(F, TMPVAR_A)]`:
+        // 1) allocate temporary variables `TMPVAR_A` (the index), `TMPVAR_B` (the count) and `TMPVAR_F`
+        // 2) emit `MAKE TMPVAR_F = F`, `MAKE TMPVAR_A = 0` and `MAKE TMPVAR_B = N`  (within `CURRENT_NODE_ID`)
+        // 3) emit `TMPVAR_A < TMPVAR_B` (within `CURRENT_NODE_ID`)
+        // 4) create `WHILE_NODE_ID` and add edge `CURRENT_NODE_ID` --jmp-when-true--> `WHILE_NODE_ID`
+        // 5) emit `APPLY(TMPVAR_F, TMPVAR_A)`, `TMPVAR_A = TMPVAR_A + 1` and `TMPVAR_A < TMPVAR_B`
+        //    (within `WHILE_NODE_ID`)
+        // 6) add edge `WHILE_NODE_ID` --jmp-when-true--> `WHILE_NODE_ID`
+        // 7) create `AFTER_NODE_ID` and add `--jmp-fallback-->` edges to it
+        //    from both `CURRENT_NODE_ID` and `WHILE_NODE_ID`
+
+        let proc_type = map_stmt.proc_expr.expr_type.clone().unwrap();
+
+        let (var_id_a, var_name_a) = self
+            .env
+            .create_tmp_var(self.current_proc_id, ExpressionType::Int);
+        let (var_id_b, var_name_b) = self
+            .env
+            .create_tmp_var(self.current_proc_id, ExpressionType::Int);
+        let (var_id_f, var_name_f) = self
+            .env
+            .create_tmp_var(self.current_proc_id, proc_type.clone());
+
+        let var_expr = |var_name: &str, var_id: SymbolId, var_type: ExpressionType| Expression {
+            expr_ast: ExpressionAst::Literal(LiteralExpr::Var(var_name.to_string(), Some(var_id))),
+            expr_type: Some(var_type),
+        };
+
+        // MAKE TMPVAR_F = F
+        self.build_assign(node_id, var_id_f, &map_stmt.proc_expr);
+
+        // MAKE TMPVAR_A = 0
+        let zero_expr = Expression {
+            expr_type: Some(ExpressionType::Int),
+            expr_ast: ExpressionAst::Literal(LiteralExpr::Int(0)),
+        };
+        self.build_assign(node_id, var_id_a, &zero_expr);
+
+        // MAKE TMPVAR_B = N
+        self.build_assign(node_id, var_id_b, &map_stmt.count_expr);
+
+        // TMPVAR_A < TMPVAR_B
+        let cond_ast = ExpressionAst::Binary(
+            BinaryOp::LessThan,
+            Box::new(var_expr(&var_name_a, var_id_a, ExpressionType::Int)),
+            Box::new(var_expr(&var_name_b, var_id_b, ExpressionType::Int)),
+        );
+        let cond_expr = Expression {
+            expr_ast: cond_ast,
+            expr_type: Some(ExpressionType::Bool),
+        };
+        self.build_expr(node_id, &cond_expr);
+
+        let while_node_id = self.cfg_graph.new_node();
+        self.add_edge(node_id, while_node_id, CfgJumpType::WhenTrue);
+
+        // APPLY(TMPVAR_F, TMPVAR_A)
+        let call_ast = ExpressionAst::IndirectCall(
+            Box::new(var_expr(&var_name_f, var_id_f, proc_type)),
+            vec![var_expr(&var_name_a, var_id_a, ExpressionType::Int)],
+        );
+        let call_expr = Expression {
+            expr_ast: call_ast,
+            expr_type: Some(ExpressionType::Unit),
+        };
+        self.build_expr(while_node_id, &call_expr);
+
+        // TMPVAR_A = TMPVAR_A + 1
+        let one_expr = Expression {
+            expr_type: Some(ExpressionType::Int),
+            expr_ast: ExpressionAst::Literal(LiteralExpr::Int(1)),
+        };
+        let incr_ast = ExpressionAst::Binary(
+            BinaryOp::Add,
+            Box::new(var_expr(&var_name_a, var_id_a, ExpressionType::Int)),
+            Box::new(one_expr),
+        );
+        let incr_expr = Expression {
+            expr_type: Some(ExpressionType::Int),
+            expr_ast: incr_ast,
+        };
+        self.build_assign(while_node_id, var_id_a, &incr_expr);
+
+        // TMPVAR_A < TMPVAR_B
+        self.build_expr(while_node_id, &cond_expr);
+        self.add_edge(while_node_id, while_node_id, CfgJumpType::WhenTrue);
+
+        let after_node_id = self.cfg_graph.new_node();
+        self.add_edge(while_node_id, after_node_id, CfgJumpType::Fallback);
+        self.add_edge(node_id, after_node_id, CfgJumpType::Fallback);
+
+        after_node_id
+    }
+
     fn build_if(&mut self, node_id: CfgNodeId, if_stmt: &IfStmt) -> CfgNodeId {
         // 1)  let's mark current CFG node as `CURRENT_NODE_ID` (the `node_id` parameter)
         //     this node is assumed to be empty
@@ -439,75 +703,64 @@ impl<'env> CfgBuilder<'env> {
         let true_block_ends_with_empty_node = self.cfg_graph.node_is_empty(last_true_block_node_id);
         let true_block_ends_with_return = self.cfg_graph.ends_with_return(last_true_block_node_id);
 
-        let mut draw_true_block_to_after_node_edge = false;
-
         let after_node_id = match true_block_ends_with_empty_node {
-            true => Some(last_true_block_node_id), // we'll reuse this empty node
+            true => last_true_block_node_id, // we'll reuse this empty node
             false => {
-                // we know the `true-block last node` isn't empty
-                // but we want to allocate a new empty CFG node **only**
-                // when the last node-statement *IS NOT* a `RETURN`-statement
-
-                if true_block_ends_with_return {
-                    // no need to draw edge `LAST_TRUE_BLOCK_NODE_ID` --jmp-always--> `AFTER_NODE_ID`
-                    None
-                } else {
-                    draw_true_block_to_after_node_edge = true;
-                    Some(self.cfg_graph.new_node())
+                let after_node_id = self.cfg_graph.new_node();
+
+                // we draw edge `LAST_TRUE_BLOCK_NODE_ID` --jmp-always--> `AFTER_NODE_ID`
+                // only if the `true-block` last statement *IS NOT* a `RETURN`-statement
+                if !true_block_ends_with_return {
+                    self.add_edge(last_true_block_node_id, after_node_id, CfgJumpType::Always);
                 }
+
+                after_node_id
             }
         };
 
-        if draw_true_block_to_after_node_edge {
-            self.add_edge(
-                last_true_block_node_id,
-                after_node_id.unwrap(),
-                CfgJumpType::Always,
-            );
-        }
-
-        if if_stmt.false_block.is_some() {
-            // we draw edge `LAST_FALSE_BLOCK_NODE_ID` --jmp-always--> `AFTER_NODE_ID`
-            // only if the `else-block` statement *IS NOT* a `RETURN`-statement
-            let last_false_block_node_id = last_false_block_node_id.unwrap();
-
-            let false_block_ends_with_return =
-                self.cfg_graph.ends_with_return(last_false_block_node_id);
-
-            if false_block_ends_with_return == false {
-                self.add_edge(
-                    last_false_block_node_id,
-                    after_node_id.unwrap(),
-                    CfgJumpType::Always,
-                );
+        match last_false_block_node_id {
+            Some(last_false_block_node_id) => {
+                // we draw edge `LAST_FALSE_BLOCK_NODE_ID` --jmp-always--> `AFTER_NODE_ID`
+                // only if the `else-block` last statement *IS NOT* a `RETURN`-statement
+                if !self.cfg_graph.ends_with_return(last_false_block_node_id) {
+                    self.add_edge(last_false_block_node_id, after_node_id, CfgJumpType::Always);
+                }
             }
-        } else {
-            // there is no `else-block`
-            // we'll draw edge `CURRENT_NODE_ID` --jmp-fallback--> `AFTER_NODE_ID`
-            // in case there is an after node
-
-            if after_node_id.is_some() {
-                self.add_edge(node_id, after_node_id.unwrap(), CfgJumpType::Fallback);
+            None => {
+                // there is no `else-block`, so we draw edge `CURRENT_NODE_ID` --jmp-fallback--> `AFTER_NODE_ID`
+                // (even when the `true-block` returns, the condition may be false)
+                self.add_edge(node_id, after_node_id, CfgJumpType::Fallback);
             }
         }
 
-        if after_node_id.is_some() {
-            after_node_id.unwrap()
-        } else {
-            self.cfg_graph.new_node()
-        }
+        after_node_id
     }
 
     fn build_block(&mut self, node_id: CfgNodeId, block_stmt: &BlockStatement) -> CfgNodeId {
         let mut last_node_id = node_id;
 
-        for stmt in &block_stmt.stmts {
-            last_node_id = self.build_stmt(node_id, stmt);
+        for (i, stmt) in block_stmt.stmts.iter().enumerate() {
+            self.add_stmt_location(last_node_id, stmt, block_stmt.get_stmt_location(i));
+
+            last_node_id = self.build_stmt(last_node_id, stmt);
         }
 
         last_node_id
     }
 
+    fn add_stmt_location(&mut self, node_id: CfgNodeId, stmt: &Statement, loc: Option<Location>) {
+        if let Statement::Procedure(_) = stmt {
+            // procedure statements emit no instructions under `node_id`
+            return;
+        }
+
+        if let Some(loc) = loc {
+            let ip = self.cfg_graph.get_node(node_id).insts.len();
+
+            self.source_map.add_stmt(node_id, ip, loc);
+        }
+    }
+
     fn append_inst(&mut self, node_id: CfgNodeId, inst: CfgInstruction) {
         let node = self.cfg_graph.get_node_mut(node_id);
 
@@ -0,0 +1,163 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::ir::{CfgGraph, CfgJumpType, CfgNodeId, CfgObject, CfgPrinter};
+use std::collections::HashMap;
+
+// renders a CFG as a Graphviz DOT digraph (e.g `dot -Tsvg cfg.dot > cfg.svg`).
+// each node is a record listing its instructions (in the textual IR format, see `CfgPrinter`),
+// and the edges are styled by their jump type:
+// * `WhenTrue` - green, labeled `true`
+// * `Fallback` - red and dashed, labeled `false`
+// * `Always`   - black
+//
+// the entry node is highlighted. when the procedures start nodes are known (see `CfgObject::to_dot`),
+// the nodes of each procedure are grouped into a cluster
+impl CfgGraph {
+    pub fn to_dot(&self, env: &Environment) -> String {
+        CfgDot::new(env).render(self, &HashMap::new())
+    }
+}
+
+impl CfgObject {
+    pub fn to_dot(&self, env: &Environment) -> String {
+        CfgDot::new(env).render(&self.graph, &self.jmp_table)
+    }
+}
+
+struct CfgDot<'env> {
+    env: &'env Environment,
+    printer: CfgPrinter<'env>,
+}
+
+impl<'env> CfgDot<'env> {
+    fn new(env: &'env Environment) -> Self {
+        Self {
+            env,
+            printer: CfgPrinter::with_env(env),
+        }
+    }
+
+    fn render(&self, graph: &CfgGraph, jmp_table: &HashMap<CfgNodeId, SymbolId>) -> String {
+        let mut buffer = String::new();
+
+        buffer.push_str("digraph cfg {\n");
+        buffer.push_str("    node [shape=record, fontname=\"monospace\"];\n");
+
+        let nodes_procs = CfgObject::nodes_procs(graph, jmp_table);
+
+        // procedures are ordered by their start node, and so are the nodes of each procedure
+        let mut procs_nodes: Vec<(CfgNodeId, SymbolId)> = jmp_table
+            .iter()
+            .map(|(node_id, proc_id)| (*node_id, *proc_id))
+            .collect();
+        procs_nodes.sort_by_key(|(node_id, _)| *node_id);
+
+        for (_, proc_id) in procs_nodes.iter() {
+            let proc = self.env.symbol_table.get_proc_by_id(*proc_id);
+
+            buffer.push_str(&format!("    subgraph cluster_{} {{\n", proc_id.0));
+            buffer.push_str(&format!("        label=\"{}\";\n", proc.name));
+
+            for node_id in graph.get_nodes_ids() {
+                if nodes_procs.get(&node_id) == Some(proc_id) {
+                    let node_dot = self.render_node(graph, node_id, jmp_table);
+
+                    buffer.push_str(&format!("        {}\n", node_dot));
+                }
+            }
+
+            buffer.push_str("    }\n");
+        }
+
+        // the nodes not owned by any procedure (e.g unreachable nodes, or a graph without a jump table)
+        for node_id in graph.get_nodes_ids() {
+            if !nodes_procs.contains_key(&node_id) {
+                let node_dot = self.render_node(graph, node_id, jmp_table);
+
+                buffer.push_str(&format!("    {}\n", node_dot));
+            }
+        }
+
+        for node_id in graph.get_nodes_ids() {
+            let node = graph.get_node(node_id);
+
+            let mut edges: Vec<(CfgNodeId, CfgJumpType)> = node
+                .outgoing
+                .iter()
+                .map(|edge| (edge.node_id, edge.jmp_type))
+                .collect();
+            edges.sort_by_key(|(dst_id, jmp_type)| {
+                let order = match jmp_type {
+                    CfgJumpType::WhenTrue => 0,
+                    CfgJumpType::Fallback => 1,
+                    CfgJumpType::Always => 2,
+                };
+
+                (*dst_id, order)
+            });
+
+            for (dst_id, jmp_type) in edges {
+                let style = match jmp_type {
+                    CfgJumpType::WhenTrue => " [label=\"true\", color=\"darkgreen\"]",
+                    CfgJumpType::Fallback => " [label=\"false\", color=\"red\", style=\"dashed\"]",
+                    CfgJumpType::Always => "",
+                };
+
+                buffer.push_str(&format!("    n{} -> n{}{};\n", node_id, dst_id, style));
+            }
+        }
+
+        buffer.push_str("}\n");
+
+        buffer
+    }
+
+    fn render_node(
+        &self,
+        graph: &CfgGraph,
+        node_id: CfgNodeId,
+        jmp_table: &HashMap<CfgNodeId, SymbolId>,
+    ) -> String {
+        let node = graph.get_node(node_id);
+
+        // each instruction is left-justified (`\l`)
+        let insts: String = node
+            .insts
+            .iter()
+            .map(|inst| {
+                format!(
+                    "{}\\l",
+                    Self::escape(&self.printer.print_inst(inst, jmp_table))
+                )
+            })
+            .collect();
+
+        let label = if insts.is_empty() {
+            format!("node {}", node_id)
+        } else {
+            format!("{{node {}|{}}}", node_id, insts)
+        };
+
+        let highlight = if node_id == graph.get_entry_node_id() {
+            ", style=\"filled\", fillcolor=\"lightblue\""
+        } else {
+            ""
+        };
+
+        format!("n{} [label=\"{}\"{}];", node_id, label, highlight)
+    }
+
+    // escapes the characters having a meaning within a record label
+    fn escape(s: &str) -> String {
+        let mut escaped = String::new();
+
+        for c in s.chars() {
+            if let '"' | '\\' | '{' | '}' | '|' | '<' | '>' = c {
+                escaped.push('\\');
+            }
+
+            escaped.push(c);
+        }
+
+        escaped
+    }
+}
@@ -1,5 +1,5 @@
 use crate::ast::semantic::SymbolId;
-use crate::ir::CfgNode;
+use crate::ir::{CfgEdge, CfgNode};
 use std::collections::HashMap;
 
 pub type CfgNodeId = usize;
@@ -88,6 +88,51 @@ impl CfgGraph {
         dst_node.add_incoming_edge(src_id, jmp_type);
     }
 
+    pub fn remove_edge(&mut self, src_id: CfgNodeId, dst_id: CfgNodeId, jmp_type: CfgJumpType) {
+        let edge = CfgEdge {
+            node_id: dst_id,
+            jmp_type,
+        };
+        self.get_node_mut(src_id).outgoing.remove(&edge);
+
+        let edge = CfgEdge {
+            node_id: src_id,
+            jmp_type,
+        };
+        self.get_node_mut(dst_id).incoming.remove(&edge);
+    }
+
+    // removes node `node_id` along with all the edges connected to it
+    pub fn remove_node(&mut self, node_id: CfgNodeId) {
+        let node = self.nodes.remove(&node_id).unwrap();
+
+        for edge in node.outgoing.iter() {
+            if let Some(dst_node) = self.nodes.get_mut(&edge.node_id) {
+                dst_node.incoming.remove(&CfgEdge {
+                    node_id,
+                    jmp_type: edge.jmp_type,
+                });
+            }
+        }
+
+        for edge in node.incoming.iter() {
+            if let Some(src_node) = self.nodes.get_mut(&edge.node_id) {
+                src_node.outgoing.remove(&CfgEdge {
+                    node_id,
+                    jmp_type: edge.jmp_type,
+                });
+            }
+        }
+    }
+
+    // the nodes ids in ascending order
+    pub fn get_nodes_ids(&self) -> Vec<CfgNodeId> {
+        let mut nodes_ids: Vec<CfgNodeId> = self.nodes.keys().cloned().collect();
+        nodes_ids.sort();
+
+        nodes_ids
+    }
+
     pub fn get_node(&self, node_id: CfgNodeId) -> &CfgNode {
         self.nodes.get(&node_id).unwrap()
     }
@@ -9,12 +9,29 @@ pub enum CfgInstruction {
     Load(SymbolId),
     Store(SymbolId),
     Call(CfgNodeId),
+
+    // pops a procedure value and calls it
+    CallIndirect,
+
+    // pushes a procedure value
+    ProcRef(CfgNodeId),
+
+    // pops the struct fields values (the last field first) and pushes a new record
+    NewStruct(SymbolId),
+
+    // pops a record and pushes its field (by the field index)
+    LoadField(usize),
+
+    // pops a value and then a record, and stores the value into the record field
+    StoreField(usize),
     Bool(bool),
     Int(isize),
     Str(String),
     Return,
     Trap,
     Print,
+    Label,
+    SetFontSize,
     EOC,
     Add,
     Mul,
@@ -1,8 +1,42 @@
 use crate::ast::semantic::SymbolId;
-use crate::ir::{CfgGraph, CfgNodeId};
+use crate::ir::{CfgGraph, CfgNodeId, CfgSourceMap};
 use std::collections::HashMap;
 
 pub struct CfgObject {
     pub graph: CfgGraph,
     pub jmp_table: HashMap<CfgNodeId, SymbolId>,
+    pub source_map: CfgSourceMap,
+}
+
+impl CfgObject {
+    // maps each node to the procedure its code belongs to.
+    // the edges never cross procedures, so a procedure owns the nodes reachable from its start node
+    pub fn get_nodes_procs(&self) -> HashMap<CfgNodeId, SymbolId> {
+        Self::nodes_procs(&self.graph, &self.jmp_table)
+    }
+
+    pub(crate) fn nodes_procs(
+        graph: &CfgGraph,
+        jmp_table: &HashMap<CfgNodeId, SymbolId>,
+    ) -> HashMap<CfgNodeId, SymbolId> {
+        let mut nodes_procs = HashMap::new();
+
+        for (node_id, proc_id) in jmp_table.iter() {
+            let mut pending = vec![*node_id];
+
+            while let Some(node_id) = pending.pop() {
+                if nodes_procs.insert(node_id, *proc_id).is_some() {
+                    continue;
+                }
+
+                let node = graph.get_node(node_id);
+
+                for edge in node.outgoing.iter() {
+                    pending.push(edge.node_id);
+                }
+            }
+        }
+
+        nodes_procs
+    }
 }
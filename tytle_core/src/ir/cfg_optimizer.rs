@@ -0,0 +1,68 @@
+use crate::ast::semantic::Environment;
+use crate::ir::passes::{
+    ConstantFolding, Inlining, JumpThreading, MergeNodes, TailCalls, UnreachableNodes,
+};
+use crate::ir::CfgObject;
+
+pub trait CfgPass {
+    fn name(&self) -> &'static str;
+
+    // returns whether the pass has changed the CFG.
+    // a pass may allocate new variables (e.g temporaries for the inlined procedures locals)
+    fn run(&self, cfg: &mut CfgObject, env: &mut Environment) -> bool;
+}
+
+// runs its passes, in order, over and over until none of them changes the CFG anymore
+pub struct CfgOptimizer {
+    passes: Vec<Box<dyn CfgPass>>,
+}
+
+impl CfgOptimizer {
+    // the default optimization pipeline (used by `-O`)
+    pub fn new() -> Self {
+        let mut optimizer = Self::empty();
+
+        optimizer.add_pass(Box::new(Inlining));
+        optimizer.add_pass(Box::new(TailCalls));
+        optimizer.add_pass(Box::new(ConstantFolding));
+        optimizer.add_pass(Box::new(UnreachableNodes));
+        optimizer.add_pass(Box::new(JumpThreading));
+        optimizer.add_pass(Box::new(MergeNodes));
+
+        optimizer
+    }
+
+    pub fn empty() -> Self {
+        Self { passes: Vec::new() }
+    }
+
+    pub fn add_pass(&mut self, pass: Box<dyn CfgPass>) {
+        self.passes.push(pass);
+    }
+
+    pub fn get_passes_names(&self) -> Vec<&'static str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    pub fn optimize(&self, cfg: &mut CfgObject, env: &mut Environment) {
+        loop {
+            let mut changed = false;
+
+            for pass in self.passes.iter() {
+                if pass.run(cfg, env) {
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+    }
+}
+
+impl Default for CfgOptimizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
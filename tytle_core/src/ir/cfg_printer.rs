@@ -0,0 +1,183 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::ir::{CfgGraph, CfgInstruction, CfgJumpType, CfgNode, CfgNodeId, CfgObject};
+use std::collections::HashMap;
+
+// prints a CFG in the textual IR format (see `CfgTextParser` for parsing it back).
+// each node is listed with its instructions, followed by its outgoing edges. for example:
+//
+// node 1 proc __main__#1:
+//     int 10
+//     store A#2
+//     call @2 SQUARE
+//     eoc
+// node 2 proc SQUARE#3:
+//     load A#2
+//     direct FORWARD
+//     return
+//
+// symbols are printed as `NAME#id` and nodes as `@id`.
+// without an environment the symbols names are omitted (e.g `load #2`)
+pub struct CfgPrinter<'env> {
+    env: Option<&'env Environment>,
+}
+
+impl<'env> CfgPrinter<'env> {
+    pub fn new() -> Self {
+        Self { env: None }
+    }
+
+    pub fn with_env(env: &'env Environment) -> Self {
+        Self { env: Some(env) }
+    }
+
+    pub fn print_object(&self, cfg: &CfgObject) -> String {
+        self.print(&cfg.graph, &cfg.jmp_table)
+    }
+
+    pub fn print_graph(&self, graph: &CfgGraph) -> String {
+        self.print(graph, &HashMap::new())
+    }
+
+    fn print(&self, graph: &CfgGraph, jmp_table: &HashMap<CfgNodeId, SymbolId>) -> String {
+        let mut buffer = String::new();
+
+        for node_id in graph.get_nodes_ids() {
+            let node = graph.get_node(node_id);
+
+            match jmp_table.get(&node_id) {
+                Some(proc_id) => buffer.push_str(&format!(
+                    "node {} proc {}:\n",
+                    node_id,
+                    self.proc_name(*proc_id)
+                )),
+                None => buffer.push_str(&format!("node {}:\n", node_id)),
+            }
+
+            for inst in node.insts.iter() {
+                buffer.push_str(&format!("    {}\n", self.print_inst(inst, jmp_table)));
+            }
+
+            for (jmp_type, dst_id) in Self::sorted_edges(node) {
+                let jmp = match jmp_type {
+                    CfgJumpType::WhenTrue => "when_true",
+                    CfgJumpType::Fallback => "fallback",
+                    CfgJumpType::Always => "always",
+                };
+
+                buffer.push_str(&format!("    {} @{}\n", jmp, dst_id));
+            }
+        }
+
+        buffer
+    }
+
+    pub(crate) fn print_inst(
+        &self,
+        inst: &CfgInstruction,
+        jmp_table: &HashMap<CfgNodeId, SymbolId>,
+    ) -> String {
+        match inst {
+            CfgInstruction::Command(cmd) => format!("cmd {}", cmd.as_str()),
+            CfgInstruction::Direction(direct) => format!("direct {}", direct.as_str()),
+            CfgInstruction::Load(var_id) => format!("load {}", self.var_name(*var_id)),
+            CfgInstruction::Store(var_id) => format!("store {}", self.var_name(*var_id)),
+            CfgInstruction::Call(node_id) => {
+                format!("call {}", self.node_ref(*node_id, jmp_table))
+            }
+            CfgInstruction::CallIndirect => "call_indirect".to_string(),
+            CfgInstruction::ProcRef(node_id) => {
+                format!("proc_ref {}", self.node_ref(*node_id, jmp_table))
+            }
+            CfgInstruction::NewStruct(struct_id) => {
+                format!("new_struct {}", self.struct_name(*struct_id))
+            }
+            CfgInstruction::LoadField(index) => format!("load_field {}", index),
+            CfgInstruction::StoreField(index) => format!("store_field {}", index),
+            CfgInstruction::Bool(v) => format!("bool {}", v),
+            CfgInstruction::Int(v) => format!("int {}", v),
+            CfgInstruction::Str(v) => format!("str \"{}\"", v),
+            CfgInstruction::Return => "return".to_string(),
+            CfgInstruction::Trap => "trap".to_string(),
+            CfgInstruction::Print => "print".to_string(),
+            CfgInstruction::Label => "label".to_string(),
+            CfgInstruction::SetFontSize => "set_font_size".to_string(),
+            CfgInstruction::EOC => "eoc".to_string(),
+            CfgInstruction::Add => "add".to_string(),
+            CfgInstruction::Mul => "mul".to_string(),
+            CfgInstruction::Div => "div".to_string(),
+            CfgInstruction::Not => "not".to_string(),
+            CfgInstruction::And => "and".to_string(),
+            CfgInstruction::Or => "or".to_string(),
+            CfgInstruction::GreaterThan => "gt".to_string(),
+            CfgInstruction::LessThan => "lt".to_string(),
+        }
+    }
+
+    // the edges are ordered by their jump type (`when_true`, `fallback` and then `always`)
+    fn sorted_edges(node: &CfgNode) -> Vec<(CfgJumpType, CfgNodeId)> {
+        let mut edges: Vec<(CfgJumpType, CfgNodeId)> = node
+            .outgoing
+            .iter()
+            .map(|edge| (edge.jmp_type, edge.node_id))
+            .collect();
+
+        edges.sort_by_key(|(jmp_type, node_id)| {
+            let order = match jmp_type {
+                CfgJumpType::WhenTrue => 0,
+                CfgJumpType::Fallback => 1,
+                CfgJumpType::Always => 2,
+            };
+
+            (order, *node_id)
+        });
+
+        edges
+    }
+
+    // a node reference is followed by the name of the procedure starting at it (when known)
+    fn node_ref(&self, node_id: CfgNodeId, jmp_table: &HashMap<CfgNodeId, SymbolId>) -> String {
+        match (jmp_table.get(&node_id), self.env) {
+            (Some(proc_id), Some(env)) => {
+                let proc = env.symbol_table.get_proc_by_id(*proc_id);
+
+                format!("@{} {}", node_id, proc.name)
+            }
+            _ => format!("@{}", node_id),
+        }
+    }
+
+    fn var_name(&self, var_id: SymbolId) -> String {
+        match self.env {
+            Some(env) => format!("{}{}", env.symbol_table.get_var_by_id(var_id).name, var_id),
+            None => var_id.to_string(),
+        }
+    }
+
+    fn proc_name(&self, proc_id: SymbolId) -> String {
+        match self.env {
+            Some(env) => format!(
+                "{}{}",
+                env.symbol_table.get_proc_by_id(proc_id).name,
+                proc_id
+            ),
+            None => proc_id.to_string(),
+        }
+    }
+
+    fn struct_name(&self, struct_id: SymbolId) -> String {
+        match self.env {
+            Some(env) => format!(
+                "{}{}",
+                env.symbol_table.get_struct_by_id(struct_id).name,
+                struct_id
+            ),
+            None => struct_id.to_string(),
+        }
+    }
+}
+
+impl<'env> Default for CfgPrinter<'env> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,106 @@
+use crate::ir::CfgNodeId;
+use crate::lexer::Location;
+use std::collections::HashMap;
+
+// maps CFG instructions back to the source statements they were generated from.
+// for each CFG node we keep the `ip` of the first instruction of each statement,
+// ordered by `ip`
+#[derive(Debug, Clone, Default)]
+pub struct CfgSourceMap {
+    nodes: HashMap<CfgNodeId, Vec<(usize, Location)>>,
+}
+
+impl CfgSourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_stmt(&mut self, node_id: CfgNodeId, ip: usize, location: Location) {
+        let stmts = self.nodes.entry(node_id).or_default();
+
+        // a statement emitting no instructions (e.g `TO`) shares its `ip`
+        // with the next statement, so the later statement wins
+        if let Some(last) = stmts.last_mut() {
+            if last.0 == ip {
+                last.1 = location;
+                return;
+            }
+        }
+
+        stmts.push((ip, location));
+    }
+
+    pub fn remove_node(&mut self, node_id: CfgNodeId) {
+        self.nodes.remove(&node_id);
+    }
+
+    // rewrites the statements `ip`s of node `node_id` after its instructions have been replaced.
+    // statements mapped into the same `ip` are merged (the later statement wins)
+    pub fn remap_node<F: Fn(usize) -> usize>(&mut self, node_id: CfgNodeId, remap_ip: F) {
+        let stmts = match self.nodes.remove(&node_id) {
+            Some(stmts) => stmts,
+            None => return,
+        };
+
+        for (ip, location) in stmts {
+            self.add_stmt(node_id, remap_ip(ip), location);
+        }
+    }
+
+    // moves the statements of node `src_id` to the end of node `dst_id`,
+    // where the instructions of `src_id` start at `dst_id` instruction `offset`
+    pub fn merge_node(&mut self, src_id: CfgNodeId, dst_id: CfgNodeId, offset: usize) {
+        let stmts = match self.nodes.remove(&src_id) {
+            Some(stmts) => stmts,
+            None => return,
+        };
+
+        for (ip, location) in stmts {
+            self.add_stmt(dst_id, ip + offset, location);
+        }
+    }
+
+    // returns whether `(node_id, ip)` is the first instruction of a statement
+    pub fn is_stmt_start(&self, node_id: CfgNodeId, ip: usize) -> bool {
+        match self.nodes.get(&node_id) {
+            Some(stmts) => stmts.iter().any(|(stmt_ip, _)| *stmt_ip == ip),
+            None => false,
+        }
+    }
+
+    // returns the location of the statement `(node_id, ip)` belongs to
+    pub fn get_location(&self, node_id: CfgNodeId, ip: usize) -> Option<Location> {
+        let stmts = self.nodes.get(&node_id)?;
+
+        stmts
+            .iter()
+            .rev()
+            .find(|(stmt_ip, _)| *stmt_ip <= ip)
+            .map(|(_, loc)| *loc)
+    }
+
+    // the `(ip, location)` of each statement of node `node_id`, ordered by `ip`
+    pub fn get_node_stmts(&self, node_id: CfgNodeId) -> &[(usize, Location)] {
+        match self.nodes.get(&node_id) {
+            Some(stmts) => stmts,
+            None => &[],
+        }
+    }
+
+    // returns the `(node_id, ip)` of each statement starting at source line `line`
+    pub fn get_line_stmts(&self, line: usize) -> Vec<(CfgNodeId, usize)> {
+        let mut positions: Vec<(CfgNodeId, usize)> = self
+            .nodes
+            .iter()
+            .flat_map(|(node_id, stmts)| {
+                stmts
+                    .iter()
+                    .filter(|(_, loc)| loc.line() == line)
+                    .map(move |(ip, _)| (*node_id, *ip))
+            })
+            .collect();
+
+        positions.sort();
+        positions
+    }
+}
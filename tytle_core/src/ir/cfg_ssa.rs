@@ -0,0 +1,331 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::ir::analysis::{DataflowSolver, Dominators, Liveness};
+use crate::ir::{CfgInstruction, CfgJumpType, CfgNodeId, CfgObject};
+use std::collections::{HashMap, HashSet};
+
+// a phi defines variable version `var_id` on entry of its node,
+// out of the version flowing from each of the node predecessors
+#[derive(Debug, Clone, PartialEq)]
+pub struct SsaPhi {
+    pub var_id: SymbolId,
+    pub args: Vec<(CfgNodeId, SymbolId)>,
+}
+
+// turns the `LOAD` / `STORE` instructions of the procedures locals into SSA form (each variable version is stored once).
+// each version is allocated as a new local of the procedure (named `NAME.N`), and the phis are kept aside from the CFG.
+//
+// a local is converted only when it isn't accessed by any other procedure (e.g a nested procedure),
+// and the value it starts with (e.g a param) remains its original variable.
+// the phis are placed where the definitions meet (the dominance frontiers) as long as the variable is live there
+#[derive(Debug)]
+pub struct SsaForm {
+    pub phis: HashMap<CfgNodeId, Vec<SsaPhi>>,
+
+    // maps each version to its original variable
+    pub versions: HashMap<SymbolId, SymbolId>,
+}
+
+impl SsaForm {
+    pub fn build(cfg: &mut CfgObject, env: &mut Environment) -> Self {
+        let mut ssa = Self {
+            phis: HashMap::new(),
+            versions: HashMap::new(),
+        };
+
+        let nodes_procs = cfg.get_nodes_procs();
+
+        // the procedures accessing each variable
+        let mut accessors: HashMap<SymbolId, HashSet<SymbolId>> = HashMap::new();
+
+        for (node_id, proc_id) in nodes_procs.iter() {
+            for inst in cfg.graph.get_node(*node_id).insts.iter() {
+                if let CfgInstruction::Load(var_id) | CfgInstruction::Store(var_id) = inst {
+                    accessors.entry(*var_id).or_default().insert(*proc_id);
+                }
+            }
+        }
+
+        let mut procs: Vec<(CfgNodeId, SymbolId)> = cfg
+            .jmp_table
+            .iter()
+            .map(|(node_id, proc_id)| (*node_id, *proc_id))
+            .collect();
+        procs.sort_by_key(|(node_id, _)| *node_id);
+
+        for (start_id, proc_id) in procs {
+            let mut vars_ids: Vec<SymbolId> = accessors
+                .iter()
+                .filter(|(var_id, procs_ids)| {
+                    let var = env.symbol_table.get_var_by_id(**var_id);
+
+                    var.proc_id == Some(proc_id) && procs_ids.len() == 1
+                })
+                .map(|(var_id, _)| *var_id)
+                .collect();
+
+            if vars_ids.is_empty() {
+                continue;
+            }
+
+            vars_ids.sort_by_key(|var_id| var_id.0);
+
+            ssa.build_proc(cfg, env, start_id, proc_id, &vars_ids);
+        }
+
+        ssa
+    }
+
+    pub fn get_phis(&self, node_id: CfgNodeId) -> &[SsaPhi] {
+        match self.phis.get(&node_id) {
+            Some(phis) => phis,
+            None => &[],
+        }
+    }
+
+    // the original variable of `var_id` (which is `var_id` itself when it isn't a version)
+    pub fn original_var(&self, var_id: SymbolId) -> SymbolId {
+        match self.versions.get(&var_id) {
+            Some(orig_id) => *orig_id,
+            None => var_id,
+        }
+    }
+
+    // translates the phis back into `LOAD` / `STORE` copies at the end of the predecessors.
+    // when a predecessor has other successors, its edge is split by a new node holding the copies.
+    // (the phis of a node are of distinct variables, so their copies don't interfere with each other)
+    pub fn destruct(self, cfg: &mut CfgObject) {
+        let mut nodes_ids: Vec<CfgNodeId> = self.phis.keys().cloned().collect();
+        nodes_ids.sort();
+
+        for node_id in nodes_ids {
+            let mut copies: Vec<(CfgNodeId, Vec<CfgInstruction>)> = Vec::new();
+
+            for phi in self.phis[&node_id].iter() {
+                for (pred_id, arg_id) in phi.args.iter() {
+                    if *arg_id == phi.var_id {
+                        continue;
+                    }
+
+                    let pos = match copies.iter().position(|(id, _)| id == pred_id) {
+                        Some(pos) => pos,
+                        None => {
+                            copies.push((*pred_id, Vec::new()));
+                            copies.len() - 1
+                        }
+                    };
+
+                    copies[pos].1.push(CfgInstruction::Load(*arg_id));
+                    copies[pos].1.push(CfgInstruction::Store(phi.var_id));
+                }
+            }
+
+            for (pred_id, insts) in copies {
+                let copy_node_id = self.copy_node(cfg, pred_id, node_id);
+                let copy_node = cfg.graph.get_node_mut(copy_node_id);
+
+                // a conditional jump is on the stack top, copying a variable leaves it as is
+                copy_node.insts.extend(insts);
+            }
+        }
+    }
+
+    fn build_proc(
+        &mut self,
+        cfg: &mut CfgObject,
+        env: &mut Environment,
+        start_id: CfgNodeId,
+        proc_id: SymbolId,
+        vars_ids: &[SymbolId],
+    ) {
+        let doms = Dominators::compute(&cfg.graph, start_id);
+        let frontiers = doms.frontiers(&cfg.graph);
+        let liveness = DataflowSolver::new(&cfg.graph).solve(&Liveness, &[start_id]);
+
+        for var_id in vars_ids {
+            let defs_ids: Vec<CfgNodeId> = doms
+                .get_nodes_ids()
+                .into_iter()
+                .filter(|node_id| {
+                    let node = cfg.graph.get_node(*node_id);
+
+                    node.insts.contains(&CfgInstruction::Store(*var_id))
+                })
+                .collect();
+
+            let mut pending = defs_ids.clone();
+            let mut phis_ids: HashSet<CfgNodeId> = HashSet::new();
+
+            while let Some(def_id) = pending.pop() {
+                let mut frontier: Vec<CfgNodeId> = frontiers[&def_id].iter().cloned().collect();
+                frontier.sort();
+
+                for node_id in frontier {
+                    if phis_ids.contains(&node_id) || !liveness.get_entry(node_id).contains(var_id)
+                    {
+                        continue;
+                    }
+
+                    phis_ids.insert(node_id);
+
+                    // the phi is renamed later on, so meanwhile it holds the original variable
+                    self.phis.entry(node_id).or_default().push(SsaPhi {
+                        var_id: *var_id,
+                        args: Vec::new(),
+                    });
+
+                    if !defs_ids.contains(&node_id) {
+                        pending.push(node_id);
+                    }
+                }
+            }
+        }
+
+        // the current version of each variable (the variable starts as its original version)
+        let mut stacks: HashMap<SymbolId, Vec<SymbolId>> = vars_ids
+            .iter()
+            .map(|var_id| (*var_id, vec![*var_id]))
+            .collect();
+
+        self.rename(cfg, env, &doms, proc_id, start_id, &mut stacks);
+    }
+
+    // renames the variables of `node_id` and then of the nodes it dominates (walking down the dominator tree),
+    // and fills the phis args of the node successors
+    fn rename(
+        &mut self,
+        cfg: &mut CfgObject,
+        env: &mut Environment,
+        doms: &Dominators,
+        proc_id: SymbolId,
+        node_id: CfgNodeId,
+        stacks: &mut HashMap<SymbolId, Vec<SymbolId>>,
+    ) {
+        let mut defined: Vec<SymbolId> = Vec::new();
+
+        let phis_count = self.get_phis(node_id).len();
+
+        for i in 0..phis_count {
+            let orig_id = self.phis[&node_id][i].var_id;
+
+            // the procedure start node may be jumped to (e.g by a tail-call), but it's entered with
+            // the original variables values, so its phis define the original variables
+            let version_id = if node_id == doms.get_entry_id() {
+                orig_id
+            } else {
+                self.new_version(env, proc_id, orig_id)
+            };
+
+            self.phis.get_mut(&node_id).unwrap()[i].var_id = version_id;
+
+            stacks.get_mut(&orig_id).unwrap().push(version_id);
+            defined.push(orig_id);
+        }
+
+        let insts_count = cfg.graph.get_node(node_id).insts.len();
+
+        for ip in 0..insts_count {
+            let inst = cfg.graph.get_node(node_id).insts[ip].clone();
+
+            let renamed = match inst {
+                CfgInstruction::Load(var_id) if stacks.contains_key(&var_id) => {
+                    CfgInstruction::Load(*stacks[&var_id].last().unwrap())
+                }
+                CfgInstruction::Store(var_id) if stacks.contains_key(&var_id) => {
+                    let version_id = self.new_version(env, proc_id, var_id);
+
+                    stacks.get_mut(&var_id).unwrap().push(version_id);
+                    defined.push(var_id);
+
+                    CfgInstruction::Store(version_id)
+                }
+                _ => continue,
+            };
+
+            cfg.graph.get_node_mut(node_id).insts[ip] = renamed;
+        }
+
+        let mut succs_ids: Vec<CfgNodeId> = cfg
+            .graph
+            .get_node(node_id)
+            .outgoing
+            .iter()
+            .map(|edge| edge.node_id)
+            .collect();
+        succs_ids.sort();
+        succs_ids.dedup();
+
+        for succ_id in succs_ids {
+            if let Some(succ_phis) = self.phis.get(&succ_id) {
+                let args: Vec<SymbolId> = succ_phis
+                    .iter()
+                    .map(|phi| {
+                        let orig_id = self.original_var(phi.var_id);
+
+                        *stacks[&orig_id].last().unwrap()
+                    })
+                    .collect();
+
+                let succ_phis = self.phis.get_mut(&succ_id).unwrap();
+
+                for (phi, arg_id) in succ_phis.iter_mut().zip(args) {
+                    phi.args.push((node_id, arg_id));
+                }
+            }
+        }
+
+        for child_id in doms.dominated_children(node_id) {
+            self.rename(cfg, env, doms, proc_id, child_id, stacks);
+        }
+
+        for var_id in defined {
+            stacks.get_mut(&var_id).unwrap().pop();
+        }
+    }
+
+    fn new_version(
+        &mut self,
+        env: &mut Environment,
+        proc_id: SymbolId,
+        orig_id: SymbolId,
+    ) -> SymbolId {
+        let version = self.versions.values().filter(|id| **id == orig_id).count() + 1;
+
+        let orig_var = env.symbol_table.get_var_by_id(orig_id);
+        let var_name = format!("{}.{}", orig_var.name, version);
+        let var_type = orig_var.var_type.clone();
+
+        let var_id = env.id_generator.get_next_id();
+        env.create_local_var(proc_id, var_id, &var_name, var_type, false);
+
+        self.versions.insert(var_id, orig_id);
+
+        var_id
+    }
+
+    // the node to append the copies of the edge `pred_id -> node_id` to
+    fn copy_node(&self, cfg: &mut CfgObject, pred_id: CfgNodeId, node_id: CfgNodeId) -> CfgNodeId {
+        let pred = cfg.graph.get_node(pred_id);
+
+        let jmp_types: Vec<CfgJumpType> = pred
+            .outgoing
+            .iter()
+            .filter(|edge| edge.node_id == node_id)
+            .map(|edge| edge.jmp_type)
+            .collect();
+
+        if jmp_types.len() == pred.outgoing.len() {
+            return pred_id;
+        }
+
+        let split_id = cfg.graph.new_node();
+
+        for jmp_type in jmp_types {
+            cfg.graph.remove_edge(pred_id, node_id, jmp_type);
+            cfg.graph.add_edge(pred_id, split_id, jmp_type);
+        }
+
+        cfg.graph.add_edge(split_id, node_id, CfgJumpType::Always);
+
+        split_id
+    }
+}
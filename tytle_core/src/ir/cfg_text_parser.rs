@@ -0,0 +1,226 @@
+use crate::ast::semantic::SymbolId;
+use crate::ast::statement::{Command, Direction};
+use crate::ir::{CfgGraph, CfgInstruction, CfgJumpType, CfgNode, CfgNodeId};
+use std::collections::HashSet;
+use std::convert::TryFrom;
+
+// the line number (starting at `1`) is attached to each error
+#[derive(Debug, PartialEq)]
+pub enum CfgTextError {
+    InvalidNodeHeader(usize),
+    DuplicateNode(usize, CfgNodeId),
+    InstructionOutsideNode(usize),
+    UnknownInstruction(usize, String),
+    InvalidOperand(usize, String),
+    UnknownNode(usize, CfgNodeId),
+}
+
+impl ToString for CfgTextError {
+    fn to_string(&self) -> String {
+        match self {
+            CfgTextError::InvalidNodeHeader(line) => format!("line {}: invalid node header", line),
+            CfgTextError::DuplicateNode(line, node_id) => {
+                format!("line {}: node `{}` is already defined", line, node_id)
+            }
+            CfgTextError::InstructionOutsideNode(line) => {
+                format!("line {}: instruction outside of a node", line)
+            }
+            CfgTextError::UnknownInstruction(line, inst) => {
+                format!("line {}: unknown instruction `{}`", line, inst)
+            }
+            CfgTextError::InvalidOperand(line, operand) => {
+                format!("line {}: invalid operand `{}`", line, operand)
+            }
+            CfgTextError::UnknownNode(line, node_id) => {
+                format!("line {}: jump to an unknown node `{}`", line, node_id)
+            }
+        }
+    }
+}
+
+// parses the textual IR format printed by `CfgPrinter` back into a `CfgGraph`.
+// the symbols names and the procedures annotations are informative only, so they're ignored
+// (only the `#id` of a symbol and the `@id` of a node are used)
+pub struct CfgTextParser;
+
+impl CfgTextParser {
+    pub fn parse(&self, text: &str) -> Result<CfgGraph, CfgTextError> {
+        let mut graph = CfgGraph::new();
+        let mut nodes: Vec<CfgNode> = Vec::new();
+        let mut nodes_ids: HashSet<CfgNodeId> = HashSet::new();
+        let mut edges: Vec<(usize, CfgNodeId, CfgNodeId, CfgJumpType)> = Vec::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line_num = i + 1;
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.starts_with("node ") {
+                let node_id = self.parse_node_header(line_num, line)?;
+
+                if !nodes_ids.insert(node_id) {
+                    return Err(CfgTextError::DuplicateNode(line_num, node_id));
+                }
+
+                nodes.push(CfgNode::new(node_id));
+                continue;
+            }
+
+            let node = match nodes.last_mut() {
+                Some(node) => node,
+                None => return Err(CfgTextError::InstructionOutsideNode(line_num)),
+            };
+
+            let (opcode, operand) = match line.find(' ') {
+                Some(pos) => (&line[..pos], line[pos + 1..].trim()),
+                None => (line, ""),
+            };
+
+            let jmp_type = match opcode {
+                "when_true" => Some(CfgJumpType::WhenTrue),
+                "fallback" => Some(CfgJumpType::Fallback),
+                "always" => Some(CfgJumpType::Always),
+                _ => None,
+            };
+
+            match jmp_type {
+                Some(jmp_type) => {
+                    let dst_id = self.parse_node_ref(line_num, operand)?;
+
+                    edges.push((line_num, node.id, dst_id, jmp_type));
+                }
+                None => {
+                    let inst = self.parse_inst(line_num, opcode, operand)?;
+
+                    node.append_inst(inst);
+                }
+            }
+        }
+
+        for node in nodes {
+            graph.add_node(node);
+        }
+
+        for (line_num, src_id, dst_id, jmp_type) in edges {
+            if !graph.nodes.contains_key(&dst_id) {
+                return Err(CfgTextError::UnknownNode(line_num, dst_id));
+            }
+
+            graph.add_edge(src_id, dst_id, jmp_type);
+        }
+
+        Ok(graph)
+    }
+
+    // `node 2:` or `node 2 proc SQUARE#3:`
+    fn parse_node_header(&self, line_num: usize, line: &str) -> Result<CfgNodeId, CfgTextError> {
+        if !line.ends_with(':') {
+            return Err(CfgTextError::InvalidNodeHeader(line_num));
+        }
+
+        let header = &line["node ".len()..line.len() - 1];
+        let node_id = header.split_whitespace().next().unwrap_or("");
+
+        node_id
+            .parse::<CfgNodeId>()
+            .map_err(|_| CfgTextError::InvalidNodeHeader(line_num))
+    }
+
+    fn parse_inst(
+        &self,
+        line_num: usize,
+        opcode: &str,
+        operand: &str,
+    ) -> Result<CfgInstruction, CfgTextError> {
+        let invalid_operand = || CfgTextError::InvalidOperand(line_num, operand.to_string());
+
+        let inst = match opcode {
+            "cmd" => match Command::parse(operand) {
+                Some(cmd) => CfgInstruction::Command(cmd),
+                None => return Err(invalid_operand()),
+            },
+            "direct" => match Direction::try_from(operand) {
+                Ok(direct) => CfgInstruction::Direction(direct),
+                Err(_) => return Err(invalid_operand()),
+            },
+            "load" => CfgInstruction::Load(self.parse_symbol(line_num, operand)?),
+            "store" => CfgInstruction::Store(self.parse_symbol(line_num, operand)?),
+            "call" => CfgInstruction::Call(self.parse_node_ref(line_num, operand)?),
+            "proc_ref" => CfgInstruction::ProcRef(self.parse_node_ref(line_num, operand)?),
+            "new_struct" => CfgInstruction::NewStruct(self.parse_symbol(line_num, operand)?),
+            "load_field" => {
+                CfgInstruction::LoadField(operand.parse().map_err(|_| invalid_operand())?)
+            }
+            "store_field" => {
+                CfgInstruction::StoreField(operand.parse().map_err(|_| invalid_operand())?)
+            }
+            "bool" => CfgInstruction::Bool(operand.parse().map_err(|_| invalid_operand())?),
+            "int" => CfgInstruction::Int(operand.parse().map_err(|_| invalid_operand())?),
+            "str" => {
+                if operand.len() < 2 || !operand.starts_with('"') || !operand.ends_with('"') {
+                    return Err(invalid_operand());
+                }
+
+                CfgInstruction::Str(operand[1..operand.len() - 1].to_string())
+            }
+            _ => {
+                // the remaining instructions have no operand
+                if !operand.is_empty() {
+                    return Err(invalid_operand());
+                }
+
+                match opcode {
+                    "call_indirect" => CfgInstruction::CallIndirect,
+                    "return" => CfgInstruction::Return,
+                    "trap" => CfgInstruction::Trap,
+                    "print" => CfgInstruction::Print,
+                    "label" => CfgInstruction::Label,
+                    "set_font_size" => CfgInstruction::SetFontSize,
+                    "eoc" => CfgInstruction::EOC,
+                    "add" => CfgInstruction::Add,
+                    "mul" => CfgInstruction::Mul,
+                    "div" => CfgInstruction::Div,
+                    "not" => CfgInstruction::Not,
+                    "and" => CfgInstruction::And,
+                    "or" => CfgInstruction::Or,
+                    "gt" => CfgInstruction::GreaterThan,
+                    "lt" => CfgInstruction::LessThan,
+                    _ => {
+                        return Err(CfgTextError::UnknownInstruction(
+                            line_num,
+                            opcode.to_string(),
+                        ))
+                    }
+                }
+            }
+        };
+
+        Ok(inst)
+    }
+
+    // `NAME#id` or `#id`
+    fn parse_symbol(&self, line_num: usize, operand: &str) -> Result<SymbolId, CfgTextError> {
+        let invalid_operand = || CfgTextError::InvalidOperand(line_num, operand.to_string());
+
+        let pos = operand.rfind('#').ok_or_else(invalid_operand)?;
+        let id = operand[pos + 1..].parse().map_err(|_| invalid_operand())?;
+
+        Ok(SymbolId(id))
+    }
+
+    // `@id`, optionally followed by the name of the procedure starting at the node
+    fn parse_node_ref(&self, line_num: usize, operand: &str) -> Result<CfgNodeId, CfgTextError> {
+        let invalid_operand = || CfgTextError::InvalidOperand(line_num, operand.to_string());
+
+        let node_ref = operand.split_whitespace().next().unwrap_or("");
+
+        if !node_ref.starts_with('@') {
+            return Err(invalid_operand());
+        }
+
+        node_ref[1..].parse().map_err(|_| invalid_operand())
+    }
+}
@@ -108,10 +108,11 @@ macro_rules! cmd_ins {
 #[macro_export]
 macro_rules! direct_ins {
     ($direct:ident) => {{
+        use std::convert::TryFrom;
         use $crate::ast::statement::Direction;
         use $crate::ir::CfgInstruction;
 
-        let direct = Direction::from(stringify!($direct));
+        let direct = Direction::try_from(stringify!($direct)).unwrap();
         CfgInstruction::Direction(direct)
     }};
 }
@@ -153,6 +154,20 @@ macro_rules! print_ins {
     }};
 }
 
+#[macro_export]
+macro_rules! label_ins {
+    () => {{
+        $crate::ir::CfgInstruction::Label
+    }};
+}
+
+#[macro_export]
+macro_rules! set_font_size_ins {
+    () => {{
+        $crate::ir::CfgInstruction::SetFontSize
+    }};
+}
+
 #[macro_export]
 macro_rules! node {
     ($node_id:expr) => {{
@@ -242,3 +257,54 @@ macro_rules! compile_cfg_graph {
         cfg_obj.graph
     }};
 }
+
+#[macro_export]
+macro_rules! compile_optimized_cfg_obj {
+    ($code: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut env);
+        let mut cfg_obj = builder.build(&ast);
+
+        let optimizer = CfgOptimizer::new();
+        optimizer.optimize(&mut cfg_obj, &mut env);
+
+        cfg_obj
+    }};
+}
+
+#[macro_export]
+macro_rules! compile_optimized_cfg_graph {
+    ($code: expr) => {{
+        let cfg_obj = compile_optimized_cfg_obj!($code);
+
+        cfg_obj.graph
+    }};
+}
+
+// the textual IR of the compiled program (see `CfgPrinter`), for writing CFG tests as snapshots
+#[macro_export]
+macro_rules! compile_cfg_ir {
+    ($code: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut env);
+        let cfg_obj = builder.build(&ast);
+
+        CfgPrinter::with_env(&env).print_object(&cfg_obj)
+    }};
+}
@@ -1,10 +1,18 @@
+pub mod analysis;
 mod cfg_builder;
+mod cfg_dot;
 mod cfg_edge;
 mod cfg_graph;
 mod cfg_instruction;
 mod cfg_node;
 mod cfg_object;
+mod cfg_optimizer;
+mod cfg_printer;
+mod cfg_source_map;
+mod cfg_ssa;
+mod cfg_text_parser;
 pub mod macros;
+pub mod passes;
 
 pub use cfg_builder::CfgBuilder;
 pub use cfg_edge::CfgEdge;
@@ -12,3 +20,8 @@ pub use cfg_graph::*;
 pub use cfg_instruction::CfgInstruction;
 pub use cfg_node::CfgNode;
 pub use cfg_object::CfgObject;
+pub use cfg_optimizer::{CfgOptimizer, CfgPass};
+pub use cfg_printer::CfgPrinter;
+pub use cfg_source_map::CfgSourceMap;
+pub use cfg_ssa::{SsaForm, SsaPhi};
+pub use cfg_text_parser::{CfgTextError, CfgTextParser};
@@ -0,0 +1,157 @@
+use crate::ast::semantic::Environment;
+use crate::ir::{CfgInstruction, CfgJumpType, CfgNodeId, CfgObject, CfgPass};
+
+// evaluates at compile-time the `Int` / `Bool` instructions sequences (e.g `Int(1), Int(2), Add` => `Int(3)`).
+// a node ending with a constant condition gets its `WhenTrue` / `Fallback` edges
+// replaced by an `Always` edge to the taken branch
+pub struct ConstantFolding;
+
+impl CfgPass for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "constant-folding"
+    }
+
+    fn run(&self, cfg: &mut CfgObject, _env: &mut Environment) -> bool {
+        let mut changed = false;
+
+        for node_id in cfg.graph.get_nodes_ids() {
+            if self.fold_node_insts(cfg, node_id) {
+                changed = true;
+            }
+
+            if self.fold_node_branch(cfg, node_id) {
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+impl ConstantFolding {
+    fn fold_node_insts(&self, cfg: &mut CfgObject, node_id: CfgNodeId) -> bool {
+        let node = cfg.graph.get_node_mut(node_id);
+
+        let mut insts: Vec<CfgInstruction> = Vec::with_capacity(node.insts.len());
+
+        // `ips[i]` is the new `ip` of the original instruction `i`
+        let mut ips: Vec<usize> = Vec::with_capacity(node.insts.len());
+        let mut changed = false;
+
+        for inst in node.insts.drain(..) {
+            ips.push(insts.len());
+            insts.push(inst);
+
+            // folding the tail, again and again, allows folding nested expressions
+            while let Some(ip) = Self::fold_tail(&mut insts) {
+                changed = true;
+
+                for new_ip in ips.iter_mut().rev() {
+                    if *new_ip <= ip {
+                        break;
+                    }
+
+                    *new_ip = ip;
+                }
+            }
+        }
+
+        let insts_count = insts.len();
+        node.insts = insts;
+
+        if changed {
+            cfg.source_map.remap_node(node_id, |ip| match ips.get(ip) {
+                Some(new_ip) => *new_ip,
+                None => insts_count,
+            });
+        }
+
+        changed
+    }
+
+    // folds the instructions at the end of `insts` into a single constant instruction.
+    // returns the `ip` of the folded instruction
+    fn fold_tail(insts: &mut Vec<CfgInstruction>) -> Option<usize> {
+        let len = insts.len();
+
+        let folded = match insts.as_slice() {
+            [.., CfgInstruction::Bool(a), CfgInstruction::Not] => {
+                Some((2, CfgInstruction::Bool(!a)))
+            }
+            [.., CfgInstruction::Int(a), CfgInstruction::Int(b), op] => {
+                Self::fold_int_binary(*a, *b, op).map(|inst| (3, inst))
+            }
+            [.., CfgInstruction::Bool(a), CfgInstruction::Bool(b), op] => {
+                Self::fold_bool_binary(*a, *b, op).map(|inst| (3, inst))
+            }
+            _ => None,
+        };
+
+        let (count, inst) = folded?;
+
+        insts.truncate(len - count);
+        insts.push(inst);
+
+        Some(len - count)
+    }
+
+    fn fold_int_binary(a: isize, b: isize, op: &CfgInstruction) -> Option<CfgInstruction> {
+        // an overflow or a division by zero are left for the runtime to report
+        let inst = match op {
+            CfgInstruction::Add => CfgInstruction::Int(a.checked_add(b)?),
+            CfgInstruction::Mul => CfgInstruction::Int(a.checked_mul(b)?),
+            CfgInstruction::Div => CfgInstruction::Int(a.checked_div(b)?),
+            CfgInstruction::LessThan => CfgInstruction::Bool(a < b),
+            CfgInstruction::GreaterThan => CfgInstruction::Bool(a > b),
+            _ => return None,
+        };
+
+        Some(inst)
+    }
+
+    fn fold_bool_binary(a: bool, b: bool, op: &CfgInstruction) -> Option<CfgInstruction> {
+        match op {
+            CfgInstruction::And => Some(CfgInstruction::Bool(a && b)),
+            CfgInstruction::Or => Some(CfgInstruction::Bool(a || b)),
+            _ => None,
+        }
+    }
+
+    fn fold_node_branch(&self, cfg: &mut CfgObject, node_id: CfgNodeId) -> bool {
+        let node = cfg.graph.get_node(node_id);
+
+        let cond = match node.insts.last() {
+            Some(CfgInstruction::Bool(cond)) => *cond,
+            _ => return false,
+        };
+
+        let taken_jmp_type = if cond {
+            CfgJumpType::WhenTrue
+        } else {
+            CfgJumpType::Fallback
+        };
+
+        let taken_edge = node
+            .outgoing
+            .iter()
+            .find(|edge| edge.jmp_type == taken_jmp_type);
+
+        let taken_node_id = match taken_edge {
+            Some(edge) => edge.node_id,
+            None => return false,
+        };
+
+        let edges: Vec<_> = node.outgoing.iter().cloned().collect();
+
+        for edge in edges {
+            cfg.graph.remove_edge(node_id, edge.node_id, edge.jmp_type);
+        }
+
+        // the condition was consumed by the branch, so it's no longer needed
+        cfg.graph.get_node_mut(node_id).insts.pop();
+        cfg.graph
+            .add_edge(node_id, taken_node_id, CfgJumpType::Always);
+
+        true
+    }
+}
@@ -0,0 +1,167 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::ir::{CfgInstruction, CfgNodeId, CfgObject, CfgPass};
+use std::collections::HashMap;
+
+// the maximum number of instructions (excluding the `RETURN`) of an inlined procedure
+pub const INLINE_MAX_INSTS: usize = 16;
+
+// replaces calls to small leaf procedures with the procedure instructions.
+// a procedure is inlined when its code is a single node ending with its only `RETURN`,
+// and it doesn't call any procedure (so it can't be recursive).
+// the callee params and locals are allocated as new locals of the caller
+pub struct Inlining;
+
+impl CfgPass for Inlining {
+    fn name(&self) -> &'static str {
+        "inlining"
+    }
+
+    fn run(&self, cfg: &mut CfgObject, env: &mut Environment) -> bool {
+        let mut changed = false;
+
+        for (node_id, caller_id) in cfg.get_nodes_procs() {
+            let mut ip = 0;
+
+            while ip < cfg.graph.get_node(node_id).insts.len() {
+                let inst = &cfg.graph.get_node(node_id).insts[ip];
+
+                let callee_node_id = match inst {
+                    CfgInstruction::Call(callee_node_id) => *callee_node_id,
+                    _ => {
+                        ip += 1;
+                        continue;
+                    }
+                };
+
+                if self.is_inlinable(cfg, env, callee_node_id) {
+                    ip += self.inline_call(cfg, env, node_id, ip, caller_id, callee_node_id);
+
+                    changed = true;
+                } else {
+                    ip += 1;
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+impl Inlining {
+    fn is_inlinable(&self, cfg: &CfgObject, env: &Environment, callee_node_id: CfgNodeId) -> bool {
+        let callee_id = cfg.jmp_table[&callee_node_id];
+        let callee = env.symbol_table.get_proc_by_id(callee_id);
+
+        // a nested procedure accesses the locals of its enclosing procedure stack-frame
+        if callee.parent_id.is_some() {
+            return false;
+        }
+
+        let has_nested_procs = cfg.jmp_table.values().any(|proc_id| {
+            let proc = env.symbol_table.get_proc_by_id(*proc_id);
+
+            proc.parent_id == Some(callee_id)
+        });
+
+        if has_nested_procs {
+            return false;
+        }
+
+        let callee_node = cfg.graph.get_node(callee_node_id);
+
+        if callee_node.has_outgoing_edges() || !callee_node.ends_with_return() {
+            return false;
+        }
+
+        let body = &callee_node.insts[..callee_node.insts.len() - 1];
+
+        if body.len() > INLINE_MAX_INSTS {
+            return false;
+        }
+
+        for inst in body {
+            if let CfgInstruction::Call(_)
+            | CfgInstruction::CallIndirect
+            | CfgInstruction::Return
+            | CfgInstruction::EOC = inst
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // replaces the `CALL` at `(node_id, ip)` with the callee instructions.
+    // returns the number of the inlined instructions
+    fn inline_call(
+        &self,
+        cfg: &mut CfgObject,
+        env: &mut Environment,
+        node_id: CfgNodeId,
+        ip: usize,
+        caller_id: SymbolId,
+        callee_node_id: CfgNodeId,
+    ) -> usize {
+        let callee_id = cfg.jmp_table[&callee_node_id];
+
+        // allocating a caller local for each of the callee locals (including its params)
+        let mut vars_ids: HashMap<SymbolId, SymbolId> = HashMap::new();
+        let mut params_ids = Vec::new();
+
+        let callee_locals = env.locals_symbols.get(&callee_id).cloned();
+
+        for var_id in callee_locals.unwrap_or_default() {
+            let var = env.symbol_table.get_var_by_id(var_id);
+            let is_param = var.is_param();
+            let var_type = var.var_type.clone().unwrap();
+
+            let (caller_var_id, _) = env.create_tmp_var(caller_id, var_type);
+            vars_ids.insert(var_id, caller_var_id);
+
+            if is_param {
+                params_ids.push(caller_var_id);
+            }
+        }
+
+        // the arguments were pushed in the params order, so the last param is stored first
+        let mut insts: Vec<CfgInstruction> = params_ids
+            .iter()
+            .rev()
+            .map(|param_id| CfgInstruction::Store(*param_id))
+            .collect();
+
+        let callee_node = cfg.graph.get_node(callee_node_id);
+        let body = &callee_node.insts[..callee_node.insts.len() - 1];
+
+        for inst in body {
+            let inst = match inst {
+                CfgInstruction::Load(var_id) if vars_ids.contains_key(var_id) => {
+                    CfgInstruction::Load(vars_ids[var_id])
+                }
+                CfgInstruction::Store(var_id) if vars_ids.contains_key(var_id) => {
+                    CfgInstruction::Store(vars_ids[var_id])
+                }
+                _ => inst.clone(),
+            };
+
+            insts.push(inst);
+        }
+
+        let inlined_count = insts.len();
+
+        let node = cfg.graph.get_node_mut(node_id);
+        node.insts.splice(ip..=ip, insts);
+
+        // the inlined instructions are attributed to the calling statement
+        cfg.source_map.remap_node(node_id, |stmt_ip| {
+            if stmt_ip > ip {
+                stmt_ip + inlined_count - 1
+            } else {
+                stmt_ip
+            }
+        });
+
+        inlined_count
+    }
+}
@@ -0,0 +1,82 @@
+use crate::ast::semantic::Environment;
+use crate::ir::{CfgJumpType, CfgNodeId, CfgObject, CfgPass};
+use std::collections::HashSet;
+
+// redirects the edges pointing at an empty node which always jumps to another node,
+// straight to the final destination node (the skipped empty node may become unreachable)
+pub struct JumpThreading;
+
+impl CfgPass for JumpThreading {
+    fn name(&self) -> &'static str {
+        "jump-threading"
+    }
+
+    fn run(&self, cfg: &mut CfgObject, _env: &mut Environment) -> bool {
+        let mut changed = false;
+
+        for node_id in cfg.graph.get_nodes_ids() {
+            let edges: Vec<_> = cfg
+                .graph
+                .get_node(node_id)
+                .outgoing
+                .iter()
+                .cloned()
+                .collect();
+
+            for edge in edges {
+                let dst_id = self.thread_dst(cfg, edge.node_id);
+
+                if dst_id != edge.node_id {
+                    cfg.graph.remove_edge(node_id, edge.node_id, edge.jmp_type);
+                    cfg.graph.add_edge(node_id, dst_id, edge.jmp_type);
+
+                    changed = true;
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+impl JumpThreading {
+    // follows the chain of empty nodes starting at `node_id`, and returns the chain last node.
+    // a chain going in circles (an empty infinite loop) is left as is
+    fn thread_dst(&self, cfg: &CfgObject, node_id: CfgNodeId) -> CfgNodeId {
+        let mut visited = HashSet::new();
+        let mut current_id = node_id;
+
+        while let Some(next_id) = self.always_jmp_dst(cfg, current_id) {
+            visited.insert(current_id);
+
+            if visited.contains(&next_id) {
+                return node_id;
+            }
+
+            current_id = next_id;
+        }
+
+        current_id
+    }
+
+    // the node an empty node `node_id` always jumps to.
+    // the procedures nodes (and the entry node) are never skipped
+    fn always_jmp_dst(&self, cfg: &CfgObject, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        if cfg.jmp_table.contains_key(&node_id) {
+            return None;
+        }
+
+        let node = cfg.graph.get_node(node_id);
+
+        if !node.is_empty() || node.outgoing.len() != 1 {
+            return None;
+        }
+
+        let edge = node.outgoing.iter().next().unwrap();
+
+        match edge.jmp_type {
+            CfgJumpType::Always => Some(edge.node_id),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::ast::semantic::Environment;
+use crate::ir::{CfgEdge, CfgJumpType, CfgNodeId, CfgObject, CfgPass};
+
+// merges a node which always jumps to a node having no other predecessors,
+// into a single node (e.g an empty node ending an `IF` statement is merged into its predecessor)
+pub struct MergeNodes;
+
+impl CfgPass for MergeNodes {
+    fn name(&self) -> &'static str {
+        "merge-nodes"
+    }
+
+    fn run(&self, cfg: &mut CfgObject, _env: &mut Environment) -> bool {
+        let mut changed = false;
+
+        for node_id in cfg.graph.get_nodes_ids() {
+            // the node may have been merged already into its predecessor
+            if !cfg.graph.nodes.contains_key(&node_id) {
+                continue;
+            }
+
+            while let Some(succ_id) = self.mergeable_succ(cfg, node_id) {
+                self.merge(cfg, node_id, succ_id);
+
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+impl MergeNodes {
+    fn mergeable_succ(&self, cfg: &CfgObject, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        let node = cfg.graph.get_node(node_id);
+
+        if node.outgoing.len() != 1 {
+            return None;
+        }
+
+        let edge = node.outgoing.iter().next().unwrap();
+        let succ_id = edge.node_id;
+
+        if edge.jmp_type != CfgJumpType::Always || succ_id == node_id {
+            return None;
+        }
+
+        // a procedure node (or the entry node) is jumped to by calls as well
+        if cfg.jmp_table.contains_key(&succ_id) {
+            return None;
+        }
+
+        let succ = cfg.graph.get_node(succ_id);
+
+        if succ.incoming.len() == 1 {
+            Some(succ_id)
+        } else {
+            None
+        }
+    }
+
+    // appends the instructions of `succ_id` to `node_id`, which takes over the `succ_id` outgoing edges
+    fn merge(&self, cfg: &mut CfgObject, node_id: CfgNodeId, succ_id: CfgNodeId) {
+        let succ = cfg.graph.nodes.remove(&succ_id).unwrap();
+
+        let node = cfg.graph.get_node_mut(node_id);
+        let offset = node.insts.len();
+
+        node.insts.extend(succ.insts);
+        node.outgoing.clear();
+
+        for edge in succ.outgoing.iter() {
+            let dst_node = cfg.graph.get_node_mut(edge.node_id);
+
+            dst_node.incoming.remove(&CfgEdge {
+                node_id: succ_id,
+                jmp_type: edge.jmp_type,
+            });
+
+            cfg.graph.add_edge(node_id, edge.node_id, edge.jmp_type);
+        }
+
+        cfg.source_map.merge_node(succ_id, node_id, offset);
+    }
+}
@@ -0,0 +1,13 @@
+mod constant_folding;
+mod inlining;
+mod jump_threading;
+mod merge_nodes;
+mod tail_calls;
+mod unreachable_nodes;
+
+pub use constant_folding::ConstantFolding;
+pub use inlining::{Inlining, INLINE_MAX_INSTS};
+pub use jump_threading::JumpThreading;
+pub use merge_nodes::MergeNodes;
+pub use tail_calls::TailCalls;
+pub use unreachable_nodes::UnreachableNodes;
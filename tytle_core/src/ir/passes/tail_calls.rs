@@ -0,0 +1,134 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::ir::{CfgInstruction, CfgJumpType, CfgNodeId, CfgObject, CfgPass};
+
+// turns a procedure calling itself in a tail position (`RETURN F(...)`, or `F(...)` as the last statement)
+// into storing the call arguments to the params, followed by a jump to the procedure start node.
+// this way a tail-recursive procedure runs within a single stack-frame
+pub struct TailCalls;
+
+impl CfgPass for TailCalls {
+    fn name(&self) -> &'static str {
+        "tail-calls"
+    }
+
+    fn run(&self, cfg: &mut CfgObject, env: &mut Environment) -> bool {
+        let mut changed = false;
+
+        for (node_id, proc_id) in cfg.get_nodes_procs() {
+            let proc_node_id = self.proc_node_id(cfg, proc_id);
+
+            if !self.is_tail_call(cfg, node_id, proc_node_id) {
+                continue;
+            }
+
+            // the stack-frame is reused, so it must not be captured by a nested procedure value
+            if self.has_nested_procs(cfg, env, proc_id) {
+                continue;
+            }
+
+            self.replace_tail_call(cfg, env, node_id, proc_id, proc_node_id);
+
+            changed = true;
+        }
+
+        changed
+    }
+}
+
+impl TailCalls {
+    fn proc_node_id(&self, cfg: &CfgObject, proc_id: SymbolId) -> CfgNodeId {
+        let (node_id, _) = cfg
+            .jmp_table
+            .iter()
+            .find(|(_, jmp_proc_id)| **jmp_proc_id == proc_id)
+            .unwrap();
+
+        *node_id
+    }
+
+    // a self-call is in a tail position when it's followed only by a `RETURN`,
+    // either within the same node or as the single instruction of the next node
+    fn is_tail_call(&self, cfg: &CfgObject, node_id: CfgNodeId, proc_node_id: CfgNodeId) -> bool {
+        let node = cfg.graph.get_node(node_id);
+        let self_call = CfgInstruction::Call(proc_node_id);
+
+        match node.insts.as_slice() {
+            [.., call, CfgInstruction::Return] if *call == self_call => node.outgoing.is_empty(),
+            [.., call] if *call == self_call => match self.always_jmp_dst(cfg, node_id) {
+                Some(dst_id) => {
+                    let dst_node = cfg.graph.get_node(dst_id);
+
+                    dst_node.insts == vec![CfgInstruction::Return] && dst_node.outgoing.is_empty()
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn always_jmp_dst(&self, cfg: &CfgObject, node_id: CfgNodeId) -> Option<CfgNodeId> {
+        let node = cfg.graph.get_node(node_id);
+
+        if node.outgoing.len() != 1 {
+            return None;
+        }
+
+        let edge = node.outgoing.iter().next().unwrap();
+
+        match edge.jmp_type {
+            CfgJumpType::Always => Some(edge.node_id),
+            _ => None,
+        }
+    }
+
+    fn has_nested_procs(&self, cfg: &CfgObject, env: &Environment, proc_id: SymbolId) -> bool {
+        cfg.jmp_table.values().any(|jmp_proc_id| {
+            let proc = env.symbol_table.get_proc_by_id(*jmp_proc_id);
+
+            proc.parent_id == Some(proc_id)
+        })
+    }
+
+    fn replace_tail_call(
+        &self,
+        cfg: &mut CfgObject,
+        env: &Environment,
+        node_id: CfgNodeId,
+        proc_id: SymbolId,
+        proc_node_id: CfgNodeId,
+    ) {
+        if let Some(dst_id) = self.always_jmp_dst(cfg, node_id) {
+            cfg.graph.remove_edge(node_id, dst_id, CfgJumpType::Always);
+        }
+
+        let node = cfg.graph.get_node_mut(node_id);
+
+        if node.ends_with_return() {
+            node.insts.pop();
+        }
+
+        // popping the `CALL`
+        node.insts.pop();
+
+        // the arguments were pushed in the params order, so the last param is stored first
+        let params_ids = Self::params_ids(env, proc_id);
+
+        for param_id in params_ids.iter().rev() {
+            node.append_inst(CfgInstruction::Store(*param_id));
+        }
+
+        cfg.graph
+            .add_edge(node_id, proc_node_id, CfgJumpType::Always);
+    }
+
+    fn params_ids(env: &Environment, proc_id: SymbolId) -> Vec<SymbolId> {
+        match env.locals_symbols.get(&proc_id) {
+            Some(locals_ids) => locals_ids
+                .iter()
+                .filter(|var_id| env.symbol_table.get_var_by_id(**var_id).is_param())
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
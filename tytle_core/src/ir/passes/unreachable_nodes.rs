@@ -0,0 +1,43 @@
+use crate::ast::semantic::Environment;
+use crate::ir::{CfgNodeId, CfgObject, CfgPass};
+use std::collections::HashSet;
+
+// removes the nodes which can't be reached from the entry node nor from any procedure node
+// (the entry node is the node of `__main__` procedure)
+pub struct UnreachableNodes;
+
+impl CfgPass for UnreachableNodes {
+    fn name(&self) -> &'static str {
+        "unreachable-nodes"
+    }
+
+    fn run(&self, cfg: &mut CfgObject, _env: &mut Environment) -> bool {
+        let mut reachable: HashSet<CfgNodeId> = HashSet::new();
+        let mut pending: Vec<CfgNodeId> = cfg.jmp_table.keys().cloned().collect();
+
+        while let Some(node_id) = pending.pop() {
+            if !reachable.insert(node_id) {
+                continue;
+            }
+
+            let node = cfg.graph.get_node(node_id);
+
+            for edge in node.outgoing.iter() {
+                pending.push(edge.node_id);
+            }
+        }
+
+        let mut changed = false;
+
+        for node_id in cfg.graph.get_nodes_ids() {
+            if !reachable.contains(&node_id) {
+                cfg.graph.remove_node(node_id);
+                cfg.source_map.remove_node(node_id);
+
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
@@ -0,0 +1,14 @@
+use super::location::Location;
+
+// a `;` comment (running up to the end of its line).
+// comments are skipped by the lexer, and kept aside for tools such as the formatter
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub location: Location,
+
+    // the text following the `;` (without the trailing whitespace)
+    pub text: String,
+
+    // whether the comment follows code on its line (e.g `FORWARD 10 ; a trailing comment`)
+    pub trailing: bool,
+}
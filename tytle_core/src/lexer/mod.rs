@@ -1,3 +1,4 @@
+mod comment;
 mod location;
 mod token;
 mod tytle_lexer;
@@ -9,6 +10,7 @@ pub trait Lexer {
     fn pop_current_token(&mut self) -> Option<(Token, Location)>;
 }
 
+pub use comment::Comment;
 pub use location::Location;
 pub use token::Token;
 pub use tytle_lexer::TytleLexer;
@@ -20,12 +20,16 @@ pub enum Token {
     GT, // >
 
     COLON, // :
+    DOT,   // .
 
     AND, // `AND`
     OR,  // `OR`
     NOT, // `NOT`
 
     VALUE(String),
+
+    // a string literal missing its closing `"` (e.g `"ABC` at the end of a line)
+    UNTERMINATED(String),
 }
 
 impl ToString for Token {
@@ -45,10 +49,11 @@ impl ToString for Token {
             Token::LT => "<",
             Token::GT => ">",
             Token::COLON => ":",
+            Token::DOT => ".",
             Token::AND => "AND",
             Token::OR => "OR",
             Token::NOT => "NOT",
-            Token::VALUE(s) => s,
+            Token::VALUE(s) | Token::UNTERMINATED(s) => s,
         };
 
         s.to_string()
@@ -133,6 +138,11 @@ mod tests {
         assert_token(":", Token::COLON);
     }
 
+    #[test]
+    pub fn token_dot() {
+        assert_token(".", Token::DOT);
+    }
+
     #[test]
     pub fn token_and() {
         assert_token("AND", Token::AND);
@@ -152,4 +162,9 @@ mod tests {
     pub fn token_value() {
         assert_token("ABC", Token::VALUE("ABC".to_string()));
     }
+
+    #[test]
+    pub fn token_unterminated() {
+        assert_token("\"ABC", Token::UNTERMINATED("\"ABC".to_string()));
+    }
 }
@@ -1,3 +1,4 @@
+use super::comment::Comment;
 use super::location::Location;
 use super::token::Token;
 use crate::lexer::Lexer;
@@ -11,6 +12,13 @@ pub struct TytleLexer<'lex> {
     location: Location,
     reached_eof: bool,
     tokens_buffer: VecDeque<(Token, Location)>,
+    comments: Vec<Comment>,
+
+    // whether the current line has any code so far
+    line_has_code: bool,
+
+    // whether the token being lexed is a string literal missing its closing `"`
+    unterminated_str: bool,
 }
 
 impl<'lex> TytleLexer<'lex> {
@@ -20,12 +28,20 @@ impl<'lex> TytleLexer<'lex> {
             code_chars: code.chars(),
             reached_eof: false,
             tokens_buffer: Default::default(),
+            comments: Vec::new(),
+            line_has_code: false,
+            unterminated_str: false,
         };
 
         lexer.buffer_more_tokens();
 
         lexer
     }
+
+    // the comments lexed so far
+    pub fn get_comments(&self) -> &[Comment] {
+        &self.comments
+    }
 }
 
 impl<'lex> Lexer for TytleLexer<'lex> {
@@ -62,6 +78,10 @@ impl<'lex> Lexer for TytleLexer<'lex> {
 
                 let ch = ch_opt.unwrap();
 
+                if ch != ' ' && ch != '\n' && ch != ';' {
+                    self.line_has_code = true;
+                }
+
                 match ch {
                     '\n' => {
                         self.push_token(&mut token);
@@ -80,6 +100,12 @@ impl<'lex> Lexer for TytleLexer<'lex> {
                         self.location.increment_column();
                         break;
                     }
+                    '.' => {
+                        self.push_token(&mut token);
+                        self.push_dot();
+                        self.location.increment_column();
+                        break;
+                    }
                     ',' => {
                         self.push_token(&mut token);
                         self.push_comma();
@@ -110,6 +136,19 @@ impl<'lex> Lexer for TytleLexer<'lex> {
                         self.location.increment_column();
                         break;
                     }
+                    ';' => {
+                        self.push_token(&mut token);
+                        self.consume_comment();
+                        continue;
+                    }
+                    '"' if token.is_empty() => {
+                        // a string literal may contain spaces,
+                        // so we consume everything up to the closing `"`
+                        self.location.increment_column();
+                        token.push(ch);
+                        self.consume_str_literal(&mut token);
+                        continue;
+                    }
                     ' ' => match token.len() {
                         0 => {
                             self.location.increment_column();
@@ -157,9 +196,12 @@ impl<'lex> TytleLexer<'lex> {
                 "AND" => Token::AND,
                 "OR" => Token::OR,
                 "NOT" => Token::NOT,
+                _ if self.unterminated_str => Token::UNTERMINATED(value),
                 _ => Token::VALUE(value),
             };
 
+            self.unterminated_str = false;
+
             let entry = (token, loc);
 
             self.tokens_buffer.push_back(entry);
@@ -168,11 +210,60 @@ impl<'lex> TytleLexer<'lex> {
         token_chars.clear();
     }
 
+    fn consume_str_literal(&mut self, token_chars: &mut Vec<char>) {
+        loop {
+            // we stop on a new line or EOF, marking the literal as an unterminated one
+            match self.code_chars.clone().next() {
+                None | Some('\n') => {
+                    self.unterminated_str = true;
+                    return;
+                }
+                Some(ch) => {
+                    self.code_chars.next();
+                    self.location.increment_column();
+                    token_chars.push(ch);
+
+                    if ch == '"' {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    // a comment runs up to the end of the line (the new line itself isn't consumed)
+    fn consume_comment(&mut self) {
+        let location = self.location;
+        let mut text = String::new();
+
+        self.location.increment_column(); // the `;`
+
+        loop {
+            match self.code_chars.clone().next() {
+                None | Some('\n') => break,
+                Some(ch) => {
+                    self.code_chars.next();
+                    self.location.increment_column();
+                    text.push(ch);
+                }
+            }
+        }
+
+        let comment = Comment {
+            location,
+            text: text.trim_end().to_string(),
+            trailing: self.line_has_code,
+        };
+
+        self.comments.push(comment);
+    }
+
     fn push_newline(&mut self) {
         self.tokens_buffer
             .push_back((Token::NEWLINE, self.location));
 
         self.location.next_line();
+        self.line_has_code = false;
     }
 
     fn push_op(&mut self, op: char) {
@@ -197,6 +288,10 @@ impl<'lex> TytleLexer<'lex> {
         self.tokens_buffer.push_back((Token::COLON, self.location));
     }
 
+    fn push_dot(&mut self) {
+        self.tokens_buffer.push_back((Token::DOT, self.location));
+    }
+
     fn push_less_than(&mut self) {
         self.tokens_buffer.push_back((Token::LT, self.location));
     }
@@ -2,9 +2,12 @@
 extern crate lazy_static;
 
 pub mod ast;
+pub mod bytecode;
+pub mod compiler;
 pub mod ir;
 pub mod lexer;
 pub mod parser;
+pub mod repl;
 pub mod vm;
 
 pub mod prelude {
@@ -12,8 +15,11 @@ pub mod prelude {
     pub use crate::ast::semantic::*;
     pub use crate::ast::statement::*;
     pub use crate::ast::*;
+    pub use crate::bytecode::*;
+    pub use crate::compiler::*;
     pub use crate::ir::*;
     pub use crate::lexer::*;
     pub use crate::parser::*;
+    pub use crate::repl::*;
     pub use crate::vm::*;
 }
@@ -4,4 +4,4 @@ mod tytle_parser;
 
 pub use parse::{Parser, ParserResult};
 pub use parse_error::ParseError;
-pub use tytle_parser::TytleParser;
+pub use tytle_parser::{TytleParser, KEYWORDS};
@@ -1,6 +1,6 @@
-use crate::lexer::Token;
+use crate::lexer::{Location, Token};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseError {
     MissingColon,
     NewLineExpected,
@@ -10,7 +10,14 @@ pub enum ParseError {
     InvalidIdentifierDeclaration(String),
     UnexpectedToken { expected: Token, actual: Token },
     UnexpectedKeyword { keyword: String },
+    StrayToken(Token),
+    InvalidDirection(String),
+    InvalidBinaryOp(String),
     ReservedKeyword(String),
+    UnterminatedStr(String),
+
+    // an integer literal too large for an `isize` (`location` is the literal's location)
+    IntegerOutOfRange { literal: String, location: Location },
     Syntax { message: String },
 }
 
@@ -36,7 +43,22 @@ impl ToString for ParseError {
             ParseError::UnexpectedKeyword { ref keyword } => {
                 format!("Unexpected keyword: `{}`", keyword)
             }
+            ParseError::StrayToken(ref tok) => format!("Unexpected token: `{}`", tok.to_string()),
+            ParseError::InvalidDirection(ref direct) => format!("Invalid direction: `{}`", direct),
+            ParseError::InvalidBinaryOp(ref op) => format!("Invalid binary operator: `{}`", op),
             ParseError::ReservedKeyword(ref kw) => format!("Reserved keyword: `{}`", kw),
+            ParseError::UnterminatedStr(ref lit) => {
+                format!("Unterminated string literal: `{}`", lit)
+            }
+            ParseError::IntegerOutOfRange {
+                ref literal,
+                ref location,
+            } => format!(
+                "Integer literal `{}` is out of range (line: {}, column: {})",
+                literal,
+                location.line(),
+                location.column()
+            ),
             ParseError::Syntax { ref message } => format!("Syntax error: `{}`", message),
         }
     }
@@ -110,6 +132,30 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_error_stray_token() {
+        assert_parse_err(
+            "Unexpected token: `)`",
+            ParseError::StrayToken(Token::RPAREN),
+        );
+    }
+
+    #[test]
+    pub fn parse_error_invalid_direction() {
+        assert_parse_err(
+            "Invalid direction: `UP`",
+            ParseError::InvalidDirection("UP".to_string()),
+        );
+    }
+
+    #[test]
+    pub fn parse_error_invalid_binary_op() {
+        assert_parse_err(
+            "Invalid binary operator: `%`",
+            ParseError::InvalidBinaryOp("%".to_string()),
+        );
+    }
+
     #[test]
     pub fn parse_error_reserved_keyword() {
         assert_parse_err(
@@ -118,6 +164,27 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn parse_error_unterminated_str() {
+        assert_parse_err(
+            "Unterminated string literal: `\"ABC`",
+            ParseError::UnterminatedStr("\"ABC".to_string()),
+        );
+    }
+
+    #[test]
+    pub fn parse_error_integer_out_of_range() {
+        let err = ParseError::IntegerOutOfRange {
+            literal: "99999999999999999999".to_string(),
+            location: Location(2, 7),
+        };
+
+        assert_parse_err(
+            "Integer literal `99999999999999999999` is out of range (line: 2, column: 7)",
+            err,
+        );
+    }
+
     #[test]
     pub fn parse_error_syntax() {
         assert_parse_err(
@@ -1,14 +1,17 @@
 use crate::ast::expression::*;
 use crate::ast::statement::*;
 use crate::ast::Ast;
+use crate::compiler::Diagnostic;
 
 use crate::lexer::{Lexer, Location, Token, TytleLexer};
 use crate::parser::{ParseError, Parser, ParserResult};
 
 use std::collections::HashSet;
+use std::convert::TryFrom;
 
 lazy_static! {
-    static ref KEYWORDS: HashSet<&'static str> = {
+    // the reserved words (which can't be used as names)
+    pub static ref KEYWORDS: HashSet<&'static str> = {
         let mut kws = HashSet::new();
         kws.insert("TRAP");
         kws.insert("TRUE");
@@ -42,6 +45,12 @@ lazy_static! {
         kws.insert("XOR");
         kws.insert("YOR");
         kws.insert("PRINT");
+        kws.insert("LABEL");
+        kws.insert("SETFONTSIZE");
+        kws.insert("PROC");
+        kws.insert("APPLY");
+        kws.insert("MAP");
+        kws.insert("STRUCT");
         kws
     };
 }
@@ -49,27 +58,60 @@ lazy_static! {
 pub type StatementResult = Result<Statement, ParseError>;
 pub type ExpressionResult = Result<Expression, ParseError>;
 
+// the errors found so far, along with the location of the statement each was found at
+type ParseErrors = Vec<(ParseError, Location)>;
+
 pub struct TytleParser;
 
 impl Parser for TytleParser {
     fn parse(&mut self, code: &str) -> ParserResult {
         let mut lexer = TytleLexer::new(code);
+        let mut errors = ParseErrors::new();
+
+        let ast = self.parse_stmts(&mut lexer, &mut errors);
 
-        self.parse(&mut lexer)
+        if errors.is_empty() {
+            Ok(ast)
+        } else {
+            let (err, _loc) = errors.remove(0);
+            Err(err)
+        }
     }
 }
 
 impl TytleParser {
-    fn parse(&mut self, lexer: &mut impl Lexer) -> ParserResult {
+    // parses `code` without stopping on the first error.
+    // a statement failing to parse is skipped (see `skip_failed_stmt`),
+    // so the returned AST holds all the statements which have been parsed successfully
+    pub fn parse_recover(&mut self, code: &str) -> (Ast, Vec<Diagnostic>) {
+        let mut lexer = TytleLexer::new(code);
+        let mut errors = ParseErrors::new();
+
+        let ast = self.parse_stmts(&mut lexer, &mut errors);
+
+        let diagnostics = errors
+            .into_iter()
+            .map(|(err, loc)| Diagnostic::parse(err, loc))
+            .collect();
+
+        (ast, diagnostics)
+    }
+
+    fn parse_stmts(&mut self, lexer: &mut impl Lexer, errors: &mut ParseErrors) -> Ast {
         let mut ast = Ast::default();
 
         loop {
-            let stmt = self.parse_statement(lexer)?;
-
-            match stmt {
-                Statement::NOP => continue,
-                Statement::EOF => break,
-                _ => ast.statements.push(stmt),
+            let location = self.peek_current_location(lexer);
+            let start_tok = self.peek_current_token(lexer);
+
+            match self.parse_statement(lexer, errors) {
+                Ok(Statement::NOP) => continue,
+                Ok(Statement::EOF) => break,
+                Ok(stmt) => ast.add_located_statement(stmt, location),
+                Err(err) => {
+                    errors.push((err, location));
+                    self.skip_failed_stmt(lexer, &start_tok, None);
+                }
             }
         }
 
@@ -77,16 +119,52 @@ impl TytleParser {
             ast.statements.push(Statement::EOF);
         }
 
-        Ok(ast)
+        ast
     }
 
-    fn parse_statement(&self, lexer: &mut impl Lexer) -> StatementResult {
-        let tok_loc = self.peek_current_token(lexer);
-        if tok_loc.is_none() {
-            return Ok(Statement::EOF);
+    // skips the rest of a statement which has failed to parse.
+    // we stop after the end of the line, or right before `end_tok` (the end of the enclosing block).
+    // blocks opened along the way are skipped as a whole
+    fn skip_failed_stmt(&self, lexer: &mut impl Lexer, start_tok: &Token, end_tok: Option<&Token>) {
+        let end_kw = Token::VALUE("END".to_string());
+
+        // a failed `TO` statement is missing its `END` (its block is parsed with recovery)
+        let mut depth = if *start_tok == Token::VALUE("TO".to_string()) {
+            1
+        } else {
+            0
+        };
+
+        loop {
+            let tok = self.peek_current_token(lexer);
+
+            if tok == Token::EOF {
+                return;
+            }
+
+            if depth == 0 {
+                if Some(&tok) == end_tok {
+                    return;
+                }
+
+                if tok == Token::NEWLINE {
+                    self.skip_token(lexer);
+                    return;
+                }
+            }
+
+            if tok == Token::LBRACKET || tok == Token::VALUE("TO".to_string()) {
+                depth += 1;
+            } else if (tok == Token::RBRACKET || tok == end_kw) && depth > 0 {
+                depth -= 1;
+            }
+
+            self.skip_token(lexer);
         }
+    }
 
-        let (token, _location) = tok_loc.unwrap();
+    fn parse_statement(&self, lexer: &mut impl Lexer, errors: &mut ParseErrors) -> StatementResult {
+        let token = self.peek_current_token(lexer);
 
         match token {
             Token::EOF => Ok(Statement::EOF),
@@ -95,13 +173,16 @@ impl TytleParser {
                 Ok(Statement::NOP)
             }
             Token::VALUE(val) => match val.as_str() {
-                "REPEAT" => self.parse_repeat_stmt(lexer),
-                "IF" => self.parse_if_stmt(lexer),
-                "TO" => self.parse_proc_stmt(lexer),
+                "REPEAT" => self.parse_repeat_stmt(lexer, errors),
+                "IF" => self.parse_if_stmt(lexer, errors),
+                "TO" => self.parse_proc_stmt(lexer, errors),
+                "STRUCT" => self.parse_struct_stmt(lexer),
                 "RETURN" => self.parse_ret_stmt(lexer),
-                _ => self.parse_basic_stmt(val.clone().as_str(), lexer),
+                // an `END` not closing any `TO` (not a variable named `END`)
+                "END" => Err(ParseError::StrayToken(Token::VALUE(val))),
+                _ => self.parse_basic_stmt(val.as_str(), lexer),
             },
-            _ => unimplemented!(),
+            _ => Err(ParseError::StrayToken(token)),
         }
     }
 
@@ -116,7 +197,7 @@ impl TytleParser {
         Ok(stmt)
     }
 
-    fn parse_proc_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+    fn parse_proc_stmt(&self, lexer: &mut impl Lexer, errors: &mut ParseErrors) -> StatementResult {
         self.skip_token(lexer); // skipping the `TO` token
 
         let name = self.expect_value(lexer)?;
@@ -125,7 +206,7 @@ impl TytleParser {
 
         let borders = (None, Token::VALUE("END".to_string()));
         let (params, return_type) = self.parse_proc_signature(lexer)?;
-        let block = self.parse_block_stmt(lexer, borders)?;
+        let block = self.parse_block_stmt(lexer, borders, errors)?;
 
         let proc_stmt = ProcedureStmt {
             id: None, // we'll assign procedure id in the symbols-generation process
@@ -150,9 +231,9 @@ impl TytleParser {
         self.expect_token(lexer, Token::LPAREN)?;
 
         while !completed {
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let tok = self.peek_current_token(lexer);
 
-            if *tok == Token::RPAREN {
+            if tok == Token::RPAREN {
                 self.skip_token(lexer); // skipping the `)`
                 completed = true
             } else {
@@ -161,42 +242,47 @@ impl TytleParser {
                 self.validate_name(param_name.as_str())?;
                 self.expect_token(lexer, Token::COLON)?;
 
-                let param_type = self.expect_value(lexer)?;
+                let param_type = self.parse_data_type(lexer)?;
 
-                self.validate_data_type(param_type.as_str())?;
+                // an optional param has a default value (`SIZE: INT = 50`)
+                let default_expr = if self.peek_current_token(lexer) == Token::ASSIGN {
+                    self.skip_token(lexer); // skipping the `=`
+
+                    Some(self.parse_expr(lexer)?)
+                } else {
+                    None
+                };
 
                 let param = ProcParam {
                     param_name,
                     param_type,
+                    default_expr,
                 };
 
                 params.push(param);
 
-                if self.peek_current_token_clone(lexer) == Token::COMMA {
+                if self.peek_current_token(lexer) == Token::COMMA {
                     self.skip_token(lexer);
                 }
             }
         }
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let tok = self.peek_current_token(lexer);
 
-        let return_type = if *tok == Token::COLON {
+        let return_type = if tok == Token::COLON {
             self.skip_token(lexer); // skipping the `:`
 
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let tok = self.peek_current_token(lexer);
 
-            if *tok == Token::NEWLINE {
+            if tok == Token::NEWLINE {
                 return Err(ParseError::MissingProcReturnType);
             } else {
-                let return_type = self.expect_value(lexer)?;
-                self.validate_data_type(return_type.as_str())?;
-
-                return_type
+                self.parse_data_type(lexer)?
             }
         } else {
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            let tok = self.peek_current_token(lexer);
 
-            if *tok == Token::NEWLINE {
+            if tok == Token::NEWLINE {
                 "UNIT".to_string() // a Procedure with no return value
             } else {
                 return Err(ParseError::MissingColon);
@@ -206,36 +292,74 @@ impl TytleParser {
         Ok((params, return_type))
     }
 
-    fn parse_repeat_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+    // `STRUCT POINT [X: INT, Y: INT]` (the fields are separated by commas and / or new lines)
+    fn parse_struct_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `STRUCT` token
+
+        let name = self.expect_value(lexer)?;
+
+        self.validate_name(name.as_str())?;
+        self.expect_token(lexer, Token::LBRACKET)?;
+
+        let mut struct_stmt = StructStmt::new(name);
+
+        loop {
+            match self.peek_current_token(lexer) {
+                Token::NEWLINE | Token::COMMA => self.skip_token(lexer),
+                Token::RBRACKET => {
+                    self.skip_token(lexer); // skipping the `]`
+                    break;
+                }
+                _ => {
+                    let field_name = self.expect_value(lexer)?;
+
+                    self.validate_name(field_name.as_str())?;
+                    self.expect_token(lexer, Token::COLON)?;
+
+                    let field_type = self.parse_data_type(lexer)?;
+
+                    let field = StructField {
+                        field_name,
+                        field_type,
+                    };
+
+                    struct_stmt.fields.push(field);
+                }
+            }
+        }
+
+        let stmt = Statement::Struct(struct_stmt);
+        Ok(stmt)
+    }
+
+    fn parse_repeat_stmt(
+        &self,
+        lexer: &mut impl Lexer,
+        errors: &mut ParseErrors,
+    ) -> StatementResult {
         self.skip_token(lexer); // skipping the `REPEAT` token
 
         let count_expr = self.parse_expr(lexer)?;
         let borders = (Some(Token::LBRACKET), Token::RBRACKET);
-        let block = self.parse_block_stmt(lexer, borders)?;
+        let block = self.parse_block_stmt(lexer, borders, errors)?;
         let repeat_stmt = RepeatStmt { count_expr, block };
 
         let stmt = Statement::Repeat(repeat_stmt);
         Ok(stmt)
     }
 
-    fn parse_if_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+    fn parse_if_stmt(&self, lexer: &mut impl Lexer, errors: &mut ParseErrors) -> StatementResult {
         self.skip_token(lexer); // skipping the `IF` token
 
         let borders = (Some(Token::LBRACKET), Token::RBRACKET);
 
         let cond_expr = self.parse_expr(lexer)?;
-        let true_block = self.parse_block_stmt(lexer, borders.clone())?;
+        let true_block = self.parse_block_stmt(lexer, borders.clone(), errors)?;
         let mut false_block = None;
 
-        let tok_loc = self.peek_current_token(lexer);
-
-        if tok_loc.is_some() {
-            let (tok, _loc) = tok_loc.unwrap();
-
-            if *tok == Token::LBRACKET {
-                let block_stmt = self.parse_block_stmt(lexer, borders.clone())?;
-                false_block = Some(block_stmt);
-            }
+        if self.peek_current_token(lexer) == Token::LBRACKET {
+            let block_stmt = self.parse_block_stmt(lexer, borders.clone(), errors)?;
+            false_block = Some(block_stmt);
         }
 
         let if_stmt = IfStmt {
@@ -252,6 +376,7 @@ impl TytleParser {
         &self,
         lexer: &mut impl Lexer,
         block_borders: (Option<Token>, Token),
+        errors: &mut ParseErrors,
     ) -> Result<BlockStatement, ParseError> {
         let mut block = BlockStatement::new();
 
@@ -261,18 +386,33 @@ impl TytleParser {
             self.expect_token(lexer, start_tok.unwrap())?;
         }
 
-        let mut completed = false;
+        loop {
+            let tok = self.peek_current_token(lexer);
 
-        while !completed {
-            let stmt = self.parse_statement(lexer)?;
+            if tok == end_tok {
+                block.end_location = Some(self.peek_current_location(lexer));
 
-            block.add_statement(stmt);
+                self.skip_token(lexer); // skipping the block `ending token`
+                break;
+            }
 
-            let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+            if tok == Token::EOF {
+                // the input has ended before the block was closed
+                let err = ParseError::UnexpectedToken {
+                    expected: end_tok,
+                    actual: Token::EOF,
+                };
+                return Err(err);
+            }
 
-            if *tok == end_tok {
-                self.skip_token(lexer); // skipping the block `ending token`
-                completed = true;
+            let location = self.peek_current_location(lexer);
+
+            match self.parse_statement(lexer, errors) {
+                Ok(stmt) => block.add_located_statement(stmt, location),
+                Err(err) => {
+                    errors.push((err, location));
+                    self.skip_failed_stmt(lexer, &tok, Some(&end_tok));
+                }
             }
         }
 
@@ -282,11 +422,14 @@ impl TytleParser {
     fn parse_basic_stmt(&self, val: &str, lexer: &mut impl Lexer) -> StatementResult {
         match val {
             "PRINT" => self.parse_print_stmt(lexer),
+            "LABEL" => self.parse_label_stmt(lexer),
+            "SETFONTSIZE" => self.parse_set_font_size_stmt(lexer),
             "TRAP" => self.parse_trap_stmt(lexer),
             "HALT" => self.parse_halt_stmt(lexer),
             "MAKE" => self.parse_make_stmt(lexer),
             "MAKEGLOBAL" => self.parse_make_global_stmt(lexer),
             "MAKELOCAL" => self.parse_make_local_stmt(lexer),
+            "MAP" => self.parse_map_stmt(lexer),
             "FORWARD" | "BACKWARD" | "RIGHT" | "LEFT" | "SETX" | "SETY" => {
                 self.parse_direct_stmt(val, lexer)
             }
@@ -331,11 +474,29 @@ impl TytleParser {
 
         self.validate_name(var_name.as_str())?;
 
+        // assigning a record field (`MAKE P.X = 10`)
+        if kind == MakeStmtKind::Assign && self.peek_current_token(lexer) == Token::DOT {
+            return self.build_set_field_stmt(lexer, var_name);
+        }
+
+        // only declarations may have a type annotation (`MAKELOCAL A: INT = 1`)
+        let var_type =
+            if kind != MakeStmtKind::Assign && self.peek_current_token(lexer) == Token::COLON {
+                self.skip_token(lexer); // skipping the `:`
+
+                let var_type = self.parse_data_type(lexer)?;
+
+                Some(var_type)
+            } else {
+                None
+            };
+
         self.expect_token(lexer, Token::ASSIGN)?;
 
         let expr = self.parse_expr(lexer)?;
         let make_stmt = MakeStmt {
             var_name,
+            var_type,
             expr,
             kind,
             var_id: None,
@@ -345,6 +506,56 @@ impl TytleParser {
         Ok(stmt)
     }
 
+    fn build_set_field_stmt(&self, lexer: &mut impl Lexer, var_name: String) -> StatementResult {
+        let var_lit = LiteralExpr::Var(var_name, None);
+        let mut record_expr = Expression::new(ExpressionAst::Literal(var_lit));
+
+        self.skip_token(lexer); // skipping the `.`
+        let mut field_name = self.expect_value(lexer)?;
+
+        // for `MAKE P.A.X = 10` the record is `P.A`
+        while self.peek_current_token(lexer) == Token::DOT {
+            self.skip_token(lexer); // skipping the `.`
+
+            let ast = ExpressionAst::Field(Box::new(record_expr), field_name);
+            record_expr = Expression::new(ast);
+
+            field_name = self.expect_value(lexer)?;
+        }
+
+        self.expect_token(lexer, Token::ASSIGN)?;
+
+        let expr = self.parse_expr(lexer)?;
+        let set_field_stmt = SetFieldStmt {
+            record_expr,
+            field_name,
+            expr,
+        };
+        let stmt = Statement::SetField(set_field_stmt);
+
+        Ok(stmt)
+    }
+
+    fn parse_map_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `MAP` token
+
+        self.expect_token(lexer, Token::LPAREN)?;
+        let proc_expr = self.parse_expr(lexer)?;
+
+        self.expect_token(lexer, Token::COMMA)?;
+        let count_expr = self.parse_expr(lexer)?;
+
+        self.expect_token(lexer, Token::RPAREN)?;
+
+        let map_stmt = MapStmt {
+            proc_expr,
+            count_expr,
+        };
+
+        let stmt = Statement::Map(map_stmt);
+        Ok(stmt)
+    }
+
     fn parse_halt_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
         self.skip_token(lexer); // skipping the `HALT` token
 
@@ -365,6 +576,24 @@ impl TytleParser {
         Ok(stmt)
     }
 
+    fn parse_label_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `LABEL` token
+
+        let expr = self.parse_expr(lexer)?;
+
+        let stmt = Statement::Label(expr);
+        Ok(stmt)
+    }
+
+    fn parse_set_font_size_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
+        self.skip_token(lexer); // skipping the `SETFONTSIZE` token
+
+        let expr = self.parse_expr(lexer)?;
+
+        let stmt = Statement::SetFontSize(expr);
+        Ok(stmt)
+    }
+
     fn parse_trap_stmt(&self, lexer: &mut impl Lexer) -> StatementResult {
         self.skip_token(lexer); // skipping the `TRAP` token
 
@@ -383,7 +612,7 @@ impl TytleParser {
 
         let direct_stmt = DirectionStmt {
             expr,
-            direction: Direction::from(direction),
+            direction: Direction::try_from(direction)?,
         };
 
         let stmt = Statement::Direction(direct_stmt);
@@ -393,7 +622,7 @@ impl TytleParser {
     fn parse_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let left_expr = self.parse_and_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let tok = self.peek_current_token(lexer);
 
         match tok {
             Token::OR => {
@@ -414,7 +643,7 @@ impl TytleParser {
     fn parse_and_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let left_expr = self.parse_cmp_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let tok = self.peek_current_token(lexer);
 
         match tok {
             Token::AND => {
@@ -435,17 +664,15 @@ impl TytleParser {
     fn parse_cmp_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let left_expr = self.parse_clause_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let tok = self.peek_current_token(lexer);
 
         match tok {
             Token::GT | Token::LT => {
-                let tok = tok.clone();
-
                 self.skip_token(lexer); // we skip the `> / >= / < / <= / == / !=` token
 
                 let right_expr = self.parse_clause_expr(lexer)?;
 
-                let binary_op = BinaryOp::from(&tok);
+                let binary_op = BinaryOp::try_from(&tok)?;
 
                 let ast =
                     ExpressionAst::Binary(binary_op, Box::new(left_expr), Box::new(right_expr));
@@ -460,9 +687,9 @@ impl TytleParser {
     fn parse_clause_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let left_expr = self.parse_mul_div_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let tok = self.peek_current_token(lexer);
 
-        if *tok == Token::ADD {
+        if tok == Token::ADD {
             self.skip_token(lexer); // we skip the `+` token
 
             let right_expr = self.parse_clause_expr(lexer)?;
@@ -480,16 +707,14 @@ impl TytleParser {
     fn parse_mul_div_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
         let lparen_expr = self.parse_parens_expr(lexer)?;
 
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
-
-        let tok = tok.clone();
+        let tok = self.peek_current_token(lexer);
 
         match tok {
             Token::MUL | Token::DIV => {
                 self.skip_token(lexer); // skip the `*` or `/`
                 let rparen_expr = self.parse_mul_div_expr(lexer)?;
 
-                let bin_op = BinaryOp::from(&tok);
+                let bin_op = BinaryOp::try_from(&tok)?;
 
                 let ast =
                     ExpressionAst::Binary(bin_op, Box::new(lparen_expr), Box::new(rparen_expr));
@@ -502,7 +727,7 @@ impl TytleParser {
     }
 
     fn parse_parens_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
-        let (tok, _loc) = self.peek_current_token(lexer).unwrap();
+        let tok = self.peek_current_token(lexer);
 
         match tok {
             Token::LPAREN => {
@@ -531,12 +756,22 @@ impl TytleParser {
     }
 
     fn parse_basic_expr(&self, lexer: &mut impl Lexer) -> ExpressionResult {
-        let (token, _location) = self.peek_next_token(lexer).unwrap();
-
-        let ast = match *token {
+        let ast = match self.peek_next_token(lexer) {
             Token::LPAREN => {
-                let (proc_name, proc_params) = self.parse_proc_call_expr(lexer)?;
-                ExpressionAst::ProcCall(proc_name, proc_params, None)
+                let (proc_name, mut proc_params) = self.parse_proc_call_expr(lexer)?;
+
+                if proc_name == "APPLY" {
+                    // `APPLY(F, ARG1, ARG2, ...)` calls the procedure value `F`
+                    if proc_params.is_empty() {
+                        let message = "`APPLY` expects a procedure".to_string();
+                        return Err(ParseError::Syntax { message });
+                    }
+
+                    let proc_expr = proc_params.remove(0);
+                    ExpressionAst::IndirectCall(Box::new(proc_expr), proc_params)
+                } else {
+                    ExpressionAst::ProcCall(proc_name, proc_params, None)
+                }
             }
             _ => {
                 let lit_expr = self.parse_literal_expr(lexer)?;
@@ -544,7 +779,18 @@ impl TytleParser {
             }
         };
 
-        let expr = Expression::new(ast);
+        let mut expr = Expression::new(ast);
+
+        // reading record fields (`P.X` or `LINE(...).START.X`)
+        while self.peek_current_token(lexer) == Token::DOT {
+            self.skip_token(lexer); // skipping the `.`
+
+            let field_name = self.expect_value(lexer)?;
+
+            let ast = ExpressionAst::Field(Box::new(expr), field_name);
+            expr = Expression::new(ast);
+        }
+
         Ok(expr)
     }
 
@@ -552,7 +798,7 @@ impl TytleParser {
         &self,
         lexer: &mut impl Lexer,
     ) -> Result<(String, Vec<Expression>), ParseError> {
-        let (token, _) = self.pop_current_token(lexer).unwrap();
+        let (token, _) = self.pop_stmt_token(lexer);
 
         if let Token::VALUE(proc_name) = token {
             self.expect_token(lexer, Token::LPAREN)?;
@@ -574,12 +820,21 @@ impl TytleParser {
         lexer: &mut impl Lexer,
     ) -> Result<Vec<Expression>, ParseError> {
         let mut params = Vec::new();
+        let mut has_named_args = false;
 
-        while self.peek_current_token_clone(lexer) != Token::RPAREN {
+        while self.peek_current_token(lexer) != Token::RPAREN {
             let param_expr = self.parse_call_param_expr(lexer)?;
 
-            if param_expr.is_some() {
-                params.push(param_expr.unwrap());
+            if let Some(param_expr) = param_expr {
+                // the named arguments come after the positional ones (`RECT(10, HEIGHT: 20)`)
+                if let ExpressionAst::NamedArg(..) = param_expr.expr_ast {
+                    has_named_args = true;
+                } else if has_named_args {
+                    let message = "Positional argument after a named argument".to_string();
+                    return Err(ParseError::Syntax { message });
+                }
+
+                params.push(param_expr);
             }
         }
 
@@ -590,9 +845,21 @@ impl TytleParser {
         &self,
         lexer: &mut impl Lexer,
     ) -> Result<Option<Expression>, ParseError> {
-        let expr = self.parse_expr(lexer)?;
+        let next_tokens = (self.peek_current_token(lexer), self.peek_next_token(lexer));
 
-        if self.peek_current_token_clone(lexer) == Token::COMMA {
+        let expr = if let (Token::VALUE(param_name), Token::COLON) = next_tokens {
+            self.skip_token(lexer); // skipping the param name
+            self.skip_token(lexer); // skipping the `:`
+
+            let arg_expr = self.parse_expr(lexer)?;
+
+            let ast = ExpressionAst::NamedArg(param_name, Box::new(arg_expr));
+            Expression::new(ast)
+        } else {
+            self.parse_expr(lexer)?
+        };
+
+        if self.peek_current_token(lexer) == Token::COMMA {
             self.skip_token(lexer);
         }
 
@@ -600,17 +867,33 @@ impl TytleParser {
     }
 
     fn parse_literal_expr(&self, lexer: &mut impl Lexer) -> Result<LiteralExpr, ParseError> {
-        let pair = self.pop_current_token(lexer);
+        let (tok, loc) = self.pop_stmt_token(lexer);
 
-        let (tok, _loc) = pair.unwrap();
+        if let Token::UNTERMINATED(v) = tok {
+            return Err(ParseError::UnterminatedStr(v));
+        }
 
         if let Token::VALUE(v) = tok {
+            // the integers are executed as `isize` values
+            let is_int = v.chars().all(|c| c.is_ascii_digit());
+
+            if is_int && v.parse::<isize>().is_err() {
+                let err = ParseError::IntegerOutOfRange {
+                    literal: v,
+                    location: loc,
+                };
+                return Err(err);
+            }
+
             match v.parse::<usize>() {
                 Ok(num) => Ok(LiteralExpr::Int(num)),
                 Err(_) => {
-                    if v.starts_with("\"") {
-                        let s = v[1..v.len() - 1].to_string();
-                        Ok(LiteralExpr::Str(s))
+                    if let Some(s) = v.strip_prefix('"') {
+                        // the literal must end with its closing `"` (so `"ABC"D` is rejected too)
+                        match s.strip_suffix('"') {
+                            Some(s) => Ok(LiteralExpr::Str(s.to_string())),
+                            None => Err(ParseError::UnterminatedStr(v)),
+                        }
                     } else {
                         let lit_expr = match v.as_str() {
                             "TRUE" => LiteralExpr::Bool(true),
@@ -634,7 +917,7 @@ impl TytleParser {
     }
 
     fn expect_value(&self, lexer: &mut impl Lexer) -> Result<String, ParseError> {
-        let (token, _loc) = self.pop_current_token(lexer).unwrap();
+        let (token, _loc) = self.pop_stmt_token(lexer);
 
         if let Token::VALUE(v) = token {
             Ok(v)
@@ -644,7 +927,7 @@ impl TytleParser {
     }
 
     fn expect_token(&self, lexer: &mut impl Lexer, expected: Token) -> Result<(), ParseError> {
-        let (actual, _loc) = self.pop_current_token(lexer).unwrap();
+        let (actual, _loc) = self.pop_stmt_token(lexer);
 
         if actual == expected {
             Ok(())
@@ -658,26 +941,50 @@ impl TytleParser {
         }
     }
 
-    fn peek_current_token<'lex>(&self, lexer: &'lex impl Lexer) -> Option<&'lex (Token, Location)> {
-        lexer.peek_current_token()
+    // once the lexer runs out of tokens we keep on seeing `EOF`,
+    // so a truncated input ends up as a parse error (rather than a panic)
+    fn peek_current_token(&self, lexer: &impl Lexer) -> Token {
+        match lexer.peek_current_token() {
+            Some((token, _)) => token.clone(),
+            None => Token::EOF,
+        }
     }
 
-    fn peek_next_token<'lex>(&self, lexer: &'lex impl Lexer) -> Option<&'lex (Token, Location)> {
-        lexer.peek_next_token()
+    fn peek_next_token(&self, lexer: &impl Lexer) -> Token {
+        match lexer.peek_next_token() {
+            Some((token, _)) => token.clone(),
+            None => Token::EOF,
+        }
     }
 
-    fn peek_current_token_clone<'lex>(&self, lexer: &'lex impl Lexer) -> Token {
-        let (token, _) = lexer.peek_current_token().unwrap();
-
-        token.clone()
+    fn peek_current_location(&self, lexer: &impl Lexer) -> Location {
+        match lexer.peek_current_token() {
+            Some((_, loc)) => *loc,
+            None => Location::default(),
+        }
     }
 
     fn skip_token(&self, lexer: &mut impl Lexer) {
         self.pop_current_token(lexer);
     }
 
-    fn pop_current_token(&self, lexer: &mut impl Lexer) -> Option<(Token, Location)> {
-        lexer.pop_current_token()
+    fn pop_current_token(&self, lexer: &mut impl Lexer) -> (Token, Location) {
+        match lexer.pop_current_token() {
+            Some(tok_loc) => tok_loc,
+            None => (Token::EOF, Location::default()),
+        }
+    }
+
+    // like `pop_current_token`, but a line ending (or `EOF`) is left in place.
+    // so when a statement fails on its last token, `skip_failed_stmt` doesn't skip the next line too
+    fn pop_stmt_token(&self, lexer: &mut impl Lexer) -> (Token, Location) {
+        match self.peek_current_token(lexer) {
+            Token::NEWLINE | Token::EOF => (
+                self.peek_current_token(lexer),
+                self.peek_current_location(lexer),
+            ),
+            _ => self.pop_current_token(lexer),
+        }
     }
 
     fn validate_name(&self, name: &str) -> Result<(), ParseError> {
@@ -711,10 +1018,58 @@ impl TytleParser {
         Ok(())
     }
 
+    // parses a data type, returning its canonical name.
+    // a procedure type is written `PROC(INT, STR): BOOL` (or `PROC(INT)` when returning nothing).
+    // any other name is a struct type (whether it's declared is checked by `SymbolTableGenerator`)
+    fn parse_data_type(&self, lexer: &mut impl Lexer) -> Result<String, ParseError> {
+        let data_type = self.expect_value(lexer)?;
+
+        if data_type != "PROC" {
+            self.validate_data_type(data_type.as_str())?;
+
+            return Ok(data_type);
+        }
+
+        self.expect_token(lexer, Token::LPAREN)?;
+
+        let mut params_types = Vec::new();
+
+        while self.peek_current_token(lexer) != Token::RPAREN {
+            params_types.push(self.parse_data_type(lexer)?);
+
+            if self.peek_current_token(lexer) == Token::COMMA {
+                self.skip_token(lexer);
+            } else {
+                break;
+            }
+        }
+
+        self.expect_token(lexer, Token::RPAREN)?;
+
+        let mut proc_type = format!("PROC({})", params_types.join(", "));
+
+        if self.peek_current_token(lexer) == Token::COLON {
+            self.skip_token(lexer); // skipping the `:`
+
+            if self.peek_current_token(lexer) == Token::VALUE("UNIT".to_string()) {
+                self.skip_token(lexer); // `PROC(INT): UNIT` is the same as `PROC(INT)`
+            } else {
+                let return_type = self.parse_data_type(lexer)?;
+                proc_type.push_str(&format!(": {}", return_type));
+            }
+        }
+
+        Ok(proc_type)
+    }
+
     fn validate_data_type(&self, data_type: &str) -> Result<(), ParseError> {
         match data_type {
             "STR" | "INT" | "BOOL" => Ok(()),
-            _ => Err(ParseError::InvalidDataType(data_type.to_owned())),
+            "UNIT" => Err(ParseError::InvalidDataType(data_type.to_owned())),
+            _ => match self.validate_name(data_type) {
+                Ok(()) => Ok(()),
+                Err(_) => Err(ParseError::InvalidDataType(data_type.to_owned())),
+            },
         }
     }
 }
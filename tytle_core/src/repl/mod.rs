@@ -0,0 +1,5 @@
+mod repl_error;
+mod repl_session;
+
+pub use repl_error::ReplError;
+pub use repl_session::ReplSession;
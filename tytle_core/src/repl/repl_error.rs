@@ -0,0 +1,20 @@
+use crate::ast::semantic::AstWalkError;
+use crate::parser::ParseError;
+use crate::vm::InterpreterException;
+
+#[derive(Debug, PartialEq)]
+pub enum ReplError {
+    Parse(ParseError),
+    AstWalk(AstWalkError),
+    Interpreter(InterpreterException),
+}
+
+impl ToString for ReplError {
+    fn to_string(&self) -> String {
+        match self {
+            ReplError::Parse(err) => err.to_string(),
+            ReplError::AstWalk(err) => err.to_string(),
+            ReplError::Interpreter(err) => format!("Runtime error: {}", err.to_string()),
+        }
+    }
+}
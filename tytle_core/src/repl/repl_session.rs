@@ -0,0 +1,99 @@
+use crate::ast::semantic::{AstTypeCheck, Environment, SymbolTableGenerator};
+use crate::ast::Ast;
+use crate::ir::{CfgBuilder, CfgObject};
+use crate::lexer::{Lexer, Token, TytleLexer};
+use crate::parser::{Parser, TytleParser};
+use crate::repl::ReplError;
+use crate::vm::{Host, Interpreter, Memory};
+
+// A `ReplSession` compiles and executes code incrementally.
+//
+// each input extends the same `Environment` and `CfgObject`,
+// and runs against the same `Memory`. so globals and procedures
+// declared by earlier inputs remain usable by the later ones.
+pub struct ReplSession {
+    env: Environment,
+    cfg: Option<CfgObject>,
+    memory: Option<Memory>,
+}
+
+impl Default for ReplSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReplSession {
+    pub fn new() -> Self {
+        let mut ast = Ast::default();
+
+        let generator = SymbolTableGenerator::new();
+        let mut env = generator.generate(&mut ast).unwrap();
+
+        let builder = CfgBuilder::new(&mut env);
+        let cfg = builder.build(&ast);
+
+        Self {
+            env,
+            cfg: Some(cfg),
+            memory: Some(Memory::new()),
+        }
+    }
+
+    pub fn eval(&mut self, code: &str, host: &mut dyn Host) -> Result<(), ReplError> {
+        let mut ast = TytleParser.parse(code).map_err(ReplError::Parse)?;
+
+        // we compile against a copy of the environment,
+        // so an input failing compilation leaves the session untouched
+        let generator = SymbolTableGenerator::with_env(self.env.clone());
+        let mut env = generator
+            .generate(&mut ast)
+            .map_err(ReplError::AstWalk)?;
+
+        let mut checker = AstTypeCheck::new(&mut env);
+        checker.check(&mut ast).map_err(ReplError::AstWalk)?;
+
+        let builder = CfgBuilder::extend(&mut env, self.cfg.take().unwrap());
+        let entry_node_id = builder.get_entry_node_id();
+        let cfg = builder.build(&ast);
+
+        let memory = self.memory.take().unwrap();
+        let mut intr = Interpreter::with_memory(&cfg, &env, host, memory, entry_node_id);
+        let res = intr.exec_code();
+
+        self.memory = Some(intr.into_memory());
+        self.cfg = Some(cfg);
+        self.env = env;
+
+        res.map_err(ReplError::Interpreter)
+    }
+
+    pub fn get_env(&self) -> &Environment {
+        &self.env
+    }
+
+    pub fn get_memory(&self) -> &Memory {
+        self.memory.as_ref().unwrap()
+    }
+
+    // returns whether `code` can be compiled as is,
+    // or whether it has unclosed `TO ... END` or `[ ... ]` blocks
+    // (in which case the REPL should keep reading more lines)
+    pub fn is_complete(code: &str) -> bool {
+        let mut lexer = TytleLexer::new(code);
+        let mut depth: isize = 0;
+
+        loop {
+            let (token, _loc) = lexer.pop_current_token().unwrap();
+
+            match token {
+                Token::EOF => return depth <= 0,
+                Token::LBRACKET => depth += 1,
+                Token::RBRACKET => depth -= 1,
+                Token::VALUE(ref v) if v == "TO" => depth += 1,
+                Token::VALUE(ref v) if v == "END" => depth -= 1,
+                _ => continue,
+            }
+        }
+    }
+}
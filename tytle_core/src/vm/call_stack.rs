@@ -5,8 +5,23 @@ use crate::ir::CfgNodeId;
 pub enum CallStackItem {
     Int(isize),
     Bool(bool),
+    Str(String),
     Addr(CfgNodeId, usize),
-    // StrRef
+
+    // a procedure value: the procedure CFG node and (for a nested procedure)
+    // the frame of its enclosing procedure activation
+    Proc(CfgNodeId, Option<FrameRef>),
+
+    // a record reference (see `MemoryValue::Struct`)
+    Struct(usize),
+}
+
+// refers to a frame for as long as it's open.
+// a frame opened later at the same `index` has a different `frame_id`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FrameRef {
+    pub index: usize,
+    pub frame_id: usize,
 }
 
 impl CallStackItem {
@@ -45,6 +60,13 @@ impl CallStackItem {
         }
     }
 
+    pub fn to_str(&self) -> &str {
+        match self {
+            CallStackItem::Str(v) => v,
+            _ => panic!("expected a string"),
+        }
+    }
+
     pub fn to_addr(&self) -> (CfgNodeId, usize) {
         match self {
             CallStackItem::Addr(node_id, ip) => (*node_id, *ip),
@@ -57,13 +79,22 @@ impl CallStackItem {
 pub struct CallStackFrame {
     items: Vec<CallStackItem>,
     pub ctx_proc: SymbolId,
+
+    // unique per opened frame
+    pub id: usize,
+
+    // the index of the frame of the lexically enclosing procedure activation
+    // (`None` for a top-level procedure). used for accessing the enclosing procedures locals
+    pub static_link: Option<usize>,
 }
 
 impl CallStackFrame {
-    pub fn new(ctx_proc: SymbolId) -> Self {
+    pub fn new(ctx_proc: SymbolId, id: usize, static_link: Option<usize>) -> Self {
         Self {
             items: Vec::new(),
             ctx_proc,
+            id,
+            static_link,
         }
     }
 
@@ -75,6 +106,14 @@ impl CallStackFrame {
         self.items.push(item);
     }
 
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&CallStackItem> {
+        self.items.get(index)
+    }
+
     pub fn load(&mut self, index: usize) -> &CallStackItem {
         self.items.get(index).unwrap()
     }
@@ -87,19 +126,31 @@ impl CallStackFrame {
         self.items.last().unwrap()
     }
 
+    pub fn try_peek(&self) -> Option<&CallStackItem> {
+        self.items.last()
+    }
+
     pub fn pop(&mut self) -> CallStackItem {
         self.items.pop().unwrap()
     }
+
+    pub fn try_pop(&mut self) -> Option<CallStackItem> {
+        self.items.pop()
+    }
 }
 
 #[derive(Debug)]
 pub struct CallStack {
     pub frames: Vec<CallStackFrame>,
+    next_frame_id: usize,
 }
 
 impl CallStack {
     pub fn new() -> Self {
-        Self { frames: Vec::new() }
+        Self {
+            frames: Vec::new(),
+            next_frame_id: 0,
+        }
     }
 
     pub fn is_empty(&self) -> bool {
@@ -116,6 +167,30 @@ impl CallStack {
         frame.store(index, item);
     }
 
+    pub fn load_frame_item(&mut self, frame_index: usize, index: usize) -> &CallStackItem {
+        self.frames[frame_index].load(index)
+    }
+
+    pub fn store_frame_item(&mut self, frame_index: usize, index: usize, item: CallStackItem) {
+        self.frames[frame_index].store(index, item);
+    }
+
+    // follows the static links, starting from the current frame, up to the activation of `proc_id`.
+    // returns `None` when `proc_id` doesn't lexically enclose the current procedure
+    pub fn lookup_frame(&self, proc_id: SymbolId) -> Option<usize> {
+        let mut frame_index = self.frames.len().checked_sub(1)?;
+
+        loop {
+            let frame = &self.frames[frame_index];
+
+            if frame.ctx_proc == proc_id {
+                return Some(frame_index);
+            }
+
+            frame_index = frame.static_link?;
+        }
+    }
+
     pub fn push_item(&mut self, item: CallStackItem) {
         let frame = self.current_frame_mut();
         frame.push(item);
@@ -126,18 +201,44 @@ impl CallStack {
         frame.pop()
     }
 
+    // returns `None` when there is no current frame or when it's empty
+    pub fn try_pop_item(&mut self) -> Option<CallStackItem> {
+        let frame = self.frames.last_mut()?;
+        frame.try_pop()
+    }
+
     pub fn peek_item(&self) -> &CallStackItem {
         let frame = self.current_frame();
         frame.peek()
     }
 
-    pub fn open_stackframe(&mut self, ctx_proc: SymbolId) -> &mut CallStackFrame {
-        let frame = CallStackFrame::new(ctx_proc);
+    pub fn open_stackframe(
+        &mut self,
+        ctx_proc: SymbolId,
+        static_link: Option<usize>,
+    ) -> &mut CallStackFrame {
+        let frame = CallStackFrame::new(ctx_proc, self.next_frame_id, static_link);
         self.frames.push(frame);
+        self.next_frame_id += 1;
 
         self.current_frame_mut()
     }
 
+    pub fn frame_ref(&self, index: usize) -> FrameRef {
+        FrameRef {
+            index,
+            frame_id: self.frames[index].id,
+        }
+    }
+
+    // whether the frame referred by `frame_ref` hasn't been closed yet
+    pub fn is_open(&self, frame_ref: &FrameRef) -> bool {
+        match self.frames.get(frame_ref.index) {
+            Some(frame) => frame.id == frame_ref.frame_id,
+            None => false,
+        }
+    }
+
     pub fn close_stackframe(&mut self) {
         self.frames.pop();
     }
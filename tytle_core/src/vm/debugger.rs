@@ -0,0 +1,349 @@
+use crate::prelude::*;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DebugEvent {
+    // a step has completed, execution is paused at the start of a statement
+    Step,
+
+    // execution is paused before executing the instruction at `(node_id, ip)`
+    Breakpoint(CfgNodeId, usize),
+
+    // a `TRAP` at `(node_id, ip)` has been executed
+    Trap(CfgNodeId, usize),
+
+    Finished,
+}
+
+pub type DebugResult = Result<DebugEvent, InterpreterException>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugVariable {
+    pub name: String,
+    pub value: MemoryValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugFrame {
+    pub proc_name: String,
+    pub node_id: CfgNodeId,
+    pub ip: usize,
+    pub location: Option<Location>,
+    pub variables: Vec<DebugVariable>,
+}
+
+// the state of a suspended `Debugger` (see `InterpreterState`)
+pub struct DebuggerState {
+    intr: InterpreterState,
+    breakpoints: HashSet<(CfgNodeId, usize)>,
+    hit_breakpoint: Option<(CfgNodeId, usize)>,
+    finished: bool,
+}
+
+pub struct Debugger<'env, 'cfg, 'host> {
+    intr: Interpreter<'env, 'cfg, 'host>,
+    breakpoints: HashSet<(CfgNodeId, usize)>,
+
+    // the breakpoint execution is paused at (so that resuming doesn't hit it once more)
+    hit_breakpoint: Option<(CfgNodeId, usize)>,
+
+    finished: bool,
+}
+
+impl<'env, 'cfg, 'host> Debugger<'env, 'cfg, 'host> {
+    pub fn new(cfg: &'cfg CfgObject, env: &'env Environment, host: &'host mut dyn Host) -> Self {
+        let intr = Interpreter::new(cfg, env, host);
+
+        Self::with_interpreter(intr)
+    }
+
+    pub fn with_interpreter(intr: Interpreter<'env, 'cfg, 'host>) -> Self {
+        Self {
+            intr,
+            breakpoints: HashSet::new(),
+            hit_breakpoint: None,
+            finished: false,
+        }
+    }
+
+    pub fn resume(
+        cfg: &'cfg CfgObject,
+        env: &'env Environment,
+        host: &'host mut dyn Host,
+        state: DebuggerState,
+    ) -> Self {
+        Self {
+            intr: Interpreter::resume(cfg, env, host, state.intr),
+            breakpoints: state.breakpoints,
+            hit_breakpoint: state.hit_breakpoint,
+            finished: state.finished,
+        }
+    }
+
+    pub fn suspend(self) -> DebuggerState {
+        DebuggerState {
+            intr: self.intr.suspend(),
+            breakpoints: self.breakpoints,
+            hit_breakpoint: self.hit_breakpoint,
+            finished: self.finished,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, node_id: CfgNodeId, ip: usize) {
+        self.breakpoints.insert((node_id, ip));
+    }
+
+    pub fn remove_breakpoint(&mut self, node_id: CfgNodeId, ip: usize) -> bool {
+        self.breakpoints.remove(&(node_id, ip))
+    }
+
+    // adds a breakpoint for each statement starting at source line `line`.
+    // returns `false` when there is no code at that line
+    pub fn add_line_breakpoint(&mut self, line: usize) -> bool {
+        let positions = self.intr.get_cfg().source_map.get_line_stmts(line);
+
+        for (node_id, ip) in positions.iter() {
+            self.add_breakpoint(*node_id, *ip);
+        }
+
+        !positions.is_empty()
+    }
+
+    pub fn remove_line_breakpoint(&mut self, line: usize) -> bool {
+        let positions = self.intr.get_cfg().source_map.get_line_stmts(line);
+
+        let mut removed = false;
+
+        for (node_id, ip) in positions.iter() {
+            removed |= self.remove_breakpoint(*node_id, *ip);
+        }
+
+        removed
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    pub fn get_breakpoints(&self) -> Vec<(CfgNodeId, usize)> {
+        let mut breakpoints: Vec<(CfgNodeId, usize)> = self.breakpoints.iter().cloned().collect();
+        breakpoints.sort();
+
+        breakpoints
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn get_position(&self) -> (CfgNodeId, usize) {
+        (self.intr.node_id, self.intr.ip)
+    }
+
+    pub fn get_location(&self) -> Option<Location> {
+        let (node_id, ip) = self.get_position();
+
+        self.intr.get_cfg().source_map.get_location(node_id, ip)
+    }
+
+    pub fn get_memory(&self) -> &Memory {
+        &self.intr.memory
+    }
+
+    // runs until hitting a breakpoint, a `TRAP` or the end of the program
+    pub fn run(&mut self) -> DebugResult {
+        self.run_until(|_| false)
+    }
+
+    // runs until reaching the next statement (entering procedure calls)
+    pub fn step_into(&mut self) -> DebugResult {
+        self.run_until(|dbg| dbg.at_stmt_start())
+    }
+
+    // runs until reaching the next statement of the current procedure
+    // (or of its caller, when the current procedure returns)
+    pub fn step_over(&mut self) -> DebugResult {
+        let depth = self.depth();
+
+        self.run_until(|dbg| dbg.depth() <= depth && dbg.at_stmt_start())
+    }
+
+    // runs until reaching the next statement after the current procedure returns
+    pub fn step_out(&mut self) -> DebugResult {
+        let depth = self.depth();
+
+        self.run_until(|dbg| dbg.depth() < depth && dbg.at_stmt_start())
+    }
+
+    // returns the call-stack frames, starting with the innermost one
+    pub fn get_frames(&self) -> Vec<DebugFrame> {
+        if self.finished {
+            return Vec::new();
+        }
+
+        let env = self.intr.get_env();
+        let source_map = &self.intr.get_cfg().source_map;
+        let frames = &self.intr.call_stack.frames;
+
+        frames
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(i, frame)| {
+                // a caller frame ends with the return address, pointing to its `call` instruction
+                let (node_id, ip) = if i == 0 {
+                    self.get_position()
+                } else {
+                    frame.peek().to_addr()
+                };
+
+                let proc = env.symbol_table.get_proc_by_id(frame.ctx_proc);
+
+                DebugFrame {
+                    proc_name: proc.name.clone(),
+                    node_id,
+                    ip,
+                    location: source_map.get_location(node_id, ip),
+                    variables: self.get_frame_variables(frame),
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_globals(&self) -> Vec<DebugVariable> {
+        let env = self.intr.get_env();
+
+        (0..env.globals_index)
+            .filter_map(|i| {
+                let var_id = env.globals_symbols[&i];
+                let var = env.symbol_table.get_var_by_id(var_id);
+                let value = self.intr.memory.get_global(Address(i))?;
+
+                Some(DebugVariable {
+                    name: var.name.clone(),
+                    value: value.clone(),
+                })
+            })
+            .collect()
+    }
+
+    // the name of the procedure starting at CFG node `node_id` (e.g of a `MemoryValue::Proc` value)
+    pub fn get_proc_name(&self, node_id: CfgNodeId) -> Option<&str> {
+        let proc_id = self.intr.get_cfg().jmp_table.get(&node_id)?;
+        let proc = self.intr.get_env().symbol_table.get_proc_by_id(*proc_id);
+
+        Some(proc.name.as_str())
+    }
+
+    // the fields of the record referenced by `struct_ref` (e.g of a `MemoryValue::Struct` value)
+    pub fn get_struct_fields(&self, struct_ref: usize) -> Option<Vec<DebugVariable>> {
+        let struct_value = self.intr.memory.get_struct(struct_ref)?;
+        let env = self.intr.get_env();
+        let struct_type = env.symbol_table.get_struct_by_id(struct_value.struct_id);
+
+        let fields = struct_type
+            .fields_names
+            .iter()
+            .zip(struct_value.fields.iter())
+            .map(|(name, value)| DebugVariable {
+                name: name.clone(),
+                value: value.clone(),
+            })
+            .collect();
+
+        Some(fields)
+    }
+
+    fn get_frame_variables(&self, frame: &CallStackFrame) -> Vec<DebugVariable> {
+        let env = self.intr.get_env();
+        let locals = env.locals_symbols.get(&frame.ctx_proc);
+
+        if locals.is_none() {
+            return Vec::new();
+        }
+
+        locals
+            .unwrap()
+            .iter()
+            .map(|var_id| env.symbol_table.get_var_by_id(*var_id))
+            .filter(|var| !var.name.starts_with('$')) // skipping compiler temporaries
+            .filter_map(|var| {
+                let item = frame.get(var.index.unwrap())?;
+                let value = MemoryValue::from_item(item.clone())?;
+
+                Some(DebugVariable {
+                    name: var.name.clone(),
+                    value,
+                })
+            })
+            .collect()
+    }
+
+    fn run_until<F>(&mut self, should_stop: F) -> DebugResult
+    where
+        F: Fn(&Self) -> bool,
+    {
+        if self.finished {
+            return Ok(DebugEvent::Finished);
+        }
+
+        // a breakpoint at the paused position (e.g at the first statement) is hit before executing anything,
+        // unless execution is paused at that same breakpoint already
+        let position = self.get_position();
+
+        if self.breakpoints.contains(&position) && self.hit_breakpoint != Some(position) {
+            self.hit_breakpoint = Some(position);
+            return Ok(DebugEvent::Breakpoint(position.0, position.1));
+        }
+
+        self.hit_breakpoint = None;
+
+        loop {
+            let (node_id, ip) = self.get_position();
+            let is_trap = self.intr.peek_next_inst() == Some(&CfgInstruction::Trap);
+
+            let completed = match self.intr.exec_next() {
+                Ok(completed) => completed,
+                Err(err) => {
+                    // the interpreter can't continue after an exception,
+                    // unless it ran out of fuel
+                    if err != InterpreterException::OutOfFuel {
+                        self.finished = true;
+                    }
+
+                    return Err(err);
+                }
+            };
+
+            if completed {
+                self.finished = true;
+                return Ok(DebugEvent::Finished);
+            }
+
+            if is_trap {
+                return Ok(DebugEvent::Trap(node_id, ip));
+            }
+
+            let (node_id, ip) = self.get_position();
+
+            if self.breakpoints.contains(&(node_id, ip)) {
+                self.hit_breakpoint = Some((node_id, ip));
+                return Ok(DebugEvent::Breakpoint(node_id, ip));
+            }
+
+            if should_stop(self) {
+                return Ok(DebugEvent::Step);
+            }
+        }
+    }
+
+    fn at_stmt_start(&self) -> bool {
+        let (node_id, ip) = self.get_position();
+
+        self.intr.get_cfg().source_map.is_stmt_start(node_id, ip)
+    }
+
+    fn depth(&self) -> usize {
+        self.intr.call_stack.frames.len()
+    }
+}
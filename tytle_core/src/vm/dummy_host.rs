@@ -17,6 +17,16 @@ impl Host for DummyHost {
         self.append_log(msg);
     }
 
+    fn exec_label(&mut self, text: &str) {
+        let msg = format!("LABEL {}", text);
+        self.append_log(msg);
+    }
+
+    fn exec_set_font_size(&mut self, size: isize) {
+        let msg = format!("SETFONTSIZE {}", size);
+        self.append_log(msg);
+    }
+
     fn exec_trap(&mut self, node_id: usize, ip: usize) {
         let msg = format!("trapping at ({}, {})", node_id, ip);
         self.append_log(msg);
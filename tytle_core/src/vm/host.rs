@@ -5,5 +5,7 @@ pub trait Host {
     fn exec_direct(&mut self, direct: &Direction, count: isize);
     fn exec_trap(&mut self, node: usize, ip: usize);
     fn exec_print(&mut self, value: isize);
+    fn exec_label(&mut self, text: &str);
+    fn exec_set_font_size(&mut self, size: isize);
     fn compilation_error(&mut self, error: &str);
 }
@@ -1,19 +1,37 @@
 use crate::prelude::*;
 
-static MAX_STACK_DEPTH: usize = 10_000;
+pub static MAX_STACK_DEPTH: usize = 10_000;
+
+pub type InterpreterResult = Result<(), InterpreterException>;
 
 #[derive(Debug, PartialEq)]
-pub enum InterpreterException {
-    StackOverflow,
+pub enum RunStatus {
+    Completed,
+
+    // the instructions quota of `run_for` has been used up, execution can be resumed
+    Yielded,
 }
 
-pub type InterpreterResult = Result<(), InterpreterException>;
+// the execution state of a suspended `Interpreter`.
+// it doesn't borrow the CFG, environment or host, so it can be kept around between calls
+pub struct InterpreterState {
+    pub ip: usize,
+    pub node_id: CfgNodeId,
+    pub memory: Memory,
+    pub call_stack: CallStack,
+    pub trace: Option<Trace>,
+    pub fuel: Option<usize>,
+    pub insts_count: usize,
+}
 
 pub struct Interpreter<'env, 'cfg, 'host> {
     pub ip: usize,
     pub node_id: CfgNodeId,
     pub memory: Memory,
     pub call_stack: CallStack,
+    trace: Option<Trace>,
+    fuel: Option<usize>,
+    insts_count: usize,
     env: &'env Environment,
     cfg: &'cfg CfgObject,
     host: &'host mut Host,
@@ -26,14 +44,29 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
 
         let main_node_id = cfg.graph.get_entry_node_id();
 
+        Self::with_memory(cfg, env, host, Memory::new(), main_node_id)
+    }
+
+    // starts executing `main` code at `entry_node_id`, on top of an existing memory.
+    // globals already living in `memory` keep their values
+    pub fn with_memory(
+        cfg: &'cfg CfgObject,
+        env: &'env Environment,
+        host: &'host mut dyn Host,
+        memory: Memory,
+        entry_node_id: CfgNodeId,
+    ) -> Self {
         let mut intr = Self {
             ip: 0,
             env,
             host,
             cfg,
-            memory: Memory::new(),
+            memory,
             call_stack: CallStack::new(),
-            node_id: main_node_id,
+            trace: None,
+            fuel: None,
+            insts_count: 0,
+            node_id: entry_node_id,
         };
 
         intr.init_memory();
@@ -42,6 +75,94 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         intr
     }
 
+    pub fn into_memory(self) -> Memory {
+        self.memory
+    }
+
+    // continues executing from a state returned by `suspend`
+    pub fn resume(
+        cfg: &'cfg CfgObject,
+        env: &'env Environment,
+        host: &'host mut dyn Host,
+        state: InterpreterState,
+    ) -> Self {
+        Self {
+            ip: state.ip,
+            node_id: state.node_id,
+            memory: state.memory,
+            call_stack: state.call_stack,
+            trace: state.trace,
+            fuel: state.fuel,
+            insts_count: state.insts_count,
+            env,
+            host,
+            cfg,
+        }
+    }
+
+    pub fn suspend(self) -> InterpreterState {
+        InterpreterState {
+            ip: self.ip,
+            node_id: self.node_id,
+            memory: self.memory,
+            call_stack: self.call_stack,
+            trace: self.trace,
+            fuel: self.fuel,
+            insts_count: self.insts_count,
+        }
+    }
+
+    // limits the number of instructions left to execute.
+    // once the fuel is used up, execution fails with `InterpreterException::OutOfFuel`
+    pub fn set_fuel(&mut self, fuel: usize) {
+        self.fuel = Some(fuel);
+    }
+
+    pub fn get_fuel(&self) -> Option<usize> {
+        self.fuel
+    }
+
+    // the number of instructions executed so far
+    pub fn get_insts_count(&self) -> usize {
+        self.insts_count
+    }
+
+    // from now on, every executed instruction is recorded (see `Trace`)
+    pub fn enable_tracing(&mut self) {
+        self.enable_tracing_with_limit(DEFAULT_TRACE_LIMIT);
+    }
+
+    // same as `enable_tracing`, recording up to `limit` instructions
+    pub fn enable_tracing_with_limit(&mut self, limit: usize) {
+        if self.trace.is_none() {
+            self.trace = Some(Trace::with_limit(limit));
+        }
+    }
+
+    pub fn get_trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    pub fn take_trace(&mut self) -> Option<Trace> {
+        self.trace.take()
+    }
+
+    pub fn get_env(&self) -> &Environment {
+        self.env
+    }
+
+    pub fn get_cfg(&self) -> &CfgObject {
+        self.cfg
+    }
+
+    // returns the instruction `exec_next` is about to execute.
+    // `None` means that the current node has no more instructions
+    pub fn peek_next_inst(&self) -> Option<&CfgInstruction> {
+        let node = self.cfg.graph.get_node(self.node_id);
+
+        node.insts.get(self.ip)
+    }
+
     pub fn exec_code(&mut self) -> InterpreterResult {
         loop {
             let completed = self.exec_next()?;
@@ -53,6 +174,22 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         }
     }
 
+    // executes up to `n_insts` instructions and returns control to the caller.
+    // a `Yielded` execution continues on the next call
+    pub fn run_for(&mut self, n_insts: usize) -> Result<RunStatus, InterpreterException> {
+        let last_inst = self.insts_count + n_insts;
+
+        while self.insts_count < last_inst {
+            let completed = self.exec_next()?;
+
+            if completed {
+                return Ok(RunStatus::Completed);
+            }
+        }
+
+        Ok(RunStatus::Yielded)
+    }
+
     pub fn exec_next(&mut self) -> Result<bool, InterpreterException> {
         let node = self.cfg.graph.get_node(self.node_id);
 
@@ -60,7 +197,7 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
 
         if inst.is_none() {
             if node.has_outgoing_edges() {
-                self.choose_outgoing_edge();
+                self.choose_outgoing_edge()?;
                 return Ok(false);
             } else {
                 // we've completed program execution
@@ -71,9 +208,32 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         let inst = inst.unwrap();
         let mut is_call = false;
 
+        // we check the fuel before executing the instruction,
+        // so that execution can be resumed right here
+        if let Some(fuel) = self.fuel {
+            if fuel == 0 {
+                return Err(InterpreterException::OutOfFuel);
+            }
+
+            self.fuel = Some(fuel - 1);
+        }
+
+        self.insts_count += 1;
+
+        if let Some(ref mut trace) = self.trace {
+            // there are no frames left once the program has completed
+            if let Some(frame) = self.call_stack.frames.last() {
+                let depth = self.call_stack.frames.len();
+
+                trace.record(self.node_id, self.ip, inst, frame.ctx_proc, depth);
+            }
+        }
+
         match inst {
             CfgInstruction::Trap => self.exec_trap(),
-            CfgInstruction::Print => self.exec_print(),
+            CfgInstruction::Print => self.exec_print()?,
+            CfgInstruction::Label => self.exec_label()?,
+            CfgInstruction::SetFontSize => self.exec_set_font_size()?,
             CfgInstruction::EOC => {
                 // reached `EOC` (END-OF-CODE)
                 // unwinding the last stackframe
@@ -85,22 +245,30 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
                 is_call = true;
                 self.exec_call(*node_id)?;
             }
+            CfgInstruction::CallIndirect => {
+                is_call = true;
+                self.exec_call_indirect()?;
+            }
+            CfgInstruction::ProcRef(ref node_id) => self.exec_proc_ref(*node_id),
+            CfgInstruction::NewStruct(struct_id) => self.exec_new_struct(*struct_id)?,
+            CfgInstruction::LoadField(index) => self.exec_load_field(*index)?,
+            CfgInstruction::StoreField(index) => self.exec_store_field(*index)?,
             CfgInstruction::Command(ref cmd) => self.exec_cmd(cmd),
-            CfgInstruction::Direction(ref direct) => self.exec_direct(direct),
+            CfgInstruction::Direction(ref direct) => self.exec_direct(direct)?,
             CfgInstruction::Bool(v) => self.exec_bool(*v),
             CfgInstruction::Int(v) => self.exec_int(*v),
-            CfgInstruction::Return => self.exec_ret(),
-            CfgInstruction::Not => self.exec_not(),
+            CfgInstruction::Return => self.exec_ret()?,
+            CfgInstruction::Not => self.exec_not()?,
             CfgInstruction::Add | CfgInstruction::Mul | CfgInstruction::Div => {
-                self.exec_int_binary(inst.clone())
+                self.exec_int_binary(inst.clone())?
             }
             CfgInstruction::Or
             | CfgInstruction::And
             | CfgInstruction::GreaterThan
-            | CfgInstruction::LessThan => self.exec_bool_binary(inst.clone()),
-            CfgInstruction::Load(var_id) => self.exec_load(*var_id),
-            CfgInstruction::Store(var_id) => self.exec_store(*var_id),
-            CfgInstruction::Str(_) => unimplemented!(),
+            | CfgInstruction::LessThan => self.exec_bool_binary(inst.clone())?,
+            CfgInstruction::Load(var_id) => self.exec_load(*var_id)?,
+            CfgInstruction::Store(var_id) => self.exec_store(*var_id)?,
+            CfgInstruction::Str(v) => self.exec_str(v.clone()),
         };
 
         if is_call == false {
@@ -110,7 +278,7 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         Ok(false)
     }
 
-    fn exec_load(&mut self, var_id: SymbolId) {
+    fn exec_load(&mut self, var_id: SymbolId) -> InterpreterResult {
         let var = self.env.symbol_table.get_var_by_id(var_id);
         let index = var.index.unwrap();
 
@@ -118,40 +286,163 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
             // for global variables the rule is: `addr` <=> `global index`
             let addr = Address(index);
 
-            let value = self.memory.get_global(addr).unwrap();
+            let item = self.memory.get_global(addr).unwrap().to_item();
 
-            match value {
-                MemoryValue::Int(v) => self.exec_int(*v),
-                MemoryValue::Bool(v) => self.exec_bool(*v),
-                MemoryValue::Str(_) => unimplemented!(),
-            };
+            self.call_stack.push_item(item);
         } else {
-            let item = self.call_stack.load_item(index);
+            let frame_index = self.local_frame(var)?;
+
+            let item = self.call_stack.load_frame_item(frame_index, index);
             let item_clone = item.clone();
             self.call_stack.push_item(item_clone);
         }
+
+        Ok(())
     }
 
-    fn exec_store(&mut self, var_id: SymbolId) {
+    fn exec_store(&mut self, var_id: SymbolId) -> InterpreterResult {
         let var = self.env.symbol_table.get_var_by_id(var_id);
         let index = var.index.unwrap();
 
-        let stack_value = self.call_stack.pop_item();
+        let stack_value = self.pop_item("a value")?;
 
         if var.global {
-            let mem_value = match stack_value {
-                CallStackItem::Int(v) => MemoryValue::Int(v),
-                CallStackItem::Bool(v) => MemoryValue::Bool(v),
-                _ => unimplemented!(),
+            let mem_value = match MemoryValue::from_item(stack_value) {
+                Some(mem_value) => mem_value,
+                None => return Err(self.invalid_stack_item("a value")),
             };
 
             self.memory.set_global(Address(index), mem_value);
         } else {
-            self.call_stack.store_item(index, stack_value);
+            let frame_index = self.local_frame(var)?;
+
+            self.call_stack
+                .store_frame_item(frame_index, index, stack_value);
+        }
+
+        Ok(())
+    }
+
+    fn exec_new_struct(&mut self, struct_id: SymbolId) -> InterpreterResult {
+        let struct_type = self.env.symbol_table.get_struct_by_id(struct_id);
+        let mut fields = Vec::with_capacity(struct_type.fields_names.len());
+
+        // the last field is on top of the stack
+        for _ in 0..struct_type.fields_names.len() {
+            let item = self.pop_item("a field value")?;
+
+            match MemoryValue::from_item(item) {
+                Some(value) => fields.push(value),
+                None => return Err(self.invalid_stack_item("a field value")),
+            }
+        }
+
+        fields.reverse();
+
+        let struct_ref = self.memory.alloc_struct(StructValue { struct_id, fields });
+
+        self.call_stack.push_item(CallStackItem::Struct(struct_ref));
+
+        Ok(())
+    }
+
+    fn exec_load_field(&mut self, index: usize) -> InterpreterResult {
+        let struct_ref = self.pop_struct()?;
+
+        let item = match self.memory.get_struct(struct_ref) {
+            Some(struct_value) => struct_value.fields[index].to_item(),
+            None => return Err(self.invalid_stack_item("a record")),
+        };
+
+        self.call_stack.push_item(item);
+
+        Ok(())
+    }
+
+    fn exec_store_field(&mut self, index: usize) -> InterpreterResult {
+        let item = self.pop_item("a value")?;
+        let struct_ref = self.pop_struct()?;
+
+        let value = match MemoryValue::from_item(item) {
+            Some(value) => value,
+            None => return Err(self.invalid_stack_item("a value")),
+        };
+
+        match self.memory.get_struct_mut(struct_ref) {
+            Some(struct_value) => struct_value.fields[index] = value,
+            None => return Err(self.invalid_stack_item("a record")),
+        }
+
+        Ok(())
+    }
+
+    // the frame holding local variable `var`.
+    // it's the current frame, unless `var` belongs to an enclosing procedure
+    fn local_frame(&self, var: &Variable) -> Result<usize, InterpreterException> {
+        let proc_id = var.proc_id.unwrap();
+
+        match self.call_stack.lookup_frame(proc_id) {
+            Some(frame_index) => Ok(frame_index),
+            None => Err(self.invalid_stack_item("an enclosing procedure frame")),
         }
     }
 
     fn exec_call(&mut self, callee_id: CfgNodeId) -> InterpreterResult {
+        let proc_id = self.cfg.jmp_table[&callee_id];
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        // a nested procedure is linked to the activation of its enclosing procedure.
+        // the caller is lexically enclosed by it, so it's found following the caller static links
+        let static_link = match proc.parent_id {
+            Some(parent_id) => self.call_stack.lookup_frame(parent_id),
+            None => None,
+        };
+
+        self.enter_proc(callee_id, static_link)
+    }
+
+    fn exec_call_indirect(&mut self) -> InterpreterResult {
+        let (callee_id, frame_ref) = match self.pop_item("a procedure")? {
+            CallStackItem::Proc(node_id, frame_ref) => (node_id, frame_ref),
+            _ => return Err(self.invalid_stack_item("a procedure")),
+        };
+
+        // the static link of a nested procedure value is the frame captured by `exec_proc_ref`
+        let static_link = match frame_ref {
+            Some(frame_ref) => {
+                if !self.call_stack.is_open(&frame_ref) {
+                    return Err(InterpreterException::ProcOutOfScope(self.error_site()));
+                }
+
+                Some(frame_ref.index)
+            }
+            None => None,
+        };
+
+        self.enter_proc(callee_id, static_link)
+    }
+
+    fn exec_proc_ref(&mut self, node_id: CfgNodeId) {
+        let proc_id = self.cfg.jmp_table[&node_id];
+        let proc = self.env.symbol_table.get_proc_by_id(proc_id);
+
+        let frame_ref = match proc.parent_id {
+            Some(parent_id) => self
+                .call_stack
+                .lookup_frame(parent_id)
+                .map(|index| self.call_stack.frame_ref(index)),
+            None => None,
+        };
+
+        self.call_stack
+            .push_item(CallStackItem::Proc(node_id, frame_ref));
+    }
+
+    fn enter_proc(
+        &mut self,
+        callee_id: CfgNodeId,
+        static_link: Option<usize>,
+    ) -> InterpreterResult {
         let old_frame = self.call_stack.current_frame_mut();
 
         let proc_id = self.cfg.jmp_table[&callee_id];
@@ -180,7 +471,7 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         }
 
         // callee allocates a new callstack frame
-        let new_frame = self.call_stack.open_stackframe(proc_id);
+        let new_frame = self.call_stack.open_stackframe(proc_id, static_link);
 
         for param in params.iter().rev() {
             new_frame.push(param.clone());
@@ -197,20 +488,22 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         Ok(())
     }
 
-    fn exec_ret(&mut self) {
+    fn exec_ret(&mut self) -> InterpreterResult {
         let current_frame = self.call_stack.current_frame();
         let current_proc = self.env.symbol_table.get_proc_by_id(current_frame.ctx_proc);
 
         let ret_item = match current_proc.return_type {
             ExpressionType::Unit => None,
-            _ => Some(self.call_stack.pop_item()),
+            _ => Some(self.pop_item("a return value")?),
         };
 
         // unwinding the procedure callstack frame
         self.call_stack.close_stackframe();
 
-        let ret_addr = self.call_stack.pop_item();
-        let (ret_node_id, ret_ip) = ret_addr.to_addr();
+        let (ret_node_id, ret_ip) = match self.call_stack.try_pop_item() {
+            Some(CallStackItem::Addr(node_id, ip)) => (node_id, ip),
+            _ => return Err(self.invalid_stack_item("a return address")),
+        };
 
         // pointing one instruction after the `call`
         self.node_id = ret_node_id;
@@ -220,64 +513,181 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
             let ret_value = ret_item.unwrap();
             self.call_stack.push_item(ret_value);
         }
+
+        Ok(())
     }
 
     fn exec_trap(&mut self) {
         self.host.exec_trap(self.node_id, self.ip);
     }
 
-    fn exec_print(&mut self) {
-        let value = self.call_stack.pop_item().to_int();
+    fn exec_print(&mut self) -> InterpreterResult {
+        let value = self.pop_int()?;
         self.host.exec_print(value);
+
+        Ok(())
+    }
+
+    fn exec_label(&mut self) -> InterpreterResult {
+        let value = self.pop_str()?;
+        self.host.exec_label(&value);
+
+        Ok(())
+    }
+
+    fn exec_set_font_size(&mut self) -> InterpreterResult {
+        let size = self.pop_int()?;
+        self.host.exec_set_font_size(size);
+
+        Ok(())
     }
 
     fn exec_cmd(&mut self, cmd: &Command) {
         self.host.exec_cmd(cmd);
     }
 
-    fn exec_direct(&mut self, direct: &Direction) {
-        let count = self.call_stack.pop_item().to_int();
+    fn exec_direct(&mut self, direct: &Direction) -> InterpreterResult {
+        let count = self.pop_int()?;
+        self.host.exec_direct(direct, count);
+
+        Ok(())
+    }
+
+    fn exec_int_binary(&mut self, op: CfgInstruction) -> InterpreterResult {
+        let a = self.pop_int()?;
+        let b = self.pop_int()?;
+
+        let res = match op {
+            CfgInstruction::Add => b.checked_add(a),
+            CfgInstruction::Mul => b.checked_mul(a),
+            CfgInstruction::Div => {
+                if a == 0 {
+                    return Err(InterpreterException::DivisionByZero(self.error_site()));
+                }
+
+                // `isize::MIN / -1` overflows
+                b.checked_div(a)
+            }
+            _ => panic!("invalid binary-op: `{:?}`", op),
+        };
 
-        self.host.exec_direct(direct, count)
+        match res {
+            Some(v) => {
+                self.exec_int(v);
+                Ok(())
+            }
+            None => Err(InterpreterException::IntegerOverflow(self.error_site())),
+        }
     }
 
-    fn exec_int_binary(&mut self, op: CfgInstruction) {
-        let a = self.call_stack.pop_item();
-        let b = self.call_stack.pop_item();
+    fn exec_not(&mut self) -> InterpreterResult {
+        let a = self.pop_bool()?;
+
+        self.exec_bool(!a);
 
-        assert!(a.is_int() && b.is_int());
+        Ok(())
+    }
 
-        let a = a.to_int();
-        let b = b.to_int();
+    fn exec_bool_binary(&mut self, op: CfgInstruction) -> InterpreterResult {
+        let v = match op {
+            CfgInstruction::And | CfgInstruction::Or => {
+                let a = self.pop_bool()?;
+                let b = self.pop_bool()?;
 
-        match op {
-            CfgInstruction::Add => self.exec_int(a + b),
-            CfgInstruction::Mul => self.exec_int(a * b),
-            CfgInstruction::Div => self.exec_int(b / a),
+                if op == CfgInstruction::And {
+                    a && b
+                } else {
+                    a || b
+                }
+            }
+            CfgInstruction::GreaterThan | CfgInstruction::LessThan => {
+                let a = self.pop_int()?;
+                let b = self.pop_int()?;
+
+                if op == CfgInstruction::GreaterThan {
+                    b > a
+                } else {
+                    b < a
+                }
+            }
             _ => panic!("invalid binary-op: `{:?}`", op),
+        };
+
+        self.exec_bool(v);
+
+        Ok(())
+    }
+
+    fn pop_item(&mut self, expected: &str) -> Result<CallStackItem, InterpreterException> {
+        match self.call_stack.try_pop_item() {
+            Some(item) => Ok(item),
+            None => Err(self.invalid_stack_item(expected)),
         }
     }
 
-    fn exec_not(&mut self) {
-        let a = self.call_stack.pop_item();
+    fn pop_int(&mut self) -> Result<isize, InterpreterException> {
+        match self.pop_item("an integer")? {
+            CallStackItem::Int(v) => Ok(v),
+            _ => Err(self.invalid_stack_item("an integer")),
+        }
+    }
 
-        assert!(a.is_bool());
+    fn pop_bool(&mut self) -> Result<bool, InterpreterException> {
+        match self.pop_item("a boolean")? {
+            CallStackItem::Bool(v) => Ok(v),
+            _ => Err(self.invalid_stack_item("a boolean")),
+        }
+    }
 
-        let b = !a.to_bool();
+    fn pop_str(&mut self) -> Result<String, InterpreterException> {
+        match self.pop_item("a string")? {
+            CallStackItem::Str(v) => Ok(v),
+            _ => Err(self.invalid_stack_item("a string")),
+        }
+    }
 
-        self.exec_bool(b);
+    fn pop_struct(&mut self) -> Result<usize, InterpreterException> {
+        match self.pop_item("a record")? {
+            CallStackItem::Struct(struct_ref) => Ok(struct_ref),
+            _ => Err(self.invalid_stack_item("a record")),
+        }
     }
 
-    fn exec_bool_binary(&mut self, op: CfgInstruction) {
-        let a = self.call_stack.pop_item();
-        let b = self.call_stack.pop_item();
+    fn peek_bool(&self) -> Result<bool, InterpreterException> {
+        let item = self
+            .call_stack
+            .frames
+            .last()
+            .and_then(|frame| frame.try_peek());
 
-        match op {
-            CfgInstruction::And => self.exec_bool(a.to_bool() && b.to_bool()),
-            CfgInstruction::Or => self.exec_bool(a.to_bool() || b.to_bool()),
-            CfgInstruction::GreaterThan => self.exec_bool(b.to_int() > a.to_int()),
-            CfgInstruction::LessThan => self.exec_bool(b.to_int() < a.to_int()),
-            _ => panic!("invalid binary-op: `{:?}`", op),
+        match item {
+            Some(CallStackItem::Bool(v)) => Ok(*v),
+            _ => Err(self.invalid_stack_item("a boolean")),
+        }
+    }
+
+    fn invalid_stack_item(&self, expected: &str) -> InterpreterException {
+        InterpreterException::InvalidStackItem {
+            expected: expected.to_string(),
+            site: self.error_site(),
+        }
+    }
+
+    // the procedure and source location of the instruction being executed
+    fn error_site(&self) -> ErrorSite {
+        let proc_name = match self.call_stack.frames.last() {
+            Some(frame) => {
+                let proc = self.env.symbol_table.get_proc_by_id(frame.ctx_proc);
+                proc.name.clone()
+            }
+            None => "__main__".to_string(),
+        };
+
+        let location = self.cfg.source_map.get_location(self.node_id, self.ip);
+
+        ErrorSite {
+            proc_name,
+            location,
         }
     }
 
@@ -289,6 +699,10 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
         self.call_stack.push_item(CallStackItem::Int(v));
     }
 
+    fn exec_str(&mut self, v: String) {
+        self.call_stack.push_item(CallStackItem::Str(v));
+    }
+
     fn init_memory(&mut self) {
         self.memory.init_globals(self.env);
     }
@@ -298,44 +712,53 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
 
         let main_proc_id = self.cfg.jmp_table[&self.node_id];
 
-        self.call_stack.open_stackframe(main_proc_id);
+        self.call_stack.open_stackframe(main_proc_id, None);
 
         // allocate `__main__` locals
         let main_proc = self.env.symbol_table.get_proc_by_name("__main__");
         self.init_proc_locals(main_proc.id);
     }
 
-    fn choose_outgoing_edge(&mut self) {
+    fn choose_outgoing_edge(&mut self) -> InterpreterResult {
         let node = self.cfg.graph.get_node(self.node_id);
 
-        assert!(node.outgoing.len() < 3);
+        // a node has either a single `Always` edge, or a `WhenTrue` edge along with a `Fallback` edge
+        if node.outgoing.len() > 2 {
+            return Err(self.malformed_cfg());
+        }
 
         for edge in node.outgoing.iter() {
             match edge.jmp_type {
                 CfgJumpType::Always => {
                     self.jmp_edge(edge, false);
-                    return;
+                    return Ok(());
                 }
                 CfgJumpType::WhenTrue => {
-                    let v = self.call_stack.peek_item().to_bool();
-
-                    if v {
+                    if self.peek_bool()? {
                         self.jmp_edge(edge, true);
-                        return;
+                        return Ok(());
                     }
                 }
                 CfgJumpType::Fallback => {
-                    let v = self.call_stack.peek_item().to_bool();
-
-                    if !v {
-                        self.jmp_edge(edge, false);
-                        return;
+                    if !self.peek_bool()? {
+                        // the condition is consumed by both branches, otherwise a loop
+                        // (e.g a tail-recursive procedure) would keep piling conditions on the stack
+                        self.jmp_edge(edge, true);
+                        return Ok(());
                     }
                 }
             }
         }
 
-        unreachable!()
+        // e.g a conditional node missing its `Fallback` edge
+        Err(self.malformed_cfg())
+    }
+
+    fn malformed_cfg(&self) -> InterpreterException {
+        InterpreterException::MalformedCfg {
+            node_id: self.node_id,
+            site: self.error_site(),
+        }
     }
 
     fn jmp_edge(&mut self, edge: &CfgEdge, pop_stack: bool) {
@@ -370,7 +793,11 @@ impl<'env, 'cfg, 'host> Interpreter<'env, 'cfg, 'host> {
             match var_type {
                 ExpressionType::Int => self.exec_int(-1),
                 ExpressionType::Bool => self.exec_bool(false),
-                ExpressionType::Str => unimplemented!(),
+                ExpressionType::Str => self.exec_str("".to_string()),
+                // there is no default procedure, calling an unassigned local is an error
+                ExpressionType::Proc(..) => self.exec_bool(false),
+                // there is no default record, reading a field of an unassigned local is an error
+                ExpressionType::Struct(_) => self.exec_bool(false),
                 ExpressionType::Unit => panic!("proc can't have a local of type `Unit`"),
             }
         }
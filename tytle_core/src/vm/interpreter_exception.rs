@@ -0,0 +1,132 @@
+use crate::ir::CfgNodeId;
+use crate::lexer::Location;
+
+// where a runtime error has occurred
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorSite {
+    pub proc_name: String,
+
+    // `None` when the CFG has no source map (e.g a CFG built out of a macro-generated AST)
+    pub location: Option<Location>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InterpreterException {
+    StackOverflow,
+
+    // the fuel budget (see `Interpreter::set_fuel`) has been used up.
+    // execution can be resumed after adding more fuel
+    OutOfFuel,
+
+    DivisionByZero(ErrorSite),
+    IntegerOverflow(ErrorSite),
+
+    // the call-stack doesn't hold the expected item (`expected` is the expected item kind)
+    InvalidStackItem { expected: String, site: ErrorSite },
+
+    // a nested procedure value has been called after its enclosing procedure has returned
+    ProcOutOfScope(ErrorSite),
+
+    // CFG node `node_id` has no outgoing edge to follow (i.e the CFG is a malformed one)
+    MalformedCfg { node_id: CfgNodeId, site: ErrorSite },
+}
+
+impl ToString for ErrorSite {
+    fn to_string(&self) -> String {
+        match self.location {
+            Some(loc) => format!(
+                "procedure: `{}`, line: {}, column: {}",
+                self.proc_name,
+                loc.line(),
+                loc.column()
+            ),
+            None => format!("procedure: `{}`", self.proc_name),
+        }
+    }
+}
+
+impl ToString for InterpreterException {
+    fn to_string(&self) -> String {
+        match self {
+            InterpreterException::StackOverflow => "Stack overflow".to_string(),
+            InterpreterException::OutOfFuel => {
+                "Out of fuel (is there a never-ending loop?)".to_string()
+            }
+            InterpreterException::DivisionByZero(site) => {
+                format!("Division by zero ({})", site.to_string())
+            }
+            InterpreterException::IntegerOverflow(site) => {
+                format!("Integer overflow ({})", site.to_string())
+            }
+            InterpreterException::InvalidStackItem { expected, site } => format!(
+                "Invalid stack item, expected {} ({})",
+                expected,
+                site.to_string()
+            ),
+            InterpreterException::ProcOutOfScope(site) => format!(
+                "Procedure called after its enclosing procedure has returned ({})",
+                site.to_string()
+            ),
+            InterpreterException::MalformedCfg { node_id, site } => format!(
+                "Malformed CFG, node {} has no edge to follow ({})",
+                node_id,
+                site.to_string()
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(location: Option<Location>) -> ErrorSite {
+        ErrorSite {
+            proc_name: "MYPROC".to_string(),
+            location,
+        }
+    }
+
+    #[test]
+    fn interpreter_exception_division_by_zero() {
+        let err = InterpreterException::DivisionByZero(site(Some(Location(3, 5))));
+
+        assert_eq!(
+            "Division by zero (procedure: `MYPROC`, line: 3, column: 5)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn interpreter_exception_integer_overflow_without_location() {
+        let err = InterpreterException::IntegerOverflow(site(None));
+
+        assert_eq!("Integer overflow (procedure: `MYPROC`)", err.to_string());
+    }
+
+    #[test]
+    fn interpreter_exception_invalid_stack_item() {
+        let err = InterpreterException::InvalidStackItem {
+            expected: "an integer".to_string(),
+            site: site(Some(Location(1, 1))),
+        };
+
+        assert_eq!(
+            "Invalid stack item, expected an integer (procedure: `MYPROC`, line: 1, column: 1)",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn interpreter_exception_malformed_cfg() {
+        let err = InterpreterException::MalformedCfg {
+            node_id: 3,
+            site: site(None),
+        };
+
+        assert_eq!(
+            "Malformed CFG, node 3 has no edge to follow (procedure: `MYPROC`)",
+            err.to_string()
+        );
+    }
+}
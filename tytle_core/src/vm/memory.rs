@@ -1,6 +1,6 @@
 use crate::ast::expression::ExpressionType;
 use crate::ast::semantic::Environment;
-use crate::vm::{Address, MemoryValue, Pen, Turtle};
+use crate::vm::{Address, MemoryValue, Pen, StructValue, Turtle};
 
 use std::collections::HashMap;
 
@@ -8,12 +8,16 @@ pub struct Memory {
     pub turtle: Turtle,
     pub pen: Pen,
     pub cells: HashMap<Address, MemoryValue>,
+
+    // the records heap. records are never freed
+    pub structs: Vec<StructValue>,
 }
 
 impl Memory {
     pub fn new() -> Self {
         Self {
             cells: HashMap::new(),
+            structs: Vec::new(),
             turtle: Turtle::new(),
             pen: Pen::new(),
         }
@@ -25,12 +29,22 @@ impl Memory {
             let var = env.symbol_table.get_var_by_id(var_id);
 
             let addr = Address(var.index.unwrap());
+
+            if self.get_global(addr).is_some() {
+                // the global has been already initialized (when resuming with an existing memory)
+                return;
+            }
+
             let var_type = var.var_type.as_ref().unwrap();
 
             let value = match var_type {
                 ExpressionType::Int => MemoryValue::Int(0),
                 ExpressionType::Bool => MemoryValue::Bool(false),
                 ExpressionType::Str => MemoryValue::Str("".to_string()),
+                // there is no default procedure, calling an unassigned global is an error
+                ExpressionType::Proc(..) => MemoryValue::Bool(false),
+                // there is no default record, reading a field of an unassigned global is an error
+                ExpressionType::Struct(_) => MemoryValue::Bool(false),
                 ExpressionType::Unit => panic!("variable can't be of type `Unit`"),
             };
 
@@ -45,4 +59,19 @@ impl Memory {
     pub fn set_global(&mut self, address: Address, value: MemoryValue) {
         self.cells.insert(address, value);
     }
+
+    // returns the reference of the new record
+    pub fn alloc_struct(&mut self, struct_value: StructValue) -> usize {
+        self.structs.push(struct_value);
+
+        self.structs.len() - 1
+    }
+
+    pub fn get_struct(&self, struct_ref: usize) -> Option<&StructValue> {
+        self.structs.get(struct_ref)
+    }
+
+    pub fn get_struct_mut(&mut self, struct_ref: usize) -> Option<&mut StructValue> {
+        self.structs.get_mut(struct_ref)
+    }
 }
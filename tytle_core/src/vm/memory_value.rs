@@ -1,6 +1,47 @@
+use crate::ast::semantic::SymbolId;
+use crate::ir::CfgNodeId;
+use crate::vm::{CallStackItem, FrameRef};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum MemoryValue {
     Int(isize),
     Bool(bool),
     Str(String),
+    Proc(CfgNodeId, Option<FrameRef>),
+
+    // a reference to a record living in the `Memory` heap (see `Memory::get_struct`)
+    Struct(usize),
+}
+
+impl MemoryValue {
+    // a return address isn't a value, so it has no `MemoryValue`
+    pub fn from_item(item: CallStackItem) -> Option<MemoryValue> {
+        let value = match item {
+            CallStackItem::Int(v) => MemoryValue::Int(v),
+            CallStackItem::Bool(v) => MemoryValue::Bool(v),
+            CallStackItem::Str(v) => MemoryValue::Str(v),
+            CallStackItem::Proc(node_id, frame_ref) => MemoryValue::Proc(node_id, frame_ref),
+            CallStackItem::Struct(struct_ref) => MemoryValue::Struct(struct_ref),
+            CallStackItem::Addr(..) => return None,
+        };
+
+        Some(value)
+    }
+
+    pub fn to_item(&self) -> CallStackItem {
+        match *self {
+            MemoryValue::Int(v) => CallStackItem::Int(v),
+            MemoryValue::Bool(v) => CallStackItem::Bool(v),
+            MemoryValue::Str(ref v) => CallStackItem::Str(v.clone()),
+            MemoryValue::Proc(node_id, frame_ref) => CallStackItem::Proc(node_id, frame_ref),
+            MemoryValue::Struct(struct_ref) => CallStackItem::Struct(struct_ref),
+        }
+    }
+}
+
+// a record of a `STRUCT` type (the fields are ordered as declared)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructValue {
+    pub struct_id: SymbolId,
+    pub fields: Vec<MemoryValue>,
 }
@@ -1,19 +1,29 @@
 mod address;
 mod call_stack;
+mod debugger;
 mod dummy_host;
 mod host;
 mod interpreter;
+mod interpreter_exception;
+mod json;
 mod memory;
 mod memory_value;
 mod pen;
+mod profile;
+mod trace;
 mod turtle;
 
 pub use address::Address;
 pub use call_stack::*;
+pub use debugger::*;
 pub use dummy_host::DummyHost;
 pub use host::Host;
 pub use interpreter::*;
+pub use interpreter_exception::*;
+pub use json::json_str;
 pub use memory::Memory;
-pub use memory_value::MemoryValue;
+pub use memory_value::{MemoryValue, StructValue};
 pub use pen::{Pen, PenState};
+pub use profile::*;
+pub use trace::*;
 pub use turtle::Turtle;
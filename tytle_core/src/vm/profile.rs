@@ -0,0 +1,242 @@
+use crate::ast::semantic::{Environment, SymbolId};
+use crate::ir::{CfgNodeId, CfgObject};
+use crate::vm::{json_str, Trace};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcProfile {
+    pub name: String,
+    pub calls: usize,
+
+    // instructions executed by the procedure itself
+    pub self_insts: usize,
+
+    // instructions executed by the procedure and by the procedures it calls
+    pub total_insts: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeProfile {
+    pub node_id: CfgNodeId,
+    pub proc_name: String,
+    pub visits: usize,
+
+    // instructions executed by the node itself
+    pub self_insts: usize,
+
+    // instructions executed by the node and by the procedures it calls
+    pub total_insts: usize,
+}
+
+// an execution profile computed out of a `Trace`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub total_insts: usize,
+
+    // sorted by `self_insts` (the hottest procedure first)
+    pub procs: Vec<ProcProfile>,
+
+    // sorted by `node_id`
+    pub nodes: Vec<NodeProfile>,
+
+    // `(call-stack of procedure names, instructions count)` sorted by the call-stack
+    pub stacks: Vec<(Vec<String>, usize)>,
+}
+
+#[derive(Default)]
+struct Counters {
+    calls: usize,
+    visits: usize,
+    self_insts: usize,
+    total_insts: usize,
+}
+
+impl Profile {
+    pub fn new(trace: &Trace, cfg: &CfgObject, env: &Environment) -> Self {
+        let mut procs: HashMap<SymbolId, Counters> = HashMap::new();
+        let mut nodes: HashMap<CfgNodeId, (SymbolId, Counters)> = HashMap::new();
+
+        // the number of frames each procedure / node is active in
+        let mut active_procs: HashMap<SymbolId, usize> = HashMap::new();
+        let mut active_nodes: HashMap<CfgNodeId, usize> = HashMap::new();
+
+        // each distinct call-stack is interned as a path in a tree of procedures.
+        // `stacks[i] = (parent index, proc id)`
+        let mut stacks: Vec<(Option<usize>, SymbolId)> = Vec::new();
+        let mut stacks_index: HashMap<(Option<usize>, SymbolId), usize> = HashMap::new();
+        let mut stacks_insts: HashMap<usize, usize> = HashMap::new();
+
+        // `(proc id, node id, stack index)` per call-stack frame
+        let mut frames: Vec<(SymbolId, CfgNodeId, usize)> = Vec::new();
+
+        for entry in trace.entries.iter() {
+            // the frame at `entry.depth` is replaced with the current position
+            while !frames.is_empty() && frames.len() >= entry.depth {
+                let (proc_id, node_id, _) = frames.pop().unwrap();
+
+                Self::deactivate(&mut active_procs, proc_id);
+                Self::deactivate(&mut active_nodes, node_id);
+            }
+
+            let parent = frames.last().map(|frame| frame.2);
+            let next_index = stacks.len();
+            let stack_index = *stacks_index
+                .entry((parent, entry.proc_id))
+                .or_insert(next_index);
+
+            if stack_index == next_index {
+                stacks.push((parent, entry.proc_id));
+            }
+
+            frames.push((entry.proc_id, entry.node_id, stack_index));
+            *active_procs.entry(entry.proc_id).or_insert(0) += 1;
+            *active_nodes.entry(entry.node_id).or_insert(0) += 1;
+
+            let proc = procs.entry(entry.proc_id).or_default();
+            proc.self_insts += 1;
+
+            if entry.ip == 0 && cfg.jmp_table.contains_key(&entry.node_id) {
+                proc.calls += 1;
+            }
+
+            let (_, node) = nodes
+                .entry(entry.node_id)
+                .or_insert_with(|| (entry.proc_id, Counters::default()));
+            node.self_insts += 1;
+
+            if entry.ip == 0 {
+                node.visits += 1;
+            }
+
+            for proc_id in active_procs.keys() {
+                procs.entry(*proc_id).or_default().total_insts += 1;
+            }
+
+            for node_id in active_nodes.keys() {
+                let (_, node) = nodes.get_mut(node_id).unwrap();
+                node.total_insts += 1;
+            }
+
+            *stacks_insts.entry(stack_index).or_insert(0) += 1;
+        }
+
+        let proc_name = |proc_id: &SymbolId| env.symbol_table.get_proc_by_id(*proc_id).name.clone();
+
+        let mut procs: Vec<ProcProfile> = procs
+            .iter()
+            .map(|(proc_id, counters)| ProcProfile {
+                name: proc_name(proc_id),
+                calls: counters.calls,
+                self_insts: counters.self_insts,
+                total_insts: counters.total_insts,
+            })
+            .collect();
+
+        procs.sort_by(|a, b| b.self_insts.cmp(&a.self_insts).then(a.name.cmp(&b.name)));
+
+        let mut nodes: Vec<NodeProfile> = nodes
+            .iter()
+            .map(|(node_id, (proc_id, counters))| NodeProfile {
+                node_id: *node_id,
+                proc_name: proc_name(proc_id),
+                visits: counters.visits,
+                self_insts: counters.self_insts,
+                total_insts: counters.total_insts,
+            })
+            .collect();
+
+        nodes.sort_by_key(|node| node.node_id);
+
+        let mut folded_stacks: Vec<(Vec<String>, usize)> = stacks_insts
+            .iter()
+            .map(|(stack_index, insts)| {
+                let mut stack = Vec::new();
+                let mut index = Some(*stack_index);
+
+                while let Some(i) = index {
+                    let (parent, proc_id) = stacks[i];
+
+                    stack.push(proc_name(&proc_id));
+                    index = parent;
+                }
+
+                stack.reverse();
+
+                (stack, *insts)
+            })
+            .collect();
+
+        folded_stacks.sort();
+
+        Self {
+            total_insts: trace.len(),
+            procs,
+            nodes,
+            stacks: folded_stacks,
+        }
+    }
+
+    pub fn get_proc(&self, name: &str) -> Option<&ProcProfile> {
+        self.procs.iter().find(|proc| proc.name == name)
+    }
+
+    pub fn get_node(&self, node_id: CfgNodeId) -> Option<&NodeProfile> {
+        self.nodes.iter().find(|node| node.node_id == node_id)
+    }
+
+    pub fn to_json(&self) -> String {
+        let procs: Vec<String> = self
+            .procs
+            .iter()
+            .map(|proc| {
+                format!(
+                    r#"{{"name": {}, "calls": {}, "self_insts": {}, "total_insts": {}}}"#,
+                    json_str(&proc.name),
+                    proc.calls,
+                    proc.self_insts,
+                    proc.total_insts
+                )
+            })
+            .collect();
+
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                format!(
+                    r#"{{"node_id": {}, "proc": {}, "visits": {}, "self_insts": {}, "total_insts": {}}}"#,
+                    node.node_id,
+                    json_str(&node.proc_name),
+                    node.visits,
+                    node.self_insts,
+                    node.total_insts
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{"total_insts": {}, "procs": [{}], "nodes": [{}]}}"#,
+            self.total_insts,
+            procs.join(", "),
+            nodes.join(", ")
+        )
+    }
+
+    // the "folded stacks" format consumed by flamegraph tools.
+    // one line per call-stack: `__main__;TREE;TREE 120`
+    pub fn to_folded(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|(stack, insts)| format!("{} {}\n", stack.join(";"), insts))
+            .collect()
+    }
+
+    fn deactivate<K: std::hash::Hash + Eq>(active: &mut HashMap<K, usize>, key: K) {
+        let count = active.get_mut(&key).unwrap();
+        *count -= 1;
+
+        if *count == 0 {
+            active.remove(&key);
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::ast::semantic::SymbolId;
+use crate::ir::{CfgInstruction, CfgNodeId};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEntry {
+    pub node_id: CfgNodeId,
+    pub ip: usize,
+    pub inst: CfgInstruction,
+
+    // the procedure owning the current call-stack frame
+    pub proc_id: SymbolId,
+
+    // the number of call-stack frames (`1` when executing `main`)
+    pub depth: usize,
+
+    // the number of instructions executed so far (including this one)
+    pub count: usize,
+}
+
+// the maximum number of entries recorded by `Trace::new`
+// (so that tracing a long-running program doesn't grow memory without limit)
+pub const DEFAULT_TRACE_LIMIT: usize = 1_000_000;
+
+// records every instruction executed by an `Interpreter` with tracing enabled.
+// once `limit` entries are recorded, the later instructions are only counted (see `is_truncated`)
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub entries: Vec<TraceEntry>,
+    limit: usize,
+
+    // the number of executed instructions which haven't been recorded
+    dropped: usize,
+}
+
+impl Trace {
+    pub fn new() -> Self {
+        Self::with_limit(DEFAULT_TRACE_LIMIT)
+    }
+
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            limit,
+            dropped: 0,
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        node_id: CfgNodeId,
+        ip: usize,
+        inst: &CfgInstruction,
+        proc_id: SymbolId,
+        depth: usize,
+    ) {
+        if self.entries.len() >= self.limit {
+            self.dropped += 1;
+            return;
+        }
+
+        let entry = TraceEntry {
+            node_id,
+            ip,
+            inst: inst.clone(),
+            proc_id,
+            depth,
+            count: self.entries.len() + 1,
+        };
+
+        self.entries.push(entry);
+    }
+
+    // whether some executed instructions haven't been recorded (because of the entries limit)
+    pub fn is_truncated(&self) -> bool {
+        self.dropped > 0
+    }
+
+    pub fn get_dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::compiler::*;
+
+macro_rules! assert_warnings {
+    ($code: expr, $config: expr, $expected: expr) => {{
+        let (res, warnings) = compile_with_lints($code, &$config);
+        assert!(res.is_ok());
+
+        let actual: Vec<String> = warnings.iter().map(|warning| warning.to_string()).collect();
+        let expected: Vec<&str> = $expected;
+
+        assert_eq!(expected, actual);
+    }};
+}
+
+#[test]
+fn lint_clean_program() {
+    let code = r#"
+        TO SQUARE(N: INT)
+            REPEAT 4 [
+                FORWARD N
+                RIGHT 90
+            ]
+        END
+
+        SQUARE(10)
+    "#;
+
+    assert_warnings!(code, LintConfig::new(), vec![]);
+}
+
+#[test]
+fn lint_unused_variables_and_params() {
+    let code = r#"
+        TO DRAW(SIZE: INT, ANGLE: INT)
+            MAKELOCAL A = 1
+            MAKELOCAL B = 2
+            MAKELOCAL C = 3
+            MAKE C = B
+            FORWARD SIZE
+        END
+
+        DRAW(10, 20)
+    "#;
+
+    assert_warnings!(
+        code,
+        LintConfig::new(),
+        vec![
+            "[2:9] Param `ANGLE` of procedure `DRAW` is never read (unused-param)",
+            "[3:13] Variable `A` is never read (unused-variable)",
+            "[5:13] Variable `C` is never read (unused-variable)",
+        ]
+    );
+}
+
+#[test]
+fn lint_unused_procedures() {
+    let code = r#"
+        TO COUNT(N: INT): INT
+            IF N > 10 [RETURN N] [RETURN COUNT(N + 1)]
+        END
+
+        TO TWICE(N: INT): INT
+            RETURN N * 2
+        END
+
+        TO SHOW(N: INT)
+            PRINT N
+        END
+
+        MAKEGLOBAL F: PROC(INT): INT = TWICE
+        SHOW(F(1))
+    "#;
+
+    // a procedure calling only itself isn't used
+    assert_warnings!(
+        code,
+        LintConfig::new(),
+        vec!["[2:9] Procedure `COUNT` is never called (unused-procedure)"]
+    );
+}
+
+#[test]
+fn lint_shadowed_globals() {
+    let code = r#"
+        MAKEGLOBAL SIZE = 10
+        MAKEGLOBAL ANGLE = 90
+
+        TO DRAW(SIZE: INT)
+            MAKELOCAL ANGLE = 45
+            RIGHT ANGLE
+            FORWARD SIZE
+        END
+
+        DRAW(SIZE + ANGLE)
+    "#;
+
+    assert_warnings!(
+        code,
+        LintConfig::new(),
+        vec![
+            "[5:9] Local variable `SIZE` shadows a global variable (shadowed-global)",
+            "[6:13] Local variable `ANGLE` shadows a global variable (shadowed-global)",
+        ]
+    );
+}
+
+#[test]
+fn lint_repeat_zero_and_constant_conditions() {
+    let code = r#"
+        MAKEGLOBAL A = 1
+
+        REPEAT 0 [FORWARD 10]
+        REPEAT (2 * 0) [FORWARD 10]
+        REPEAT A [FORWARD 10]
+
+        IF 1 > 2 [
+            IF NOT FALSE [PENUP]
+        ]
+
+        IF A > 2 AND TRUE [PENDOWN]
+    "#;
+
+    assert_warnings!(
+        code,
+        LintConfig::new(),
+        vec![
+            "[4:9] `REPEAT 0` never runs its block (repeat-zero)",
+            "[5:9] `REPEAT 0` never runs its block (repeat-zero)",
+            "[8:9] `IF` condition is always `FALSE` (constant-condition)",
+            "[9:13] `IF` condition is always `TRUE` (constant-condition)",
+        ]
+    );
+}
+
+#[test]
+fn lint_config_disables_lints() {
+    let code = r#"
+        TO DRAW(SIZE: INT)
+            MAKELOCAL A = 1
+            REPEAT 0 [FORWARD 10]
+        END
+    "#;
+
+    let mut config = LintConfig::new();
+    config.disable(LintKind::UnusedVariable);
+    config.disable(LintKind::UnusedParam);
+
+    assert_warnings!(
+        code,
+        config,
+        vec![
+            "[2:9] Procedure `DRAW` is never called (unused-procedure)",
+            "[4:13] `REPEAT 0` never runs its block (repeat-zero)",
+        ]
+    );
+
+    config.enable(LintKind::UnusedVariable);
+    config.disable(LintKind::UnusedProcedure);
+    config.disable(LintKind::RepeatZero);
+
+    assert_warnings!(
+        code,
+        config,
+        vec!["[3:13] Variable `A` is never read (unused-variable)"]
+    );
+
+    assert_warnings!(code, LintConfig::none(), vec![]);
+}
+
+#[test]
+fn lint_kinds_names() {
+    for kind in LintKind::all() {
+        assert_eq!(Some(kind), LintKind::parse(kind.name()));
+    }
+
+    assert_eq!(None, LintKind::parse("unused"));
+}
+
+#[test]
+fn lint_skipped_on_compilation_errors() {
+    let (res, warnings) =
+        compile_with_lints("MAKELOCAL A = B\nREPEAT 0 [PENUP]", &LintConfig::new());
+
+    assert!(res.is_err());
+    assert!(warnings.is_empty());
+}
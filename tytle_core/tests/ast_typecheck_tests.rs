@@ -2,6 +2,7 @@ extern crate tytle;
 
 use tytle::ast::expression::*;
 use tytle::ast::semantic::*;
+use tytle::lexer::Location;
 use tytle::parser::{Parser, TytleParser};
 
 macro_rules! assert_type_err {
@@ -142,6 +143,108 @@ fn ast_typecheck_var_assign_str_literal() {
     assert_eq!(var_a.var_type, Some(ExpressionType::Str));
 }
 
+#[test]
+fn ast_typecheck_and_or_exprs_are_bools() {
+    let code = r#"
+            MAKEGLOBAL A = TRUE AND FALSE
+            MAKEGLOBAL B = 1 < 2 OR FALSE
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "A", &SymbolKind::Var);
+    let var_a = symbol.unwrap().as_var();
+    assert_eq!(var_a.var_type, Some(ExpressionType::Bool));
+
+    let symbol = env.symbol_table.lookup(0, "B", &SymbolKind::Var);
+    let var_b = symbol.unwrap().as_var();
+    assert_eq!(var_b.var_type, Some(ExpressionType::Bool));
+}
+
+#[test]
+fn ast_typecheck_global_used_before_its_declaration() {
+    let code = r#"
+            TO MYPROC()
+                FORWARD A
+            END
+
+            MAKEGLOBAL A = 10
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "A", &SymbolKind::Var);
+    let var_a = symbol.unwrap().as_var();
+    assert_eq!(var_a.var_type, Some(ExpressionType::Int));
+}
+
+#[test]
+fn ast_typecheck_var_type_inferred_out_of_another_var() {
+    let code = r#"
+            TO MYPROC(): BOOL
+                RETURN B
+            END
+
+            MAKEGLOBAL B = NOT A
+            MAKEGLOBAL A = 1 < C
+            MAKEGLOBAL C = 10
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "B", &SymbolKind::Var);
+    let var_b = symbol.unwrap().as_var();
+    assert_eq!(var_b.var_type, Some(ExpressionType::Bool));
+}
+
+#[test]
+fn ast_typecheck_var_with_type_annotation() {
+    let code = r#"
+            MAKEGLOBAL A: STR = "Hello"
+
+            TO MYPROC()
+                MAKELOCAL B: INT = 10
+                FORWARD B
+            END
+        "#;
+
+    do_typecheck!(code, env);
+
+    let symbol = env.symbol_table.lookup(0, "A", &SymbolKind::Var);
+    let var_a = symbol.unwrap().as_var();
+    assert_eq!(var_a.var_type, Some(ExpressionType::Str));
+}
+
+#[test]
+fn ast_typecheck_error_var_expr_not_matching_type_annotation() {
+    let code = r#"
+            TO MYPROC()
+                MAKELOCAL A: BOOL = 10
+            END
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Bool, ExpressionType::Int);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_conflicting_assignment_to_string() {
+    let code = r#"
+            MAKEGLOBAL A = 10
+            MAKE A = TRUE
+        "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    let err = AstTypeCheck::new(&mut env).check(&mut ast).err().unwrap();
+
+    assert_eq!(
+        "Conflicting assignment to `A`. declared: `Integer` (line: 2, column: 13), assigned: `Boolean`",
+        err.to_string()
+    );
+}
+
 #[test]
 fn ast_typecheck_error_cannot_add_strings() {
     let code = r#"
@@ -271,7 +374,12 @@ fn ast_typecheck_error_if_stmt_block_with_assigning_var_expr_with_wrong_type() {
             END
         "#;
 
-    let expected = AstWalkError::TypeMismatch(ExpressionType::Bool, ExpressionType::Int);
+    let expected = AstWalkError::ConflictingAssignment {
+        var_name: "A".to_string(),
+        var_type: ExpressionType::Bool,
+        declared_at: Some(Location(5, 25)),
+        assigned_type: ExpressionType::Int,
+    };
 
     assert_type_err!(expected, code);
 }
@@ -283,7 +391,12 @@ fn ast_typecheck_error_assigning_global_int_var_a_boolean_value() {
             MAKE A = TRUE
         "#;
 
-    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Bool);
+    let expected = AstWalkError::ConflictingAssignment {
+        var_name: "A".to_string(),
+        var_type: ExpressionType::Int,
+        declared_at: Some(Location(2, 13)),
+        assigned_type: ExpressionType::Bool,
+    };
 
     assert_type_err!(expected, code);
 }
@@ -295,7 +408,12 @@ fn ast_typecheck_error_assigning_global_int_var_a_string_value() {
             MAKE A = "Hello"
         "#;
 
-    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Str);
+    let expected = AstWalkError::ConflictingAssignment {
+        var_name: "A".to_string(),
+        var_type: ExpressionType::Int,
+        declared_at: Some(Location(2, 13)),
+        assigned_type: ExpressionType::Str,
+    };
 
     assert_type_err!(expected, code);
 }
@@ -304,13 +422,19 @@ fn ast_typecheck_error_assigning_global_int_var_a_string_value() {
 fn ast_typecheck_error_assigning_global_int_var_and_than_proc_call_result_which_returns_a_string() {
     let code = r#"
             TO MYPROC(): BOOL
+                RETURN TRUE
             END
 
             MAKEGLOBAL A = 10
             MAKE A = MYPROC()
         "#;
 
-    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Bool);
+    let expected = AstWalkError::ConflictingAssignment {
+        var_name: "A".to_string(),
+        var_type: ExpressionType::Int,
+        declared_at: Some(Location(6, 13)),
+        assigned_type: ExpressionType::Bool,
+    };
 
     assert_type_err!(expected, code);
 }
@@ -332,6 +456,7 @@ fn ast_typecheck_error_adding_int_and_string_expressions() {
 fn ast_typecheck_error_adding_int_and_proc_call_having_str_return_type() {
     let code = r#"
             TO MYPROC(): STR
+                RETURN "Hello"
             END
 
             MAKEGLOBAL B = 10 + MYPROC()
@@ -347,6 +472,7 @@ fn ast_typecheck_error_adding_int_and_proc_call_having_str_return_type() {
 fn ast_typecheck_error_proc_call_wrong_args_count() {
     let code = r#"
             TO MYPROC(A: INT): BOOL
+                RETURN A > 0
             END
 
             MAKEGLOBAL B = MYPROC(1, 2)
@@ -361,6 +487,7 @@ fn ast_typecheck_error_proc_call_wrong_args_count() {
 fn ast_typecheck_error_proc_call_args_type_mismatch() {
     let code = r#"
             TO MYPROC(A: INT): BOOL
+                RETURN A > 0
             END
 
             MAKEGLOBAL B = "Hello"
@@ -373,6 +500,214 @@ fn ast_typecheck_error_proc_call_args_type_mismatch() {
     assert_type_err!(expected, code);
 }
 
+#[test]
+fn ast_typecheck_proc_value_types() {
+    let code = r#"
+            TO MYPROC(A: INT, B: STR): BOOL
+                RETURN A > 0
+            END
+
+            MAKEGLOBAL F = MYPROC
+            MAKEGLOBAL B = F(1, "Hello")
+        "#;
+
+    do_typecheck!(code, env);
+
+    let f = env
+        .symbol_table
+        .lookup(0, "F", &SymbolKind::Var)
+        .unwrap()
+        .as_var();
+    let b = env
+        .symbol_table
+        .lookup(0, "B", &SymbolKind::Var)
+        .unwrap()
+        .as_var();
+
+    let f_type = ExpressionType::from("PROC(INT, STR): BOOL");
+
+    assert_eq!(Some(f_type), f.var_type);
+    assert_eq!(Some(ExpressionType::Bool), b.var_type);
+}
+
+#[test]
+fn ast_typecheck_error_indirect_call_args_type_mismatch() {
+    let code = r#"
+            TO MYPROC(A: INT): BOOL
+                RETURN A > 0
+            END
+
+            MAKEGLOBAL B = APPLY(MYPROC, "Hello")
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(1, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_indirect_call_args_count_mismatch() {
+    let code = r#"
+            TO MYPROC(A: INT): BOOL
+                RETURN A > 0
+            END
+
+            MAKEGLOBAL F = MYPROC
+            MAKEGLOBAL B = F()
+        "#;
+
+    let expected = AstWalkError::InvalidProcCallArgsCount("F".to_string(), 1, 0);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_calling_a_non_proc_variable() {
+    let code = r#"
+            MAKEGLOBAL F = 10
+            APPLY(F)
+        "#;
+
+    let expected = AstWalkError::NotProcExpr("F".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_map_proc_type_mismatch() {
+    let code = r#"
+            TO MYPROC(A: STR)
+            END
+
+            MAP(MYPROC, 3)
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(
+        ExpressionType::from("PROC(INT)"),
+        ExpressionType::from("PROC(STR)"),
+    );
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_unknown_named_arg() {
+    let code = r#"
+            TO SQUARE(SIZE: INT = 50)
+            END
+
+            SQUARE(SIDE: 80)
+        "#;
+
+    let expected = AstWalkError::UnknownProcParam("SQUARE".to_string(), "SIDE".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_arg_passed_twice() {
+    let code = r#"
+            TO RECT(WIDTH: INT, HEIGHT: INT)
+            END
+
+            RECT(10, WIDTH: 20)
+        "#;
+
+    let expected = AstWalkError::DuplicateProcCallArg("RECT".to_string(), "WIDTH".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_named_arg_passed_twice() {
+    let code = r#"
+            TO SQUARE(SIZE: INT)
+            END
+
+            SQUARE(SIZE: 10, SIZE: 20)
+        "#;
+
+    let expected = AstWalkError::DuplicateProcCallArg("SQUARE".to_string(), "SIZE".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_call_missing_arg() {
+    let code = r#"
+            TO RECT(WIDTH: INT = 10, HEIGHT: INT)
+            END
+
+            RECT(WIDTH: 20)
+        "#;
+
+    let expected = AstWalkError::MissingProcCallArg("RECT".to_string(), "HEIGHT".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_named_arg_type_mismatch() {
+    let code = r#"
+            TO RECT(WIDTH: INT = 10, HEIGHT: INT = 20)
+            END
+
+            RECT(HEIGHT: "Hello")
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(2, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_param_default_value_type_mismatch() {
+    let code = r#"
+            TO SQUARE(SIZE: INT = "Hello")
+            END
+        "#;
+
+    let expected = AstWalkError::InvalidProcParamDefaultType(
+        "SIZE".to_string(),
+        ExpressionType::Int,
+        ExpressionType::Str,
+    );
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_param_default_value_calls_its_proc() {
+    let code = r#"
+            TO F(A: INT = F()): INT
+              RETURN A
+            END
+        "#;
+
+    let expected = AstWalkError::RecursiveProcParamDefault("F".to_string(), "A".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_param_default_value_calls_its_proc_indirectly() {
+    let code = r#"
+            TO F(A: INT = G() + 1): INT
+              RETURN A
+            END
+
+            TO G(B: INT = F()): INT
+              RETURN B
+            END
+        "#;
+
+    let expected = AstWalkError::RecursiveProcParamDefault("F".to_string(), "A".to_string());
+
+    assert_type_err!(expected, code);
+}
+
 #[test]
 fn ast_typecheck_error_adding_int_and_proc_call_having_no_return_type() {
     let code = r#"
@@ -413,6 +748,39 @@ fn ast_typecheck_error_direct_stmt_expr_must_be_an_integer() {
     assert_type_err!(expected, code);
 }
 
+#[test]
+fn ast_typecheck_error_label_stmt_expr_must_be_a_string() {
+    let code = r#"
+            LABEL 1 + 2
+        "#;
+
+    let expected = AstWalkError::NotStrExpr("1 + 2".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_print_stmt_expr_must_be_an_integer() {
+    let code = r#"
+            PRINT "HELLO"
+        "#;
+
+    let expected = AstWalkError::NotIntExpr("\"HELLO\"".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_set_font_size_stmt_expr_must_be_an_integer() {
+    let code = r#"
+            SETFONTSIZE "BIG"
+        "#;
+
+    let expected = AstWalkError::NotIntExpr("\"BIG\"".to_string());
+
+    assert_type_err!(expected, code);
+}
+
 #[test]
 fn ast_typecheck_error_wrong_return_type() {
     let code = r#"
@@ -426,6 +794,24 @@ fn ast_typecheck_error_wrong_return_type() {
     assert_type_err!(expected, code);
 }
 
+#[test]
+fn ast_typecheck_error_wrong_return_type_in_nested_proc() {
+    // `INNER` returns the type of `INNER` (not the type of `OUTER`)
+    let code = r#"
+            TO OUTER(): BOOL
+                TO INNER(): INT
+                    RETURN TRUE
+                END
+
+                RETURN TRUE
+            END
+        "#;
+
+    let expected = AstWalkError::InvalidReturnType(ExpressionType::Int, ExpressionType::Bool);
+
+    assert_type_err!(expected, code);
+}
+
 #[test]
 fn ast_typecheck_error_cannot_halt_from_proc_not_returning_unit() {
     let code = r#"
@@ -462,3 +848,226 @@ fn ast_typecheck_error_cannot_return_from_root_scope() {
 
     assert_type_err!(expected, code);
 }
+
+#[test]
+fn ast_typecheck_symbol_table_dump_has_the_inferred_types() {
+    let code = r#"
+            TO MYPROC(A: INT, B: STR): BOOL
+                MAKELOCAL C = A + 1
+                RETURN C > 2
+            END
+
+            MAKEGLOBAL D: STR = "Hello"
+        "#;
+
+    do_typecheck!(code, env);
+
+    let expected = r#"#0 proc __main__()
+#1 proc MYPROC(INT, STR): BOOL
+#2 global D: STR
+#3 param A: INT
+#4 param B: STR
+#5 local C: INT
+"#;
+
+    assert_eq!(expected, env.symbol_table.dump());
+}
+
+#[test]
+fn ast_typecheck_proc_returns_on_every_if_branch() {
+    let code = r#"
+            TO MYPROC(A: INT): INT
+                IF A > 0 [
+                    RETURN 1
+                ] [
+                    IF A < 0 [RETURN 2] [RETURN 3]
+                ]
+            END
+        "#;
+
+    do_typecheck!(code, env);
+}
+
+#[test]
+fn ast_typecheck_error_proc_missing_return() {
+    let code = r#"
+            TO MYPROC(): INT
+                FORWARD 10
+            END
+        "#;
+
+    let expected = AstWalkError::MissingReturn("MYPROC".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_if_without_else_missing_return() {
+    let code = r#"
+            TO MYPROC(A: INT): INT
+                IF A > 0 [
+                    RETURN 1
+                ]
+            END
+        "#;
+
+    let expected = AstWalkError::MissingReturn("MYPROC".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_proc_return_only_within_repeat() {
+    let code = r#"
+            TO MYPROC(): INT
+                REPEAT 3 [
+                    RETURN 1
+                ]
+            END
+        "#;
+
+    let expected = AstWalkError::MissingReturn("MYPROC".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_unreachable_stmt_after_return() {
+    let code = r#"
+            TO MYPROC(): INT
+                RETURN 1
+                FORWARD 10
+            END
+        "#;
+
+    let expected = AstWalkError::UnreachableStmt("MYPROC".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_unreachable_stmt_after_halt() {
+    let code = r#"
+            HALT
+            FORWARD 10
+        "#;
+
+    let expected = AstWalkError::UnreachableStmt("__main__".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_struct_fields() {
+    let code = r#"
+            STRUCT POINT [X: INT, Y: INT]
+            STRUCT LINE [START: POINT, FINISH: POINT, COLOR: STR]
+
+            TO LENGTH(L: LINE): INT
+                RETURN L.FINISH.X + L.START.X * 2
+            END
+
+            MAKEGLOBAL L = LINE(POINT(1, 2), FINISH: POINT(Y: 4, X: 3), COLOR: "RED")
+            MAKEGLOBAL N = LENGTH(L)
+            MAKEGLOBAL C = L.COLOR
+            MAKE L.START.Y = N
+        "#;
+
+    do_typecheck!(code, env);
+
+    let expected = r#"#0 proc __main__()
+#1 struct POINT [X: INT, Y: INT]
+#2 struct LINE [START: POINT, FINISH: POINT, COLOR: STR]
+#3 proc LENGTH(LINE): INT
+#4 global L: LINE
+#5 global N: INT
+#6 global C: STR
+#7 param L: LINE
+"#;
+
+    assert_eq!(expected, env.symbol_table.dump());
+}
+
+#[test]
+fn ast_typecheck_error_struct_field_arg_type() {
+    let code = r#"
+            STRUCT POINT [X: INT, Y: INT]
+
+            MAKEGLOBAL P = POINT(1, Y: "2")
+        "#;
+
+    let expected =
+        AstWalkError::InvalidProcCallArgType(2, ExpressionType::Int, ExpressionType::Str);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_struct_missing_field_arg() {
+    let code = r#"
+            STRUCT POINT [X: INT, Y: INT]
+
+            MAKEGLOBAL P = POINT(Y: 2)
+        "#;
+
+    let expected = AstWalkError::MissingProcCallArg("POINT".to_string(), "X".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_unknown_struct_field() {
+    let code = r#"
+            STRUCT POINT [X: INT, Y: INT]
+
+            MAKEGLOBAL P = POINT(1, 2)
+            MAKEGLOBAL Z = P.Z
+        "#;
+
+    let expected = AstWalkError::UnknownStructField("POINT".to_string(), "Z".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_field_of_non_struct() {
+    let code = r#"
+            MAKEGLOBAL A = 10
+            MAKEGLOBAL B = A.X
+        "#;
+
+    let expected = AstWalkError::NotStructExpr("A".to_string());
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_set_field_type_mismatch() {
+    let code = r#"
+            STRUCT POINT [X: INT, Y: INT]
+
+            MAKEGLOBAL P = POINT(1, 2)
+            MAKE P.X = TRUE
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(ExpressionType::Int, ExpressionType::Bool);
+
+    assert_type_err!(expected, code);
+}
+
+#[test]
+fn ast_typecheck_error_assign_struct_of_another_type() {
+    let code = r#"
+            STRUCT POINT [X: INT, Y: INT]
+            STRUCT SIZE [X: INT, Y: INT]
+
+            MAKEGLOBAL P: POINT = SIZE(1, 2)
+        "#;
+
+    let expected = AstWalkError::TypeMismatch(
+        ExpressionType::Struct("POINT".to_string()),
+        ExpressionType::Struct("SIZE".to_string()),
+    );
+
+    assert_type_err!(expected, code);
+}
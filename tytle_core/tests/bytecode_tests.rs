@@ -0,0 +1,578 @@
+extern crate tytle;
+
+use tytle::ast::statement::Direction;
+use tytle::bytecode::*;
+use tytle::compiler::compile;
+use tytle::ir::CfgOptimizer;
+use tytle::lexer::Location;
+use tytle::vm::*;
+
+macro_rules! compile_bytecode {
+    ($code: expr) => {{
+        let (env, cfg) = compile($code).unwrap();
+
+        BytecodeBuilder::new(&cfg, &env).build()
+    }};
+}
+
+// runs `code` with the `Interpreter`, and its bytecode (before and after serialization) with the `BytecodeVm`,
+// all the runs are expected to end the same way
+macro_rules! assert_same_run {
+    ($code: expr, $optimize: expr, $expected_res: expr, $expected_log: expr) => {{
+        let (mut env, mut cfg) = compile($code).unwrap();
+
+        if $optimize {
+            CfgOptimizer::new().optimize(&mut cfg, &mut env);
+        }
+
+        let mut intr_host = DummyHost::new();
+        let mut intr = Interpreter::new(&cfg, &env, &mut intr_host);
+        let intr_res = intr.exec_code();
+
+        let program = BytecodeBuilder::new(&cfg, &env).build();
+        let loaded = BytecodeProgram::from_bytes(&program.to_bytes()).unwrap();
+        assert_eq!(program, loaded);
+
+        let mut vm_host = DummyHost::new();
+        let mut vm = BytecodeVm::new(&loaded, &mut vm_host);
+        let vm_res = vm.exec_code();
+
+        let expected_res: InterpreterResult = $expected_res;
+        let expected_log: Vec<&str> = $expected_log;
+
+        assert_eq!(expected_res, intr_res);
+        assert_eq!(expected_res, vm_res);
+        assert_eq!(expected_log, intr_host.get_log());
+        assert_eq!(expected_log, vm_host.get_log());
+        assert_eq!(intr_host.xycors(), vm_host.xycors());
+    }};
+}
+
+#[test]
+fn bytecode_lowering_main_code() {
+    let program = compile_bytecode!("FORWARD 10");
+
+    let main_proc = &program.procs[program.main_proc];
+
+    assert_eq!("__main__", main_proc.name);
+    assert_eq!(0, main_proc.entry_pc);
+
+    assert_eq!(
+        vec![
+            BytecodeOp::Int(10),
+            BytecodeOp::Direction(Direction::Forward),
+            BytecodeOp::End
+        ],
+        program.code
+    );
+
+    assert_eq!(Some(Location(1, 1)), program.get_location(1));
+}
+
+#[test]
+fn bytecode_lowering_resolves_jumps_and_slots() {
+    let program = compile_bytecode!(
+        r#"
+        MAKEGLOBAL A = 0
+        TO INC(N: INT)
+            MAKELOCAL B = N
+            MAKE A = A + B
+        END
+        REPEAT 2 [INC(3)]
+    "#
+    );
+
+    let inc = program
+        .procs
+        .iter()
+        .find(|proc| proc.name == "INC")
+        .unwrap();
+
+    assert_eq!(1, inc.params_count);
+    assert_eq!(vec![BytecodeConst::Int(-1)], inc.locals);
+    assert_eq!(false, inc.returns_value);
+    assert_eq!(vec![BytecodeConst::Int(0)], program.globals);
+
+    let inc_code = &program.code[inc.entry_pc..inc.entry_pc + 7];
+
+    assert_eq!(
+        vec![
+            BytecodeOp::LoadLocal(0, 0),
+            BytecodeOp::StoreLocal(0, 1),
+            BytecodeOp::LoadGlobal(0),
+            BytecodeOp::LoadLocal(0, 1),
+            BytecodeOp::Add,
+            BytecodeOp::StoreGlobal(0),
+            BytecodeOp::Return,
+        ],
+        inc_code
+    );
+
+    // every jump lands on an op
+    for op in program.code.iter() {
+        match op {
+            BytecodeOp::Jump(pc) | BytecodeOp::JumpIfTrue(pc) | BytecodeOp::JumpIfFalse(pc) => {
+                assert!(*pc < program.code.len())
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn bytecode_lowering_nested_proc_hops() {
+    let program = compile_bytecode!(
+        r#"
+        TO OUTER(SIZE: INT)
+            TO MIDDLE()
+                TO INNER()
+                    FORWARD SIZE
+                END
+
+                INNER()
+            END
+
+            MIDDLE()
+        END
+
+        OUTER(10)
+    "#
+    );
+
+    assert!(program.code.contains(&BytecodeOp::LoadLocal(2, 0)));
+    assert!(program.code.contains(&BytecodeOp::Call(3, Some(0))));
+    assert!(program.code.contains(&BytecodeOp::Call(2, Some(0))));
+}
+
+#[test]
+fn bytecode_vm_proc_calls() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            REPEAT 4 [
+                FORWARD SIZE
+                RIGHT SIZE
+            ]
+        END
+
+        SQUARE(10)
+        SQUARE(5)
+    "#;
+
+    assert_same_run!(code, false, Ok(()), vec![]);
+    assert_same_run!(code, true, Ok(()), vec![]);
+}
+
+#[test]
+fn bytecode_vm_recursion_and_conditions() {
+    let code = r#"
+        TO COUNT(I: INT, N: INT): INT
+            IF I < N [
+                PRINT I
+                RETURN COUNT(I + 1, N)
+            ][
+                RETURN I * 10
+            ]
+        END
+
+        PRINT COUNT(0, 3)
+    "#;
+
+    assert_same_run!(code, false, Ok(()), vec!["0", "1", "2", "30"]);
+    assert_same_run!(code, true, Ok(()), vec!["0", "1", "2", "30"]);
+}
+
+#[test]
+fn bytecode_vm_nested_procs_and_proc_values() {
+    let code = r#"
+        TO APPLYN(G: PROC(INT): INT, X: INT): INT
+            RETURN G(X)
+        END
+
+        TO OUTER(BASE: INT): INT
+            MAKELOCAL CALLS = 0
+
+            TO ADDBASE(X: INT): INT
+                MAKE CALLS = CALLS + 1
+                RETURN X + BASE
+            END
+
+            MAKELOCAL A = APPLYN(ADDBASE, 5)
+            PRINT CALLS
+            RETURN A + ADDBASE(1)
+        END
+
+        TO TWICE(X: INT): INT
+            RETURN X * 2
+        END
+
+        TO SHOW(I: INT)
+            PRINT I
+        END
+
+        PRINT OUTER(100)
+        MAKEGLOBAL F: PROC(INT): INT = TWICE
+        PRINT APPLY(F, 21)
+        MAP(SHOW, 2)
+    "#;
+
+    assert_same_run!(code, false, Ok(()), vec!["1", "206", "42", "0", "1"]);
+    assert_same_run!(code, true, Ok(()), vec!["1", "206", "42", "0", "1"]);
+}
+
+#[test]
+fn bytecode_vm_structs() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        TO MOVE(P: POINT, DX: INT)
+            MAKE P.X = P.X + DX
+        END
+
+        MAKEGLOBAL P = POINT(1, 2)
+        MAKEGLOBAL Q = P
+
+        MAKE Q.Y = 5
+        MOVE(P, 10)
+
+        PRINT P.X
+        PRINT P.Y
+        SETX Q.X
+    "#;
+
+    assert_same_run!(code, false, Ok(()), vec!["11", "5"]);
+}
+
+#[test]
+fn bytecode_vm_labels() {
+    let code = r#"
+        MAKEGLOBAL S = "HELLO"
+        SETFONTSIZE 12
+        LABEL S
+    "#;
+
+    let (env, cfg) = compile(code).unwrap();
+
+    let mut intr_host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut intr_host);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    let program = BytecodeBuilder::new(&cfg, &env).build();
+
+    let mut vm_host = DummyHost::new();
+    let mut vm = BytecodeVm::new(&program, &mut vm_host);
+    assert_eq!(Ok(()), vm.exec_code());
+
+    assert_eq!(intr_host.get_log(), vm_host.get_log());
+}
+
+#[test]
+fn bytecode_vm_division_by_zero() {
+    let code = r#"
+        TO DIVIDE(A: INT, B: INT): INT
+            RETURN A / B
+        END
+        PRINT DIVIDE(10, 0)
+    "#;
+
+    let site = ErrorSite {
+        proc_name: "DIVIDE".to_string(),
+        location: Some(Location(3, 13)),
+    };
+
+    assert_same_run!(
+        code,
+        false,
+        Err(InterpreterException::DivisionByZero(site)),
+        vec![]
+    );
+}
+
+#[test]
+fn bytecode_vm_integer_overflow() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        REPEAT 100 [
+            MAKE A = A * 2
+        ]
+    "#;
+
+    let site = ErrorSite {
+        proc_name: "__main__".to_string(),
+        location: Some(Location(4, 13)),
+    };
+
+    assert_same_run!(
+        code,
+        false,
+        Err(InterpreterException::IntegerOverflow(site)),
+        vec![]
+    );
+}
+
+#[test]
+fn bytecode_vm_field_of_unassigned_struct() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        TO SHOW()
+            PRINT P.X
+        END
+
+        SHOW()
+        MAKEGLOBAL P = POINT(1, 2)
+    "#;
+
+    let err = InterpreterException::InvalidStackItem {
+        expected: "a record".to_string(),
+        site: ErrorSite {
+            proc_name: "SHOW".to_string(),
+            location: Some(Location(5, 13)),
+        },
+    };
+
+    assert_same_run!(code, false, Err(err), vec![]);
+}
+
+#[test]
+fn bytecode_vm_corrupted_local_slot() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            FORWARD SIZE
+        END
+        SQUARE(10)
+    "#;
+
+    let site = ErrorSite {
+        proc_name: "SQUARE".to_string(),
+        location: Some(Location(3, 13)),
+    };
+
+    let corrupted_ops = vec![
+        (BytecodeOp::LoadLocal(0, 5), "a local variable"),
+        (BytecodeOp::LoadLocal(1, 0), "an enclosing procedure frame"),
+    ];
+
+    for (corrupted_op, expected) in corrupted_ops {
+        let mut program = compile_bytecode!(code);
+
+        let pc = program
+            .code
+            .iter()
+            .position(|op| *op == BytecodeOp::LoadLocal(0, 0))
+            .unwrap();
+        program.code[pc] = corrupted_op;
+
+        // the slots are checked when executing (the format doesn't tell each op procedure)
+        let loaded = BytecodeProgram::from_bytes(&program.to_bytes()).unwrap();
+
+        let mut host = DummyHost::new();
+        let mut vm = BytecodeVm::new(&loaded, &mut host);
+
+        let err = InterpreterException::InvalidStackItem {
+            expected: expected.to_string(),
+            site: site.clone(),
+        };
+
+        assert_eq!(Err(err), vm.exec_code());
+    }
+}
+
+#[test]
+fn bytecode_vm_corrupted_field_index() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+        MAKEGLOBAL P = POINT(1, 2)
+        PRINT P.Y
+    "#;
+
+    let site = ErrorSite {
+        proc_name: "__main__".to_string(),
+        location: Some(Location(4, 9)),
+    };
+
+    let mut program = compile_bytecode!(code);
+
+    let pc = program
+        .code
+        .iter()
+        .position(|op| *op == BytecodeOp::LoadField(1))
+        .unwrap();
+    program.code[pc] = BytecodeOp::LoadField(7);
+
+    let loaded = BytecodeProgram::from_bytes(&program.to_bytes()).unwrap();
+
+    let mut host = DummyHost::new();
+    let mut vm = BytecodeVm::new(&loaded, &mut host);
+
+    let err = InterpreterException::InvalidStackItem {
+        expected: "a record having the field".to_string(),
+        site,
+    };
+
+    assert_eq!(Err(err), vm.exec_code());
+}
+
+#[test]
+fn bytecode_vm_nested_proc_value_called_after_its_enclosing_proc_returned() {
+    let code = r#"
+        TO ZERO(): INT
+            RETURN 0
+        END
+
+        MAKEGLOBAL KEEP: PROC(): INT = ZERO
+
+        TO OUTER(BASE: INT)
+            TO GETBASE(): INT
+                RETURN BASE
+            END
+
+            MAKE KEEP = GETBASE
+        END
+
+        OUTER(100)
+        PRINT KEEP()
+    "#;
+
+    let site = ErrorSite {
+        proc_name: "__main__".to_string(),
+        location: Some(Location(17, 9)),
+    };
+
+    assert_same_run!(
+        code,
+        false,
+        Err(InterpreterException::ProcOutOfScope(site)),
+        vec![]
+    );
+}
+
+#[test]
+fn bytecode_vm_stack_overflow() {
+    let code = r#"
+        TO OVERFLOW(I: INT): INT
+            RETURN OVERFLOW(I + 1)
+        END
+        OVERFLOW(0)
+    "#;
+
+    assert_same_run!(
+        code,
+        false,
+        Err(InterpreterException::StackOverflow),
+        vec![]
+    );
+}
+
+#[test]
+fn bytecode_vm_out_of_fuel() {
+    let program = compile_bytecode!(
+        r#"
+        REPEAT 1000000000 [
+            FORWARD 1
+        ]
+    "#
+    );
+
+    let mut host = DummyHost::new();
+    let mut vm = BytecodeVm::new(&program, &mut host);
+    vm.set_fuel(1000);
+
+    assert_eq!(Err(InterpreterException::OutOfFuel), vm.exec_code());
+}
+
+#[test]
+fn bytecode_format_header() {
+    let program = compile_bytecode!("FORWARD 10");
+    let bytes = program.to_bytes();
+
+    assert_eq!(b"TYB\0", &bytes[0..4]);
+    assert_eq!(&BYTECODE_VERSION.to_le_bytes(), &bytes[4..6]);
+}
+
+#[test]
+fn bytecode_format_invalid_magic() {
+    let res = BytecodeProgram::from_bytes(b"TYL\0\x01\x00");
+
+    assert_eq!(Err(BytecodeFormatError::InvalidMagic), res);
+
+    let res = BytecodeProgram::from_bytes(b"TY");
+
+    assert_eq!(Err(BytecodeFormatError::InvalidMagic), res);
+}
+
+#[test]
+fn bytecode_format_unsupported_version() {
+    let program = compile_bytecode!("FORWARD 10");
+    let mut bytes = program.to_bytes();
+    bytes[4] = 2;
+
+    let res = BytecodeProgram::from_bytes(&bytes);
+
+    assert_eq!(Err(BytecodeFormatError::UnsupportedVersion(2)), res);
+    assert_eq!(
+        "Unsupported bytecode version: 2 (expected: 1)",
+        res.err().unwrap().to_string()
+    );
+}
+
+#[test]
+fn bytecode_format_truncated() {
+    let program = compile_bytecode!("FORWARD 10");
+    let bytes = program.to_bytes();
+
+    for len in 6..bytes.len() {
+        let res = BytecodeProgram::from_bytes(&bytes[..len]);
+
+        assert_eq!(Err(BytecodeFormatError::UnexpectedEof), res);
+    }
+
+    let mut bytes = bytes;
+    bytes.push(0);
+
+    assert_eq!(
+        Err(BytecodeFormatError::TrailingBytes),
+        BytecodeProgram::from_bytes(&bytes)
+    );
+}
+
+#[test]
+fn bytecode_format_invalid_program() {
+    let mut program = compile_bytecode!("FORWARD 10");
+    program.code.push(BytecodeOp::Jump(100));
+
+    let res = BytecodeProgram::from_bytes(&program.to_bytes());
+
+    assert_eq!(
+        Err(BytecodeFormatError::InvalidProgram(
+            "op refers to a missing entry".to_string()
+        )),
+        res
+    );
+}
+
+#[test]
+fn bytecode_format_invalid_params_count() {
+    let mut program = compile_bytecode!("TO F()\nEND\nF()");
+    program.procs[1].params_count = usize::MAX;
+
+    let res = BytecodeProgram::from_bytes(&program.to_bytes());
+
+    assert_eq!(
+        Err(BytecodeFormatError::InvalidProgram(
+            "procedure having more params than the code ops".to_string()
+        )),
+        res
+    );
+
+    let mut program = compile_bytecode!("FORWARD 10");
+    program.procs[program.main_proc].params_count = 1;
+
+    let res = BytecodeProgram::from_bytes(&program.to_bytes());
+
+    assert_eq!(
+        Err(BytecodeFormatError::InvalidProgram(
+            "main procedure having params".to_string()
+        )),
+        res
+    );
+}
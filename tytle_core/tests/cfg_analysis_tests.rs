@@ -0,0 +1,205 @@
+#[macro_use]
+extern crate tytle;
+
+use std::collections::HashSet;
+use tytle::ast::semantic::SymbolId;
+use tytle::ir::analysis::*;
+use tytle::ir::*;
+
+// node 1: X = 0
+// node 2: loop header (`X < 3`)
+// node 3 / node 4: Y = 1 / Y = 2
+// node 5: X = X + Y (loops back to node 2 while `X < 10`)
+// node 6: PRINT Y
+fn loop_with_diamond() -> CfgGraph {
+    cfg_graph! {
+        node!(1, int_ins!(0), store_ins!(1)),
+        node!(2, load_ins!(1), int_ins!(3), lt_ins!()),
+        node!(3, int_ins!(1), store_ins!(2)),
+        node!(4, int_ins!(2), store_ins!(2)),
+        node!(5, load_ins!(1), load_ins!(2), add_ins!(), store_ins!(1), load_ins!(1), int_ins!(10), lt_ins!()),
+        node!(6, load_ins!(2), print_ins!(), eoc_ins!()),
+        edge_always_jmp!(1, 2),
+        edge_true_jmp!(2, 3),
+        edge_fallback_jmp!(2, 4),
+        edge_always_jmp!(3, 5),
+        edge_always_jmp!(4, 5),
+        edge_true_jmp!(5, 2),
+        edge_fallback_jmp!(5, 6)
+    }
+}
+
+fn set<T: std::hash::Hash + Eq>(elems: Vec<T>) -> HashSet<T> {
+    elems.into_iter().collect()
+}
+
+#[test]
+fn cfg_analysis_reverse_postorder() {
+    let graph = loop_with_diamond();
+
+    let order = DataflowSolver::new(&graph).reverse_postorder(&[1]);
+
+    assert_eq!(vec![1, 2, 4, 3, 5, 6], order);
+}
+
+#[test]
+fn cfg_analysis_dominators() {
+    let graph = loop_with_diamond();
+    let doms = Dominators::compute(&graph, 1);
+
+    assert_eq!(None, doms.immediate_dominator(1));
+    assert_eq!(Some(1), doms.immediate_dominator(2));
+    assert_eq!(Some(2), doms.immediate_dominator(3));
+    assert_eq!(Some(2), doms.immediate_dominator(4));
+    assert_eq!(Some(2), doms.immediate_dominator(5));
+    assert_eq!(Some(5), doms.immediate_dominator(6));
+
+    assert!(doms.dominates(2, 6));
+    assert!(doms.dominates(5, 5));
+    assert!(!doms.dominates(3, 5));
+    assert!(!doms.dominates(6, 2));
+
+    assert_eq!(vec![3, 4, 5], doms.dominated_children(2));
+}
+
+#[test]
+fn cfg_analysis_dominance_frontiers() {
+    let graph = loop_with_diamond();
+    let frontiers = Dominators::compute(&graph, 1).frontiers(&graph);
+
+    assert_eq!(set(vec![]), frontiers[&1]);
+    assert_eq!(set(vec![2]), frontiers[&2]);
+    assert_eq!(set(vec![5]), frontiers[&3]);
+    assert_eq!(set(vec![5]), frontiers[&4]);
+    assert_eq!(set(vec![2]), frontiers[&5]);
+    assert_eq!(set(vec![]), frontiers[&6]);
+}
+
+#[test]
+fn cfg_analysis_dominators_skip_unreachable_nodes() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(1)),
+        node!(2, int_ins!(2)),
+        node!(3, eoc_ins!()),
+        edge_always_jmp!(1, 3),
+        edge_always_jmp!(2, 3)
+    };
+
+    let doms = Dominators::compute(&graph, 1);
+
+    assert!(!doms.is_reachable(2));
+    assert_eq!(Some(1), doms.immediate_dominator(3));
+    assert_eq!(vec![1, 3], doms.get_nodes_ids());
+}
+
+#[test]
+fn cfg_analysis_liveness() {
+    let graph = loop_with_diamond();
+    let live = DataflowSolver::new(&graph).solve(&Liveness, &[1]);
+
+    let (x, y) = (SymbolId(1), SymbolId(2));
+
+    assert_eq!(&set(vec![]), live.get_entry(1));
+    assert_eq!(&set(vec![x]), live.get_entry(2));
+    assert_eq!(&set(vec![x]), live.get_entry(3));
+    assert_eq!(&set(vec![x]), live.get_entry(4));
+    assert_eq!(&set(vec![x, y]), live.get_entry(5));
+    assert_eq!(&set(vec![y]), live.get_entry(6));
+
+    assert_eq!(&set(vec![x, y]), live.get_exit(5));
+    assert_eq!(&set(vec![]), live.get_exit(6));
+}
+
+#[test]
+fn cfg_analysis_reaching_definitions() {
+    let graph = loop_with_diamond();
+    let reaching = DataflowSolver::new(&graph).solve(&ReachingDefs, &[1]);
+
+    let def = |node_id, ip, var_id| Definition {
+        node_id,
+        ip,
+        var_id: SymbolId(var_id),
+    };
+
+    let (d1, d3, d4, d5) = (def(1, 1, 1), def(3, 1, 2), def(4, 1, 2), def(5, 3, 1));
+
+    assert_eq!(&set(vec![]), reaching.get_entry(1));
+    assert_eq!(&set(vec![d1]), reaching.get_exit(1));
+    assert_eq!(&set(vec![d1, d3, d4, d5]), reaching.get_entry(2));
+    assert_eq!(&set(vec![d1, d3, d5]), reaching.get_exit(3));
+    assert_eq!(&set(vec![d3, d4, d5]), reaching.get_exit(5));
+    assert_eq!(&set(vec![d3, d4, d5]), reaching.get_entry(6));
+}
+
+// a custom lattice: whether a `PRINT` may have been executed
+#[derive(Debug, Clone, PartialEq)]
+struct MayPrint(bool);
+
+impl Lattice for MayPrint {
+    fn bottom() -> Self {
+        MayPrint(false)
+    }
+
+    fn join(&mut self, other: &Self) -> bool {
+        let changed = !self.0 && other.0;
+
+        self.0 = self.0 || other.0;
+
+        changed
+    }
+}
+
+struct MayPrintAnalysis;
+
+impl DataflowAnalysis for MayPrintAnalysis {
+    type Fact = MayPrint;
+
+    fn direction(&self) -> DataflowDirection {
+        DataflowDirection::Forward
+    }
+
+    fn boundary(&self) -> Self::Fact {
+        MayPrint(false)
+    }
+
+    fn transfer(&self, node: &CfgNode, input: &Self::Fact) -> Self::Fact {
+        MayPrint(input.0 || node.insts.contains(&CfgInstruction::Print))
+    }
+}
+
+#[test]
+fn cfg_analysis_custom_lattice() {
+    let graph = cfg_graph! {
+        node!(1, bool_ins!(true)),
+        node!(2, int_ins!(1), print_ins!()),
+        node!(3, cmd_ins!(PENUP)),
+        node!(4, bool_ins!(true)),
+        node!(5, eoc_ins!()),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 4),
+        edge_always_jmp!(3, 4),
+        edge_true_jmp!(4, 1),
+        edge_fallback_jmp!(4, 5)
+    };
+
+    let result = DataflowSolver::new(&graph).solve(&MayPrintAnalysis, &[1]);
+
+    // node 1 is a loop header, so a `PRINT` of a previous iteration may have been executed
+    assert_eq!(&MayPrint(true), result.get_entry(1));
+    assert_eq!(&MayPrint(true), result.get_exit(3));
+    assert_eq!(&MayPrint(true), result.get_entry(5));
+}
+
+#[test]
+fn cfg_analysis_must_set_lattice() {
+    let mut facts: MustSet<usize> = MustSet::bottom();
+    assert!(facts.contains(&10));
+
+    assert!(facts.join(&MustSet(Some(set(vec![1, 2, 3])))));
+    assert!(facts.join(&MustSet(Some(set(vec![2, 3, 4])))));
+    assert!(!facts.join(&MustSet::bottom()));
+    assert!(!facts.join(&MustSet(Some(set(vec![2, 3])))));
+
+    assert_eq!(MustSet(Some(set(vec![2, 3]))), facts);
+}
@@ -374,6 +374,30 @@ fn compile_cfg_graph_print() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn compile_cfg_graph_label() {
+    let code = r#"
+        MAKEGLOBAL S = "HELLO"
+        SETFONTSIZE 20
+        LABEL S
+    "#;
+
+    let actual = compile_cfg_graph!(code);
+    let expected = cfg_graph! {
+        node!(1,
+            str_ins!("HELLO"),
+            store_ins!(1),          // S = "HELLO"
+            int_ins!(20),
+            set_font_size_ins!(),   // SETFONTSIZE 20
+            load_ins!(1),
+            label_ins!(),           // LABEL S
+            eoc_ins!()
+        )
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn compile_cfg_graph_repeat_stmt() {
     let code = r#"
@@ -555,11 +579,11 @@ fn compile_cfg_graph_recursive_procedure() {
 fn compile_cfg_graph_mutually_exclusive_procedures() {
     let code = r#"
         TO F(A: INT): INT
-            10 + G(1)
+            RETURN 10 + G(1)
         END
 
         TO G(B: INT): INT
-            20 * F(2)
+            RETURN 20 * F(2)
         END
 
         F(1)
@@ -591,3 +615,335 @@ fn compile_cfg_graph_mutually_exclusive_procedures() {
 
     assert_eq!(expected, actual.graph);
 }
+
+#[test]
+fn compile_cfg_source_map() {
+    let code = r#"
+        TO MOVE()
+            FORWARD 10
+        END
+
+        MAKEGLOBAL A = 1
+        MOVE()
+    "#;
+
+    let actual = compile_cfg_obj!(code);
+    let source_map = actual.source_map;
+
+    // `MAKEGLOBAL A = 1` emits 2 instructions under node `1`
+    assert_eq!(vec![(1, 0)], source_map.get_line_stmts(6));
+    assert_eq!(vec![(1, 2)], source_map.get_line_stmts(7));
+    assert_eq!(vec![(2, 0)], source_map.get_line_stmts(3));
+    assert!(source_map.get_line_stmts(2).is_empty());
+
+    assert!(source_map.is_stmt_start(1, 2));
+    assert!(!source_map.is_stmt_start(1, 1));
+    assert_eq!(6, source_map.get_location(1, 1).unwrap().line());
+}
+
+#[test]
+fn compile_optimized_cfg_graph_constant_folding() {
+    let code = r#"
+        MAKEGLOBAL A = ((1 + 2) * 5) / 2
+        MAKEGLOBAL B = NOT (1 > 2) AND TRUE
+        MAKEGLOBAL C = 1 / 0
+    "#;
+
+    let actual = compile_optimized_cfg_obj!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(7),
+            store_ins!(1),    // A = 7
+            bool_ins!(true),
+            store_ins!(2),    // B = TRUE
+            int_ins!(1),
+            int_ins!(0),
+            div_ins!(),       // the division by zero is left for the runtime
+            store_ins!(3),
+            eoc_ins!()
+        )
+    };
+
+    assert_eq!(expected.nodes, actual.graph.nodes);
+
+    // the statements are mapped to their folded instructions
+    let source_map = actual.source_map;
+    assert_eq!(vec![(1, 0)], source_map.get_line_stmts(2));
+    assert_eq!(vec![(1, 2)], source_map.get_line_stmts(3));
+    assert_eq!(vec![(1, 4)], source_map.get_line_stmts(4));
+}
+
+#[test]
+fn compile_optimized_cfg_graph_if_stmt_with_constant_cond() {
+    let code = r#"
+        MAKEGLOBAL A = 10
+
+        IF 1 < 2 [
+            MAKE A = 20
+        ]
+        MAKEGLOBAL B = A + 1
+    "#;
+
+    let actual = compile_optimized_cfg_obj!(code);
+
+    // the `IF` true-block and the after-node are merged into the entry node
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(10),
+            store_ins!(1),
+            int_ins!(20),
+            store_ins!(1),
+            load_ins!(1),
+            int_ins!(1),
+            add_ins!(),
+            store_ins!(2),
+            eoc_ins!()
+        )
+    };
+
+    assert_eq!(expected.nodes, actual.graph.nodes);
+    assert_eq!(vec![(1, 2)], actual.source_map.get_line_stmts(5));
+    assert_eq!(vec![(1, 4)], actual.source_map.get_line_stmts(7));
+}
+
+#[test]
+fn compile_optimized_cfg_graph_if_stmt_with_constant_false_cond() {
+    let code = r#"
+        MAKEGLOBAL A = 10
+
+        IF 2 < 1 [MAKE A = 20] [MAKE A = 30]
+
+        MAKEGLOBAL B = A + 1
+    "#;
+
+    let actual = compile_optimized_cfg_graph!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(10),
+            store_ins!(1),
+            int_ins!(30),
+            store_ins!(1),
+            load_ins!(1),
+            int_ins!(1),
+            add_ins!(),
+            store_ins!(2),
+            eoc_ins!()
+        )
+    };
+
+    assert_eq!(expected.nodes, actual.nodes);
+}
+
+#[test]
+fn compile_optimized_cfg_graph_jump_threading() {
+    let code = r#"
+        MAKEGLOBAL A = 10
+        MAKEGLOBAL B = 20
+
+        IF A < 2 [FORWARD 10] [IF B < 2 [FORWARD 20] [FORWARD 30]]
+
+        MAKEGLOBAL C = 1
+    "#;
+
+    let actual = compile_optimized_cfg_graph!(code);
+
+    // the empty node `6` (after the inner `IF`) has been skipped and removed
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(10),
+            store_ins!(1),
+            int_ins!(20),
+            store_ins!(2),
+            load_ins!(1),
+            int_ins!(2),
+            lt_ins!()
+        ),
+        node!(2,
+            int_ins!(10),
+            direct_ins!(FORWARD)
+        ),
+        node!(3,
+            load_ins!(2),
+            int_ins!(2),
+            lt_ins!()
+        ),
+        node!(4,
+            int_ins!(20),
+            direct_ins!(FORWARD)
+        ),
+        node!(5,
+            int_ins!(30),
+            direct_ins!(FORWARD)
+        ),
+        node!(7,
+            int_ins!(1),
+            store_ins!(3),
+            eoc_ins!()
+        ),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_true_jmp!(3, 4),
+        edge_fallback_jmp!(3, 5),
+        edge_always_jmp!(2, 7),
+        edge_always_jmp!(4, 7),
+        edge_always_jmp!(5, 7)
+    };
+
+    assert_eq!(expected.nodes, actual.nodes);
+}
+
+#[test]
+fn compile_optimized_cfg_graph_repeat_stmt() {
+    let code = r#"
+        REPEAT 1 + 1 [
+            FORWARD 10
+        ]
+    "#;
+
+    let actual = compile_optimized_cfg_graph!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(0),
+            store_ins!(1),  // TMPVAR_A = 0
+            int_ins!(2),
+            store_ins!(2),  // TMPVAR_B = 2
+            load_ins!(1),
+            load_ins!(2),
+            lt_ins!()
+        ),
+        node!(2,
+            int_ins!(10),
+            direct_ins!(FORWARD),
+            load_ins!(1),
+            int_ins!(1),
+            add_ins!(),
+            store_ins!(1),
+            load_ins!(1),
+            load_ins!(2),
+            lt_ins!()
+        ),
+        node!(3,
+            eoc_ins!()
+        ),
+        edge_true_jmp!(1, 2),
+        edge_true_jmp!(2, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_fallback_jmp!(2, 3)
+    };
+
+    assert_eq!(expected.nodes, actual.nodes);
+}
+
+#[test]
+fn compile_optimized_cfg_graph_removes_unreachable_nodes() {
+    let code = r#"
+        TO RECUR_PROC(I: INT, N: INT, ACC: INT): INT
+            IF I < N [RETURN RECUR_PROC(I + 1, N, ACC * (I + 1))] [RETURN ACC]
+        END
+        RECUR_PROC(0, 5, 1)
+    "#;
+
+    let actual = compile_optimized_cfg_obj!(code);
+
+    // the node `5` is unreachable since both `IF` blocks end with `RETURN`
+    assert_eq!(vec![1, 2, 3, 4], actual.graph.get_nodes_ids());
+
+    let expected_jmp_table = hashmap! { 1 => SymbolId(0), 2 => SymbolId(1) };
+    assert_eq!(expected_jmp_table, actual.jmp_table);
+}
+
+#[test]
+fn compile_optimized_cfg_graph_inlining() {
+    let code = r#"
+        TO DOUBLE(A: INT): INT
+            MAKELOCAL B = A * 2
+            RETURN B
+        END
+
+        MAKEGLOBAL C = DOUBLE(5) + DOUBLE(1)
+    "#;
+
+    let actual = compile_optimized_cfg_graph!(code);
+
+    // each inlined call allocates `main` locals for `A` and `B` (`#5, #6` and `#7, #8`)
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(5),
+            store_ins!(5),  // A = 5
+            load_ins!(5),
+            int_ins!(2),
+            mul_ins!(),
+            store_ins!(6),  // B = A * 2
+            load_ins!(6),   // RETURN B
+            int_ins!(1),
+            store_ins!(7),  // A = 1
+            load_ins!(7),
+            int_ins!(2),
+            mul_ins!(),
+            store_ins!(8),  // B = A * 2
+            load_ins!(8),   // RETURN B
+            add_ins!(),
+            store_ins!(2),  // C = DOUBLE(5) + DOUBLE(1)
+            eoc_ins!()
+        ),
+        node!(2,
+            load_ins!(3),
+            int_ins!(2),
+            mul_ins!(),
+            store_ins!(4),
+            load_ins!(4),
+            ret_ins!()
+        )
+    };
+
+    assert_eq!(expected.nodes, actual.nodes);
+}
+
+#[test]
+fn compile_optimized_cfg_graph_tail_call() {
+    let code = r#"
+        TO SPIRAL(N: INT, MAX: INT)
+            FORWARD N
+            IF N < MAX [SPIRAL(N + 1, MAX)]
+        END
+
+        SPIRAL(0, 10)
+    "#;
+
+    let actual = compile_optimized_cfg_graph!(code);
+
+    let expected = cfg_graph! {
+        node!(1,
+            int_ins!(0),
+            int_ins!(10),
+            call_ins!(2),
+            eoc_ins!()
+        ),
+        node!(2,
+            load_ins!(2),
+            direct_ins!(FORWARD),
+            load_ins!(2),
+            load_ins!(3),
+            lt_ins!()
+        ),
+        node!(3,
+            load_ins!(2),
+            int_ins!(1),
+            add_ins!(),
+            load_ins!(3),
+            store_ins!(3),  // MAX = MAX
+            store_ins!(2)   // N = N + 1
+        ),
+        node!(4,
+            ret_ins!()
+        ),
+        edge_true_jmp!(2, 3),
+        edge_fallback_jmp!(2, 4),
+        edge_always_jmp!(3, 2)  // jumping back instead of `SPIRAL(N + 1, MAX)`
+    };
+
+    assert_eq!(expected.nodes, actual.nodes);
+}
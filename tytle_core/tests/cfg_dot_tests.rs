@@ -0,0 +1,58 @@
+extern crate tytle;
+
+use tytle::compiler::compile;
+
+#[test]
+fn cfg_dot_repeat_within_proc() {
+    let (env, cfg) = compile(
+        r#"
+        TO LINE(N: INT)
+            REPEAT N [FORWARD 1]
+        END
+
+        LINE(3)
+    "#,
+    )
+    .unwrap();
+
+    let expected = r#"digraph cfg {
+    node [shape=record, fontname="monospace"];
+    subgraph cluster_0 {
+        label="__main__";
+        n1 [label="{node 1|int 3\lcall @2 LINE\leoc\l}", style="filled", fillcolor="lightblue"];
+    }
+    subgraph cluster_1 {
+        label="LINE";
+        n2 [label="{node 2|int 0\lstore $TMP3#3\lload N#2\lstore $TMP4#4\lload $TMP3#3\lload $TMP4#4\llt\l}"];
+        n3 [label="{node 3|int 1\ldirect FORWARD\lload $TMP3#3\lint 1\ladd\lstore $TMP3#3\lload $TMP3#3\lload $TMP4#4\llt\l}"];
+        n4 [label="{node 4|return\l}"];
+    }
+    n2 -> n3 [label="true", color="darkgreen"];
+    n2 -> n4 [label="false", color="red", style="dashed"];
+    n3 -> n3 [label="true", color="darkgreen"];
+    n3 -> n4 [label="false", color="red", style="dashed"];
+}
+"#;
+
+    assert_eq!(expected, cfg.to_dot(&env));
+}
+
+#[test]
+fn cfg_dot_if_branches_and_escaping() {
+    let (env, cfg) = compile(
+        r#"
+        MAKEGLOBAL A = 1
+        IF A > 0 [LABEL "<A|B>"] [PENUP]
+    "#,
+    )
+    .unwrap();
+
+    let dot = cfg.graph.to_dot(&env);
+
+    assert!(!dot.contains("subgraph"));
+    assert!(dot.contains(r#"str \"\<A\|B\>\"\l"#));
+    assert!(dot.contains(r#"n1 -> n2 [label="true", color="darkgreen"];"#));
+    assert!(dot.contains(r#"n1 -> n3 [label="false", color="red", style="dashed"];"#));
+    assert!(dot.contains("n2 -> n4;"));
+    assert!(dot.contains("n3 -> n4;"));
+}
@@ -0,0 +1,241 @@
+extern crate tytle;
+
+use std::collections::HashSet;
+use tytle::ast::semantic::*;
+use tytle::compiler::compile;
+use tytle::ir::*;
+use tytle::vm::*;
+
+macro_rules! run_cfg {
+    ($cfg: expr, $env: expr) => {{
+        let mut host = DummyHost::new();
+        let mut intr = Interpreter::new(&$cfg, &$env, &mut host);
+        let res = intr.exec_code();
+        drop(intr);
+
+        let log: Vec<String> = host.get_log().iter().map(|line| line.to_string()).collect();
+
+        (res, log)
+    }};
+}
+
+// converts `code` into SSA form and back, the program is expected to run the same way as before
+macro_rules! assert_ssa_roundtrip {
+    ($code: expr, $optimize: expr) => {{
+        let (mut env, mut cfg) = compile($code).unwrap();
+
+        if $optimize {
+            CfgOptimizer::new().optimize(&mut cfg, &mut env);
+        }
+
+        let expected = run_cfg!(cfg, env);
+
+        let ssa = SsaForm::build(&mut cfg, &mut env);
+        assert_single_assignment(&cfg, &ssa);
+
+        ssa.destruct(&mut cfg);
+
+        assert_eq!(expected, run_cfg!(cfg, env));
+    }};
+}
+
+// each variable version is defined once (either by a `STORE` or by a phi)
+fn assert_single_assignment(cfg: &CfgObject, ssa: &SsaForm) {
+    let mut defined = HashSet::new();
+
+    for node_id in cfg.graph.get_nodes_ids() {
+        for phi in ssa.get_phis(node_id) {
+            if ssa.versions.contains_key(&phi.var_id) {
+                assert!(defined.insert(phi.var_id));
+            }
+        }
+
+        for inst in cfg.graph.get_node(node_id).insts.iter() {
+            if let CfgInstruction::Store(var_id) = inst {
+                if ssa.versions.contains_key(var_id) {
+                    assert!(defined.insert(*var_id));
+                }
+            }
+        }
+    }
+}
+
+fn var_name(env: &Environment, var_id: SymbolId) -> String {
+    env.symbol_table.get_var_by_id(var_id).name.clone()
+}
+
+#[test]
+fn cfg_ssa_loop_phis() {
+    let (mut env, mut cfg) = compile(
+        r#"
+        TO SUM(N: INT): INT
+            MAKELOCAL I = 0
+            MAKELOCAL S = 0
+
+            REPEAT N [
+                MAKE I = I + 1
+                MAKE S = S + I
+            ]
+
+            RETURN S
+        END
+
+        PRINT SUM(4)
+    "#,
+    )
+    .unwrap();
+
+    let ssa = SsaForm::build(&mut cfg, &mut env);
+
+    let mut phis_nodes: Vec<CfgNodeId> = ssa.phis.keys().cloned().collect();
+    phis_nodes.sort();
+
+    // the definitions meet at the loop header and after the loop
+    assert_eq!(vec![3, 4], phis_nodes);
+
+    let header_id = 3;
+    let mut phis_names: Vec<String> = ssa
+        .get_phis(header_id)
+        .iter()
+        .map(|phi| var_name(&env, phi.var_id))
+        .collect();
+    phis_names.sort();
+
+    // the REPEAT counter (a temporary) along with `I` and `S`
+    assert_eq!(3, phis_names.len());
+    assert!(phis_names.contains(&"I.2".to_string()));
+    assert!(phis_names.contains(&"S.2".to_string()));
+
+    // only `S` is live after the loop
+    assert_eq!(1, ssa.get_phis(4).len());
+    assert_eq!("S.4", var_name(&env, ssa.get_phis(4)[0].var_id));
+
+    for phi in ssa.get_phis(header_id) {
+        let orig_id = ssa.original_var(phi.var_id);
+
+        // entering the loop, and looping back
+        assert_eq!(2, phi.args.len());
+
+        for (_, arg_id) in phi.args.iter() {
+            assert_eq!(orig_id, ssa.original_var(*arg_id));
+            assert_ne!(phi.var_id, *arg_id);
+        }
+    }
+
+    assert_single_assignment(&cfg, &ssa);
+}
+
+#[test]
+fn cfg_ssa_pruned_phis() {
+    let (mut env, mut cfg) = compile(
+        r#"
+        TO SHOW(A: INT)
+            MAKELOCAL B = 0
+
+            IF A > 1 [MAKE B = 1] [MAKE B = 2]
+            PRINT A
+
+            IF A > 2 [MAKE B = 3] [MAKE B = 4]
+            PRINT B
+        END
+
+        SHOW(3)
+    "#,
+    )
+    .unwrap();
+
+    let ssa = SsaForm::build(&mut cfg, &mut env);
+
+    // `B` isn't live after the first `IF`, so only the second `IF` needs a phi
+    let phis: Vec<&SsaPhi> = ssa.phis.values().flatten().collect();
+
+    assert_eq!(1, phis.len());
+    assert_eq!("B.6", var_name(&env, phis[0].var_id));
+}
+
+#[test]
+fn cfg_ssa_skips_globals_and_captured_locals() {
+    let (mut env, mut cfg) = compile(
+        r#"
+        MAKEGLOBAL G = 0
+
+        TO SQUARE(SIZE: INT)
+            MAKELOCAL SIDES = 0
+
+            TO SIDE()
+                FORWARD SIZE
+                MAKE SIDES = SIDES + 1
+            END
+
+            REPEAT 4 [SIDE()]
+            MAKE G = SIDES
+        END
+
+        SQUARE(10)
+    "#,
+    )
+    .unwrap();
+
+    let ssa = SsaForm::build(&mut cfg, &mut env);
+
+    let originals: HashSet<String> = ssa
+        .versions
+        .values()
+        .map(|var_id| var_name(&env, *var_id))
+        .collect();
+
+    assert!(!originals.contains("G"));
+    assert!(!originals.contains("SIZE"));
+    assert!(!originals.contains("SIDES"));
+}
+
+#[test]
+fn cfg_ssa_roundtrip_loops_and_branches() {
+    let code = r#"
+        TO SUM(N: INT): INT
+            MAKELOCAL I = 0
+            MAKELOCAL S = 0
+
+            REPEAT N [
+                MAKE I = I + 1
+
+                IF I > 2 [
+                    MAKE S = S + I * 10
+                ] [
+                    MAKE S = S + I
+                ]
+            ]
+
+            RETURN S
+        END
+
+        TO SHAPE(SIDE: INT, TIMES: INT)
+            MAKELOCAL ANGLE = 90
+            REPEAT TIMES [
+                FORWARD SIDE
+                RIGHT ANGLE
+                MAKE SIDE = SIDE * 2
+            ]
+        END
+
+        PRINT SUM(5)
+        SHAPE(5, 4)
+    "#;
+
+    assert_ssa_roundtrip!(code, false);
+    assert_ssa_roundtrip!(code, true);
+}
+
+#[test]
+fn cfg_ssa_roundtrip_tail_calls() {
+    let code = r#"
+        TO SUM(I: INT, N: INT, ACC: INT): INT
+            IF I < N [RETURN SUM(I + 1, N, ACC + I)] [RETURN ACC]
+        END
+
+        PRINT SUM(0, 10, 0)
+    "#;
+
+    assert_ssa_roundtrip!(code, false);
+    assert_ssa_roundtrip!(code, true);
+}
@@ -0,0 +1,288 @@
+#[macro_use]
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+
+macro_rules! assert_ir_roundtrip {
+    ($code: expr, $optimize: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut env);
+        assert!(checker.check(&mut ast).is_ok());
+
+        let builder = CfgBuilder::new(&mut env);
+        let mut cfg_obj = builder.build(&ast);
+
+        if $optimize {
+            CfgOptimizer::new().optimize(&mut cfg_obj, &mut env);
+        }
+
+        let text = CfgPrinter::with_env(&env).print_object(&cfg_obj);
+        let graph = CfgTextParser.parse(&text).unwrap();
+
+        assert_eq!(cfg_obj.graph.nodes, graph.nodes);
+    }};
+}
+
+#[test]
+fn cfg_ir_print_proc_and_branches() {
+    let actual = compile_cfg_ir!(
+        r#"
+        MAKEGLOBAL A = 0
+
+        TO SHOW(N: INT)
+            PRINT N
+        END
+
+        IF A > 1 [PENUP] [SHOW(10)]
+        LABEL "DONE"
+    "#
+    );
+
+    let expected = r#"node 1 proc __main__#0:
+    int 0
+    store A#1
+    load A#1
+    int 1
+    gt
+    when_true @3
+    fallback @4
+node 2 proc SHOW#2:
+    load N#3
+    print
+    return
+node 3:
+    cmd PENUP
+    always @5
+node 4:
+    int 10
+    call @2 SHOW
+    always @5
+node 5:
+    str "DONE"
+    label
+    eoc
+"#;
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn cfg_ir_print_structs_and_proc_values() {
+    let actual = compile_cfg_ir!(
+        r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        TO TWICE(X: INT): INT
+            RETURN X * 2
+        END
+
+        MAKEGLOBAL P = POINT(1, 2)
+        MAKEGLOBAL F: PROC(INT): INT = TWICE
+        MAKE P.Y = F(P.X)
+    "#
+    );
+
+    let expected = r#"node 1 proc __main__#0:
+    int 1
+    int 2
+    new_struct POINT#1
+    store P#3
+    proc_ref @2 TWICE
+    store F#4
+    load P#3
+    load P#3
+    load_field 0
+    load F#4
+    call_indirect
+    store_field 1
+    eoc
+node 2 proc TWICE#2:
+    load X#5
+    int 2
+    mul
+    return
+"#;
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn cfg_ir_print_graph_without_env() {
+    let graph = cfg_graph! {
+        node!(1, int_ins!(1), store_ins!(2), load_ins!(2), bool_ins!(true)),
+        node!(2, str_ins!("HELLO"), direct_ins!(FORWARD), cmd_ins!(PENUP)),
+        node!(3, call_ins!(2), eoc_ins!()),
+        edge_true_jmp!(1, 2),
+        edge_fallback_jmp!(1, 3),
+        edge_always_jmp!(2, 3)
+    };
+
+    let expected = r#"node 1:
+    int 1
+    store #2
+    load #2
+    bool true
+    when_true @2
+    fallback @3
+node 2:
+    str "HELLO"
+    direct FORWARD
+    cmd PENUP
+    always @3
+node 3:
+    call @2
+    eoc
+"#;
+
+    assert_eq!(expected, CfgPrinter::new().print_graph(&graph));
+}
+
+#[test]
+fn cfg_ir_parse_snapshot() {
+    let text = r#"
+        node 1 proc __main__#0:
+            int 0
+            store I#1
+            always @2
+        node 2:
+            load I#1
+            int 3
+            lt
+            when_true @3
+            fallback @4
+        node 3:
+            load I#1
+            direct FORWARD
+            load I#1
+            int 1
+            add
+            store I#1
+            always @2
+        node 4:
+            eoc
+    "#;
+
+    let expected = cfg_graph! {
+        node!(1, int_ins!(0), store_ins!(1)),
+        node!(2, load_ins!(1), int_ins!(3), lt_ins!()),
+        node!(3, load_ins!(1), direct_ins!(FORWARD), load_ins!(1), int_ins!(1), add_ins!(), store_ins!(1)),
+        node!(4, eoc_ins!()),
+        edge_always_jmp!(1, 2),
+        edge_true_jmp!(2, 3),
+        edge_fallback_jmp!(2, 4),
+        edge_always_jmp!(3, 2)
+    };
+
+    let actual = CfgTextParser.parse(text).unwrap();
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn cfg_ir_roundtrip_loops_and_conditions() {
+    assert_ir_roundtrip!(
+        r#"
+        MAKEGLOBAL A = 0
+        REPEAT 3 [
+            IF A < 2 [
+                MAKE A = A + 1
+            ] [
+                PENUP
+            ]
+        ]
+    "#,
+        false
+    );
+}
+
+#[test]
+fn cfg_ir_roundtrip_nested_procs() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            MAKELOCAL SIDES = 0
+
+            TO SIDE()
+                FORWARD SIZE
+                RIGHT 90
+                MAKE SIDES = SIDES + 1
+            END
+
+            REPEAT 4 [SIDE()]
+            PRINT SIDES
+        END
+
+        TO COUNT(I: INT, N: INT): INT
+            IF I < N [RETURN COUNT(I + 1, N)][RETURN I]
+        END
+
+        SQUARE(10)
+        PRINT COUNT(0, 3)
+    "#;
+
+    assert_ir_roundtrip!(code, false);
+    assert_ir_roundtrip!(code, true);
+}
+
+#[test]
+fn cfg_ir_roundtrip_structs_and_strings() {
+    assert_ir_roundtrip!(
+        r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        MAKEGLOBAL P = POINT(1, 2)
+        MAKEGLOBAL S = "HELLO WORLD"
+
+        SETFONTSIZE P.X
+        LABEL S
+        TRAP
+    "#,
+        false
+    );
+}
+
+#[test]
+fn cfg_ir_parse_errors() {
+    let parse = |text: &str| CfgTextParser.parse(text).err().unwrap();
+
+    assert_eq!(CfgTextError::InstructionOutsideNode(1), parse("int 1"));
+    assert_eq!(CfgTextError::InvalidNodeHeader(1), parse("node X:"));
+    assert_eq!(CfgTextError::InvalidNodeHeader(1), parse("node 1"));
+    assert_eq!(
+        CfgTextError::DuplicateNode(3, 1),
+        parse("node 1:\n    eoc\nnode 1:")
+    );
+    assert_eq!(
+        CfgTextError::UnknownInstruction(2, "jump".to_string()),
+        parse("node 1:\n    jump")
+    );
+    assert_eq!(
+        CfgTextError::InvalidOperand(2, "A".to_string()),
+        parse("node 1:\n    load A")
+    );
+    assert_eq!(
+        CfgTextError::InvalidOperand(2, "2".to_string()),
+        parse("node 1:\n    call 2")
+    );
+    assert_eq!(
+        CfgTextError::InvalidOperand(2, "HELLO".to_string()),
+        parse("node 1:\n    str HELLO")
+    );
+    assert_eq!(
+        CfgTextError::InvalidOperand(2, "1".to_string()),
+        parse("node 1:\n    return 1")
+    );
+    assert_eq!(
+        CfgTextError::UnknownNode(2, 5),
+        parse("node 1:\n    always @5")
+    );
+
+    assert_eq!(
+        "line 2: jump to an unknown node `5`",
+        parse("node 1:\n    always @5").to_string()
+    );
+}
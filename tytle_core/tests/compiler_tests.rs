@@ -0,0 +1,247 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::compiler::*;
+use tytle::lexer::{Location, Token};
+use tytle::parser::*;
+
+macro_rules! assert_diagnostics {
+    ($code: expr, $expected: expr) => {{
+        let diagnostics = compile($code).err().unwrap();
+
+        let actual: Vec<String> = diagnostics.iter().map(|diag| diag.to_string()).collect();
+        let expected: Vec<&str> = $expected;
+
+        assert_eq!(expected, actual);
+    }};
+}
+
+#[test]
+fn compile_valid_program() {
+    let code = r#"
+        TO SQUARE(N: INT)
+            REPEAT 4 [
+                FORWARD N
+                RIGHT 90
+            ]
+        END
+
+        SQUARE(10)
+    "#;
+
+    assert!(compile(code).is_ok());
+}
+
+#[test]
+fn parse_recover_collects_errors_and_keeps_valid_statements() {
+    let code = "FORWARD )\nLEFT 10\nRIGHT ]\nBACKWARD 20";
+
+    let (ast, diagnostics) = TytleParser.parse_recover(code);
+
+    let actual: Vec<(String, Option<Location>)> = diagnostics
+        .iter()
+        .map(|diag| (diag.error.to_string(), diag.location))
+        .collect();
+
+    assert_eq!(
+        vec![
+            (
+                "Syntax error: `Invalid syntax: `)``".to_string(),
+                Some(Location(1, 1))
+            ),
+            (
+                "Syntax error: `Invalid syntax: `]``".to_string(),
+                Some(Location(3, 1))
+            ),
+        ],
+        actual
+    );
+
+    // the valid statements are kept in the best-effort AST
+    assert_eq!(2, ast.statements.len());
+}
+
+#[test]
+fn parse_recover_error_at_line_end_keeps_the_next_line() {
+    let code = "MAKEGLOBAL A = 1 +\nLEFT 10";
+
+    let (ast, diagnostics) = TytleParser.parse_recover(code);
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(1, ast.statements.len());
+}
+
+#[test]
+fn parse_recover_error_inside_block() {
+    let code = "REPEAT 3 [\n  FORWARD )\n  LEFT 10\n]\nRIGHT 20";
+
+    let (ast, diagnostics) = TytleParser.parse_recover(code);
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!(Some(Location(2, 3)), diagnostics[0].location);
+    assert_eq!(2, ast.statements.len());
+}
+
+#[test]
+fn parse_recover_stray_end() {
+    let code = "FORWARD 10\n  END\nLEFT 10";
+
+    let (ast, diagnostics) = TytleParser.parse_recover(code);
+
+    assert_eq!(1, diagnostics.len());
+    assert_eq!("Unexpected token: `END`", diagnostics[0].error.to_string());
+    assert_eq!(Some(Location(2, 3)), diagnostics[0].location);
+    assert_eq!(2, ast.statements.len());
+
+    let expected = ParseError::StrayToken(Token::VALUE("END".to_string()));
+    assert_eq!(expected, TytleParser.parse(code).err().unwrap());
+}
+
+#[test]
+fn parse_still_returns_the_first_error() {
+    let code = "FORWARD )\nRIGHT ]";
+
+    let actual = TytleParser.parse(code).err().unwrap();
+    let expected = ParseError::Syntax {
+        message: "Invalid syntax: `)`".to_string(),
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn generate_recover_collects_errors() {
+    let code = "MAKEGLOBAL B = 1\nMAKEGLOBAL B = 2\nFOO()\nPRINT X";
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let (_env, diagnostics) = SymbolTableGenerator::new().generate_recover(&mut ast);
+
+    let actual: Vec<String> = diagnostics.iter().map(|diag| diag.to_string()).collect();
+
+    assert_eq!(
+        vec![
+            "[2:1] Duplicate global var: `B`",
+            "[3:1] Missing procedure declaration for `FOO`",
+            "[4:1] Missing variable declaration for `X`",
+        ],
+        actual
+    );
+}
+
+#[test]
+fn check_recover_collects_errors() {
+    let code = "FORWARD TRUE\nRIGHT 10\nIF 1 [ LEFT 10 ]";
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    let diagnostics = AstTypeCheck::new(&mut env).check_recover(&mut ast);
+
+    let actual: Vec<String> = diagnostics.iter().map(|diag| diag.to_string()).collect();
+
+    assert_eq!(
+        vec![
+            "[1:1] Expression `TRUE` isn't an Integer expression",
+            "[3:1] Expression `1` isn't a Boolean expression",
+        ],
+        actual
+    );
+}
+
+#[test]
+fn compile_reports_errors_of_all_passes_sorted_by_location() {
+    let code = "PRINT X\nFORWARD TRUE\nRIGHT )\nTO F(A: INT, A: INT)\nEND";
+
+    assert_diagnostics!(
+        code,
+        vec![
+            "[1:1] Missing variable declaration for `X`",
+            "[2:1] Expression `TRUE` isn't an Integer expression",
+            "[3:1] Syntax error: `Invalid syntax: `)``",
+            "[4:1] Duplicate procedure param: `A` (procedure: `F`)",
+        ]
+    );
+}
+
+#[test]
+fn compile_no_cascading_errors_for_unknown_var() {
+    let code = "MAKEGLOBAL A = B\nPRINT A + 1\nMAKE A = 3\nFORWARD A";
+
+    assert_diagnostics!(code, vec!["[1:1] Missing variable declaration for `B`"]);
+}
+
+#[test]
+fn compile_no_cascading_errors_for_unknown_proc_type() {
+    let code = "TO F(A: FOO)\nEND\nF(1)\nF(2)\nTO G(): BAR\n  RETURN 1\nEND\nMAKEGLOBAL X = G()\nFORWARD X";
+
+    assert_diagnostics!(
+        code,
+        vec![
+            "[1:1] Missing type declaration for `FOO`",
+            "[5:1] Missing type declaration for `BAR`",
+        ]
+    );
+}
+
+#[test]
+fn compile_no_cascading_errors_for_unknown_var_type() {
+    let code = "MAKEGLOBAL A: FOO = 1\nMAKE A = 2\nFORWARD A";
+
+    assert_diagnostics!(code, vec!["[1:1] Missing type declaration for `FOO`"]);
+
+    let code = "TO F()\n  MAKELOCAL B: FOO = 1\n  MAKE B = 2\n  FORWARD B\nEND\nF()";
+
+    assert_diagnostics!(code, vec!["[2:3] Missing type declaration for `FOO`"]);
+}
+
+#[test]
+fn compile_recursive_param_default_value() {
+    let code = "TO F(A: INT = F()): INT\n  RETURN A\nEND\nPRINT F()";
+
+    assert_diagnostics!(
+        code,
+        vec!["[1:1] The default value of param `A` calls procedure `F` recursively"]
+    );
+}
+
+#[test]
+fn compile_errors_inside_procedure() {
+    let code = r#"TO F(): INT
+  PRINT Y
+  RETURN TRUE
+END
+F()"#;
+
+    assert_diagnostics!(
+        code,
+        vec![
+            "[2:3] Missing variable declaration for `Y`",
+            "[3:3] Invalid return type. expected: `Integer`, actual: `Boolean`",
+        ]
+    );
+}
+
+#[test]
+fn compile_return_paths_errors() {
+    let code = r#"TO F(A: INT): INT
+  IF A > 0 [
+    RETURN 1
+    FORWARD 10
+    LEFT 10
+  ] [
+    RETURN 2
+  ]
+  RIGHT 10
+END
+TO G(): BOOL
+  PRINT 1
+END"#;
+
+    assert_diagnostics!(
+        code,
+        vec![
+            "[4:5] Unreachable statement (procedure: `F`)",
+            "[9:3] Unreachable statement (procedure: `F`)",
+            "[11:1] Not all paths return a value (procedure: `G`)",
+        ]
+    );
+}
@@ -0,0 +1,316 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+macro_rules! setup_debugger {
+    ($code: expr, $env: ident, $cfg: ident, $host: ident, $dbg: ident) => {
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut $env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut $env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut $env);
+        let $cfg = builder.build(&ast);
+
+        let mut $host = DummyHost::new();
+        let mut $dbg = Debugger::new(&$cfg, &$env, &mut $host);
+    };
+}
+
+macro_rules! current_line {
+    ($dbg: expr) => {{
+        $dbg.get_location().unwrap().line()
+    }};
+}
+
+#[test]
+fn debugger_run_without_breakpoints() {
+    let code = r#"
+        FORWARD 10
+        FORWARD 20
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+    assert!(dbg.is_finished());
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+    assert!(dbg.get_frames().is_empty());
+
+    drop(dbg);
+    assert_eq!((0, 30), host.xycors());
+}
+
+#[test]
+fn debugger_pauses_after_trap() {
+    let code = r#"
+        FORWARD 10
+        TRAP
+        FORWARD 20
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    match dbg.run() {
+        Ok(DebugEvent::Trap(..)) => {}
+        other => panic!("unexpected debug event: {:?}", other),
+    }
+    assert_eq!(4, current_line!(dbg));
+
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+
+    drop(dbg);
+    assert_eq!((0, 30), host.xycors());
+}
+
+#[test]
+fn debugger_line_breakpoint() {
+    let code = r#"
+        FORWARD 10
+        FORWARD 20
+        FORWARD 30
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert!(dbg.add_line_breakpoint(3));
+    assert!(!dbg.add_line_breakpoint(100));
+
+    match dbg.run() {
+        Ok(DebugEvent::Breakpoint(..)) => {}
+        other => panic!("unexpected debug event: {:?}", other),
+    }
+    assert_eq!(3, current_line!(dbg));
+
+    assert!(dbg.remove_line_breakpoint(3));
+    assert!(dbg.get_breakpoints().is_empty());
+
+    drop(dbg);
+    assert_eq!((0, 10), host.xycors());
+}
+
+#[test]
+fn debugger_breakpoint_at_first_line() {
+    let code = "FORWARD 10\nFORWARD 20";
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert!(dbg.add_line_breakpoint(1));
+
+    match dbg.run() {
+        Ok(DebugEvent::Breakpoint(..)) => {}
+        other => panic!("unexpected debug event: {:?}", other),
+    }
+    assert_eq!(1, current_line!(dbg));
+
+    // resuming from the breakpoint doesn't hit it once more
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+
+    drop(dbg);
+    assert_eq!((0, 30), host.xycors());
+}
+
+#[test]
+fn debugger_breakpoint_at_paused_line() {
+    let code = r#"
+        FORWARD 10
+        FORWARD 20
+        FORWARD 30
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert_eq!(Ok(DebugEvent::Step), dbg.step_into());
+    assert_eq!(3, current_line!(dbg));
+
+    // a breakpoint added at the paused line is hit by the next `run`
+    assert!(dbg.add_line_breakpoint(3));
+
+    match dbg.run() {
+        Ok(DebugEvent::Breakpoint(..)) => {}
+        other => panic!("unexpected debug event: {:?}", other),
+    }
+    assert_eq!(3, current_line!(dbg));
+
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+
+    drop(dbg);
+    assert_eq!((0, 60), host.xycors());
+}
+
+#[test]
+fn debugger_breakpoint_inside_loop_hits_each_iteration() {
+    let code = r#"
+        MAKEGLOBAL A = 0
+        REPEAT 3 [
+            MAKE A = A + 1
+        ]
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    dbg.add_line_breakpoint(4);
+
+    for i in 0..3 {
+        match dbg.run() {
+            Ok(DebugEvent::Breakpoint(..)) => {}
+            other => panic!("unexpected debug event: {:?}", other),
+        }
+
+        let globals = dbg.get_globals();
+        assert_eq!("A", globals[0].name);
+        assert_eq!(MemoryValue::Int(i), globals[0].value);
+    }
+
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+}
+
+#[test]
+fn debugger_step_into_and_out() {
+    let code = r#"
+        TO MOVE(X: INT)
+            FORWARD X
+            FORWARD X
+        END
+
+        MOVE(10)
+        FORWARD 1
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert_eq!(Ok(DebugEvent::Step), dbg.step_into());
+    assert_eq!(3, current_line!(dbg));
+
+    let frames = dbg.get_frames();
+    assert_eq!(2, frames.len());
+    assert_eq!("MOVE", frames[0].proc_name);
+    assert_eq!("__main__", frames[1].proc_name);
+    assert_eq!(7, frames[1].location.unwrap().line());
+    assert_eq!(
+        vec![DebugVariable {
+            name: "X".to_string(),
+            value: MemoryValue::Int(10)
+        }],
+        frames[0].variables
+    );
+
+    assert_eq!(Ok(DebugEvent::Step), dbg.step_into());
+    assert_eq!(4, current_line!(dbg));
+
+    assert_eq!(Ok(DebugEvent::Step), dbg.step_out());
+    assert_eq!(8, current_line!(dbg));
+    assert_eq!(1, dbg.get_frames().len());
+
+    drop(dbg);
+    assert_eq!((0, 20), host.xycors());
+}
+
+#[test]
+fn debugger_step_over_proc_call() {
+    let code = r#"
+        TO MOVE()
+            FORWARD 10
+        END
+
+        MOVE()
+        MOVE()
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert_eq!(6, current_line!(dbg));
+
+    assert_eq!(Ok(DebugEvent::Step), dbg.step_over());
+    assert_eq!(7, current_line!(dbg));
+
+    assert_eq!(Ok(DebugEvent::Finished), dbg.step_over());
+
+    drop(dbg);
+    assert_eq!((0, 20), host.xycors());
+}
+
+#[test]
+fn debugger_step_over_stops_at_breakpoint_inside_proc() {
+    let code = r#"
+        TO MOVE()
+            FORWARD 10
+        END
+
+        MOVE()
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    dbg.add_line_breakpoint(3);
+
+    match dbg.step_over() {
+        Ok(DebugEvent::Breakpoint(..)) => {}
+        other => panic!("unexpected debug event: {:?}", other),
+    }
+    assert_eq!(3, current_line!(dbg));
+    assert_eq!("MOVE", dbg.get_frames()[0].proc_name);
+}
+
+#[test]
+fn debugger_suspend_and_resume() {
+    let code = r#"
+        FORWARD 10
+        FORWARD 20
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    assert_eq!(Ok(DebugEvent::Step), dbg.step_into());
+    let state = dbg.suspend();
+
+    let mut dbg = Debugger::resume(&cfg, &env, &mut host, state);
+    assert_eq!(3, current_line!(dbg));
+    assert_eq!(Ok(DebugEvent::Finished), dbg.run());
+}
+
+#[test]
+fn debugger_struct_global_fields() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        MAKEGLOBAL P = POINT(10, 20)
+        MAKE P.Y = 30
+        TRAP
+    "#;
+
+    setup_debugger!(code, env, cfg, host, dbg);
+
+    match dbg.run() {
+        Ok(DebugEvent::Trap(..)) => {}
+        other => panic!("unexpected debug event: {:?}", other),
+    }
+
+    let globals = dbg.get_globals();
+    assert_eq!("P", globals[0].name);
+
+    let struct_ref = match globals[0].value {
+        MemoryValue::Struct(struct_ref) => struct_ref,
+        ref other => panic!("expected a record. got: {:?}", other),
+    };
+
+    let expected = vec![
+        DebugVariable {
+            name: "X".to_string(),
+            value: MemoryValue::Int(10),
+        },
+        DebugVariable {
+            name: "Y".to_string(),
+            value: MemoryValue::Int(30),
+        },
+    ];
+
+    assert_eq!(Some(expected), dbg.get_struct_fields(struct_ref));
+}
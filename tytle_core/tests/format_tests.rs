@@ -0,0 +1,415 @@
+extern crate tytle;
+
+use tytle::ast::expression::*;
+use tytle::ast::statement::*;
+use tytle::ast::Ast;
+use tytle::compiler::*;
+use tytle::parser::{Parser, TytleParser};
+
+macro_rules! assert_format {
+    ($code: expr, $expected: expr) => {{
+        let formatted = format_code($code).unwrap();
+        assert_eq!($expected, formatted);
+
+        // formatting is idempotent, and doesn't change the AST
+        assert_eq!(Ok(true), is_formatted(&formatted).map_err(|_| ()));
+        assert_eq!(
+            TytleParser.parse($code).unwrap(),
+            TytleParser.parse(&formatted).unwrap()
+        );
+    }};
+}
+
+#[test]
+fn format_indents_blocks() {
+    let code = r#"
+TO SQUARE(SIZE: INT = 50, N: INT):INT
+REPEAT 4 [FORWARD SIZE RIGHT 90]
+   IF SIZE > 10 [RETURN SIZE][
+  RETURN N + (1 + 2) * 3]
+END
+MAKEGLOBAL  A:INT=SQUARE(N: 10)
+REPEAT 2 []
+"#;
+
+    let expected = r#"TO SQUARE(SIZE: INT = 50, N: INT): INT
+  REPEAT 4 [
+    FORWARD SIZE
+    RIGHT 90
+  ]
+  IF SIZE > 10 [
+    RETURN SIZE
+  ][
+    RETURN N + (1 + 2) * 3
+  ]
+END
+
+MAKEGLOBAL A: INT = SQUARE(N: 10)
+REPEAT 2 []
+"#;
+
+    assert_format!(code, expected);
+}
+
+#[test]
+fn format_preserves_comments() {
+    let code = r#"; a square
+TO SQUARE() ;   no params
+  ; the sides
+  REPEAT 4 [FORWARD 10] ; done
+  ; a comment before `END`
+END
+PRINT "A;B" ; a trailing comment
+; the last comment"#;
+
+    let expected = r#"; a square
+TO SQUARE() ;   no params
+  ; the sides
+  REPEAT 4 [
+    FORWARD 10
+  ] ; done
+  ; a comment before `END`
+END
+
+PRINT "A;B" ; a trailing comment
+; the last comment
+"#;
+
+    assert_format!(code, expected);
+}
+
+#[test]
+fn format_collapses_blank_lines() {
+    let code = "\n\nSTRUCT POINT [\n  X: INT\n  Y: INT\n]\nPENUP\n\n\n\nHALT\n\n";
+    let expected = "STRUCT POINT [X: INT, Y: INT]\n\nPENUP\n\nHALT\n";
+
+    assert_format!(code, expected);
+}
+
+#[test]
+fn format_empty_code() {
+    assert_format!("", "");
+    assert_format!("\n ; only a comment \n", "; only a comment\n");
+}
+
+#[test]
+fn format_parse_error() {
+    let res = format_code("REPEAT 4 [\n  FORWARD 10\n");
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn format_check() {
+    assert_eq!(Ok(true), is_formatted("PENUP\n").map_err(|_| ()));
+    assert_eq!(Ok(false), is_formatted("PENUP").map_err(|_| ()));
+    assert_eq!(Ok(false), is_formatted("  PENUP\n").map_err(|_| ()));
+}
+
+// a xorshift generator, so that the property tests are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 % n as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.next(items.len())]
+    }
+}
+
+const NAMES: &[&str] = &["A", "B2", "SIZE", "MY_VAR"];
+const TYPES: &[&str] = &[
+    "INT",
+    "STR",
+    "BOOL",
+    "POINT",
+    "PROC()",
+    "PROC(INT, STR): INT",
+];
+
+fn new_expr(ast: ExpressionAst) -> Expression {
+    Expression::new(ast)
+}
+
+fn gen_atom(rng: &mut Rng, depth: usize) -> Expression {
+    let var = || LiteralExpr::Var(NAMES[0].to_string(), None);
+
+    let ast = match rng.next(if depth == 0 { 5 } else { 8 }) {
+        0 => ExpressionAst::Literal(LiteralExpr::Int(rng.next(1000))),
+        1 => ExpressionAst::Literal(LiteralExpr::Bool(rng.next(2) == 0)),
+        2 => ExpressionAst::Literal(LiteralExpr::Str("HELLO ; WORLD".to_string())),
+        3 => ExpressionAst::Literal(LiteralExpr::Var(rng.pick(NAMES).to_string(), None)),
+        4 => ExpressionAst::Field(
+            Box::new(new_expr(ExpressionAst::Literal(var()))),
+            rng.pick(NAMES).to_string(),
+        ),
+        5 => gen_call(rng, depth - 1).expr_ast,
+        6 => ExpressionAst::IndirectCall(
+            Box::new(new_expr(ExpressionAst::Literal(var()))),
+            vec![gen_expr(rng, depth - 1)],
+        ),
+        _ => ExpressionAst::Parentheses(Box::new(gen_expr(rng, depth - 1))),
+    };
+
+    new_expr(ast)
+}
+
+// the named arguments come after the positional ones
+fn gen_call(rng: &mut Rng, depth: usize) -> Expression {
+    let mut args = Vec::new();
+
+    for _ in 0..rng.next(3) {
+        args.push(gen_expr(rng, depth));
+    }
+
+    if rng.next(2) == 0 {
+        let arg = gen_expr(rng, depth);
+        args.push(new_expr(ExpressionAst::NamedArg(
+            rng.pick(NAMES).to_string(),
+            Box::new(arg),
+        )));
+    }
+
+    let name = rng.pick(&["DRAW", "MOVE_TO"]).to_string();
+
+    new_expr(ExpressionAst::ProcCall(name, args, None))
+}
+
+// the operands of a binary expression are atoms (nesting is done with parentheses)
+fn gen_expr(rng: &mut Rng, depth: usize) -> Expression {
+    match rng.next(4) {
+        0 => {
+            let ops = [
+                BinaryOp::And,
+                BinaryOp::Or,
+                BinaryOp::Add,
+                BinaryOp::Mul,
+                BinaryOp::Div,
+                BinaryOp::GreaterThan,
+                BinaryOp::LessThan,
+            ];
+
+            let op = ops[rng.next(ops.len())].clone();
+            let lexpr = gen_atom(rng, depth);
+            let rexpr = gen_atom(rng, depth);
+
+            new_expr(ExpressionAst::Binary(op, Box::new(lexpr), Box::new(rexpr)))
+        }
+        1 => new_expr(ExpressionAst::Not(Box::new(gen_atom(rng, depth)))),
+        _ => gen_atom(rng, depth),
+    }
+}
+
+fn gen_block(rng: &mut Rng, depth: usize) -> BlockStatement {
+    let mut block = BlockStatement::new();
+
+    for _ in 0..rng.next(4) {
+        block.add_statement(gen_stmt(rng, depth));
+    }
+
+    block
+}
+
+fn gen_stmt(rng: &mut Rng, depth: usize) -> Statement {
+    let kinds = if depth == 0 { 12 } else { 15 };
+
+    match rng.next(kinds) {
+        0 => Statement::Expression(gen_call(rng, 1)),
+        1 => Statement::Print(gen_expr(rng, 2)),
+        2 => Statement::Label(gen_expr(rng, 2)),
+        3 => Statement::SetFontSize(gen_expr(rng, 2)),
+        4 => {
+            let cmd = Command::parse(rng.pick(&["PENUP", "XCOR", "CLEAN", "WAIT", "STOP"]));
+            let cmd = if rng.next(4) == 0 {
+                Command::Trap
+            } else {
+                cmd.unwrap()
+            };
+
+            Statement::Command(cmd)
+        }
+        5 => {
+            let directions = ["FORWARD", "BACKWARD", "LEFT", "RIGHT", "SETX", "SETY"];
+            let direction = std::convert::TryFrom::try_from(directions[rng.next(6)]).unwrap();
+
+            Statement::Direction(DirectionStmt {
+                direction,
+                expr: gen_expr(rng, 2),
+            })
+        }
+        6 => {
+            let kind = [
+                MakeStmtKind::Global,
+                MakeStmtKind::Local,
+                MakeStmtKind::Assign,
+            ][rng.next(3)]
+            .clone();
+
+            let var_type = match kind {
+                MakeStmtKind::Assign => None,
+                _ if rng.next(2) == 0 => None,
+                _ => Some(rng.pick(TYPES).to_string()),
+            };
+
+            Statement::Make(MakeStmt {
+                kind,
+                var_name: rng.pick(NAMES).to_string(),
+                var_id: None,
+                var_type,
+                expr: gen_expr(rng, 2),
+            })
+        }
+        7 => {
+            let mut record_expr = new_expr(ExpressionAst::Literal(LiteralExpr::Var(
+                rng.pick(NAMES).to_string(),
+                None,
+            )));
+
+            if rng.next(2) == 0 {
+                let field_name = rng.pick(NAMES).to_string();
+                record_expr = new_expr(ExpressionAst::Field(Box::new(record_expr), field_name));
+            }
+
+            Statement::SetField(SetFieldStmt {
+                record_expr,
+                field_name: rng.pick(NAMES).to_string(),
+                expr: gen_expr(rng, 2),
+            })
+        }
+        8 => {
+            let mut struct_stmt = StructStmt::new("POINT".to_string());
+
+            for _ in 0..rng.next(3) {
+                struct_stmt.fields.push(StructField {
+                    field_name: rng.pick(NAMES).to_string(),
+                    field_type: rng.pick(TYPES).to_string(),
+                });
+            }
+
+            Statement::Struct(struct_stmt)
+        }
+        9 => Statement::Map(MapStmt {
+            proc_expr: gen_atom(rng, 0),
+            count_expr: gen_expr(rng, 1),
+        }),
+        10 => {
+            let expr = if rng.next(3) == 0 {
+                None
+            } else {
+                Some(gen_expr(rng, 2))
+            };
+
+            Statement::Return(ReturnStmt::new(expr))
+        }
+        11 => {
+            let mut proc_stmt = ProcedureStmt::new(rng.pick(&["DRAW", "MOVE_TO"]).to_string());
+
+            for _ in 0..rng.next(3) {
+                let default_expr = if rng.next(2) == 0 {
+                    None
+                } else {
+                    Some(gen_expr(rng, 1))
+                };
+
+                proc_stmt.params.push(ProcParam {
+                    param_name: rng.pick(NAMES).to_string(),
+                    param_type: rng.pick(TYPES).to_string(),
+                    default_expr,
+                });
+            }
+
+            // the parser marks a procedure returning nothing as returning `UNIT`
+            proc_stmt.return_type = rng.pick(&["UNIT", "INT", "PROC(BOOL)"]).to_string();
+            proc_stmt.block = gen_block(rng, depth);
+
+            Statement::Procedure(proc_stmt)
+        }
+        12 | 13 => Statement::If(IfStmt {
+            cond_expr: gen_expr(rng, 2),
+            true_block: gen_block(rng, depth - 1),
+            false_block: if rng.next(2) == 0 {
+                None
+            } else {
+                Some(gen_block(rng, depth - 1))
+            },
+        }),
+        _ => Statement::Repeat(RepeatStmt {
+            count_expr: gen_expr(rng, 2),
+            block: gen_block(rng, depth - 1),
+        }),
+    }
+}
+
+fn gen_ast(rng: &mut Rng) -> Ast {
+    let mut ast = Ast::default();
+
+    for _ in 0..1 + rng.next(6) {
+        ast.statements.push(gen_stmt(rng, 3));
+    }
+
+    ast
+}
+
+#[test]
+fn format_property_reparses_to_same_ast() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..500 {
+        let ast = gen_ast(&mut rng);
+        let code = PrettyPrintAst::pprint_ast(&ast);
+
+        let reparsed = TytleParser.parse(&code).unwrap();
+        assert_eq!(ast, reparsed, "\n{}", code);
+
+        let formatted = format_code(&code).unwrap();
+        assert_eq!(code, formatted);
+    }
+}
+
+#[test]
+fn format_property_keeps_comments() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for i in 0..300 {
+        let ast = gen_ast(&mut rng);
+        let code = PrettyPrintAst::pprint_ast(&ast);
+
+        // a comment is added after some of the lines, and some comments are on lines of their own
+        let mut commented = String::new();
+        let mut comments = Vec::new();
+
+        for (j, line) in code.lines().enumerate() {
+            match rng.next(4) {
+                0 => commented.push_str(&format!("{} ; C{}_{}\n", line, i, j)),
+                1 => commented.push_str(&format!(";C{}_{}\n{}\n", i, j, line)),
+                _ => {
+                    commented.push_str(line);
+                    commented.push('\n');
+                    continue;
+                }
+            }
+
+            comments.push(format!("C{}_{}", i, j));
+        }
+
+        let formatted = format_code(&commented).unwrap();
+
+        assert_eq!(
+            ast,
+            TytleParser.parse(&formatted).unwrap(),
+            "\n{}",
+            formatted
+        );
+        assert_eq!(Ok(true), is_formatted(&formatted).map_err(|_| ()));
+
+        for comment in comments {
+            assert!(formatted.contains(&comment), "\n{}", formatted);
+        }
+    }
+}
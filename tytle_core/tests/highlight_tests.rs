@@ -0,0 +1,126 @@
+extern crate tytle;
+
+use tytle::compiler::*;
+
+// each span as `kind@line:column+len`
+fn spans_str(code: &str) -> Vec<String> {
+    highlight(code)
+        .iter()
+        .map(|span| {
+            format!(
+                "{}@{}:{}+{}",
+                span.kind.name(),
+                span.location.line(),
+                span.location.column(),
+                span.len
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn highlight_vars_procs_and_commands() {
+    let code = r#"MAKEGLOBAL SIZE = 10 ; the side length
+TO SQUARE(N: INT): BOOL
+  MAKELOCAL I = N * 2
+  REPEAT 4 [FORWARD I RIGHT 90]
+  PENUP
+  RETURN TRUE
+END
+PRINT SQUARE(SIZE)"#;
+
+    let expected = vec![
+        "keyword@1:1+10",
+        "global@1:12+4",
+        "operator@1:17+1",
+        "literal@1:19+2",
+        "comment@1:22+17",
+        "keyword@2:1+2",
+        "procedure@2:4+6",
+        "param@2:11+1",
+        "type@2:14+3",
+        "type@2:20+4",
+        "keyword@3:3+9",
+        "local@3:13+1",
+        "operator@3:15+1",
+        "param@3:17+1",
+        "operator@3:19+1",
+        "literal@3:21+1",
+        "keyword@4:3+6",
+        "literal@4:10+1",
+        "direction@4:13+7",
+        "local@4:21+1",
+        "direction@4:23+5",
+        "literal@4:29+2",
+        "command@5:3+5",
+        "keyword@6:3+6",
+        "literal@6:10+4",
+        "keyword@7:1+3",
+        "command@8:1+5",
+        "procedure@8:7+6",
+        "global@8:14+4",
+    ];
+
+    assert_eq!(expected, spans_str(code));
+}
+
+#[test]
+fn highlight_records_and_strings() {
+    let code = r#"STRUCT POINT [X: INT]
+MAKEGLOBAL P = POINT(X: 1)
+IF P.X > 0 AND NOT FALSE [PRINT "x is positive"]"#;
+
+    let expected = vec![
+        "keyword@1:1+6",
+        "type@1:8+5",
+        "type@1:18+3",
+        "keyword@2:1+10",
+        "global@2:12+1",
+        "operator@2:14+1",
+        "type@2:16+5",
+        "literal@2:25+1",
+        "keyword@3:1+2",
+        "global@3:4+1",
+        "field@3:6+1",
+        "operator@3:8+1",
+        "literal@3:10+1",
+        "operator@3:12+3",
+        "operator@3:16+3",
+        "literal@3:20+5",
+        "command@3:27+5",
+        "literal@3:33+15",
+    ];
+
+    assert_eq!(expected, spans_str(code));
+}
+
+#[test]
+fn highlight_code_with_errors() {
+    // `B` isn't declared, so it's left unclassified
+    let code = "MAKEGLOBAL A = B\nFORWARD A";
+
+    let expected = vec![
+        "keyword@1:1+10",
+        "global@1:12+1",
+        "operator@1:14+1",
+        "direction@2:1+7",
+        "global@2:9+1",
+    ];
+
+    assert_eq!(expected, spans_str(code));
+}
+
+#[test]
+fn highlight_unterminated_str() {
+    // the code being typed (the closing `"` is missing)
+    let expected = vec!["command@1:1+5", "literal@1:7+1"];
+    assert_eq!(expected, spans_str("PRINT \""));
+
+    let expected = vec![
+        "command@1:1+5",
+        "literal@1:7+4",
+        "direction@2:1+7",
+        "literal@2:9+2",
+    ];
+    assert_eq!(expected, spans_str("LABEL \"ABC\nFORWARD 10"));
+}
@@ -2,6 +2,7 @@ extern crate tytle;
 
 use tytle::ast::semantic::*;
 use tytle::ir::*;
+use tytle::lexer::Location;
 use tytle::parser::{Parser, TytleParser};
 use tytle::vm::*;
 
@@ -402,6 +403,344 @@ pub fn interpreter_mutually_recursive_procedures() {
     assert_eq!(expected, host.get_log());
 }
 
+#[test]
+pub fn interpreter_nested_proc_accessing_enclosing_locals() {
+    let code = r#"
+        TO SQUARE(SIZE: INT)
+            MAKELOCAL SIDES = 0
+
+            TO SIDE()
+                FORWARD SIZE
+                RIGHT SIZE
+                MAKE SIDES = SIDES + 1
+            END
+
+            REPEAT 4 [SIDE()]
+            PRINT SIDES
+        END
+
+        SQUARE(10)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(vec!["4"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_nested_recursive_helper_proc() {
+    let code = r#"
+        TO SPIRAL(MAX: INT)
+            MAKELOCAL STEPS = 0
+
+            TO STEP(N: INT)
+                IF N < MAX [
+                    FORWARD N
+                    STEP(N + 1)
+                ]
+
+                MAKE STEPS = STEPS + 1
+            END
+
+            STEP(0)
+            PRINT STEPS
+        END
+
+        SPIRAL(5)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(vec!["6"], host.get_log());
+    assert_eq!((0, 10), host.xycors());
+}
+
+#[test]
+pub fn interpreter_nested_proc_uses_its_enclosing_proc_activation() {
+    // each `OUTER` activation has its own `DEPTH`.
+    // `INNER` (called by `MIDDLE`) should read the `DEPTH` of the `OUTER` activation calling them
+    let code = r#"
+        TO OUTER(DEPTH: INT)
+            TO MIDDLE()
+                TO INNER()
+                    PRINT DEPTH
+                END
+
+                INNER()
+            END
+
+            IF DEPTH < 2 [
+                OUTER(DEPTH + 1)
+            ]
+
+            MIDDLE()
+        END
+
+        OUTER(0)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    let expected = vec!["2", "1", "0"];
+
+    assert_eq!(expected, host.get_log());
+}
+
+#[test]
+pub fn interpreter_proc_passed_as_arg() {
+    let code = r#"
+        TO LINE(SIZE: INT)
+            FORWARD SIZE
+        END
+
+        TO DRAW(SHAPE: PROC(INT), COPIES: INT)
+            REPEAT COPIES [
+                SHAPE(10)
+                FORWARD 20
+            ]
+        END
+
+        DRAW(LINE, 3)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!((0, 90), host.xycors());
+}
+
+#[test]
+pub fn interpreter_apply_and_map() {
+    let code = r#"
+        TO TWICE(X: INT): INT
+            RETURN X * 2
+        END
+
+        TO SHOW(I: INT)
+            PRINT I
+        END
+
+        MAKEGLOBAL F: PROC(INT): INT = TWICE
+        PRINT APPLY(F, 21)
+        PRINT F(5)
+        MAP(SHOW, 3)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(vec!["42", "10", "0", "1", "2"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_nested_proc_value_accessing_enclosing_locals() {
+    let code = r#"
+        TO APPLYN(G: PROC(INT): INT, X: INT): INT
+            RETURN G(X)
+        END
+
+        TO OUTER(BASE: INT): INT
+            TO ADDBASE(X: INT): INT
+                RETURN X + BASE
+            END
+
+            RETURN APPLYN(ADDBASE, 5)
+        END
+
+        PRINT OUTER(100)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(vec!["105"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_error_nested_proc_value_called_after_its_enclosing_proc_returned() {
+    let code = r#"
+        TO ZERO(): INT
+            RETURN 0
+        END
+
+        MAKEGLOBAL KEEP: PROC(): INT = ZERO
+
+        TO OUTER(BASE: INT)
+            TO GETBASE(): INT
+                RETURN BASE
+            END
+
+            MAKE KEEP = GETBASE
+        END
+
+        OUTER(100)
+        PRINT KEEP()
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    let site = ErrorSite {
+        proc_name: "__main__".to_string(),
+        location: Some(Location(17, 9)),
+    };
+
+    assert_eq!(Err(InterpreterException::ProcOutOfScope(site)), res);
+}
+
+#[test]
+pub fn interpreter_proc_call_default_and_named_args() {
+    let code = r#"
+        SQUARE()
+        SQUARE(SIZE: 80)
+
+        MAKEGLOBAL SCALE = 2
+
+        TO SQUARE(SIZE: INT = 50)
+            PRINT SIZE
+        END
+
+        TO RECT(WIDTH: INT = 10, HEIGHT: INT = SCALE * 10)
+            PRINT WIDTH * 100 + HEIGHT
+        END
+
+        RECT()
+        RECT(3)
+        RECT(HEIGHT: 4)
+        RECT(5, HEIGHT: 6)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+
+    let expected = vec!["50", "80", "1020", "320", "1004", "506"];
+
+    assert_eq!(expected, host.get_log());
+}
+
+#[test]
+pub fn interpreter_nested_proc_default_arg_uses_enclosing_local() {
+    let code = r#"
+        TO OUTER(BASE: INT)
+            TO INNER(X: INT = BASE)
+                PRINT X
+            END
+
+            INNER()
+            INNER(1)
+        END
+
+        OUTER(7)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(vec!["7", "1"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_struct_passed_to_geometry_procs() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+        STRUCT LINE [START: POINT, FINISH: POINT]
+
+        TO MID(L: LINE): POINT
+            MAKELOCAL A = L.START
+            MAKELOCAL B = L.FINISH
+
+            RETURN POINT(X: (A.X + B.X) / 2, Y: (A.Y + B.Y) / 2)
+        END
+
+        TO DRAW(L: LINE)
+            SETX L.START.X
+            SETY L.START.Y
+            SETX L.FINISH.X
+            SETY L.FINISH.Y
+        END
+
+        MAKEGLOBAL L = LINE(POINT(10, 20), POINT(30, 60))
+        MAKEGLOBAL M = MID(L)
+
+        PRINT M.X
+        PRINT M.Y
+        DRAW(L)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(vec!["20", "40"], host.get_log());
+    assert_eq!((30, 60), host.xycors());
+}
+
+#[test]
+pub fn interpreter_struct_field_write_is_seen_through_every_reference() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        TO MOVE(P: POINT, DX: INT)
+            MAKE P.X = P.X + DX
+        END
+
+        MAKEGLOBAL P = POINT(1, 2)
+        MAKEGLOBAL Q = P
+
+        MAKE Q.Y = 5
+        MOVE(P, 10)
+
+        PRINT P.X
+        PRINT P.Y
+        PRINT Q.X
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    assert_eq!(Ok(()), res);
+    assert_eq!(vec!["11", "5", "11"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_error_field_of_unassigned_struct() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+
+        TO SHOW()
+            PRINT P.X
+        END
+
+        SHOW()
+        MAKEGLOBAL P = POINT(1, 2)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    let site = ErrorSite {
+        proc_name: "SHOW".to_string(),
+        location: Some(Location(5, 13)),
+    };
+
+    let expected = InterpreterException::InvalidStackItem {
+        expected: "a record".to_string(),
+        site,
+    };
+
+    assert_eq!(Err(expected), res);
+}
+
 #[test]
 pub fn interpreter_stack_overflow() {
     let code = r#"
@@ -417,6 +756,172 @@ pub fn interpreter_stack_overflow() {
     assert_eq!(Err(InterpreterException::StackOverflow), res);
 }
 
+#[test]
+pub fn interpreter_division_by_zero() {
+    let code = r#"
+        TO DIVIDE(A: INT, B: INT): INT
+            RETURN A / B
+        END
+        PRINT DIVIDE(10, 0)
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    let site = ErrorSite {
+        proc_name: "DIVIDE".to_string(),
+        location: Some(Location(3, 13)),
+    };
+
+    assert_eq!(Err(InterpreterException::DivisionByZero(site)), res);
+}
+
+#[test]
+pub fn interpreter_integer_overflow() {
+    let code = r#"
+        MAKEGLOBAL A = 2
+        REPEAT 100 [
+            MAKE A = A * 2
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let res = intr.exec_code();
+
+    let site = ErrorSite {
+        proc_name: "__main__".to_string(),
+        location: Some(Location(4, 13)),
+    };
+
+    assert_eq!(Err(InterpreterException::IntegerOverflow(site)), res);
+}
+
+#[test]
+pub fn interpreter_invalid_stack_item() {
+    let code = r#"
+        PRINT "HELLO"
+    "#;
+
+    // the type checker rejects `PRINT "HELLO"`, so it's skipped here
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let res = intr.exec_code();
+
+    let err = InterpreterException::InvalidStackItem {
+        expected: "an integer".to_string(),
+        site: ErrorSite {
+            proc_name: "__main__".to_string(),
+            location: Some(Location(2, 9)),
+        },
+    };
+
+    assert_eq!(Err(err), res);
+}
+
+#[test]
+pub fn interpreter_out_of_fuel() {
+    let code = r#"
+        REPEAT 1000000000 [
+            FORWARD 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_fuel(1000);
+
+    let res = intr.exec_code();
+
+    assert_eq!(Err(InterpreterException::OutOfFuel), res);
+    assert_eq!(Some(0), intr.get_fuel());
+    assert_eq!(1000, intr.get_insts_count());
+}
+
+#[test]
+pub fn interpreter_resume_after_refuel() {
+    let code = r#"
+        REPEAT 10 [
+            FORWARD 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    intr.set_fuel(20);
+
+    assert_eq!(Err(InterpreterException::OutOfFuel), intr.exec_code());
+
+    intr.set_fuel(1000);
+    assert_eq!(Ok(()), intr.exec_code());
+
+    drop(intr);
+    assert_eq!((0, 10), host.xycors());
+}
+
+#[test]
+pub fn interpreter_run_for_yields() {
+    let code = r#"
+        REPEAT 10 [
+            FORWARD 1
+        ]
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+
+    let mut yields = 0;
+
+    loop {
+        let status = intr.run_for(5).unwrap();
+
+        if status == RunStatus::Completed {
+            break;
+        }
+
+        yields += 1;
+        assert_eq!(yields * 5, intr.get_insts_count());
+    }
+
+    assert!(yields > 1);
+
+    drop(intr);
+    assert_eq!((0, 10), host.xycors());
+}
+
+#[test]
+pub fn interpreter_label_str_lit() {
+    let code = r#"
+        SETFONTSIZE 20
+        LABEL "HELLO WORLD"
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(vec!["SETFONTSIZE 20", "LABEL HELLO WORLD"], host.get_log());
+}
+
+#[test]
+pub fn interpreter_label_str_vars() {
+    let code = r#"
+        MAKEGLOBAL TITLE = "CHART"
+
+        TO ANNOTATE(S: STR)
+            MAKELOCAL T = S
+            LABEL TITLE
+            LABEL T
+        END
+
+        ANNOTATE("AXIS")
+    "#;
+
+    setup_interpreter!(code, env, cfg, host, intr);
+    let _ = intr.exec_code();
+
+    assert_eq!(vec!["LABEL CHART", "LABEL AXIS"], host.get_log());
+}
+
 #[test]
 pub fn interpreter_xcor() {
     let code = r#"
@@ -532,6 +1037,81 @@ pub fn interpreter_hide_turtle() {
     assert_eq!(false, host.get_turtle().is_visible());
 }
 
+#[test]
+pub fn interpreter_optimized_cfg() {
+    let code = r#"
+        TO SUM(I: INT, N: INT): INT
+            IF I > N [RETURN 0] [RETURN I + SUM(I + 1, N)]
+        END
+
+        MAKEGLOBAL A = 2 * 3
+        IF 1 < 2 [
+            REPEAT A [FORWARD 1]
+        ] [
+            FORWARD 100
+        ]
+        IF A > 5 [MAKE A = SUM(1, A)] [MAKE A = 0]
+        PRINT A
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let generator = SymbolTableGenerator::new();
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    let mut checker = AstTypeCheck::new(&mut env);
+    assert!(checker.check(&mut ast).is_ok());
+
+    let mut cfg = CfgBuilder::new(&mut env).build(&ast);
+    CfgOptimizer::new().optimize(&mut cfg, &mut env);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let res = intr.exec_code();
+
+    assert!(res.is_ok());
+    assert_eq!(vec!["21"], host.get_log());
+    assert_eq!((0, 6), host.xycors());
+}
+
+#[test]
+pub fn interpreter_optimized_tail_recursion() {
+    let code = r#"
+        TO SPIRAL(N: INT, MAX: INT)
+            FORWARD 1
+            RIGHT N
+            IF N < MAX [SPIRAL(N + 1, MAX)]
+        END
+
+        SPIRAL(1, 20000)
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let generator = SymbolTableGenerator::new();
+
+    let mut env = generator.generate(&mut ast).unwrap();
+    let mut checker = AstTypeCheck::new(&mut env);
+    assert!(checker.check(&mut ast).is_ok());
+
+    let mut cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    {
+        // without the optimizations, each call allocates a stack-frame
+        let mut host = DummyHost::new();
+        let mut intr = Interpreter::new(&cfg, &env, &mut host);
+
+        assert_eq!(Err(InterpreterException::StackOverflow), intr.exec_code());
+    }
+
+    CfgOptimizer::new().optimize(&mut cfg, &mut env);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    let res = intr.exec_code();
+
+    assert!(res.is_ok());
+    assert_eq!((200010000, 20000), host.xycors());
+}
+
 #[test]
 #[ignore]
 pub fn interpreter_wait_const_expr() {}
@@ -547,3 +1127,86 @@ pub fn interpreter_stop_within_main_proc() {}
 #[test]
 #[ignore]
 pub fn interpreter_stop_within_sub_proc() {}
+
+#[test]
+pub fn interpreter_error_malformed_cfg() {
+    let code = r#"
+        IF 1 > 2 [FORWARD 10]
+        FORWARD 20
+    "#;
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    AstTypeCheck::new(&mut env).check(&mut ast).unwrap();
+    let mut cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    // dropping the `Fallback` edge of the `IF` node
+    let (src_id, dst_id) = cfg
+        .graph
+        .get_nodes_ids()
+        .into_iter()
+        .find_map(|node_id| {
+            let node = cfg.graph.get_node(node_id);
+
+            node.outgoing
+                .iter()
+                .find(|edge| edge.jmp_type == CfgJumpType::Fallback)
+                .map(|edge| (node_id, edge.node_id))
+        })
+        .unwrap();
+
+    cfg.graph.remove_edge(src_id, dst_id, CfgJumpType::Fallback);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+
+    match intr.exec_code() {
+        Err(InterpreterException::MalformedCfg { node_id, .. }) => assert_eq!(src_id, node_id),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+pub fn interpreter_if_returning_true_block() {
+    let guard_code = r#"
+        TO G(K: INT): INT
+            IF K < 4 [RETURN G(K + 1) + 1]
+            RETURN 0
+        END
+
+        PRINT G(0)
+        PRINT G(7)
+    "#;
+
+    let else_code = r#"
+        TO H(K: INT): INT
+            IF K < 4 [RETURN 1] [PRINT K]
+            RETURN 0
+        END
+
+        PRINT H(1)
+        PRINT H(9)
+    "#;
+
+    let programs = vec![
+        (guard_code, vec!["4", "0"]),
+        (else_code, vec!["1", "9", "0"]),
+    ];
+
+    for (code, expected) in programs.iter() {
+        for optimize in [false, true].iter() {
+            let (mut env, mut cfg) = tytle::compiler::compile(code).unwrap();
+
+            if *optimize {
+                CfgOptimizer::new().optimize(&mut cfg, &mut env);
+            }
+
+            let mut host = DummyHost::new();
+            let mut intr = Interpreter::new(&cfg, &env, &mut host);
+            intr.exec_code().unwrap();
+
+            // both the `true-block` and the code following the `IF` have been executed
+            assert_eq!(*expected, host.get_log());
+        }
+    }
+}
@@ -106,3 +106,55 @@ fn pp_proc_call_expr() {
 
     assert_eq!(buffer, "MYPROC(1, 2)".to_string());
 }
+
+#[test]
+fn pp_proc_call_expr_with_named_arg() {
+    let ast1 = ExpressionAst::Literal(LiteralExpr::Int(1));
+    let ast2 = ExpressionAst::Literal(LiteralExpr::Int(2));
+    let named_ast = ExpressionAst::NamedArg("SIZE".to_string(), Box::new(Expression::new(ast2)));
+
+    let proc_args = vec![Expression::new(ast1), Expression::new(named_ast)];
+    let ast = ExpressionAst::ProcCall("MYPROC".to_string(), proc_args, None);
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "MYPROC(1, SIZE: 2)".to_string());
+}
+
+#[test]
+fn pp_proc_call_expr_without_args() {
+    let ast = ExpressionAst::ProcCall("MYPROC".to_string(), vec![], None);
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "MYPROC()".to_string());
+}
+
+#[test]
+fn pp_field_expr() {
+    let var_ast = ExpressionAst::Literal(LiteralExpr::Var("L".to_string(), None));
+    let start_ast = ExpressionAst::Field(Box::new(Expression::new(var_ast)), "START".to_string());
+    let ast = ExpressionAst::Field(Box::new(Expression::new(start_ast)), "X".to_string());
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "L.START.X".to_string());
+}
+
+#[test]
+fn pp_new_struct_expr() {
+    let ast1 = ExpressionAst::Literal(LiteralExpr::Int(1));
+    let ast2 = ExpressionAst::Literal(LiteralExpr::Int(2));
+    let named_ast = ExpressionAst::NamedArg("Y".to_string(), Box::new(Expression::new(ast2)));
+
+    let fields_args = vec![Expression::new(ast1), Expression::new(named_ast)];
+    let ast = ExpressionAst::NewStruct("POINT".to_string(), fields_args, None);
+
+    let expr = Expression::new(ast);
+    let buffer = PrettyPrintAst::pprint_expr(&expr);
+
+    assert_eq!(buffer, "POINT(1, Y: 2)".to_string());
+}
@@ -0,0 +1,282 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::ast::statement::Direction;
+use tytle::ir::*;
+use tytle::parser::{Parser, TytleParser};
+use tytle::vm::*;
+
+macro_rules! trace_code {
+    ($code: expr, $env: ident, $cfg: ident, $trace: ident) => {
+        let mut ast = TytleParser.parse($code).unwrap();
+        let generator = SymbolTableGenerator::new();
+
+        let mut $env = generator.generate(&mut ast).unwrap();
+        let mut checker = AstTypeCheck::new(&mut $env);
+
+        let res = checker.check(&mut ast);
+        assert!(res.is_ok());
+
+        let builder = CfgBuilder::new(&mut $env);
+        let $cfg = builder.build(&ast);
+
+        let mut host = DummyHost::new();
+        let mut intr = Interpreter::new(&$cfg, &$env, &mut host);
+
+        intr.enable_tracing();
+        intr.exec_code().unwrap();
+
+        let $trace = intr.take_trace().unwrap();
+    };
+}
+
+#[test]
+fn trace_disabled_by_default() {
+    let code = "FORWARD 10";
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    AstTypeCheck::new(&mut env).check(&mut ast).unwrap();
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    intr.exec_code().unwrap();
+
+    assert!(intr.get_trace().is_none());
+}
+
+#[test]
+fn trace_records_executed_instructions() {
+    let code = r#"
+        TO MOVE()
+            FORWARD 10
+        END
+
+        MOVE()
+    "#;
+
+    trace_code!(code, env, cfg, trace);
+
+    let main_id = env.main_proc_id.unwrap();
+    let move_id = env.symbol_table.get_proc_by_name("MOVE").id;
+
+    let actual: Vec<(CfgNodeId, usize, CfgInstruction, SymbolId, usize, usize)> = trace
+        .entries
+        .iter()
+        .map(|e| (e.node_id, e.ip, e.inst.clone(), e.proc_id, e.depth, e.count))
+        .collect();
+
+    let expected = vec![
+        (1, 0, CfgInstruction::Call(2), main_id, 1, 1),
+        (2, 0, CfgInstruction::Int(10), move_id, 2, 2),
+        (
+            2,
+            1,
+            CfgInstruction::Direction(Direction::Forward),
+            move_id,
+            2,
+            3,
+        ),
+        (2, 2, CfgInstruction::Return, move_id, 2, 4),
+        (1, 1, CfgInstruction::EOC, main_id, 1, 5),
+    ];
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn trace_limit() {
+    let code = "REPEAT 100 [FORWARD 1]";
+
+    let mut ast = TytleParser.parse(code).unwrap();
+    let mut env = SymbolTableGenerator::new().generate(&mut ast).unwrap();
+    AstTypeCheck::new(&mut env).check(&mut ast).unwrap();
+    let cfg = CfgBuilder::new(&mut env).build(&ast);
+
+    let mut host = DummyHost::new();
+    let mut intr = Interpreter::new(&cfg, &env, &mut host);
+    intr.enable_tracing_with_limit(10);
+    intr.exec_code().unwrap();
+
+    let insts_count = intr.get_insts_count();
+    let trace = intr.take_trace().unwrap();
+
+    assert_eq!(10, trace.len());
+    assert!(trace.is_truncated());
+    assert_eq!(insts_count - 10, trace.get_dropped());
+
+    // the recorded entries are the first executed instructions
+    let counts: Vec<usize> = trace.entries.iter().map(|e| e.count).collect();
+    assert_eq!((1..=10).collect::<Vec<usize>>(), counts);
+
+    let profile = Profile::new(&trace, &cfg, &env);
+    assert_eq!(10, profile.total_insts);
+}
+
+#[test]
+fn profile_procs_self_and_total() {
+    let code = r#"
+        TO SQUARE()
+            REPEAT 4 [
+                SIDE()
+            ]
+        END
+
+        TO SIDE()
+            FORWARD 10
+            RIGHT 90
+        END
+
+        SQUARE()
+        SIDE()
+    "#;
+
+    trace_code!(code, env, cfg, trace);
+
+    let profile = Profile::new(&trace, &cfg, &env);
+
+    let main = profile.get_proc("__main__").unwrap();
+    let square = profile.get_proc("SQUARE").unwrap();
+    let side = profile.get_proc("SIDE").unwrap();
+
+    assert_eq!(1, main.calls);
+    assert_eq!(1, square.calls);
+    assert_eq!(5, side.calls);
+
+    // each `SIDE` call executes: `INT, FORWARD, INT, RIGHT, RETURN`
+    assert_eq!(25, side.self_insts);
+    assert_eq!(25, side.total_insts);
+
+    assert_eq!(square.self_insts + 20, square.total_insts);
+    assert_eq!(trace.len(), main.total_insts);
+    assert_eq!(trace.len(), profile.total_insts);
+    assert_eq!(
+        trace.len(),
+        main.self_insts + square.self_insts + side.self_insts
+    );
+
+    // the hottest procedure comes first
+    let self_insts: Vec<usize> = profile.procs.iter().map(|p| p.self_insts).collect();
+    let mut sorted = self_insts.clone();
+    sorted.sort_by(|a, b| b.cmp(a));
+
+    assert_eq!(sorted, self_insts);
+}
+
+#[test]
+fn profile_recursive_proc() {
+    let code = r#"
+        TO F(N: INT)
+            IF N < 3 [
+                F(N + 1)
+            ]
+        END
+
+        F(0)
+    "#;
+
+    trace_code!(code, env, cfg, trace);
+
+    let profile = Profile::new(&trace, &cfg, &env);
+
+    let main = profile.get_proc("__main__").unwrap();
+    let f = profile.get_proc("F").unwrap();
+
+    assert_eq!(4, f.calls);
+
+    // instructions of recursive calls are counted once under `total_insts`
+    assert_eq!(f.self_insts, f.total_insts);
+    assert_eq!(trace.len(), main.self_insts + f.self_insts);
+
+    let stacks: Vec<String> = profile
+        .stacks
+        .iter()
+        .map(|(stack, _)| stack.join(";"))
+        .collect();
+
+    assert_eq!(
+        vec![
+            "__main__",
+            "__main__;F",
+            "__main__;F;F",
+            "__main__;F;F;F",
+            "__main__;F;F;F;F"
+        ],
+        stacks
+    );
+
+    let folded_insts: usize = profile.stacks.iter().map(|(_, insts)| insts).sum();
+    assert_eq!(trace.len(), folded_insts);
+}
+
+#[test]
+fn profile_nodes() {
+    let code = r#"
+        TO SIDE()
+            FORWARD 10
+        END
+
+        REPEAT 3 [
+            SIDE()
+        ]
+    "#;
+
+    trace_code!(code, env, cfg, trace);
+
+    let profile = Profile::new(&trace, &cfg, &env);
+
+    let side_node_id = cfg
+        .jmp_table
+        .iter()
+        .find(|(_, proc_id)| env.symbol_table.get_proc_by_id(**proc_id).name == "SIDE")
+        .map(|(node_id, _)| *node_id)
+        .unwrap();
+
+    let side_node = profile.get_node(side_node_id).unwrap();
+    assert_eq!("SIDE", side_node.proc_name);
+    assert_eq!(3, side_node.visits);
+    assert_eq!(9, side_node.self_insts);
+    assert_eq!(9, side_node.total_insts);
+
+    // the loop body node calls `SIDE`
+    let loop_node = profile
+        .nodes
+        .iter()
+        .find(|node| node.proc_name == "__main__" && node.visits == 3)
+        .unwrap();
+
+    assert_eq!(loop_node.self_insts + 9, loop_node.total_insts);
+
+    let self_insts: usize = profile.nodes.iter().map(|node| node.self_insts).sum();
+    assert_eq!(trace.len(), self_insts);
+}
+
+#[test]
+fn profile_export() {
+    let code = r#"
+        TO SIDE()
+            FORWARD 10
+        END
+
+        SIDE()
+    "#;
+
+    trace_code!(code, env, cfg, trace);
+
+    let profile = Profile::new(&trace, &cfg, &env);
+
+    assert_eq!("__main__ 2\n__main__;SIDE 3\n", profile.to_folded());
+
+    let expected = concat!(
+        r#"{"total_insts": 5, "#,
+        r#""procs": ["#,
+        r#"{"name": "SIDE", "calls": 1, "self_insts": 3, "total_insts": 3}, "#,
+        r#"{"name": "__main__", "calls": 1, "self_insts": 2, "total_insts": 5}], "#,
+        r#""nodes": ["#,
+        r#"{"node_id": 1, "proc": "__main__", "visits": 1, "self_insts": 2, "total_insts": 5}, "#,
+        r#"{"node_id": 2, "proc": "SIDE", "visits": 1, "self_insts": 3, "total_insts": 3}]}"#
+    );
+
+    assert_eq!(expected, profile.to_json());
+}
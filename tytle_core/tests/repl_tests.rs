@@ -0,0 +1,89 @@
+extern crate tytle;
+
+use tytle::ast::expression::*;
+use tytle::ast::semantic::*;
+use tytle::repl::*;
+use tytle::vm::*;
+
+#[test]
+pub fn repl_globals_persist_between_inputs() {
+    let mut session = ReplSession::new();
+    let mut host = DummyHost::new();
+
+    assert_eq!(Ok(()), session.eval("MAKEGLOBAL X = 10", &mut host));
+    assert_eq!(Ok(()), session.eval("MAKE X = X + 5", &mut host));
+    assert_eq!(Ok(()), session.eval("PRINT X", &mut host));
+
+    assert_eq!(vec!["15"], host.get_log());
+}
+
+#[test]
+pub fn repl_procedures_persist_between_inputs() {
+    let mut session = ReplSession::new();
+    let mut host = DummyHost::new();
+
+    let code = r#"
+        TO SQUARE(N: INT): INT
+            RETURN N * N
+        END
+    "#;
+
+    assert_eq!(Ok(()), session.eval(code, &mut host));
+    assert_eq!(Ok(()), session.eval("PRINT SQUARE(3)", &mut host));
+    assert_eq!(Ok(()), session.eval("REPEAT 2 [PRINT SQUARE(4)]", &mut host));
+
+    assert_eq!(vec!["9", "16", "16"], host.get_log());
+}
+
+#[test]
+pub fn repl_turtle_state_persists_between_inputs() {
+    let mut session = ReplSession::new();
+    let mut host = DummyHost::new();
+
+    assert_eq!(Ok(()), session.eval("FORWARD 10", &mut host));
+    assert_eq!(Ok(()), session.eval("REPEAT 3 [FORWARD 5]", &mut host));
+
+    assert_eq!((0, 25), host.xycors());
+}
+
+#[test]
+pub fn repl_failed_input_leaves_session_untouched() {
+    let mut session = ReplSession::new();
+    let mut host = DummyHost::new();
+
+    assert_eq!(Ok(()), session.eval("MAKEGLOBAL X = 10", &mut host));
+
+    let res = session.eval("MAKEGLOBAL Y = TRUE + 1", &mut host);
+    let expected =
+        AstWalkError::InvalidBinaryOp(BinaryOp::Add, ExpressionType::Bool, ExpressionType::Int);
+    assert_eq!(Err(ReplError::AstWalk(expected)), res);
+
+    // `Y` wasn't declared, so it may be declared again
+    assert_eq!(Ok(()), session.eval("MAKEGLOBAL Y = X * 2", &mut host));
+    assert_eq!(Ok(()), session.eval("PRINT Y", &mut host));
+
+    assert_eq!(vec!["20"], host.get_log());
+}
+
+#[test]
+pub fn repl_error_duplicate_global_between_inputs() {
+    let mut session = ReplSession::new();
+    let mut host = DummyHost::new();
+
+    assert_eq!(Ok(()), session.eval("MAKEGLOBAL X = 10", &mut host));
+
+    let res = session.eval("MAKEGLOBAL X = 20", &mut host);
+    let expected = AstWalkError::DuplicateGlobalVar("X".to_string());
+
+    assert_eq!(Err(ReplError::AstWalk(expected)), res);
+}
+
+#[test]
+pub fn repl_input_is_complete() {
+    assert!(ReplSession::is_complete("FORWARD 10"));
+    assert!(ReplSession::is_complete("REPEAT 2 [FORWARD 10]"));
+    assert!(ReplSession::is_complete("TO F()\n FORWARD 10\nEND"));
+
+    assert!(!ReplSession::is_complete("REPEAT 2 ["));
+    assert!(!ReplSession::is_complete("TO F()\n REPEAT 2 [FORWARD 10]"));
+}
@@ -0,0 +1,99 @@
+extern crate tytle;
+
+use tytle::ast::semantic::*;
+use tytle::compiler::*;
+use tytle::lexer::Location;
+
+macro_rules! build_index {
+    ($code: expr) => {{
+        let (ast, env, diagnostics) = analyze($code);
+        assert!(diagnostics.is_empty());
+
+        let index = SymbolIndex::build($code, &ast, &env);
+
+        (index, env)
+    }};
+}
+
+// each occurrence as `NAME#id@line:column` (with a `*` suffix for a declaration)
+fn refs_str(index: &SymbolIndex) -> Vec<String> {
+    index
+        .get_refs()
+        .iter()
+        .map(|sym_ref| {
+            format!(
+                "{}{}@{}:{}{}",
+                sym_ref.name,
+                sym_ref.symbol_id,
+                sym_ref.location.line(),
+                sym_ref.location.column(),
+                if sym_ref.is_decl { "*" } else { "" }
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn symbol_index_procs_and_vars() {
+    let code = r#"MAKEGLOBAL SIZE = 10
+TO SQUARE(SIZE: INT, N: INT = 4)
+  REPEAT N [FORWARD SIZE RIGHT 90]
+END
+SQUARE(SIZE, N: SIZE)"#;
+
+    let (index, env) = build_index!(code);
+
+    let expected = vec![
+        "SIZE#1@1:12*",
+        "SQUARE#2@2:4*",
+        "SIZE#3@2:11*",
+        "N#4@2:22*",
+        "N#4@3:10",
+        "SIZE#3@3:21",
+        "SQUARE#2@5:1",
+        "SIZE#1@5:8",
+        "SIZE#1@5:17",
+    ];
+
+    assert_eq!(expected, refs_str(&index));
+
+    let sym_ref = index.ref_at(Location(5, 19)).unwrap();
+    assert_eq!(SymbolId(1), sym_ref.symbol_id);
+
+    let decl = index.get_decl(sym_ref.symbol_id).unwrap();
+    assert_eq!(Location(1, 12), decl.location);
+
+    let symbol = env.symbol_table.get_symbols()[1].clone();
+    assert_eq!("global SIZE: INT", symbol.describe());
+}
+
+#[test]
+fn symbol_index_records_and_proc_values() {
+    let code = r#"STRUCT POINT [X: INT, Y: INT]
+TO MOVE(P: POINT)
+  SETX P.X
+END
+MAKEGLOBAL X = POINT(X: 1, Y: 2)
+MAKE X.Y = 3
+MAKEGLOBAL F = MOVE
+F(X)"#;
+
+    let (index, _env) = build_index!(code);
+
+    let expected = vec![
+        "POINT#1@1:8*",
+        "MOVE#2@2:4*",
+        "P#5@2:9*",
+        "P#5@3:8",
+        "X#3@5:12*",
+        "POINT#1@5:16",
+        "X#3@6:6",
+        "F#4@7:12*",
+        "MOVE#2@7:16",
+        "F#4@8:1",
+        "X#3@8:3",
+    ];
+
+    assert_eq!(expected, refs_str(&index));
+    assert_eq!(None, index.ref_at(Location(3, 10)));
+}
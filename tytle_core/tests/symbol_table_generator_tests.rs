@@ -78,6 +78,7 @@ fn sym_generate_ast_records_var_global_index() {
         kind: MakeStmtKind::Global,
         var_name: "B".to_string(),
         var_id: Some(SymbolId(2)),
+        var_type: None,
         expr: Expression {
             expr_ast,
             expr_type: None,
@@ -295,6 +296,188 @@ fn sym_generate_proc_call_inject_proc_id() {
     assert!(called);
 }
 
+#[test]
+fn sym_generate_proc_call_before_proc_declaration() {
+    let code = r#"
+            FOO(10)
+
+            TO FOO(A: INT)
+            END
+        "#;
+
+    gen_symbols!(code, env, ast);
+
+    let proc = env.symbol_table.get_proc_by_name("FOO");
+
+    if let Statement::Expression(proc_call_expr) = &ast.statements[0] {
+        let (_, _, proc_id) = proc_call_expr.as_proc_call_expr();
+
+        assert_eq!(proc.id, *proc_id.unwrap());
+    } else {
+        panic!("expected a procedure call");
+    }
+}
+
+#[test]
+fn sym_generate_mutually_recursive_procs() {
+    let code = r#"
+            TO EVEN(N: INT): BOOL
+                RETURN ODD(N)
+            END
+
+            TO ODD(N: INT): BOOL
+                RETURN EVEN(N)
+            END
+        "#;
+
+    gen_symbols!(code, env, ast);
+
+    let even = env.symbol_table.get_proc_by_name("EVEN");
+    let odd = env.symbol_table.get_proc_by_name("ODD");
+
+    // `(procedure statement index, expected called procedure)`
+    for (stmt_index, callee) in vec![(0, odd), (1, even)] {
+        let proc_stmt = match &ast.statements[stmt_index] {
+            Statement::Procedure(proc_stmt) => proc_stmt,
+            _ => panic!("expected a procedure"),
+        };
+
+        let ret_stmt = match &proc_stmt.block.stmts[0] {
+            Statement::Return(ret_stmt) => ret_stmt,
+            _ => panic!("expected a `RETURN` statement"),
+        };
+
+        let (proc_name, _, proc_id) = ret_stmt.expr.as_ref().unwrap().as_proc_call_expr();
+
+        assert_eq!(callee.name, *proc_name);
+        assert_eq!(callee.id, *proc_id.unwrap());
+    }
+}
+
+#[test]
+fn sym_generate_nested_procs() {
+    let code = r#"
+            TO OUTER(A: INT)
+                MAKELOCAL B = A
+                INNER()
+
+                TO INNER()
+                    MAKELOCAL C = B
+                END
+            END
+        "#;
+
+    gen_symbols!(code, env, ast);
+
+    let outer = env.symbol_table.get_proc_by_name("OUTER");
+    assert_eq!(None, outer.parent_id);
+
+    // `INNER` is declared within `OUTER` scope, not under the root scope
+    let root_symbol = env.symbol_table.lookup(0, "INNER", &SymbolKind::Proc);
+    assert!(root_symbol.is_none());
+
+    let outer_stmt = match &ast.statements[0] {
+        Statement::Procedure(proc_stmt) => proc_stmt,
+        _ => panic!("expected a procedure"),
+    };
+
+    let inner_stmt = match &outer_stmt.block.stmts[2] {
+        Statement::Procedure(proc_stmt) => proc_stmt,
+        _ => panic!("expected a procedure"),
+    };
+
+    let inner = env.symbol_table.get_proc_by_id(inner_stmt.id.unwrap());
+    assert_eq!("INNER", inner.name);
+    assert_eq!(Some(outer.id), inner.parent_id);
+
+    // `INNER` is called before its declaration
+    if let Statement::Expression(proc_call_expr) = &outer_stmt.block.stmts[1] {
+        let (_, _, proc_id) = proc_call_expr.as_proc_call_expr();
+
+        assert_eq!(inner.id, *proc_id.unwrap());
+    } else {
+        panic!("expected a procedure call");
+    }
+
+    // `C` is a local of `INNER`, initialized with `B` (a local of `OUTER`)
+    let make_stmt = match &inner_stmt.block.stmts[0] {
+        Statement::Make(make_stmt) => make_stmt,
+        _ => panic!("expected a `MAKELOCAL` statement"),
+    };
+
+    let var_c = env.symbol_table.get_var_by_id(make_stmt.var_id.unwrap());
+    assert_eq!(Some(inner.id), var_c.proc_id);
+
+    let (var_name, var_id) = match make_stmt.expr.as_lit_expr() {
+        LiteralExpr::Var(var_name, var_id) => (var_name, var_id),
+        _ => panic!("expected a variable"),
+    };
+
+    let var_b = env.symbol_table.get_var_by_id(var_id.unwrap());
+    assert_eq!("B", var_name);
+    assert_eq!(Some(outer.id), var_b.proc_id);
+}
+
+#[test]
+fn sym_generate_error_nested_proc_called_outside_its_procedure() {
+    let code = r#"
+            TO OUTER()
+                TO INNER()
+                END
+            END
+
+            INNER()
+        "#;
+
+    let expected = AstWalkError::MissingProcDeclaration("INNER".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_nested_proc_declaration_within_proc_block() {
+    let code = r#"
+            TO OUTER()
+                REPEAT 2 [
+                    TO INNER()
+                    END
+                ]
+            END
+        "#;
+
+    let expected = AstWalkError::NestedProcDeclaration("INNER".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_nested_proc_duplicate() {
+    let code = r#"
+            TO OUTER()
+                TO OUTER()
+                END
+            END
+        "#;
+
+    let expected = AstWalkError::DuplicateProc("OUTER".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_nested_proc_declaration() {
+    let code = r#"
+            IF TRUE [
+                TO FOO()
+                END
+            ]
+        "#;
+
+    let expected = AstWalkError::NestedProcDeclaration("FOO".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
 #[test]
 fn sym_generate_error_global_use_before_declare() {
     let code = r#"
@@ -319,6 +502,30 @@ fn sym_generate_error_local_use_before_declare() {
     assert_symbol_err!(expected, code);
 }
 
+#[test]
+fn sym_generate_error_expr_var_use_before_declare() {
+    let code = r#"
+            MAKEGLOBAL A = B + 1
+        "#;
+
+    let expected = AstWalkError::MissingVarDeclaration("B".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_undefined_proc_call() {
+    let code = r#"
+            TO MYPROC()
+                UNDEFINED()
+            END
+        "#;
+
+    let expected = AstWalkError::MissingProcDeclaration("UNDEFINED".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
 #[test]
 fn sym_generate_error_duplicate_global_variable_declaration() {
     let code = r#"
@@ -426,3 +633,185 @@ fn sym_generate_error_locals_not_allowed_under_root_scope() {
 
     assert_symbol_err!(expected, code);
 }
+
+#[test]
+fn sym_generate_struct_declared_after_its_use() {
+    let code = r#"
+            TO ORIGIN(): POINT
+                RETURN POINT(0, 0)
+            END
+
+            STRUCT POINT [X: INT, Y: INT]
+        "#;
+
+    gen_symbols!(code, env, ast);
+
+    let struct_type = env.symbol_table.get_struct_by_name("POINT").unwrap();
+
+    assert_eq!(
+        vec!["X".to_string(), "Y".to_string()],
+        struct_type.fields_names
+    );
+    assert_eq!(
+        vec![ExpressionType::Int, ExpressionType::Int],
+        struct_type.fields_types
+    );
+
+    let proc = env.symbol_table.get_proc_by_name("ORIGIN");
+    assert_eq!(
+        ExpressionType::Struct("POINT".to_string()),
+        proc.return_type
+    );
+
+    // the call to `POINT` constructs a record
+    let proc_stmt = match ast.statements[0] {
+        Statement::Procedure(ref proc_stmt) => proc_stmt,
+        _ => unreachable!(),
+    };
+
+    let ret_expr = match proc_stmt.block.stmts[0] {
+        Statement::Return(ref ret_stmt) => ret_stmt.expr.as_ref().unwrap(),
+        _ => unreachable!(),
+    };
+
+    let (struct_name, _fields_args, struct_id) = ret_expr.as_new_struct_expr();
+
+    assert_eq!("POINT", struct_name);
+    assert_eq!(Some(&struct_type.id), struct_id);
+}
+
+#[test]
+fn sym_generate_error_duplicate_struct() {
+    let code = r#"
+            STRUCT POINT [X: INT]
+            STRUCT POINT [Y: INT]
+        "#;
+
+    let expected = AstWalkError::DuplicateStruct("POINT".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_duplicate_struct_field() {
+    let code = "STRUCT POINT [X: INT, X: STR]";
+
+    let expected = AstWalkError::DuplicateStructField("POINT".to_string(), "X".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_nested_struct_declaration() {
+    let code = r#"
+            TO MYPROC()
+                STRUCT POINT [X: INT]
+            END
+        "#;
+
+    let expected = AstWalkError::NestedStructDeclaration("POINT".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_missing_struct_field_type() {
+    let code = "STRUCT LINE [START: POINT]";
+
+    let expected = AstWalkError::MissingTypeDeclaration("POINT".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_missing_proc_param_type() {
+    let code = r#"
+            TO MYPROC(MYVAR: INTEGER)
+            END
+        "#;
+
+    let expected = AstWalkError::MissingTypeDeclaration("INTEGER".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_missing_type_within_proc_type() {
+    let code = r#"
+            TO MYPROC(SHAPE: PROC(INT): STRING)
+            END
+        "#;
+
+    let expected = AstWalkError::MissingTypeDeclaration("STRING".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_missing_make_local_type() {
+    let code = r#"
+            TO MYPROC()
+                MAKELOCAL A: INTEGER = 1
+            END
+        "#;
+
+    let expected = AstWalkError::MissingTypeDeclaration("INTEGER".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_struct_named_as_builtin_type() {
+    let code = "STRUCT INT [X: INT]";
+
+    let expected = AstWalkError::StructNamedAsBuiltinType("INT".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_recursive_struct() {
+    let code = "STRUCT NODE [VALUE: INT, NEXT: NODE]";
+
+    let expected = AstWalkError::RecursiveStruct("NODE".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_recursive_struct_through_another_struct() {
+    let code = r#"
+            STRUCT A [B: B]
+            STRUCT B [A: A]
+        "#;
+
+    let expected = AstWalkError::RecursiveStruct("A".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_proc_named_as_struct() {
+    let code = r#"
+            STRUCT POINT [X: INT]
+
+            TO POINT()
+            END
+        "#;
+
+    let expected = AstWalkError::ProcNamedAsStruct("POINT".to_string());
+
+    assert_symbol_err!(expected, code);
+}
+
+#[test]
+fn sym_generate_error_global_named_as_struct() {
+    let code = r#"
+            MAKEGLOBAL POINT = 10
+            STRUCT POINT [X: INT]
+        "#;
+
+    let expected = AstWalkError::GlobalVarNamedAsStruct("POINT".to_string());
+
+    assert_symbol_err!(expected, code);
+}
@@ -1,6 +1,6 @@
 extern crate tytle;
 
-use tytle::lexer::{Lexer, Location, Token, TytleLexer};
+use tytle::lexer::{Comment, Lexer, Location, Token, TytleLexer};
 
 macro_rules! assert_current_token {
     ($lexer:ident, $expected_tok:expr, $expected_loc:expr) => {{
@@ -479,6 +479,24 @@ fn lexer_colon() {
     assert_eq!(tok5, Token::VALUE("C".to_string()));
 }
 
+#[test]
+fn lexer_dot() {
+    let mut lexer = TytleLexer::new("P.X");
+
+    let (tok1, loc1) = lexer.pop_current_token().unwrap();
+    let (tok2, loc2) = lexer.pop_current_token().unwrap();
+    let (tok3, loc3) = lexer.pop_current_token().unwrap();
+
+    assert_eq!(loc1, Location(1, 1));
+    assert_eq!(tok1, Token::VALUE("P".to_string()));
+
+    assert_eq!(loc2, Location(1, 2));
+    assert_eq!(tok2, Token::DOT);
+
+    assert_eq!(loc3, Location(1, 3));
+    assert_eq!(tok3, Token::VALUE("X".to_string()));
+}
+
 #[test]
 fn lexer_and() {
     let mut lexer = TytleLexer::new("AND");
@@ -508,3 +526,82 @@ fn lexer_not() {
     assert_eq!(loc1, Location(1, 1));
     assert_eq!(tok1, Token::NOT);
 }
+
+#[test]
+fn lexer_str_literal_with_spaces() {
+    let mut lexer = TytleLexer::new(r#"LABEL "HELLO WORLD" 10"#);
+
+    let (tok1, loc1) = lexer.pop_current_token().unwrap();
+    let (tok2, loc2) = lexer.pop_current_token().unwrap();
+    let (tok3, loc3) = lexer.pop_current_token().unwrap();
+
+    assert_eq!(loc1, Location(1, 1));
+    assert_eq!(tok1, Token::VALUE("LABEL".to_string()));
+
+    assert_eq!(loc2, Location(1, 7));
+    assert_eq!(tok2, Token::VALUE("\"HELLO WORLD\"".to_string()));
+
+    assert_eq!(loc3, Location(1, 21));
+    assert_eq!(tok3, Token::VALUE("10".to_string()));
+}
+
+#[test]
+fn lexer_comments() {
+    let mut lexer = TytleLexer::new("; a comment\nFORWARD 10 ;go;  \nLABEL \"A;B\"");
+
+    let (tok1, loc1) = lexer.pop_current_token().unwrap();
+    let (tok2, loc2) = lexer.pop_current_token().unwrap();
+    let (tok3, _) = lexer.pop_current_token().unwrap();
+    let (tok4, _) = lexer.pop_current_token().unwrap();
+    let (tok5, _) = lexer.pop_current_token().unwrap();
+    let (tok6, _) = lexer.pop_current_token().unwrap();
+
+    assert_eq!(loc1, Location(1, 12));
+    assert_eq!(tok1, Token::NEWLINE);
+
+    assert_eq!(loc2, Location(2, 1));
+    assert_eq!(tok2, Token::VALUE("FORWARD".to_string()));
+
+    assert_eq!(tok3, Token::VALUE("10".to_string()));
+    assert_eq!(tok4, Token::NEWLINE);
+    assert_eq!(tok5, Token::VALUE("LABEL".to_string()));
+    assert_eq!(tok6, Token::VALUE("\"A;B\"".to_string()));
+
+    let comments = vec![
+        Comment {
+            location: Location(1, 1),
+            text: " a comment".to_string(),
+            trailing: false,
+        },
+        Comment {
+            location: Location(2, 12),
+            text: "go;".to_string(),
+            trailing: true,
+        },
+    ];
+
+    assert_eq!(lexer.get_comments(), &comments[..]);
+}
+
+#[test]
+fn lexer_unterminated_str() {
+    let mut lexer = TytleLexer::new("LABEL \"A B\nPRINT \"\nLABEL \"C\"");
+
+    let tokens: Vec<Token> = (0..9)
+        .map(|_| lexer.pop_current_token().unwrap().0)
+        .collect();
+
+    let expected = vec![
+        Token::VALUE("LABEL".to_string()),
+        Token::UNTERMINATED("\"A B".to_string()),
+        Token::NEWLINE,
+        Token::VALUE("PRINT".to_string()),
+        Token::UNTERMINATED("\"".to_string()),
+        Token::NEWLINE,
+        Token::VALUE("LABEL".to_string()),
+        Token::VALUE("\"C\"".to_string()),
+        Token::EOF,
+    ];
+
+    assert_eq!(expected, tokens);
+}
@@ -1,7 +1,9 @@
 #[macro_use]
 extern crate tytle;
 
+use tytle::ast::semantic::{AstWalkError, SymbolTableGenerator};
 use tytle::ast::{expression::*, statement::*};
+use tytle::lexer::{Location, Token};
 use tytle::parser::{ParseError, Parser, TytleParser};
 
 macro_rules! assert_parse_err {
@@ -11,6 +13,19 @@ macro_rules! assert_parse_err {
     }};
 }
 
+// an unknown type name may be a struct declared anywhere in the program,
+// so it's reported by the `SymbolTableGenerator` (and not by the parser)
+macro_rules! assert_missing_type {
+    ($type_name: expr, $code: expr) => {{
+        let mut ast = TytleParser.parse($code).unwrap();
+        let res = SymbolTableGenerator::new().generate(&mut ast);
+
+        let expected = AstWalkError::MissingTypeDeclaration($type_name.to_string());
+
+        assert_eq!(Some(expected), res.err());
+    }};
+}
+
 macro_rules! assert_reserved_word {
     ($keyword:expr) => {
         let var_code = format!("MAKEGLOBAL {} = 1", $keyword);
@@ -353,6 +368,30 @@ fn parse_print_var_expr() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_label_str_lit() {
+    let actual = TytleParser.parse(r#"LABEL "HELLO WORLD""#).unwrap();
+
+    let expected = ast! {
+        label_stmt!(str_lit_expr!("HELLO WORLD"))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_set_font_size() {
+    let actual = TytleParser.parse("SETFONTSIZE 2 * 10").unwrap();
+
+    let expr = binary_expr!("*", boxed_int_lit_expr!(2), boxed_int_lit_expr!(10));
+
+    let expected = ast! {
+        set_font_size_stmt!(expr)
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parse_make_variable_assign_an_integer() {
     let actual = TytleParser.parse("MAKE MYVAR = 2").unwrap();
@@ -427,6 +466,30 @@ fn parse_make_local_variable_assign_an_integer() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_make_global_variable_with_type_annotation() {
+    let actual = TytleParser.parse("MAKEGLOBAL MYVAR: INT = 2").unwrap();
+
+    let expected = ast! {
+        make_global_stmt!("MYVAR", "INT", int_lit_expr!(2))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_make_local_variable_with_type_annotation() {
+    let actual = TytleParser
+        .parse("MAKELOCAL MYVAR: STR = \"Hello\"")
+        .unwrap();
+
+    let expected = ast! {
+        make_local_stmt!("MYVAR", "STR", str_lit_expr!("Hello"))
+    };
+
+    assert_eq!(expected, actual);
+}
+
 #[test]
 fn parse_make_global_variable_assign_a_boolean_true() {
     let actual = TytleParser.parse("MAKEGLOBAL MYVAR = TRUE").unwrap();
@@ -730,6 +793,211 @@ fn parse_proc_stmt_with_params_and_explicit_return_value() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_proc_stmt_with_proc_type_params() {
+    let code = r#"
+        TO DRAW(SHAPE: PROC(INT, STR): BOOL, PICK: PROC(PROC(INT): UNIT))
+        END
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        proc_stmt! {
+            name: "DRAW",
+            params: [
+                proc_param!("SHAPE", "PROC(INT, STR): BOOL"),
+                proc_param!("PICK", "PROC(PROC(INT))")
+            ],
+            returns: UNIT,
+            body: block_stmt! { }
+        }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_make_local_variable_with_proc_type_annotation() {
+    let actual = TytleParser
+        .parse("MAKELOCAL F: PROC(): INT = MYPROC")
+        .unwrap();
+
+    let expected = ast! {
+        make_local_stmt!("F", "PROC(): INT", var_lit_expr!("MYPROC"))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_apply_expr() {
+    let actual = TytleParser.parse("APPLY(F, 1, 2)").unwrap();
+
+    let call_expr = indirect_call_expr! {
+        proc: var_lit_expr!("F"),
+        params: [int_lit_expr!(1), int_lit_expr!(2)]
+    };
+
+    let expected = ast! { expr_stmt!(call_expr) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_map_stmt() {
+    let actual = TytleParser.parse("MAP(DRAW, 3)").unwrap();
+
+    let expected = ast! {
+        map_stmt!(var_lit_expr!("DRAW"), int_lit_expr!(3))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_struct_stmt() {
+    let code = r#"
+        STRUCT POINT [X: INT, Y: INT]
+        STRUCT LINE [
+            START: POINT
+            FINISH: POINT, COLOR: STR
+        ]
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        struct_stmt! {
+            name: "POINT",
+            fields: [("X", "INT"), ("Y", "INT")]
+        },
+        struct_stmt! {
+            name: "LINE",
+            fields: [("START", "POINT"), ("FINISH", "POINT"), ("COLOR", "STR")]
+        }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_struct_typed_proc_signature() {
+    let code = r#"
+        TO MID(A: POINT, B: POINT): POINT
+        END
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        proc_stmt! {
+            name: "MID",
+            params: [proc_param!("A", "POINT"), proc_param!("B", "POINT")],
+            returns: POINT,
+            body: block_stmt! { }
+        }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_field_expr() {
+    let actual = TytleParser.parse("P.START.X + 1").unwrap();
+
+    let record_expr = field_expr!(var_lit_expr!("P"), "START");
+
+    let expected = ast! {
+        expr_stmt!(binary_expr!(
+            "+",
+            boxed_expr!(field_expr!(record_expr, "X")),
+            boxed_int_lit_expr!(1)
+        ))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_set_field_stmt() {
+    let actual = TytleParser.parse("MAKE P.START.X = 10").unwrap();
+
+    let record_expr = field_expr!(var_lit_expr!("P"), "START");
+
+    let expected = ast! {
+        set_field_stmt!(record_expr, "X", int_lit_expr!(10))
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_error_apply_without_proc() {
+    let expected = ParseError::Syntax {
+        message: "`APPLY` expects a procedure".to_string(),
+    };
+
+    assert_parse_err!(expected, "APPLY()");
+}
+
+#[test]
+fn parse_proc_stmt_with_default_param_values() {
+    let code = r#"
+        TO RECT(WIDTH: INT, HEIGHT: INT = 50, COLOR: STR = "RED")
+        END
+    "#;
+
+    let actual = TytleParser.parse(code).unwrap();
+
+    let expected = ast! {
+        proc_stmt! {
+            name: "RECT",
+            params: [
+                proc_param!("WIDTH", "INT"),
+                proc_param!("HEIGHT", "INT", default: int_lit_expr!(50)),
+                proc_param!("COLOR", "STR", default: str_lit_expr!("RED"))
+            ],
+            returns: UNIT,
+            body: block_stmt! { }
+        }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_proc_call_with_named_args() {
+    let actual = TytleParser
+        .parse("RECT(10, COLOR: \"BLUE\", HEIGHT: 2 + 3)")
+        .unwrap();
+
+    let call_expr = proc_call_expr! {
+        name: "RECT",
+        params: [
+            int_lit_expr!(10),
+            named_arg_expr!("COLOR", str_lit_expr!("BLUE")),
+            named_arg_expr!(
+                "HEIGHT",
+                binary_expr!("+", boxed_int_lit_expr!(2), boxed_int_lit_expr!(3))
+            )
+        ]
+    };
+
+    let expected = ast! { expr_stmt!(call_expr) };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_error_positional_arg_after_named_arg() {
+    let expected = ParseError::Syntax {
+        message: "Positional argument after a named argument".to_string(),
+    };
+
+    assert_parse_err!(expected, "RECT(HEIGHT: 20, 10)");
+}
+
 #[test]
 fn parse_return_stmt_with_expr() {
     let code = r#"
@@ -882,6 +1150,67 @@ fn parse_command_stop() {
     assert_eq!(expected, actual);
 }
 
+#[test]
+fn parse_repeat_stmt_empty_block() {
+    let actual = TytleParser.parse("REPEAT 2 [ ]").unwrap();
+
+    let expected = ast! {
+        repeat_stmt! { int_lit_expr!(2), block_stmt!() }
+    };
+
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_error_stray_token() {
+    let code = "FORWARD 10 ]";
+
+    let expected = ParseError::StrayToken(Token::RBRACKET);
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_unclosed_block() {
+    let code = r#"
+        REPEAT 3 [
+            FORWARD 10
+    "#;
+
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::RBRACKET,
+        actual: Token::EOF,
+    };
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_proc_missing_end() {
+    let code = r#"
+        TO MYPROC()
+            FORWARD 10
+    "#;
+
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::VALUE("END".to_string()),
+        actual: Token::EOF,
+    };
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_truncated_expr() {
+    let code = "MAKEGLOBAL A = 1 +";
+
+    let expected = ParseError::Syntax {
+        message: "Invalid syntax: `End of file`".to_string(),
+    };
+
+    assert_parse_err!(expected, code);
+}
+
 #[test]
 fn parse_error_proc_param_missing_colon() {
     let code = r#"
@@ -975,7 +1304,14 @@ fn parse_error_proc_invalid_param_type() {
         END
     "#;
 
-    let expected = ParseError::InvalidDataType("INTEGER".to_string());
+    assert_missing_type!("INTEGER", code);
+
+    let code = r#"
+        TO MYPROC(MYVAR: integer)
+        END
+    "#;
+
+    let expected = ParseError::InvalidDataType("integer".to_string());
 
     assert_parse_err!(expected, code);
 }
@@ -987,7 +1323,43 @@ fn parse_error_proc_invalid_return_type() {
         END
     "#;
 
-    let expected = ParseError::InvalidDataType("STRING".to_string());
+    assert_missing_type!("STRING", code);
+
+    let code = r#"
+        TO MYPROC(MYVAR: INT) : string
+        END
+    "#;
+
+    let expected = ParseError::InvalidDataType("string".to_string());
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_make_local_invalid_type_annotation() {
+    let code = r#"
+        TO MYPROC()
+            MAKELOCAL A: INTEGER = 1
+        END
+    "#;
+
+    assert_missing_type!("INTEGER", code);
+
+    let code = "MAKELOCAL A: integer = 1";
+
+    let expected = ParseError::InvalidDataType("integer".to_string());
+
+    assert_parse_err!(expected, code);
+}
+
+#[test]
+fn parse_error_make_assign_cannot_have_a_type_annotation() {
+    let code = "MAKE A: INT = 1";
+
+    let expected = ParseError::UnexpectedToken {
+        expected: Token::ASSIGN,
+        actual: Token::COLON,
+    };
 
     assert_parse_err!(expected, code);
 }
@@ -1027,6 +1399,16 @@ fn parse_error_print_is_a_reserved_keyword() {
     assert_reserved_word!("PRINT");
 }
 
+#[test]
+fn parse_error_label_is_a_reserved_keyword() {
+    assert_reserved_word!("LABEL");
+}
+
+#[test]
+fn parse_error_setfontsize_is_a_reserved_keyword() {
+    assert_reserved_word!("SETFONTSIZE");
+}
+
 #[test]
 fn parse_error_true_is_a_reserved_keyword() {
     assert_reserved_word!("TRUE");
@@ -1152,6 +1534,11 @@ fn parse_error_clearscreen_is_a_reserved_keyword() {
     assert_reserved_word!("CLEARSCREEN");
 }
 
+#[test]
+fn parse_error_proc_is_a_reserved_keyword() {
+    assert_reserved_word!("PROC");
+}
+
 #[test]
 fn parse_error_and_is_a_reserved_keyword() {
     assert_invalid_identifier!("AND");
@@ -1166,3 +1553,39 @@ fn parse_error_or_is_a_reserved_keyword() {
 fn parse_error_not_is_a_reserved_keyword() {
     assert_invalid_identifier!("NOT");
 }
+
+#[test]
+fn parse_error_struct_is_a_reserved_keyword() {
+    assert_reserved_word!("STRUCT");
+}
+
+#[test]
+fn parse_error_integer_out_of_range() {
+    // above `usize::MAX`
+    let err = ParseError::IntegerOutOfRange {
+        literal: "99999999999999999999".to_string(),
+        location: Location(1, 7),
+    };
+    assert_parse_err!(err, "PRINT 99999999999999999999");
+
+    // above `isize::MAX`
+    let err = ParseError::IntegerOutOfRange {
+        literal: "18446744073709551615".to_string(),
+        location: Location(2, 11),
+    };
+    assert_parse_err!(err, "FORWARD 10\nPRINT 1 + 18446744073709551615");
+
+    let code = format!("PRINT {}", isize::MAX);
+    assert!(TytleParser.parse(&code).is_ok());
+}
+
+#[test]
+fn parse_error_unterminated_str() {
+    assert_parse_err!(
+        ParseError::UnterminatedStr("\"ABC DEF".to_string()),
+        "LABEL \"ABC DEF\nFORWARD 10"
+    );
+
+    assert_parse_err!(ParseError::UnterminatedStr("\"".to_string()), "PRINT \"");
+    assert_parse_err!(ParseError::UnterminatedStr("\"".to_string()), "LABEL \"");
+}
@@ -0,0 +1,358 @@
+use serde_json::{json, Value};
+use tytle::prelude::*;
+
+// LSP positions are zero-based (lines and characters), while the Tytle locations are one-based.
+// an LSP character counts UTF-16 code units, while a Tytle column counts `char`s
+// (so they differ on lines having characters out of the Basic Multilingual Plane, e.g emojis)
+fn utf16_character(line: &str, column: usize) -> usize {
+    let chars_count = line.chars().count();
+    let utf16_len: usize = line.chars().take(column - 1).map(char::len_utf16).sum();
+
+    // a column past the end of the line (e.g the end of a range)
+    utf16_len + (column - 1).saturating_sub(chars_count)
+}
+
+// the inverse of `utf16_character` (a character within a surrogate pair moves to the next `char`)
+fn char_column(line: &str, character: usize) -> usize {
+    let mut utf16_len = 0;
+
+    for (i, ch) in line.chars().enumerate() {
+        if utf16_len >= character {
+            return i + 1;
+        }
+
+        utf16_len += ch.len_utf16();
+    }
+
+    line.chars().count() + character.saturating_sub(utf16_len) + 1
+}
+
+// LSP `CompletionItemKind` and `SymbolKind` values
+const COMPLETION_FUNCTION: u32 = 3;
+const COMPLETION_KEYWORD: u32 = 14;
+const SYMBOL_FUNCTION: u32 = 12;
+
+// the semantic tokens legend, the highlighting kinds are mapped into the LSP standard token types
+// (so that the editors color them without any configuration)
+pub const TOKEN_TYPES: [&str; 10] = [
+    "keyword",
+    "function",
+    "variable",
+    "parameter",
+    "type",
+    "property",
+    "number",
+    "string",
+    "operator",
+    "comment",
+];
+
+pub const TOKEN_MODIFIERS: [&str; 2] = ["defaultLibrary", "static"];
+
+const MODIFIER_DEFAULT_LIBRARY: u32 = 1;
+const MODIFIER_STATIC: u32 = 1 << 1;
+
+// LSP `DiagnosticSeverity` values
+const SEVERITY_ERROR: u32 = 1;
+const SEVERITY_WARNING: u32 = 2;
+
+// an open document, analyzed on each change.
+// the analysis is a best-effort one, so the features keep on working while the code has errors
+pub struct Document {
+    code: String,
+    ast: Ast,
+    env: Environment,
+    diagnostics: Vec<Diagnostic>,
+    warnings: Vec<Warning>,
+    index: SymbolIndex,
+}
+
+impl Document {
+    pub fn new(code: &str) -> Self {
+        let (mut ast, env, diagnostics) = analyze(code);
+
+        // the lints expect a program compiling with no errors
+        let warnings = if diagnostics.is_empty() {
+            AstLint::new(&env, &LintConfig::new()).lint(&mut ast)
+        } else {
+            Vec::new()
+        };
+
+        let index = SymbolIndex::build(code, &ast, &env);
+
+        Self {
+            code: code.to_string(),
+            ast,
+            env,
+            diagnostics,
+            warnings,
+            index,
+        }
+    }
+
+    // the text of the one-based `line` (empty past the end of the document)
+    fn line(&self, line: usize) -> &str {
+        self.code.lines().nth(line - 1).unwrap_or("")
+    }
+
+    pub fn to_position(&self, location: Location) -> Value {
+        let line = self.line(location.line());
+
+        json!({
+            "line": location.line() - 1,
+            "character": utf16_character(line, location.column()),
+        })
+    }
+
+    pub fn location_at(&self, position: &Value) -> Option<Location> {
+        let line = position["line"].as_u64()? as usize;
+        let character = position["character"].as_u64()? as usize;
+
+        let column = char_column(self.line(line + 1), character);
+
+        Some(Location(line + 1, column))
+    }
+
+    fn to_range(&self, start: Location, end: Location) -> Value {
+        json!({
+            "start": self.to_position(start),
+            "end": self.to_position(end),
+        })
+    }
+
+    // the errors followed by the lints warnings, each spans the rest of the line it's located at
+    pub fn diagnostics(&self) -> Value {
+        let errors = self.diagnostics.iter().map(|diag| {
+            json!({
+                "range": self.line_range(diag.location),
+                "severity": SEVERITY_ERROR,
+                "source": "tytle",
+                "message": diag.error.to_string(),
+            })
+        });
+
+        let warnings = self.warnings.iter().map(|warning| {
+            json!({
+                "range": self.line_range(warning.location),
+                "severity": SEVERITY_WARNING,
+                "source": "tytle",
+                "code": warning.warning.kind().name(),
+                "message": warning.warning.to_string(),
+            })
+        });
+
+        Value::Array(errors.chain(warnings).collect())
+    }
+
+    // the declaration of the symbol at `location` (`None` when there's no symbol there)
+    pub fn definition(&self, uri: &str, location: Location) -> Option<Value> {
+        let sym_ref = self.index.ref_at(location)?;
+        let decl = self.index.get_decl(sym_ref.symbol_id)?;
+
+        Some(json!({
+            "uri": uri,
+            "range": self.name_range(decl),
+        }))
+    }
+
+    // shows the symbol at `location` along with its type (e.g `param SIZE: INT`)
+    pub fn hover(&self, location: Location) -> Option<Value> {
+        let sym_ref = self.index.ref_at(location)?;
+        let symbol = self
+            .env
+            .symbol_table
+            .lookup_by_symbol_id(sym_ref.symbol_id)?;
+
+        Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```tytle\n{}\n```", symbol.describe()),
+            },
+            "range": self.name_range(sym_ref),
+        }))
+    }
+
+    // the keywords and the procedures of the document
+    pub fn completion(&self) -> Value {
+        let mut keywords: Vec<&str> = KEYWORDS.iter().cloned().collect();
+        keywords.sort();
+
+        let keywords = keywords.into_iter().map(|keyword| {
+            json!({
+                "label": keyword,
+                "kind": COMPLETION_KEYWORD,
+            })
+        });
+
+        let procs =
+            self.env
+                .symbol_table
+                .get_symbols()
+                .into_iter()
+                .filter_map(|symbol| match symbol {
+                    Symbol::Proc(proc) if !proc.name.starts_with("__") => Some(json!({
+                        "label": proc.name,
+                        "kind": COMPLETION_FUNCTION,
+                        "detail": proc.signature(),
+                    })),
+                    _ => None,
+                });
+
+        Value::Array(keywords.chain(procs).collect())
+    }
+
+    // the highlighting spans encoded as LSP semantic tokens, i.e 5 integers per token:
+    // the line (relative to the previous token), the start character (relative to the previous token
+    // when on the same line), the length, the token type and the modifiers (see `TOKEN_TYPES`)
+    pub fn semantic_tokens(&self) -> Value {
+        let mut data = Vec::new();
+
+        // the previous token line and start character (in UTF-16 code units)
+        let mut prev = (1, 0);
+
+        for span in highlight_with_index(&self.code, &self.env, &self.index) {
+            let location = span.location;
+            let line = self.line(location.line());
+
+            let first_char = line.chars().nth(location.column() - 1);
+
+            let (token_type, modifiers) = Self::token_type(span.kind, first_char);
+
+            let start = utf16_character(line, location.column());
+            let end = utf16_character(line, location.column() + span.len);
+
+            let delta_line = location.line() - prev.0;
+            let delta_start = if delta_line == 0 {
+                start - prev.1
+            } else {
+                start
+            };
+
+            data.extend(vec![
+                delta_line,
+                delta_start,
+                end - start,
+                token_type,
+                modifiers as usize,
+            ]);
+
+            prev = (location.line(), start);
+        }
+
+        json!({ "data": data })
+    }
+
+    // the index of the token type within `TOKEN_TYPES` and the modifiers bits.
+    // the literals are told apart by their first character (`TRUE` and `FALSE` are keywords)
+    fn token_type(kind: HighlightKind, first_char: Option<char>) -> (usize, u32) {
+        let (name, modifiers) = match kind {
+            HighlightKind::Keyword => ("keyword", 0),
+            HighlightKind::Command | HighlightKind::Direction => {
+                ("function", MODIFIER_DEFAULT_LIBRARY)
+            }
+            HighlightKind::Procedure => ("function", 0),
+            HighlightKind::GlobalVar => ("variable", MODIFIER_STATIC),
+            HighlightKind::LocalVar => ("variable", 0),
+            HighlightKind::Param => ("parameter", 0),
+            HighlightKind::Type => ("type", 0),
+            HighlightKind::Field => ("property", 0),
+            HighlightKind::Literal => match first_char {
+                Some('"') => ("string", 0),
+                Some(ch) if ch.is_ascii_digit() => ("number", 0),
+                _ => ("keyword", 0),
+            },
+            HighlightKind::Operator => ("operator", 0),
+            HighlightKind::Comment => ("comment", 0),
+        };
+
+        let index = TOKEN_TYPES.iter().position(|ty| *ty == name).unwrap();
+
+        (index, modifiers)
+    }
+
+    // the `TO ... END` blocks (a nested procedure is a child of its enclosing procedure)
+    pub fn symbols(&self) -> Value {
+        Value::Array(self.block_symbols(&self.ast.statements, &self.ast.locations))
+    }
+
+    fn block_symbols(&self, stmts: &[Statement], locations: &[Location]) -> Vec<Value> {
+        let mut symbols = Vec::new();
+
+        for (stmt, location) in stmts.iter().zip(locations.iter()) {
+            match stmt {
+                Statement::Procedure(proc_stmt) => {
+                    symbols.push(self.proc_symbol(proc_stmt, *location));
+                }
+                Statement::If(if_stmt) => {
+                    let block = &if_stmt.true_block;
+                    symbols.extend(self.block_symbols(&block.stmts, &block.locations));
+
+                    if let Some(ref block) = if_stmt.false_block {
+                        symbols.extend(self.block_symbols(&block.stmts, &block.locations));
+                    }
+                }
+                Statement::Repeat(repeat_stmt) => {
+                    let block = &repeat_stmt.block;
+                    symbols.extend(self.block_symbols(&block.stmts, &block.locations));
+                }
+                _ => (),
+            }
+        }
+
+        symbols
+    }
+
+    fn proc_symbol(&self, proc_stmt: &ProcedureStmt, location: Location) -> Value {
+        let block = &proc_stmt.block;
+
+        // the range ends after `END`
+        let end = match block.end_location {
+            Some(end) => Location(end.line(), end.column() + 3),
+            None => location,
+        };
+
+        let decl = proc_stmt
+            .id
+            .and_then(|proc_id| self.index.get_decl(proc_id));
+
+        let selection_range = match decl {
+            Some(decl) => self.name_range(decl),
+            None => self.to_range(location, location),
+        };
+
+        let detail = match proc_stmt.id {
+            Some(proc_id) => self.env.symbol_table.get_proc_by_id(proc_id).signature(),
+            None => proc_stmt.name.clone(),
+        };
+
+        json!({
+            "name": proc_stmt.name,
+            "detail": detail,
+            "kind": SYMBOL_FUNCTION,
+            "range": self.to_range(location, end),
+            "selectionRange": selection_range,
+            "children": self.block_symbols(&block.stmts, &block.locations),
+        })
+    }
+
+    fn name_range(&self, sym_ref: &SymbolRef) -> Value {
+        let start = sym_ref.location;
+        let end = Location(start.line(), start.column() + sym_ref.name.chars().count());
+
+        self.to_range(start, end)
+    }
+
+    // from `location` up to the end of its line (the first line when there's no location)
+    fn line_range(&self, location: Option<Location>) -> Value {
+        let start = location.unwrap_or_default();
+
+        let line_len = match self.code.lines().nth(start.line() - 1) {
+            Some(line) => line.chars().count(),
+            None => 0,
+        };
+
+        let end = Location(start.line(), line_len.max(start.column() - 1) + 1);
+
+        self.to_range(start, end)
+    }
+}
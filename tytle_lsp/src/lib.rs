@@ -0,0 +1,9 @@
+extern crate serde_json;
+extern crate tytle;
+
+mod document;
+mod server;
+mod transport;
+
+pub use server::LspServer;
+pub use transport::{read_message, write_message};
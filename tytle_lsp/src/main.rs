@@ -0,0 +1,55 @@
+extern crate serde_json;
+extern crate tytle_lsp;
+
+use serde_json::{json, Value};
+use std::io::{self, BufReader};
+use std::process;
+
+use tytle_lsp::{read_message, write_message, LspServer};
+
+// JSON-RPC error code of a message which isn't a valid JSON
+const PARSE_ERROR: i64 = -32700;
+
+// a Tytle language server, talking LSP over stdin / stdout
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    let mut reader = BufReader::new(stdin.lock());
+    let mut writer = stdout.lock();
+
+    let mut server = LspServer::new();
+
+    loop {
+        let content = match read_message(&mut reader) {
+            Ok(Some(content)) => content,
+            Ok(None) => process::exit(1), // the client has gone without an `exit`
+            Err(err) => {
+                eprintln!("Failed reading a message: {}", err);
+                process::exit(1);
+            }
+        };
+
+        let replies = match serde_json::from_str::<Value>(&content) {
+            Ok(msg) => {
+                if msg["method"] == "exit" {
+                    process::exit(if server.is_shutdown() { 0 } else { 1 });
+                }
+
+                server.handle(&msg)
+            }
+            Err(err) => vec![LspServer::error_response(
+                &json!(null),
+                PARSE_ERROR,
+                &err.to_string(),
+            )],
+        };
+
+        for reply in replies.iter() {
+            if let Err(err) = write_message(&mut writer, &reply.to_string()) {
+                eprintln!("Failed writing a message: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+}
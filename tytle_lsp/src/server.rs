@@ -0,0 +1,213 @@
+use crate::document::{Document, TOKEN_MODIFIERS, TOKEN_TYPES};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// JSON-RPC error codes
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+// the `TextDocumentSyncKind` of the server (each change holds the whole document)
+const SYNC_FULL: u32 = 1;
+
+// a language server for the Tytle documents.
+// the server knows nothing about the transport: each client message is handled by `handle`,
+// which returns the messages to send back to the client
+pub struct LspServer {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+}
+
+impl LspServer {
+    pub fn new() -> Self {
+        Self {
+            documents: HashMap::new(),
+            shutdown: false,
+        }
+    }
+
+    // whether the client has asked the server to shut down (so that `exit` is expected next)
+    pub fn is_shutdown(&self) -> bool {
+        self.shutdown
+    }
+
+    pub fn handle(&mut self, msg: &Value) -> Vec<Value> {
+        let method = msg["method"].as_str().unwrap_or("");
+        let params = &msg["params"];
+
+        match msg.get("id") {
+            Some(id) => vec![self.handle_request(id, method, params)],
+            None => self.handle_notification(method, params),
+        }
+    }
+
+    fn handle_request(&mut self, id: &Value, method: &str, params: &Value) -> Value {
+        if self.shutdown {
+            return Self::error_response(id, INVALID_REQUEST, "the server is shutting down");
+        }
+
+        let result = match method {
+            "initialize" => Ok(Self::capabilities()),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.on_definition(params),
+            "textDocument/hover" => self.on_hover(params),
+            "textDocument/completion" => self.on_completion(params),
+            "textDocument/documentSymbol" => self.on_document_symbol(params),
+            "textDocument/semanticTokens/full" => self.on_semantic_tokens(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+        };
+
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => Self::error_response(id, code, &message),
+        }
+    }
+
+    // the notifications which aren't handled (e.g `initialized`) are ignored
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let code = params["textDocument"]["text"].as_str().unwrap_or("");
+                self.open_document(uri, code)
+            }
+            "textDocument/didChange" => {
+                // with a full sync, the last change holds the whole document
+                let changes = params["contentChanges"].as_array();
+
+                match changes.and_then(|changes| changes.last()) {
+                    Some(change) => {
+                        let code = change["text"].as_str().unwrap_or("");
+                        self.open_document(uri, code)
+                    }
+                    None => Vec::new(),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+
+                // the diagnostics of a closed document are cleared
+                vec![Self::publish_diagnostics(&uri, json!([]))]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn open_document(&mut self, uri: String, code: &str) -> Vec<Value> {
+        let doc = Document::new(code);
+        let diagnostics = doc.diagnostics();
+
+        self.documents.insert(uri.clone(), doc);
+
+        vec![Self::publish_diagnostics(&uri, diagnostics)]
+    }
+
+    fn on_definition(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (uri, doc, location) = self.document_position(params)?;
+
+        Ok(doc.definition(uri, location).unwrap_or(Value::Null))
+    }
+
+    fn on_hover(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_uri, doc, location) = self.document_position(params)?;
+
+        Ok(doc.hover(location).unwrap_or(Value::Null))
+    }
+
+    fn on_completion(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_uri, doc) = self.document(params)?;
+
+        Ok(doc.completion())
+    }
+
+    fn on_document_symbol(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_uri, doc) = self.document(params)?;
+
+        Ok(doc.symbols())
+    }
+
+    fn on_semantic_tokens(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let (_uri, doc) = self.document(params)?;
+
+        Ok(doc.semantic_tokens())
+    }
+
+    fn document<'a>(&self, params: &'a Value) -> Result<(&'a str, &Document), (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+
+        match self.documents.get(uri) {
+            Some(doc) => Ok((uri, doc)),
+            None => Err((INVALID_PARAMS, format!("unknown document `{}`", uri))),
+        }
+    }
+
+    fn document_position<'a>(
+        &self,
+        params: &'a Value,
+    ) -> Result<(&'a str, &Document, tytle::lexer::Location), (i64, String)> {
+        let (uri, doc) = self.document(params)?;
+
+        match doc.location_at(&params["position"]) {
+            Some(location) => Ok((uri, doc, location)),
+            None => Err((INVALID_PARAMS, "invalid position".to_string())),
+        }
+    }
+
+    fn capabilities() -> Value {
+        json!({
+            "capabilities": {
+                "textDocumentSync": SYNC_FULL,
+                "definitionProvider": true,
+                "hoverProvider": true,
+                "completionProvider": {},
+                "documentSymbolProvider": true,
+                "semanticTokensProvider": {
+                    "legend": {
+                        "tokenTypes": TOKEN_TYPES,
+                        "tokenModifiers": TOKEN_MODIFIERS,
+                    },
+                    "full": true,
+                },
+            },
+            "serverInfo": {
+                "name": "tytle-lsp",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn publish_diagnostics(uri: &str, diagnostics: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": {
+                "uri": uri,
+                "diagnostics": diagnostics,
+            },
+        })
+    }
+
+    pub fn error_response(id: &Value, code: i64, message: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": code,
+                "message": message,
+            },
+        })
+    }
+}
+
+impl Default for LspServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,56 @@
+use std::io::{self, BufRead, Write};
+
+// the LSP base protocol: each message is a JSON content preceded by headers, e.g:
+//
+// Content-Length: 52\r\n
+// \r\n
+// {"jsonrpc":"2.0","method":"initialized","params":{}}
+
+// reads the content of the next message (`None` once the input has ended)
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut content_length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        // other headers (i.e `Content-Type`) are ignored
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            let length = value.trim().parse::<usize>().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid `Content-Length`")
+            })?;
+
+            content_length = Some(length);
+        }
+    }
+
+    let content_length = content_length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing `Content-Length`"))?;
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+
+    String::from_utf8(content)
+        .map(Some)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "the content isn't UTF-8"))
+}
+
+pub fn write_message(writer: &mut impl Write, content: &str) -> io::Result<()> {
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+
+    writer.flush()
+}
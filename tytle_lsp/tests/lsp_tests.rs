@@ -0,0 +1,339 @@
+extern crate serde_json;
+extern crate tytle_lsp;
+
+use serde_json::{json, Value};
+use std::io::Cursor;
+use tytle_lsp::{read_message, write_message, LspServer};
+
+const URI: &str = "file:///square.tytle";
+
+const CODE: &str = r#"MAKEGLOBAL SIZE = 10
+TO SQUARE(N: INT)
+  TO SIDE()
+    FORWARD N
+  END
+  REPEAT 4 [SIDE() RIGHT 90]
+END
+SQUARE(SIZE)"#;
+
+macro_rules! open_server {
+    ($code: expr) => {{
+        let mut server = LspServer::new();
+
+        let msg = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": URI, "languageId": "tytle", "version": 1, "text": $code },
+            },
+        });
+
+        let replies = server.handle(&msg);
+        assert_eq!(1, replies.len());
+
+        (server, replies[0].clone())
+    }};
+}
+
+fn request(server: &mut LspServer, method: &str, params: Value) -> Value {
+    let msg = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+
+    let replies = server.handle(&msg);
+    assert_eq!(1, replies.len());
+
+    replies[0]["result"].clone()
+}
+
+fn position_params(line: u64, character: u64) -> Value {
+    json!({
+        "textDocument": { "uri": URI },
+        "position": { "line": line, "character": character },
+    })
+}
+
+fn range(start: (u64, u64), end: (u64, u64)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 },
+    })
+}
+
+#[test]
+fn lsp_diagnostics_on_change() {
+    let (mut server, published) = open_server!(CODE);
+
+    assert_eq!("textDocument/publishDiagnostics", published["method"]);
+    assert_eq!(json!([]), published["params"]["diagnostics"]);
+
+    let msg = json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didChange",
+        "params": {
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "MAKEGLOBAL A = 1\nMAKE A = B\nTO F()\nEND\n" }],
+        },
+    });
+
+    let replies = server.handle(&msg);
+
+    let expected = json!([
+        {
+            "range": range((1, 0), (1, 10)),
+            "severity": 1,
+            "source": "tytle",
+            "message": "Missing variable declaration for `B`",
+        },
+    ]);
+
+    assert_eq!(expected, replies[0]["params"]["diagnostics"]);
+}
+
+#[test]
+fn lsp_lints_warnings() {
+    let (_server, published) = open_server!("TO F()\nEND\n");
+
+    let diagnostic = &published["params"]["diagnostics"][0];
+
+    assert_eq!(2, diagnostic["severity"]);
+    assert_eq!("unused-procedure", diagnostic["code"]);
+}
+
+#[test]
+fn lsp_unterminated_str() {
+    // the closing `"` of a string literal is yet to be typed
+    let (mut server, published) = open_server!("PRINT \"");
+
+    let expected = json!([
+        {
+            "range": range((0, 0), (0, 7)),
+            "severity": 1,
+            "source": "tytle",
+            "message": "Unterminated string literal: `\"`",
+        },
+    ]);
+
+    assert_eq!(expected, published["params"]["diagnostics"]);
+
+    let res = request(
+        &mut server,
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+
+    assert_eq!(json!({ "data": [0, 0, 5, 1, 1, 0, 6, 1, 7, 0] }), res);
+}
+
+#[test]
+fn lsp_utf16_positions() {
+    // the LSP characters count UTF-16 code units, a `🐢` is two of them
+    let code = "MAKEGLOBAL SIZE = 10\nTO F(A: STR, B: INT)\nEND\nF(\"🐢\", SIZE)\nF(\"🐢\", X)";
+    let (mut server, published) = open_server!(code);
+
+    let expected = json!([
+        {
+            "range": range((4, 0), (4, 10)),
+            "severity": 1,
+            "source": "tytle",
+            "message": "Missing variable declaration for `X`",
+        },
+    ]);
+
+    assert_eq!(expected, published["params"]["diagnostics"]);
+
+    // `SIZE` within `F("🐢", SIZE)`
+    let res = request(
+        &mut server,
+        "textDocument/definition",
+        position_params(3, 9),
+    );
+    let expected = json!({ "uri": URI, "range": range((0, 11), (0, 15)) });
+    assert_eq!(expected, res);
+
+    let res = request(&mut server, "textDocument/hover", position_params(3, 8));
+    assert_eq!(json!(range((3, 8), (3, 12))), res["range"]);
+
+    let (mut server, _) = open_server!("LABEL \"🐢\" ; 🐢");
+
+    let res = request(
+        &mut server,
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+
+    let expected = json!({
+        "data": [
+            0, 0, 5, 1, 1, // `LABEL`
+            0, 6, 4, 7, 0, // `"🐢"`
+            0, 5, 4, 9, 0, // `; 🐢`
+        ],
+    });
+
+    assert_eq!(expected, res);
+}
+
+#[test]
+fn lsp_definition() {
+    let (mut server, _) = open_server!(CODE);
+
+    // `SIZE` within `SQUARE(SIZE)`
+    let res = request(
+        &mut server,
+        "textDocument/definition",
+        position_params(7, 9),
+    );
+    let expected = json!({ "uri": URI, "range": range((0, 11), (0, 15)) });
+    assert_eq!(expected, res);
+
+    // `N` within the nested procedure
+    let res = request(
+        &mut server,
+        "textDocument/definition",
+        position_params(3, 12),
+    );
+    let expected = json!({ "uri": URI, "range": range((1, 10), (1, 11)) });
+    assert_eq!(expected, res);
+
+    // `SIDE()` within `REPEAT`
+    let res = request(
+        &mut server,
+        "textDocument/definition",
+        position_params(5, 13),
+    );
+    let expected = json!({ "uri": URI, "range": range((2, 5), (2, 9)) });
+    assert_eq!(expected, res);
+
+    // not a symbol (`FORWARD`)
+    let res = request(
+        &mut server,
+        "textDocument/definition",
+        position_params(3, 6),
+    );
+    assert_eq!(Value::Null, res);
+}
+
+#[test]
+fn lsp_hover() {
+    let (mut server, _) = open_server!(CODE);
+
+    let res = request(&mut server, "textDocument/hover", position_params(7, 0));
+
+    let expected = json!({
+        "contents": { "kind": "markdown", "value": "```tytle\nproc SQUARE(INT)\n```" },
+        "range": range((7, 0), (7, 6)),
+    });
+
+    assert_eq!(expected, res);
+
+    let res = request(&mut server, "textDocument/hover", position_params(0, 13));
+    assert_eq!("```tytle\nglobal SIZE: INT\n```", res["contents"]["value"]);
+}
+
+#[test]
+fn lsp_completion() {
+    let (mut server, _) = open_server!(CODE);
+
+    let res = request(
+        &mut server,
+        "textDocument/completion",
+        position_params(0, 0),
+    );
+    let items = res.as_array().unwrap();
+
+    assert!(items.contains(&json!({ "label": "REPEAT", "kind": 14 })));
+    assert!(items.contains(&json!({ "label": "SQUARE", "kind": 3, "detail": "SQUARE(INT)" })));
+    assert!(items.contains(&json!({ "label": "SIDE", "kind": 3, "detail": "SIDE()" })));
+    assert!(!items.iter().any(|item| item["label"] == "__main__"));
+}
+
+#[test]
+fn lsp_document_symbols() {
+    let (mut server, _) = open_server!(CODE);
+
+    let res = request(
+        &mut server,
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+
+    let expected = json!([
+        {
+            "name": "SQUARE",
+            "detail": "SQUARE(INT)",
+            "kind": 12,
+            "range": range((1, 0), (6, 3)),
+            "selectionRange": range((1, 3), (1, 9)),
+            "children": [
+                {
+                    "name": "SIDE",
+                    "detail": "SIDE()",
+                    "kind": 12,
+                    "range": range((2, 2), (4, 5)),
+                    "selectionRange": range((2, 5), (2, 9)),
+                    "children": [],
+                },
+            ],
+        },
+    ]);
+
+    assert_eq!(expected, res);
+}
+
+#[test]
+fn lsp_semantic_tokens() {
+    let (mut server, _) = open_server!("MAKEGLOBAL A = 1 ; one\nFORWARD A");
+
+    let res = request(
+        &mut server,
+        "textDocument/semanticTokens/full",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+
+    let expected = json!({
+        "data": [
+            0, 0, 10, 0, 0, // `MAKEGLOBAL` (keyword)
+            0, 11, 1, 2, 2, // `A` (static variable)
+            0, 2, 1, 8, 0,  // `=` (operator)
+            0, 2, 1, 6, 0,  // `1` (number)
+            0, 2, 5, 9, 0,  // `; one` (comment)
+            1, 0, 7, 1, 1,  // `FORWARD` (default library function)
+            0, 8, 1, 2, 2,  // `A` (static variable)
+        ],
+    });
+
+    assert_eq!(expected, res);
+}
+
+#[test]
+fn lsp_unknown_method_and_shutdown() {
+    let mut server = LspServer::new();
+
+    let msg = json!({ "jsonrpc": "2.0", "id": 7, "method": "textDocument/rename", "params": {} });
+    let replies = server.handle(&msg);
+    assert_eq!(-32601, replies[0]["error"]["code"]);
+    assert_eq!(7, replies[0]["id"]);
+
+    assert!(!server.is_shutdown());
+    let msg = json!({ "jsonrpc": "2.0", "id": 8, "method": "shutdown" });
+    assert_eq!(Value::Null, server.handle(&msg)[0]["result"]);
+    assert!(server.is_shutdown());
+}
+
+#[test]
+fn lsp_transport_round_trip() {
+    let mut buffer = Vec::new();
+    write_message(&mut buffer, r#"{"jsonrpc":"2.0"}"#).unwrap();
+    write_message(&mut buffer, "{}").unwrap();
+
+    assert_eq!(
+        "Content-Length: 17\r\n\r\n{\"jsonrpc\":\"2.0\"}Content-Length: 2\r\n\r\n{}",
+        String::from_utf8(buffer.clone()).unwrap()
+    );
+
+    let mut reader = Cursor::new(buffer);
+    assert_eq!(
+        Some(r#"{"jsonrpc":"2.0"}"#.to_string()),
+        read_message(&mut reader).unwrap()
+    );
+    assert_eq!(Some("{}".to_string()), read_message(&mut reader).unwrap());
+    assert_eq!(None, read_message(&mut reader).unwrap());
+}